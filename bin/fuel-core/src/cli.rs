@@ -1,3 +1,4 @@
+use anyhow::Context;
 use clap::Parser;
 use std::{
     env,
@@ -44,6 +45,11 @@ pub enum Fuel {
 
 pub const LOG_FILTER: &str = "RUST_LOG";
 pub const HUMAN_LOGGING: &str = "HUMAN_LOGGING";
+/// The verbosity of the p2p logs, overrides the `RUST_LOG` for the p2p targets
+pub const P2P_LOG_LEVEL: &str = "P2P_LOG_LEVEL";
+
+/// The targets of the p2p logs and spans
+const P2P_LOG_TARGETS: [&str; 3] = ["fuel_core_p2p", "fuel-p2p", "fuel-libp2p"];
 
 #[cfg(feature = "env")]
 fn init_environment() -> Option<PathBuf> {
@@ -56,12 +62,20 @@ fn init_environment() -> Option<PathBuf> {
 }
 
 pub async fn init_logging() -> anyhow::Result<()> {
-    let filter = match env::var_os(LOG_FILTER) {
+    let mut filter = match env::var_os(LOG_FILTER) {
         Some(_) => {
             EnvFilter::try_from_default_env().expect("Invalid `RUST_LOG` provided")
         }
         None => EnvFilter::new("info"),
     };
+    if let Ok(level) = env::var(P2P_LOG_LEVEL) {
+        for target in P2P_LOG_TARGETS {
+            let directive = format!("{target}={level}")
+                .parse()
+                .with_context(|| format!("Invalid `P2P_LOG_LEVEL` provided: {level}"))?;
+            filter = filter.add_directive(directive);
+        }
+    }
 
     let human_logging = env::var_os(HUMAN_LOGGING)
         .map(|s| {
@@ -2,18 +2,31 @@ use anyhow::anyhow;
 use clap::{
     builder::ArgPredicate::IsPresent,
     Args,
+    ValueEnum,
 };
 use fuel_core::{
     p2p::{
         config::{
             convert_to_libp2p_keypair,
+            load_or_generate_keypair,
             Config,
             NotInitialized,
+            OverflowPolicy,
+            PeerSelectionStrategy,
+            TransportSecurity,
             MAX_RESPONSE_SIZE,
         },
-        gossipsub_config::default_gossipsub_builder,
+        gossipsub::messages::GossipTopicTag,
+        gossipsub_config::{
+            default_gossipsub_builder,
+            GossipMessageIdStrategy,
+            GossipsubScoringConfig,
+        },
         heartbeat,
+        IpNetwork,
         Multiaddr,
+        PeerId,
+        VersionReq,
     },
     types::{
         fuel_crypto,
@@ -42,10 +55,16 @@ pub struct P2PArgs {
 
     /// Peering secret key. Supports either a hex encoded secret key inline or a path to bip32 mnemonic encoded secret file.
     #[clap(long = "keypair", env, value_parser = KeypairArg::try_from_string)]
-    #[arg(required_if_eq("enable_p2p", "true"))]
     #[arg(requires_if(IsPresent, "enable_p2p"))]
     pub keypair: Option<KeypairArg>,
 
+    /// Path to the file with the hex encoded peering secret key.
+    /// If the file doesn't exist, the new key is generated and stored there,
+    /// so the node keeps the same peer id across restarts.
+    #[clap(long = "keypair-path", env, conflicts_with = "keypair")]
+    #[arg(requires_if(IsPresent, "enable_p2p"))]
+    pub keypair_path: Option<PathBuf>,
+
     /// p2p network's IP Address
     #[clap(long = "address", env)]
     pub address: Option<IpAddr>,
@@ -58,14 +77,245 @@ pub struct P2PArgs {
     #[clap(long = "peering-port", default_value = "30333", env)]
     pub peering_port: u16,
 
+    /// Additional addresses to listen on, besides the `address` with the `peering-port`.
+    /// For example, `/ip6/::/tcp/30333` to accept the connections over IPv6 as well.
+    #[clap(long = "listen-addresses", value_delimiter = ',', env)]
+    pub listen_addresses: Vec<Multiaddr>,
+
     /// Max Block size
     #[clap(long = "max-block-size", default_value = MAX_RESPONSE_SIZE_STR, env)]
     pub max_block_size: usize,
 
+    /// Compress the gossip messages and the responses larger than the threshold in bytes.
+    /// The compression is disabled if not set.
+    #[clap(long = "compression-threshold", env)]
+    pub compression_threshold: Option<usize>,
+
+    /// Buffer the broadcasted transactions for up to this interval and publish them as a single message.
+    /// The batching is disabled if not set.
+    #[clap(long = "tx-batch-interval", env)]
+    pub tx_batch_interval: Option<humantime::Duration>,
+
+    /// Max size of the transactions batch in bytes, the full batch is published immediately
+    #[clap(long = "tx-batch-max-size", default_value = "65536", env)]
+    pub tx_batch_max_size: usize,
+
+    /// Hold up to this number of the gossip messages received while the node is starting
+    /// and deliver them once all services are started. The gossip isn't held if not set.
+    #[clap(long = "startup-gossip-buffer-size", env)]
+    pub startup_gossip_buffer_size: Option<usize>,
+
+    /// Max number of the transactions received via gossip remembered to drop their duplicates
+    #[clap(long = "gossip-duplicates-cache-size", default_value = "1024", env)]
+    pub gossip_duplicates_cache_size: usize,
+
+    /// The transaction received via gossip again within this window isn't passed to the txpool
+    #[clap(long = "gossip-duplicates-ttl", default_value = "30s", env)]
+    pub gossip_duplicates_ttl: humantime::Duration,
+
+    /// Max number of the gossiped transactions waiting for the slowest subscriber
+    #[clap(long = "tx-broadcast-capacity", default_value = "10240", env)]
+    pub tx_broadcast_capacity: usize,
+
+    /// What is dropped when the slowest subscriber of the gossiped transactions
+    /// is `tx-broadcast-capacity` behind
+    #[clap(
+        long = "tx-overflow-policy",
+        value_enum,
+        default_value = "drop-oldest",
+        env
+    )]
+    pub tx_overflow_policy: Overflow,
+
+    /// Max number of the block heights of the peers waiting for the slowest subscriber
+    #[clap(long = "block-height-broadcast-capacity", default_value = "10240", env)]
+    pub block_height_broadcast_capacity: usize,
+
+    /// What is dropped when the slowest subscriber of the block heights of the peers
+    /// is `block-height-broadcast-capacity` behind
+    #[clap(
+        long = "block-height-overflow-policy",
+        value_enum,
+        default_value = "drop-oldest",
+        env
+    )]
+    pub block_height_overflow_policy: Overflow,
+
+    /// Max number of the requests of the other services waiting for the p2p task
+    #[clap(long = "request-channel-capacity", default_value = "10240", env)]
+    pub request_channel_capacity: usize,
+
+    /// What happens to the new request of the other services
+    /// when `request-channel-capacity` requests are waiting.
+    /// Only `block` and `drop-newest` are supported.
+    #[clap(
+        long = "request-overflow-policy",
+        value_enum,
+        default_value = "block",
+        env
+    )]
+    pub request_overflow_policy: Overflow,
+
+    /// Max number of the network events waiting for the slowest subscriber
+    #[clap(
+        long = "network-event-broadcast-capacity",
+        default_value = "10240",
+        env
+    )]
+    pub network_event_broadcast_capacity: usize,
+
+    /// Max size of the gossiped transaction in bytes, the larger messages are rejected
+    #[clap(long = "max-tx-gossip-size", default_value = "262144", env)]
+    pub max_tx_gossip_size: usize,
+
+    /// Max size of the gossiped transactions batch in bytes, the larger messages are rejected
+    #[clap(long = "max-tx-batch-gossip-size", default_value = "1048576", env)]
+    pub max_tx_batch_gossip_size: usize,
+
     /// Max number of headers in a single headers request response
     #[clap(long = "max-headers-per-request", default_value = "100", env)]
     pub max_headers_per_request: u32,
 
+    /// Size in bytes of the chunks the blocks too large for one response are served in
+    #[clap(long = "block-chunk-size", default_value = "1048576", env)]
+    pub block_chunk_size: usize,
+
+    /// Max number of the chunks of the blocks requested from a single peer at once
+    #[clap(long = "max-block-chunks-in-flight", default_value = "4", env)]
+    pub max_block_chunks_in_flight: usize,
+
+    /// The time to receive all chunks of the block, before the transfer is abandoned
+    #[clap(long = "block-chunks-timeout", default_value = "60s", env)]
+    pub block_chunks_timeout: humantime::Duration,
+
+    /// Max size in bytes of the block reassembled from the chunks
+    #[clap(long = "max-chunked-block-size", default_value = "67108864", env)]
+    pub max_chunked_block_size: usize,
+
+    /// Max number of inbound requests per second accepted from a single peer
+    #[clap(long = "max-requests-per-second", default_value = "20", env)]
+    pub max_requests_per_second: u32,
+
+    /// Max number of inbound requests a single peer can send at once
+    #[clap(long = "max-requests-burst", default_value = "50", env)]
+    pub max_requests_burst: u32,
+
+    /// Silently drop the requests above the rate limit instead of answering them with an error
+    #[clap(long = "drop-rate-limited-requests", env)]
+    pub drop_rate_limited_requests: bool,
+
+    /// Max number of response bytes sent to a single peer per minute.
+    /// The block requests above it are answered with an error until the minute ends.
+    #[clap(long = "max-response-bytes-per-peer-per-minute", env)]
+    pub max_response_bytes_per_peer_per_minute: Option<u64>,
+
+    /// Number of distinct peers the same request for headers is sent to.
+    /// The fastest successful response wins.
+    #[clap(long = "redundant-request-factor", default_value = "1", env)]
+    pub redundant_request_factor: u32,
+
+    /// Max number of outbound requests awaiting the response.
+    /// The requests above it are rejected right away instead of being sent.
+    #[clap(long = "max-outbound-requests", env)]
+    pub max_outbound_requests: Option<usize>,
+
+    /// Max number of database lookups for inbound requests running at once
+    #[clap(long = "max-concurrent-db-lookups", default_value = "16", env)]
+    pub max_concurrent_db_lookups: usize,
+
+    /// Max number of database lookups waiting for their turn.
+    /// Inbound requests above the limit are rejected.
+    #[clap(long = "max-queued-db-lookups", default_value = "1024", env)]
+    pub max_queued_db_lookups: usize,
+
+    /// Capacity of the channel delivering the results of database lookups
+    #[clap(long = "db-lookup-responses-capacity", default_value = "100", env)]
+    pub db_lookup_responses_capacity: usize,
+
+    /// Time in seconds to answer the already received requests during the shutdown
+    #[clap(long = "shutdown-grace-period", default_value = "2", env)]
+    pub shutdown_grace_period: u64,
+
+    /// How many times to retry starting the network if the previous attempt failed
+    #[clap(long = "max-start-retries", default_value = "5", env)]
+    pub max_start_retries: u32,
+
+    /// Time in seconds before the first retry to start the network, doubled after each failure
+    #[clap(long = "start-retry-backoff", default_value = "1", env)]
+    pub start_retry_backoff: u64,
+
+    /// The number of consecutive failures to pass the gossiped data to a consumer
+    /// after which the consumer is reported as gone. Zero disables the report
+    #[clap(long = "consumer-gone-threshold", default_value = "10", env)]
+    pub consumer_gone_threshold: u32,
+
+    /// The number of the connected peers required for the node to report
+    /// that it has enough peers. Zero makes the node ready from the start
+    #[clap(long = "min-peers-for-ready", default_value = "1", env)]
+    pub min_peers_for_ready: usize,
+
+    /// How long the request for the headers waits for a peer that can serve it,
+    /// if none is connected. The requests fail right away if not set
+    #[clap(long = "request-queue-timeout", env)]
+    pub request_queue_timeout: Option<humantime::Duration>,
+
+    /// Max number of the requests waiting for a peer.
+    /// The oldest request is rejected when a new one doesn't fit
+    #[clap(long = "request-queue-capacity", default_value = "100", env)]
+    pub request_queue_capacity: usize,
+
+    /// The remembered peers not seen for longer than this age
+    /// aren't dialed on the startup and are removed from the database
+    #[clap(long = "known-peers-max-age", default_value = "7days", env)]
+    pub known_peers_max_age: humantime::Duration,
+
+    /// Max number of the most recently seen remembered peers dialed on the startup.
+    /// Zero disables the dialing
+    #[clap(long = "max-known-peers-to-dial", default_value = "20", env)]
+    pub max_known_peers_to_dial: usize,
+
+    /// How often the connected peers are saved to the database
+    #[clap(long = "known-peers-save-interval", default_value = "5m", env)]
+    pub known_peers_save_interval: humantime::Duration,
+
+    /// Advertises to the peers that the node keeps and serves all blocks since the genesis
+    #[clap(long = "archival", env)]
+    pub archival: bool,
+
+    /// The same p2p warning, repeated for every message or request,
+    /// is logged at most once per this interval. Zero logs every warning
+    #[clap(long = "p2p-warn-logs-interval", default_value = "10s", env)]
+    pub warn_logs_interval: humantime::Duration,
+
+    /// The strategy used to pick the peer for an outbound request
+    #[clap(long = "peer-selection", value_enum, default_value = "random", env)]
+    pub peer_selection: PeerSelection,
+
+    /// The security protocol used to authenticate and encrypt the connections.
+    /// The peers using another protocol can't connect to the node
+    #[clap(long = "transport-security", value_enum, default_value = "noise", env)]
+    pub transport_security: Security,
+
+    /// How the ids of the gossip messages are computed. The messages with the same id
+    /// are delivered once, so `sha256-of-data` drops the repeated payloads
+    /// and `source-and-sequence` delivers every published message
+    #[clap(
+        long = "gossip-message-id",
+        value_enum,
+        default_value = "sha256-of-data",
+        env
+    )]
+    pub gossip_message_id: GossipMessageId,
+
+    /// The gossip topics the node subscribes to at the start
+    #[clap(
+        long = "subscribed-topics",
+        value_delimiter = ',',
+        default_value = "new_tx,new_tx_batch",
+        env
+    )]
+    pub subscribed_topics: Vec<GossipTopicTag>,
+
     /// Addresses of the bootstrap nodes
     /// They should contain PeerId within their `Multiaddr`
     #[clap(long = "bootstrap-nodes", value_delimiter = ',', env)]
@@ -80,10 +330,44 @@ pub struct P2PArgs {
     #[clap(long = "reserved-nodes-only-mode", env)]
     pub reserved_nodes_only_mode: bool,
 
+    /// The delay before the second redial of a disconnected reserved or bootstrap node,
+    /// doubled for each following redial
+    #[clap(long = "redial-backoff-base", default_value = "1s", env)]
+    pub redial_backoff_base: humantime::Duration,
+
+    /// The upper bound of the delay between redials of a disconnected node
+    #[clap(long = "redial-backoff-max", default_value = "60s", env)]
+    pub redial_backoff_max: humantime::Duration,
+
+    /// Log a warning once the reserved or bootstrap node fails this number of dials in a row
+    #[clap(long = "redial-warn-after-failures", default_value = "5", env)]
+    pub redial_warn_after_failures: u32,
+
+    /// If set, the node connects only to these peers, in both directions.
+    /// The reserved and bootstrap nodes should be in the list as well.
+    #[clap(long = "allowed-peers", value_delimiter = ',', env)]
+    pub allowed_peers: Option<Vec<PeerId>>,
+
+    /// IP ranges the node doesn't connect to, in both directions, e.g. `10.0.0.0/8`
+    #[clap(long = "denied-ips", value_delimiter = ',', env)]
+    pub denied_ips: Vec<IpNetwork>,
+
+    /// Disconnect the peers running the fuel-core version not matching the requirement, e.g. `>=0.22.0`
+    #[clap(long = "min-peer-version", env)]
+    pub min_peer_version: Option<VersionReq>,
+
+    /// Disconnect the peers with the unknown version as well, if `--min-peer-version` is set
+    #[clap(long = "strict-peer-version", env)]
+    pub strict_peer_version: bool,
+
     /// Allow nodes to be discoverable on the local network
     #[clap(long = "enable-mdns", env)]
     pub enable_mdns: bool,
 
+    /// Don't discover the peers beyond the bootstrap and reserved nodes via the Kademlia DHT
+    #[clap(long = "disable-kademlia", env)]
+    pub disable_kademlia: bool,
+
     /// Max number of unique peers connected
     /// This number should be at least number of `mesh_n` from `Gossipsub` configuration.
     /// The total number of connections will be `(max_peers_connected + reserved_nodes.len()) * max_connections_per_peer`
@@ -95,6 +379,14 @@ pub struct P2PArgs {
     #[clap(long = "max-connections-per-peer", default_value = "3", env)]
     pub max_connections_per_peer: u32,
 
+    /// Max number of established inbound connections, unlimited by default
+    #[clap(long = "max-inbound-connections", env)]
+    pub max_inbound_connections: Option<u32>,
+
+    /// Max number of established outbound connections, unlimited by default
+    #[clap(long = "max-outbound-connections", env)]
+    pub max_outbound_connections: Option<u32>,
+
     /// Set the delay between random walks for p2p node discovery in seconds.
     /// If it's not set the random walk will be disabled.
     /// Also if `reserved_nodes_only_mode` is set to `true`,
@@ -111,6 +403,11 @@ pub struct P2PArgs {
     #[clap(long = "connection-idle-timeout", default_value = "120", env)]
     pub connection_idle_timeout: u64,
 
+    /// Disconnect non-reserved peers that send no gossip, requests or responses
+    /// for this period. Disabled if not set
+    #[clap(long = "idle-connection-timeout", env)]
+    pub idle_connection_timeout: Option<humantime::Duration>,
+
     /// Choose how often to receive PeerInfo from other nodes
     #[clap(long = "info-interval", default_value = "3", env)]
     pub info_interval: u64,
@@ -132,6 +429,56 @@ pub struct P2PArgs {
     #[clap(long = "ideal-mesh-size", default_value = "6", env)]
     pub ideal_mesh_size: usize,
 
+    /// Peers with the gossipsub score below this threshold don't receive the gossip
+    #[clap(
+        long = "gossip-score-threshold",
+        default_value = "-4000",
+        allow_hyphen_values = true,
+        env
+    )]
+    pub gossip_score_threshold: f64,
+
+    /// Peers with the gossipsub score below this threshold don't receive the published messages
+    #[clap(
+        long = "publish-score-threshold",
+        default_value = "-8000",
+        allow_hyphen_values = true,
+        env
+    )]
+    pub publish_score_threshold: f64,
+
+    /// Messages of the peers with the gossipsub score below this threshold are ignored
+    #[clap(
+        long = "graylist-score-threshold",
+        default_value = "-16000",
+        allow_hyphen_values = true,
+        env
+    )]
+    pub graylist_score_threshold: f64,
+
+    /// The gossipsub score weight of each invalid message delivered by the peer
+    #[clap(
+        long = "invalid-message-weight",
+        default_value = "-200",
+        allow_hyphen_values = true,
+        env
+    )]
+    pub invalid_message_weight: f64,
+
+    /// The gossipsub score weight of the penalty for the peers pruned from the mesh
+    /// without delivering enough messages. Zero disables the penalty
+    #[clap(
+        long = "mesh-failure-penalty-weight",
+        default_value = "0",
+        allow_hyphen_values = true,
+        env
+    )]
+    pub mesh_failure_penalty_weight: f64,
+
+    /// The decay of the mesh failure penalty, between 0 and 1
+    #[clap(long = "mesh-failure-penalty-decay", default_value = "0", env)]
+    pub mesh_failure_penalty_decay: f64,
+
     /// Number of heartbeats to keep in the gossipsub `memcache`
     #[clap(long = "history-length", default_value = "5", env)]
     pub history_length: usize,
@@ -193,6 +540,70 @@ pub struct SyncArgs {
     pub header_batch_size: u32,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum PeerSelection {
+    Random,
+    RoundRobin,
+    LowestLatency,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Security {
+    Noise,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Overflow {
+    DropOldest,
+    DropNewest,
+    Block,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum GossipMessageId {
+    Sha256OfData,
+    SourceAndSequence,
+}
+
+impl From<GossipMessageId> for GossipMessageIdStrategy {
+    fn from(value: GossipMessageId) -> Self {
+        match value {
+            GossipMessageId::Sha256OfData => GossipMessageIdStrategy::Sha256OfData,
+            GossipMessageId::SourceAndSequence => {
+                GossipMessageIdStrategy::SourceAndSequence
+            }
+        }
+    }
+}
+
+impl From<PeerSelection> for PeerSelectionStrategy {
+    fn from(value: PeerSelection) -> Self {
+        match value {
+            PeerSelection::Random => PeerSelectionStrategy::Random,
+            PeerSelection::RoundRobin => PeerSelectionStrategy::RoundRobin,
+            PeerSelection::LowestLatency => PeerSelectionStrategy::LowestLatency,
+        }
+    }
+}
+
+impl From<Security> for TransportSecurity {
+    fn from(value: Security) -> Self {
+        match value {
+            Security::Noise => TransportSecurity::Noise,
+        }
+    }
+}
+
+impl From<Overflow> for OverflowPolicy {
+    fn from(value: Overflow) -> Self {
+        match value {
+            Overflow::DropOldest => OverflowPolicy::DropOldest,
+            Overflow::DropNewest => OverflowPolicy::DropNewest,
+            Overflow::Block => OverflowPolicy::Block,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum KeypairArg {
     Path(PathBuf),
@@ -239,8 +650,8 @@ impl P2PArgs {
         }
 
         let local_keypair = {
-            match self.keypair.expect("mandatory value") {
-                KeypairArg::Path(path) => {
+            match (self.keypair, self.keypair_path) {
+                (Some(KeypairArg::Path(path)), _) => {
                     let phrase = std::fs::read_to_string(path)?;
                     let secret_key =
                         fuel_crypto::SecretKey::new_from_mnemonic_phrase_with_path(
@@ -250,16 +661,19 @@ impl P2PArgs {
 
                     convert_to_libp2p_keypair(&mut secret_key.to_vec())?
                 }
-                KeypairArg::InlineSecret(secret_key) => {
+                (Some(KeypairArg::InlineSecret(secret_key)), _) => {
                     convert_to_libp2p_keypair(&mut secret_key.to_vec())?
                 }
+                (None, Some(path)) => load_or_generate_keypair(&path)?,
+                (None, None) => {
+                    return Err(anyhow!(
+                        "P2P requires either `--keypair` or `--keypair-path`"
+                    ))
+                }
             }
         };
 
         let gossipsub_config = default_gossipsub_builder()
-            .mesh_n(self.ideal_mesh_size)
-            .mesh_n_low(self.min_mesh_size)
-            .mesh_n_high(self.max_mesh_size)
             .history_length(self.history_length)
             .history_gossip(self.history_gossip)
             .heartbeat_interval(self.gossip_heartbeat_interval.into())
@@ -267,6 +681,16 @@ impl P2PArgs {
             .build()
             .expect("valid gossipsub configuration");
 
+        let gossipsub_scoring_config = GossipsubScoringConfig {
+            gossip_threshold: self.gossip_score_threshold,
+            publish_threshold: self.publish_score_threshold,
+            graylist_threshold: self.graylist_score_threshold,
+            invalid_message_deliveries_weight: self.invalid_message_weight,
+            mesh_failure_penalty_weight: self.mesh_failure_penalty_weight,
+            mesh_failure_penalty_decay: self.mesh_failure_penalty_decay,
+            ..Default::default()
+        };
+
         let random_walk = if self.random_walk == 0 {
             None
         } else {
@@ -286,26 +710,85 @@ impl P2PArgs {
         let config = Config {
             keypair: local_keypair,
             network_name,
+            subscribed_topics: self.subscribed_topics.into_iter().collect(),
             checksum: Default::default(),
             address: self
                 .address
                 .unwrap_or_else(|| IpAddr::V4(Ipv4Addr::from([0, 0, 0, 0]))),
             public_address: self.public_address,
             tcp_port: self.peering_port,
+            listen_addresses: self.listen_addresses,
             max_block_size: self.max_block_size,
+            compression_threshold: self.compression_threshold,
+            tx_batch_interval: self.tx_batch_interval.map(Into::into),
+            tx_batch_max_size: self.tx_batch_max_size,
+            startup_gossip_buffer_size: self.startup_gossip_buffer_size,
+            gossip_duplicates_cache_size: self.gossip_duplicates_cache_size,
+            gossip_duplicates_ttl: self.gossip_duplicates_ttl.into(),
+            tx_broadcast_capacity: self.tx_broadcast_capacity,
+            tx_overflow_policy: self.tx_overflow_policy.into(),
+            block_height_broadcast_capacity: self.block_height_broadcast_capacity,
+            block_height_overflow_policy: self.block_height_overflow_policy.into(),
+            request_channel_capacity: self.request_channel_capacity,
+            request_overflow_policy: self.request_overflow_policy.into(),
+            network_event_broadcast_capacity: self.network_event_broadcast_capacity,
+            max_tx_gossip_size: self.max_tx_gossip_size,
+            max_tx_batch_gossip_size: self.max_tx_batch_gossip_size,
             max_headers_per_request: self.max_headers_per_request,
+            block_chunk_size: self.block_chunk_size,
+            max_block_chunks_in_flight: self.max_block_chunks_in_flight,
+            block_chunks_timeout: self.block_chunks_timeout.into(),
+            max_chunked_block_size: self.max_chunked_block_size,
+            max_requests_per_second: self.max_requests_per_second,
+            max_requests_burst: self.max_requests_burst,
+            drop_rate_limited_requests: self.drop_rate_limited_requests,
+            max_response_bytes_per_peer_per_minute: self
+                .max_response_bytes_per_peer_per_minute,
+            redundant_request_factor: self.redundant_request_factor,
+            max_outbound_requests: self.max_outbound_requests,
+            max_concurrent_db_lookups: self.max_concurrent_db_lookups,
+            max_queued_db_lookups: self.max_queued_db_lookups,
+            db_lookup_responses_capacity: self.db_lookup_responses_capacity,
+            shutdown_grace_period: Duration::from_secs(self.shutdown_grace_period),
+            max_start_retries: self.max_start_retries,
+            start_retry_backoff: Duration::from_secs(self.start_retry_backoff),
+            consumer_gone_threshold: self.consumer_gone_threshold,
+            min_peers_for_ready: self.min_peers_for_ready,
+            request_queue_timeout: self.request_queue_timeout.map(Into::into),
+            request_queue_capacity: self.request_queue_capacity,
+            known_peers_max_age: self.known_peers_max_age.into(),
+            max_known_peers_to_dial: self.max_known_peers_to_dial,
+            known_peers_save_interval: self.known_peers_save_interval.into(),
+            warn_logs_interval: self.warn_logs_interval.into(),
+            is_archival: self.archival,
             bootstrap_nodes: self.bootstrap_nodes,
             reserved_nodes: self.reserved_nodes,
             reserved_nodes_only_mode: self.reserved_nodes_only_mode,
+            redial_backoff_base: self.redial_backoff_base.into(),
+            redial_backoff_max: self.redial_backoff_max.into(),
+            redial_warn_after_failures: self.redial_warn_after_failures,
+            allowed_peers: self.allowed_peers,
+            denied_ips: self.denied_ips,
+            min_peer_version: self.min_peer_version,
+            strict_peer_version: self.strict_peer_version,
             enable_mdns: self.enable_mdns,
+            enable_kademlia: !self.disable_kademlia,
             max_peers_connected: self.max_peers_connected,
             max_connections_per_peer: self.max_connections_per_peer,
+            max_inbound_connections: self.max_inbound_connections,
+            max_outbound_connections: self.max_outbound_connections,
+            idle_connection_timeout: self.idle_connection_timeout.map(Into::into),
             allow_private_addresses: self.allow_private_addresses,
             random_walk,
             connection_idle_timeout: Some(Duration::from_secs(
                 self.connection_idle_timeout,
             )),
             gossipsub_config,
+            gossip_message_id_strategy: self.gossip_message_id.into(),
+            ideal_mesh_size: self.ideal_mesh_size,
+            min_mesh_size: self.min_mesh_size,
+            max_mesh_size: self.max_mesh_size,
+            gossipsub_scoring_config,
             heartbeat_config,
             set_request_timeout: Duration::from_secs(self.request_timeout),
             set_connection_keep_alive: Duration::from_secs(self.connection_keep_alive),
@@ -316,6 +799,9 @@ impl P2PArgs {
             heartbeat_max_time_since_last: Duration::from_secs(
                 self.heartbeat_max_time_since_last,
             ),
+            peer_reputation_config: Default::default(),
+            transport_security: self.transport_security.into(),
+            peer_selection_strategy: self.peer_selection.into(),
             info_interval: Some(Duration::from_secs(self.info_interval)),
             identify_interval: Some(Duration::from_secs(self.identify_interval)),
             metrics,
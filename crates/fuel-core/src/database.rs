@@ -79,8 +79,10 @@ pub mod block;
 pub mod coin;
 pub mod contracts;
 pub mod database_description;
+pub mod known_peers;
 pub mod message;
 pub mod metadata;
+pub mod peer_bans;
 pub mod sealed_block;
 pub mod state;
 pub mod statistic;
@@ -157,6 +159,9 @@ where
         })
     }
 
+    /// Creates the database that keeps the data only in the memory.
+    /// Each call creates a new empty store that is not shared with other databases,
+    /// so it is deterministic and isolated, which makes it suitable for tests.
     pub fn in_memory() -> Self {
         Self {
             data: StructuredStorage::new(Arc::new(MemoryStore::default()).into()),
@@ -534,4 +539,40 @@ mod tests {
     fn column_keys_not_exceed_count_test_relayer() {
         column_keys_not_exceed_count::<Relayer>();
     }
+
+    #[test]
+    fn in_memory_databases_do_not_share_the_data() {
+        use crate::database::Database;
+        use fuel_core_storage::{
+            tables::ContractsState,
+            ContractsStateKey,
+            StorageAsMut,
+            StorageAsRef,
+        };
+        use fuel_core_types::fuel_types::{
+            Bytes32,
+            ContractId,
+        };
+
+        // given
+        let first_database = &mut Database::<OnChain>::in_memory();
+        let second_database = &mut Database::<OnChain>::in_memory();
+        let key = ContractsStateKey::new(&ContractId::zeroed(), &Bytes32::zeroed());
+
+        // when
+        first_database
+            .storage_as_mut::<ContractsState>()
+            .insert(&key, &Bytes32::new([1; 32]))
+            .unwrap();
+
+        // then
+        assert!(first_database
+            .storage_as_ref::<ContractsState>()
+            .contains_key(&key)
+            .unwrap());
+        assert!(!second_database
+            .storage_as_ref::<ContractsState>()
+            .contains_key(&key)
+            .unwrap());
+    }
 }
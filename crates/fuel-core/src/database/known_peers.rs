@@ -0,0 +1,108 @@
+use crate::{
+    database::{
+        database_description::on_chain::OnChain,
+        storage::UseStructuredImplementation,
+        Database,
+    },
+    state::DataSource,
+};
+use fuel_core_storage::{
+    blueprint::plain::Plain,
+    codec::postcard::Postcard,
+    column::Column,
+    iter::IterDirection,
+    structured_storage::{
+        StructuredStorage,
+        TableWithBlueprint,
+    },
+    Mappable,
+    Result as StorageResult,
+    StorageMutate,
+};
+use fuel_core_types::services::p2p::{
+    KnownPeer,
+    PeerId,
+};
+
+/// The table that stores the peers the node was connected to,
+/// so they can be dialed after the restart of the node.
+pub struct KnownPeers;
+
+impl Mappable for KnownPeers {
+    type Key = PeerId;
+    type OwnedKey = Self::Key;
+    type Value = KnownPeer;
+    type OwnedValue = Self::Value;
+}
+
+impl TableWithBlueprint for KnownPeers {
+    type Blueprint = Plain<Postcard, Postcard>;
+    type Column = Column;
+
+    fn column() -> Self::Column {
+        Column::KnownPeers
+    }
+}
+
+impl UseStructuredImplementation<KnownPeers> for StructuredStorage<DataSource<OnChain>> {}
+
+impl Database {
+    pub fn insert_known_peer(
+        &mut self,
+        peer_id: &PeerId,
+        peer: &KnownPeer,
+    ) -> StorageResult<()> {
+        <_ as StorageMutate<KnownPeers>>::insert(&mut self.data, peer_id, peer)?;
+        Ok(())
+    }
+
+    pub fn remove_known_peer(&mut self, peer_id: &PeerId) -> StorageResult<()> {
+        <_ as StorageMutate<KnownPeers>>::remove(&mut self.data, peer_id)?;
+        Ok(())
+    }
+
+    pub fn load_known_peers(&self) -> StorageResult<Vec<(PeerId, KnownPeer)>> {
+        self.iter_all::<KnownPeers>(Some(IterDirection::Forward))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::in_memory::memory_store::MemoryStore;
+    use std::{
+        sync::Arc,
+        time::SystemTime,
+    };
+
+    #[test]
+    fn known_peers_are_loaded_after_restart() {
+        let store = Arc::new(MemoryStore::<OnChain>::default());
+        let peer_id = PeerId::from(vec![1, 2, 3]);
+        let forgotten_peer_id = PeerId::from(vec![4, 5, 6]);
+        let peer = KnownPeer {
+            addresses: vec![vec![4, 127, 0, 0, 1]],
+            last_seen: SystemTime::now(),
+            score: 50.,
+        };
+
+        // Before restart
+        let mut database = Database::<OnChain>::new(store.clone());
+        database
+            .insert_known_peer(&peer_id, &peer)
+            .expect("Should insert a peer");
+        database
+            .insert_known_peer(&forgotten_peer_id, &peer)
+            .expect("Should insert a peer");
+        database
+            .remove_known_peer(&forgotten_peer_id)
+            .expect("Should remove a peer");
+        drop(database);
+
+        // After restart
+        let database = Database::<OnChain>::new(store);
+        let peers = database.load_known_peers().expect("Should load peers");
+        assert_eq!(peers, vec![(peer_id, peer)]);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{
+    database::{
+        database_description::on_chain::OnChain,
+        storage::UseStructuredImplementation,
+        Database,
+    },
+    state::DataSource,
+};
+use fuel_core_storage::{
+    blueprint::plain::Plain,
+    codec::postcard::Postcard,
+    column::Column,
+    iter::IterDirection,
+    structured_storage::{
+        StructuredStorage,
+        TableWithBlueprint,
+    },
+    Mappable,
+    Result as StorageResult,
+    StorageMutate,
+};
+use fuel_core_types::services::p2p::{
+    PeerBan,
+    PeerId,
+};
+
+/// The table that stores the bans of misbehaving peers,
+/// so they survive the restart of the node.
+pub struct PeerBans;
+
+impl Mappable for PeerBans {
+    type Key = PeerId;
+    type OwnedKey = Self::Key;
+    type Value = PeerBan;
+    type OwnedValue = Self::Value;
+}
+
+impl TableWithBlueprint for PeerBans {
+    type Blueprint = Plain<Postcard, Postcard>;
+    type Column = Column;
+
+    fn column() -> Self::Column {
+        Column::PeerBans
+    }
+}
+
+impl UseStructuredImplementation<PeerBans> for StructuredStorage<DataSource<OnChain>> {}
+
+impl Database {
+    pub fn insert_peer_ban(
+        &mut self,
+        peer_id: &PeerId,
+        ban: &PeerBan,
+    ) -> StorageResult<()> {
+        <_ as StorageMutate<PeerBans>>::insert(&mut self.data, peer_id, ban)?;
+        Ok(())
+    }
+
+    pub fn remove_peer_ban(&mut self, peer_id: &PeerId) -> StorageResult<()> {
+        <_ as StorageMutate<PeerBans>>::remove(&mut self.data, peer_id)?;
+        Ok(())
+    }
+
+    pub fn load_peer_bans(&self) -> StorageResult<Vec<(PeerId, PeerBan)>> {
+        self.iter_all::<PeerBans>(Some(IterDirection::Forward))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::in_memory::memory_store::MemoryStore;
+    use std::{
+        sync::Arc,
+        time::{
+            Duration,
+            SystemTime,
+        },
+    };
+
+    #[test]
+    fn peer_bans_are_loaded_after_restart() {
+        let store = Arc::new(MemoryStore::<OnChain>::default());
+        let peer_id = PeerId::from(vec![1, 2, 3]);
+        let ban = PeerBan {
+            reason: "Low gossip reputation".to_string(),
+            expiry: SystemTime::now()
+                .checked_add(Duration::from_secs(60))
+                .unwrap(),
+        };
+
+        // Before restart
+        let mut database = Database::<OnChain>::new(store.clone());
+        database
+            .insert_peer_ban(&peer_id, &ban)
+            .expect("Should insert a ban");
+        drop(database);
+
+        // After restart
+        let database = Database::<OnChain>::new(store);
+        let bans = database.load_peer_bans().expect("Should load bans");
+        assert_eq!(bans, vec![(peer_id, ban)]);
+    }
+
+    #[test]
+    fn removed_peer_ban_is_not_loaded() {
+        let mut database = Database::<OnChain>::default();
+        let peer_id = PeerId::from(vec![1, 2, 3]);
+        let ban = PeerBan {
+            reason: "Low gossip reputation".to_string(),
+            expiry: SystemTime::now(),
+        };
+        database
+            .insert_peer_ban(&peer_id, &ban)
+            .expect("Should insert a ban");
+
+        database
+            .remove_peer_ban(&peer_id)
+            .expect("Should remove a ban");
+
+        let bans = database.load_peer_bans().expect("Should load bans");
+        assert!(bans.is_empty());
+    }
+}
@@ -1,15 +1,48 @@
 use crate::database::Database;
 use fuel_core_storage::{
     tables::ContractsState,
+    transactional::Transaction,
     ContractsStateKey,
     Error as StorageError,
+    MerkleRootStorage,
+    Result as StorageResult,
+    StorageAsMut,
     StorageBatchMutate,
 };
-use fuel_core_types::fuel_types::{
-    Bytes32,
-    ContractId,
+use fuel_core_types::{
+    fuel_merkle::sparse::{
+        in_memory,
+        MerkleTreeKey,
+    },
+    fuel_types::{
+        Bytes32,
+        ContractId,
+    },
 };
-use itertools::Itertools;
+use itertools::{
+    EitherOrBoth,
+    Itertools,
+};
+
+/// The difference between the states of the same contract in two databases.
+/// Each list is ordered by the slot key.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct StateDiff {
+    /// The slots present only in the other database, with their values.
+    pub added: Vec<(Bytes32, Bytes32)>,
+    /// The slots present only in this database, with their values.
+    pub removed: Vec<(Bytes32, Bytes32)>,
+    /// The slots present in both databases with different values,
+    /// as `(key, this value, other value)`.
+    pub changed: Vec<(Bytes32, Bytes32, Bytes32)>,
+}
+
+impl StateDiff {
+    /// Returns `true` if the states of the contract are the same.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
 
 impl Database {
     /// Initialize the state of the contract from all leaves.
@@ -31,13 +64,115 @@ impl Database {
             &mut slots.iter().map(|(key, value)| (key, value)),
         )
     }
+
+    /// Returns `true` if the contract has at least one slot in its state.
+    pub fn contract_has_state(
+        &self,
+        contract_id: &ContractId,
+    ) -> Result<bool, StorageError> {
+        let first_slot = self
+            .iter_all_by_prefix::<ContractsState, _>(Some(contract_id.as_ref()))
+            .next()
+            .transpose()?;
+
+        Ok(first_slot.is_some())
+    }
+
+    /// Removes all slots of the contract's state in one transaction.
+    /// Returns the number of the removed slots.
+    pub fn clear_contract_state(
+        &mut self,
+        contract_id: &ContractId,
+    ) -> Result<u64, StorageError> {
+        let keys = self
+            .iter_all_by_prefix::<ContractsState, _>(Some(contract_id.as_ref()))
+            .map(|result| result.map(|(key, _)| key))
+            .collect::<StorageResult<Vec<_>>>()?;
+
+        let mut transaction = self.transaction();
+        let mut removed = 0u64;
+        for key in keys.iter() {
+            transaction.storage::<ContractsState>().remove(key)?;
+            removed = removed.saturating_add(1);
+        }
+        transaction.commit()?;
+
+        Ok(removed)
+    }
+
+    /// Recomputes the state root of the contract from its stored slots
+    /// and compares it with the stored root.
+    /// It is an expensive diagnostic for debugging the divergence of the state.
+    pub fn verify_contract_state_root(
+        &self,
+        contract_id: &ContractId,
+    ) -> Result<bool, StorageError> {
+        let slots = self
+            .iter_all_by_prefix::<ContractsState, _>(Some(contract_id.as_ref()))
+            .collect::<StorageResult<Vec<_>>>()?;
+        let (computed_root, _) = in_memory::MerkleTree::nodes_from_set(
+            slots
+                .iter()
+                .map(|(key, value)| (MerkleTreeKey::new(key), value)),
+        );
+        let stored_root =
+            MerkleRootStorage::<ContractId, ContractsState>::root(self, contract_id)?;
+
+        Ok(computed_root == stored_root)
+    }
+
+    /// Compares the state of the contract with its state in the `other` database.
+    /// Both states are iterated in lockstep, so neither is loaded into memory.
+    pub fn diff_contract_state(
+        &self,
+        other: &Database,
+        contract_id: &ContractId,
+    ) -> Result<StateDiff, StorageError> {
+        let this_slots =
+            self.iter_all_by_prefix::<ContractsState, _>(Some(contract_id.as_ref()));
+        let other_slots =
+            other.iter_all_by_prefix::<ContractsState, _>(Some(contract_id.as_ref()));
+
+        let mut diff = StateDiff::default();
+        let joined = this_slots.merge_join_by(other_slots, |this, other| {
+            match (this, other) {
+                (Ok((this_key, _)), Ok((other_key, _))) => {
+                    this_key.as_ref().cmp(other_key.as_ref())
+                }
+                // Yield the error as soon as possible
+                (Err(_), _) => core::cmp::Ordering::Less,
+                (_, Err(_)) => core::cmp::Ordering::Greater,
+            }
+        });
+        for item in joined {
+            match item {
+                EitherOrBoth::Both(this, other) => {
+                    let (key, this_value) = this?;
+                    let (_, other_value) = other?;
+                    if this_value != other_value {
+                        diff.changed
+                            .push((*key.state_key(), this_value, other_value));
+                    }
+                }
+                EitherOrBoth::Left(this) => {
+                    let (key, value) = this?;
+                    diff.removed.push((*key.state_key(), value));
+                }
+                EitherOrBoth::Right(other) => {
+                    let (key, value) = other?;
+                    diff.added.push((*key.state_key(), value));
+                }
+            }
+        }
+
+        Ok(diff)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::database::database_description::on_chain::OnChain;
-    use fuel_core_storage::StorageAsMut;
     use fuel_core_types::fuel_types::Bytes32;
     use rand::Rng;
 
@@ -50,6 +185,177 @@ mod tests {
         bytes.into()
     }
 
+    #[test]
+    fn verify_contract_state_root_detects_tampered_slot() {
+        use fuel_core_storage::{
+            column::Column,
+            kv_store::KeyValueStore,
+        };
+        use rand::{
+            rngs::StdRng,
+            SeedableRng,
+        };
+
+        // given
+        let rng = &mut StdRng::seed_from_u64(1234);
+        let contract_id = ContractId::from([1u8; 32]);
+        let database = &mut Database::<OnChain>::default();
+        let slots = (0..10)
+            .map(|_| (random_bytes32(rng), random_bytes32(rng)))
+            .collect::<Vec<_>>();
+        database
+            .init_contract_state(&contract_id, slots.clone().into_iter())
+            .unwrap();
+        assert!(database.verify_contract_state_root(&contract_id).unwrap());
+
+        // when
+        // The slot is changed without updating the merkle tree
+        let tampered_key = ContractsStateKey::new(&contract_id, &slots[0].0);
+        database
+            .data
+            .as_ref()
+            .write(
+                tampered_key.as_ref(),
+                Column::ContractsState,
+                random_bytes32(rng).as_ref(),
+            )
+            .unwrap();
+
+        // then
+        assert!(!database.verify_contract_state_root(&contract_id).unwrap());
+    }
+
+    #[test]
+    fn contract_has_state_respects_the_prefix_of_the_contract() {
+        // given
+        let populated_contract_id = ContractId::from([1u8; 32]);
+        // The contracts with the numerically adjacent prefixes
+        let mut previous_contract_id = [1u8; 32];
+        previous_contract_id[31] = 0;
+        let previous_contract_id = ContractId::from(previous_contract_id);
+        let mut next_contract_id = [1u8; 32];
+        next_contract_id[31] = 2;
+        let next_contract_id = ContractId::from(next_contract_id);
+
+        let database = &mut Database::<OnChain>::default();
+        database
+            .storage::<ContractsState>()
+            .insert(
+                &ContractsStateKey::new(&populated_contract_id, &Bytes32::zeroed()),
+                &Bytes32::new([1; 32]),
+            )
+            .unwrap();
+
+        // then
+        assert!(database.contract_has_state(&populated_contract_id).unwrap());
+        assert!(!database.contract_has_state(&previous_contract_id).unwrap());
+        assert!(!database.contract_has_state(&next_contract_id).unwrap());
+    }
+
+    #[test]
+    fn clear_contract_state_removes_only_the_state_of_the_contract() {
+        use rand::{
+            rngs::StdRng,
+            SeedableRng,
+        };
+
+        // given
+        let rng = &mut StdRng::seed_from_u64(1234);
+        let cleared_contract_id = ContractId::from([1u8; 32]);
+        // The contract with the adjacent prefix
+        let mut other_contract_id = [1u8; 32];
+        other_contract_id[31] = 2;
+        let other_contract_id = ContractId::from(other_contract_id);
+
+        let database = &mut Database::<OnChain>::default();
+        let cleared_slots = (0..10)
+            .map(|_| (random_bytes32(rng), random_bytes32(rng)))
+            .collect::<Vec<_>>();
+        let other_slots = (0..10)
+            .map(|_| (random_bytes32(rng), random_bytes32(rng)))
+            .collect::<Vec<_>>();
+        database
+            .init_contract_state(&cleared_contract_id, cleared_slots.clone().into_iter())
+            .unwrap();
+        database
+            .init_contract_state(&other_contract_id, other_slots.clone().into_iter())
+            .unwrap();
+        let other_root = database
+            .storage::<ContractsState>()
+            .root(&other_contract_id)
+            .unwrap();
+
+        // when
+        let removed = database.clear_contract_state(&cleared_contract_id).unwrap();
+
+        // then
+        assert_eq!(removed, 10);
+        for (key, _) in cleared_slots.iter() {
+            let value = database
+                .storage::<ContractsState>()
+                .get(&ContractsStateKey::new(&cleared_contract_id, key))
+                .unwrap();
+            assert!(value.is_none());
+        }
+        for (key, value) in other_slots.iter() {
+            let stored_value = database
+                .storage::<ContractsState>()
+                .get(&ContractsStateKey::new(&other_contract_id, key))
+                .unwrap()
+                .unwrap()
+                .into_owned();
+            assert_eq!(&stored_value, value);
+        }
+        assert_eq!(
+            database
+                .storage::<ContractsState>()
+                .root(&other_contract_id)
+                .unwrap(),
+            other_root
+        );
+        assert!(database
+            .verify_contract_state_root(&cleared_contract_id)
+            .unwrap());
+    }
+
+    #[test]
+    fn diff_contract_state_reports_changed_and_added_slots() {
+        // given
+        let contract_id = ContractId::from([1u8; 32]);
+        let slots = vec![
+            (Bytes32::new([1; 32]), Bytes32::new([10; 32])),
+            (Bytes32::new([2; 32]), Bytes32::new([20; 32])),
+        ];
+        let database = &mut Database::<OnChain>::default();
+        database
+            .init_contract_state(&contract_id, slots.clone().into_iter())
+            .unwrap();
+        let changed_value = Bytes32::new([21; 32]);
+        let added_slot = (Bytes32::new([3; 32]), Bytes32::new([30; 32]));
+        let other_database = &mut Database::<OnChain>::default();
+        other_database
+            .init_contract_state(
+                &contract_id,
+                vec![slots[0], (slots[1].0, changed_value), added_slot].into_iter(),
+            )
+            .unwrap();
+
+        // when
+        let diff = database
+            .diff_contract_state(other_database, &contract_id)
+            .unwrap();
+
+        // then
+        assert_eq!(
+            diff,
+            StateDiff {
+                added: vec![added_slot],
+                removed: vec![],
+                changed: vec![(slots[1].0, slots[1].1, changed_value)],
+            }
+        );
+    }
+
     #[test]
     fn init_contract_state_works() {
         use rand::{
@@ -132,7 +132,8 @@ impl Bootstrap {
         let codec = PostcardCodec::new(bootstrap_config.max_block_size);
         let (sender, _) =
             broadcast::channel(bootstrap_config.reserved_nodes.len().saturating_add(1));
-        let mut bootstrap = FuelP2PService::new(sender, bootstrap_config, codec);
+        let mut bootstrap = FuelP2PService::new(sender, bootstrap_config, codec)
+            .expect("Bootstrap node should be created");
         bootstrap.start().await.unwrap();
 
         let listeners = bootstrap.multiaddrs();
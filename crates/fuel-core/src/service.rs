@@ -273,6 +273,12 @@ impl RunnableService for Task {
         for service in &self.services {
             service.start_and_await().await?;
         }
+
+        // The consumers of the gossip are started, so the gossip held during the startup can be delivered
+        #[cfg(feature = "p2p")]
+        if let Some(network) = &self.shared.network {
+            network.notify_consumers_ready()?;
+        }
         Ok(self)
     }
 }
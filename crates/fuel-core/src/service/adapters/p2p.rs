@@ -3,16 +3,27 @@ use crate::database::Database;
 use fuel_core_p2p::ports::{
     BlockHeightImporter,
     P2pDb,
+    P2pPeerStore,
 };
 use fuel_core_services::stream::BoxStream;
-use fuel_core_storage::Result as StorageResult;
+use fuel_core_storage::{
+    tables::FuelBlocks,
+    Result as StorageResult,
+    StorageAsRef,
+};
 use fuel_core_types::{
     blockchain::{
         consensus::Genesis,
+        primitives::BlockId,
         SealedBlockHeader,
     },
     fuel_types::BlockHeight,
-    services::p2p::Transactions,
+    services::p2p::{
+        KnownPeer,
+        PeerBan,
+        PeerId,
+        Transactions,
+    },
 };
 use std::ops::Range;
 
@@ -34,6 +45,47 @@ impl P2pDb for Database {
     fn get_genesis(&self) -> StorageResult<Genesis> {
         self.get_genesis()
     }
+
+    fn get_current_tip(&self) -> StorageResult<Option<(BlockHeight, BlockId)>> {
+        Ok(self.get_current_block()?.map(|block| {
+            let header = block.header();
+            (*header.height(), header.id())
+        }))
+    }
+
+    fn has_block(&self, height: &BlockHeight) -> StorageResult<bool> {
+        self.storage::<FuelBlocks>().contains_key(height)
+    }
+}
+
+impl P2pPeerStore for Database {
+    fn insert_peer_ban(&mut self, peer_id: &PeerId, ban: &PeerBan) -> StorageResult<()> {
+        Database::insert_peer_ban(self, peer_id, ban)
+    }
+
+    fn remove_peer_ban(&mut self, peer_id: &PeerId) -> StorageResult<()> {
+        Database::remove_peer_ban(self, peer_id)
+    }
+
+    fn load_peer_bans(&self) -> StorageResult<Vec<(PeerId, PeerBan)>> {
+        Database::load_peer_bans(self)
+    }
+
+    fn insert_known_peer(
+        &mut self,
+        peer_id: &PeerId,
+        peer: &KnownPeer,
+    ) -> StorageResult<()> {
+        Database::insert_known_peer(self, peer_id, peer)
+    }
+
+    fn remove_known_peer(&mut self, peer_id: &PeerId) -> StorageResult<()> {
+        Database::remove_known_peer(self, peer_id)
+    }
+
+    fn load_known_peers(&self) -> StorageResult<Vec<(PeerId, KnownPeer)>> {
+        Database::load_known_peers(self)
+    }
 }
 
 impl BlockHeightImporter for BlockImporterAdapter {
@@ -3,11 +3,13 @@ use super::{
     ConsensusAdapter,
     P2PAdapter,
 };
+use fuel_core_p2p::error::P2pError;
 use fuel_core_poa::ports::RelayerPort;
 use fuel_core_services::stream::BoxStream;
 use fuel_core_sync::ports::{
     BlockImporterPort,
     ConsensusPort,
+    DataNotFound,
     PeerReportReason,
     PeerToPeerPort,
 };
@@ -51,7 +53,10 @@ impl PeerToPeerPort for P2PAdapter {
         block_height_range: Range<u32>,
     ) -> anyhow::Result<SourcePeer<Option<Vec<SealedBlockHeader>>>> {
         let result = if let Some(service) = &self.service {
-            service.get_sealed_block_headers(block_height_range).await
+            service
+                .get_sealed_block_headers(block_height_range)
+                .await
+                .map_err(into_sync_error)
         } else {
             Err(anyhow::anyhow!("No P2P service available"))
         };
@@ -74,9 +79,11 @@ impl PeerToPeerPort for P2PAdapter {
             data: range,
         } = range;
         if let Some(service) = &self.service {
-            service
+            let transactions = service
                 .get_transactions_from_peer(peer_id.into(), range)
                 .await
+                .map_err(into_sync_error)?;
+            Ok(transactions)
         } else {
             Err(anyhow::anyhow!("No P2P service available"))
         }
@@ -94,6 +101,17 @@ impl PeerToPeerPort for P2PAdapter {
     }
 }
 
+/// Keeps the missing data typed, so the sync can request it from another peer
+fn into_sync_error(error: P2pError) -> anyhow::Error {
+    match error {
+        P2pError::NotFound(peer_id) => DataNotFound {
+            peer_id: peer_id.to_bytes().into(),
+        }
+        .into(),
+        error => error.into(),
+    }
+}
+
 impl P2PAdapter {
     fn process_report(&self, reason: PeerReportReason) -> P2PAdapterPeerReport {
         let score = match &reason {
@@ -53,22 +53,16 @@ impl fuel_core_txpool::ports::PeerToPeer for P2PAdapter {
 
     fn broadcast_transaction(&self, transaction: Arc<Transaction>) -> anyhow::Result<()> {
         if let Some(service) = &self.service {
-            service.broadcast_transaction(transaction)
+            service.broadcast_transaction(transaction)?;
+            Ok(())
         } else {
             Ok(())
         }
     }
 
     fn gossiped_transaction_events(&self) -> BoxStream<Self::GossipedTransaction> {
-        use tokio_stream::{
-            wrappers::BroadcastStream,
-            StreamExt,
-        };
         if let Some(service) = &self.service {
-            Box::pin(
-                BroadcastStream::new(service.subscribe_tx())
-                    .filter_map(|result| result.ok()),
-            )
+            service.subscribe_tx().into_stream()
         } else {
             fuel_core_services::stream::IntoBoxStream::into_boxed(tokio_stream::pending())
         }
@@ -80,7 +74,8 @@ impl fuel_core_txpool::ports::PeerToPeer for P2PAdapter {
         validity: GossipsubMessageAcceptance,
     ) -> anyhow::Result<()> {
         if let Some(service) = &self.service {
-            service.notify_gossip_transaction_validity(message_info, validity)
+            service.notify_gossip_transaction_validity(message_info, validity)?;
+            Ok(())
         } else {
             Ok(())
         }
@@ -34,7 +34,7 @@ use fuel_core_types::blockchain::primitives::DaBlockHeight;
 pub type PoAService =
     fuel_core_poa::Service<TxPoolAdapter, BlockProducerAdapter, BlockImporterAdapter>;
 #[cfg(feature = "p2p")]
-pub type P2PService = fuel_core_p2p::service::Service<Database>;
+pub type P2PService = fuel_core_p2p::service::Service<Database, Database>;
 pub type TxPoolService = fuel_core_txpool::Service<P2PAdapter, Database>;
 pub type BlockProducerService = fuel_core_producer::block_producer::Producer<
     Database,
@@ -106,6 +106,8 @@ pub fn init_sub_services(
             config.chain_conf.consensus_parameters.chain_id,
             p2p_config,
             database.on_chain().clone(),
+            // The dedicated handle for the peers' state the service writes
+            database.on_chain().clone(),
             importer_adapter.clone(),
         )
     });
@@ -1,15 +1,54 @@
 use once_cell::race::OnceBox;
 use prometheus_client::{
-    metrics::counter::Counter,
+    encoding::EncodeLabelSet,
+    metrics::{
+        counter::Counter,
+        family::Family,
+        gauge::Gauge,
+    },
     registry::Registry,
 };
 use std::sync::OnceLock;
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct TopicLabel {
+    // the gossip topic tag
+    topic: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct ChannelLabel {
+    // the channel passing the gossiped data to the consumers
+    channel: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct BandwidthLabel {
+    // the protocol that carried the bytes
+    protocol: String,
+    // `inbound` for the received bytes, `outbound` for the sent ones
+    direction: String,
+}
+
 pub struct P2PMetrics {
     pub gossip_sub_registry: OnceBox<Registry>,
     // For descriptions of each Counter, see the `new` function where each Counter/Histogram is initialized
     pub peer_metrics: Registry,
     pub unique_peers: Counter,
+    pub gossip_decode_errors: Counter,
+    pub gossip_report_errors: Counter,
+    pub gossip_unknown_version_errors: Counter,
+    gossip_oversized_messages: Family<TopicLabel, Counter>,
+    consumer_channel_drops: Family<ChannelLabel, Counter>,
+    bandwidth_bytes: Family<BandwidthLabel, Counter>,
+    pub gossip_tx_subscriber_lagged: Counter,
+    pub gossip_duplicate_txs: Counter,
+    pub denied_connections: Counter,
+    pub chain_mismatches: Counter,
+    pub kad_routing_table_size: Gauge,
+    pub db_lookups_in_flight: Gauge,
+    pub db_lookups_queued: Gauge,
+    pub p2p_service_restarts: Counter,
 }
 
 impl P2PMetrics {
@@ -17,11 +56,39 @@ impl P2PMetrics {
         let peer_metrics = Registry::default();
 
         let unique_peers = Counter::default();
+        let gossip_decode_errors = Counter::default();
+        let gossip_report_errors = Counter::default();
+        let gossip_unknown_version_errors = Counter::default();
+        let gossip_oversized_messages = Family::<TopicLabel, Counter>::default();
+        let consumer_channel_drops = Family::<ChannelLabel, Counter>::default();
+        let bandwidth_bytes = Family::<BandwidthLabel, Counter>::default();
+        let gossip_tx_subscriber_lagged = Counter::default();
+        let gossip_duplicate_txs = Counter::default();
+        let denied_connections = Counter::default();
+        let chain_mismatches = Counter::default();
+        let kad_routing_table_size = Gauge::default();
+        let db_lookups_in_flight = Gauge::default();
+        let db_lookups_queued = Gauge::default();
+        let p2p_service_restarts = Counter::default();
 
         let mut metrics = P2PMetrics {
             gossip_sub_registry: OnceBox::new(),
             peer_metrics,
             unique_peers,
+            gossip_decode_errors,
+            gossip_report_errors,
+            gossip_unknown_version_errors,
+            gossip_oversized_messages,
+            consumer_channel_drops,
+            bandwidth_bytes,
+            gossip_tx_subscriber_lagged,
+            gossip_duplicate_txs,
+            denied_connections,
+            chain_mismatches,
+            kad_routing_table_size,
+            db_lookups_in_flight,
+            db_lookups_queued,
+            p2p_service_restarts,
         };
 
         metrics.peer_metrics.register(
@@ -30,8 +97,125 @@ impl P2PMetrics {
             metrics.unique_peers.clone(),
         );
 
+        metrics.peer_metrics.register(
+            "Gossip_Decode_Error_Counter",
+            "A Counter which keeps track of the received gossip messages that failed to decode",
+            metrics.gossip_decode_errors.clone(),
+        );
+
+        metrics.peer_metrics.register(
+            "Gossip_Report_Error_Counter",
+            "The number of gossip message validation reports that failed to be processed",
+            metrics.gossip_report_errors.clone(),
+        );
+
+        metrics.peer_metrics.register(
+            "Gossip_Unknown_Version_Counter",
+            "The number of received gossip messages with the unknown version of the wire format",
+            metrics.gossip_unknown_version_errors.clone(),
+        );
+
+        metrics.peer_metrics.register(
+            "Gossip_Oversized_Message_Counter",
+            "The number of received gossip messages rejected for exceeding the max size of the topic",
+            metrics.gossip_oversized_messages.clone(),
+        );
+
+        metrics.peer_metrics.register(
+            "Consumer_Channel_Drop_Counter",
+            "The number of gossiped messages dropped because the slowest consumer of the channel is too far behind",
+            metrics.consumer_channel_drops.clone(),
+        );
+
+        metrics.peer_metrics.register(
+            "P2p_Bandwidth_Bytes",
+            "The number of bytes exchanged with all peers, per protocol and direction",
+            metrics.bandwidth_bytes.clone(),
+        );
+
+        metrics.peer_metrics.register(
+            "Gossip_Tx_Subscriber_Lagged_Counter",
+            "The number of gossiped transactions skipped by the lagging subscribers",
+            metrics.gossip_tx_subscriber_lagged.clone(),
+        );
+
+        metrics.peer_metrics.register(
+            "Gossip_Duplicate_Tx_Counter",
+            "The number of transactions received via gossip again and not passed to the consumers",
+            metrics.gossip_duplicate_txs.clone(),
+        );
+
+        metrics.peer_metrics.register(
+            "Denied_Connection_Counter",
+            "The number of connections denied by the allowed peers and the denied IP ranges",
+            metrics.denied_connections.clone(),
+        );
+
+        metrics.peer_metrics.register(
+            "Chain_Mismatch_Counter",
+            "The number of peers disconnected because they follow another chain",
+            metrics.chain_mismatches.clone(),
+        );
+
+        metrics.peer_metrics.register(
+            "Kad_Routing_Table_Size",
+            "The number of peers in the Kademlia routing table used for the discovery",
+            metrics.kad_routing_table_size.clone(),
+        );
+
+        metrics.peer_metrics.register(
+            "Db_Lookups_In_Flight",
+            "The number of database lookups for the inbound requests that are running now",
+            metrics.db_lookups_in_flight.clone(),
+        );
+
+        metrics.peer_metrics.register(
+            "Db_Lookups_Queued",
+            "The number of database lookups for the inbound requests waiting for their turn",
+            metrics.db_lookups_queued.clone(),
+        );
+
+        metrics.peer_metrics.register(
+            "P2p_Service_Restarts",
+            "The number of times the p2p service was restarted after a failed start",
+            metrics.p2p_service_restarts.clone(),
+        );
+
         metrics
     }
+
+    pub fn count_oversized_gossip(&self, topic: &str) {
+        self.gossip_oversized_messages
+            .get_or_create(&TopicLabel {
+                topic: topic.to_string(),
+            })
+            .inc();
+    }
+
+    pub fn count_consumer_channel_drop(&self, channel: &str) {
+        self.consumer_channel_drops
+            .get_or_create(&ChannelLabel {
+                channel: channel.to_string(),
+            })
+            .inc();
+    }
+
+    pub fn count_bandwidth(&self, protocol: &str, direction: &str, bytes: u64) {
+        self.bandwidth_bytes
+            .get_or_create(&BandwidthLabel {
+                protocol: protocol.to_string(),
+                direction: direction.to_string(),
+            })
+            .inc_by(bytes);
+    }
+
+    pub fn consumer_channel_drops(&self, channel: &str) -> u64 {
+        self.consumer_channel_drops
+            .get_or_create(&ChannelLabel {
+                channel: channel.to_string(),
+            })
+            .get()
+    }
 }
 
 static P2P_METRICS: OnceLock<P2PMetrics> = OnceLock::new();
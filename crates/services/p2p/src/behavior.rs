@@ -4,11 +4,13 @@ use crate::{
         NetworkCodec,
     },
     config::Config,
+    connection_gater,
     discovery,
     gossipsub::{
         config::build_gossipsub_behaviour,
         topics::GossipTopic,
     },
+    handshake::ChainHandshake,
     heartbeat,
     peer_report,
     request_response::messages::{
@@ -19,13 +21,19 @@ use crate::{
 use fuel_core_types::fuel_types::BlockHeight;
 use libp2p::{
     allow_block_list,
+    connection_limits::{
+        self,
+        ConnectionLimits,
+    },
     gossipsub::{
         self,
         MessageAcceptance,
         MessageId,
         PublishError,
+        SubscriptionError,
     },
     identify,
+    kad,
     request_response::{
         self,
         OutboundRequestId,
@@ -37,6 +45,11 @@ use libp2p::{
     PeerId,
 };
 
+/// The protocol version advertised via the identify protocol
+pub const IDENTIFY_PROTOCOL_VERSION: &str = "/fuel/1.0";
+/// The protocol version advertised by the nodes serving all blocks since the genesis
+pub const ARCHIVAL_IDENTIFY_PROTOCOL_VERSION: &str = "/fuel/1.0/archival";
+
 /// Handles all p2p protocols needed for Fuel.
 #[derive(NetworkBehaviour)]
 pub struct FuelBehaviour {
@@ -45,6 +58,12 @@ pub struct FuelBehaviour {
     /// The Behaviour to manage connections to blocked peers.
     blocked_peer: allow_block_list::Behaviour<allow_block_list::BlockedPeers>,
 
+    /// The Behaviour to deny connections with not allowed peers and denied IP ranges.
+    connection_gater: connection_gater::Behaviour,
+
+    /// The Behaviour to limit the number of established connections.
+    connection_limits: connection_limits::Behaviour,
+
     /// Message propagation for p2p
     gossipsub: gossipsub::Behaviour,
 
@@ -65,7 +84,7 @@ pub struct FuelBehaviour {
 }
 
 impl FuelBehaviour {
-    pub(crate) fn new(p2p_config: &Config, codec: PostcardCodec) -> Self {
+    pub(crate) fn new(p2p_config: &Config, codec: PostcardCodec) -> anyhow::Result<Self> {
         let local_public_key = p2p_config.keypair.public();
         let local_peer_id = PeerId::from_public_key(&local_public_key);
 
@@ -75,10 +94,12 @@ impl FuelBehaviour {
 
             discovery_config
                 .enable_mdns(p2p_config.enable_mdns)
+                .enable_kad(p2p_config.enable_kademlia)
                 .max_peers_connected(p2p_config.max_peers_connected as usize)
                 .with_bootstrap_nodes(p2p_config.bootstrap_nodes.clone())
                 .with_reserved_nodes(p2p_config.reserved_nodes.clone())
-                .enable_reserved_nodes_only_mode(p2p_config.reserved_nodes_only_mode);
+                .enable_reserved_nodes_only_mode(p2p_config.reserved_nodes_only_mode)
+                .enable_metrics(p2p_config.metrics);
 
             if let Some(random_walk) = p2p_config.random_walk {
                 discovery_config.with_random_walk(random_walk);
@@ -91,15 +112,37 @@ impl FuelBehaviour {
             discovery_config
         };
 
-        let gossipsub = build_gossipsub_behaviour(p2p_config);
+        let connection_limits = {
+            let reserved_nodes =
+                u32::try_from(p2p_config.reserved_nodes.len()).unwrap_or(u32::MAX);
+            let max_established = p2p_config
+                .max_peers_connected
+                .saturating_add(reserved_nodes)
+                .saturating_mul(p2p_config.max_connections_per_peer);
+
+            let limits = ConnectionLimits::default()
+                .with_max_established(Some(max_established))
+                .with_max_established_per_peer(Some(p2p_config.max_connections_per_peer))
+                .with_max_established_incoming(p2p_config.max_inbound_connections)
+                .with_max_established_outgoing(p2p_config.max_outbound_connections);
+            connection_limits::Behaviour::new(limits)
+        };
+
+        let gossipsub = build_gossipsub_behaviour(p2p_config)?;
 
         let peer_report = peer_report::Behaviour::new(p2p_config);
 
         let identify = {
+            let protocol_version = if p2p_config.is_archival {
+                ARCHIVAL_IDENTIFY_PROTOCOL_VERSION
+            } else {
+                IDENTIFY_PROTOCOL_VERSION
+            };
             let identify_config = identify::Config::new(
-                "/fuel/1.0".to_string(),
+                protocol_version.to_string(),
                 p2p_config.keypair.public(),
-            );
+            )
+            .with_agent_version(ChainHandshake::new(p2p_config).agent_version());
             if let Some(interval) = p2p_config.identify_interval {
                 identify::Behaviour::new(identify_config.with_interval(interval))
             } else {
@@ -112,8 +155,10 @@ impl FuelBehaviour {
             BlockHeight::default(),
         );
 
-        let req_res_protocol =
-            core::iter::once((codec.get_req_res_protocol(), ProtocolSupport::Full));
+        let req_res_protocol = codec
+            .get_req_res_protocols()
+            .into_iter()
+            .map(|protocol| (protocol, ProtocolSupport::Full));
 
         let req_res_config = request_response::Config::default();
         req_res_config
@@ -126,15 +171,17 @@ impl FuelBehaviour {
             req_res_config,
         );
 
-        Self {
+        Ok(Self {
             discovery: discovery_config.finish(),
             gossipsub,
             peer_report,
             request_response,
             blocked_peer: Default::default(),
+            connection_gater: connection_gater::Behaviour::new(p2p_config),
+            connection_limits,
             identify,
             heartbeat,
-        }
+        })
     }
 
     pub fn add_addresses_to_discovery(
@@ -147,6 +194,23 @@ impl FuelBehaviour {
         }
     }
 
+    pub fn set_discovery(&mut self, mdns: Option<bool>, kad: Option<bool>) {
+        if let Some(enabled) = mdns {
+            self.discovery.set_mdns(enabled);
+        }
+        if let Some(enabled) = kad {
+            self.discovery.set_kad(enabled);
+        }
+    }
+
+    pub fn find_peers(&mut self) -> Option<kad::QueryId> {
+        self.discovery.find_peers()
+    }
+
+    pub fn discovery_status(&mut self) -> discovery::DiscoveryStatus {
+        self.discovery.status()
+    }
+
     pub fn publish_message(
         &mut self,
         topic: GossipTopic,
@@ -155,6 +219,29 @@ impl FuelBehaviour {
         self.gossipsub.publish(topic, encoded_data)
     }
 
+    /// Returns the peers subscribed to the topic, the published messages are sent to them
+    pub fn topic_peers(&self, topic: &GossipTopic) -> Vec<PeerId> {
+        let topic_hash = topic.hash();
+        self.gossipsub
+            .all_peers()
+            .filter(|(_, topics)| topics.contains(&&topic_hash))
+            .map(|(peer_id, _)| *peer_id)
+            .collect()
+    }
+
+    pub fn subscribe(&mut self, topic: &GossipTopic) -> Result<bool, SubscriptionError> {
+        self.gossipsub.subscribe(topic)
+    }
+
+    pub fn unsubscribe(&mut self, topic: &GossipTopic) -> Result<bool, PublishError> {
+        self.gossipsub.unsubscribe(topic)
+    }
+
+    pub fn is_subscribed(&self, topic: &GossipTopic) -> bool {
+        let topic_hash = topic.hash();
+        self.gossipsub.topics().any(|topic| topic == &topic_hash)
+    }
+
     pub fn send_request_msg(
         &mut self,
         message_request: RequestMessage,
@@ -171,46 +258,58 @@ impl FuelBehaviour {
         self.request_response.send_response(channel, message)
     }
 
+    /// Sends the message from the gossipsub cache to the mesh peers again.
+    /// Returns `false` if the message is not in the cache.
+    pub fn forward_message(
+        &mut self,
+        msg_id: &MessageId,
+        local_peer_id: &PeerId,
+    ) -> Result<bool, PublishError> {
+        // The cached message is forwarded on the validation, even if it is validated again.
+        // The local peer is passed as the source, so the scores of the peers stay untouched.
+        self.gossipsub.report_message_validation_result(
+            msg_id,
+            local_peer_id,
+            MessageAcceptance::Accept,
+        )
+    }
+
+    /// Returns `false` if the message isn't in the gossipsub cache anymore
     pub fn report_message_validation_result(
         &mut self,
         msg_id: &MessageId,
         propagation_source: &PeerId,
         acceptance: MessageAcceptance,
-    ) -> Option<f64> {
-        let should_check_score = matches!(acceptance, MessageAcceptance::Reject);
-
-        match self.gossipsub.report_message_validation_result(
+    ) -> Result<bool, PublishError> {
+        self.gossipsub.report_message_validation_result(
             msg_id,
             propagation_source,
             acceptance,
-        ) {
-            Ok(true) => {
-                tracing::debug!(target: "fuel-p2p", "Sent a report for MessageId: {} from PeerId: {}", msg_id, propagation_source);
-                if should_check_score {
-                    return self.gossipsub.peer_score(propagation_source);
-                }
-            }
-            Ok(false) => {
-                tracing::warn!(target: "fuel-p2p", "Message with MessageId: {} not found in the Gossipsub Message Cache", msg_id);
-            }
-            Err(e) => {
-                tracing::error!(target: "fuel-p2p", "Failed to report Message with MessageId: {} with Error: {:?}", msg_id, e);
-            }
-        }
-
-        None
+        )
     }
 
     pub fn update_block_height(&mut self, block_height: BlockHeight) {
         self.heartbeat.update_block_height(block_height);
     }
 
-    #[cfg(test)]
     pub fn get_peer_score(&self, peer_id: &PeerId) -> Option<f64> {
         self.gossipsub.peer_score(peer_id)
     }
 
+    /// Explicit peers always receive the gossiped messages and are never graylisted
+    pub fn add_explicit_peer(&mut self, peer_id: &PeerId) {
+        self.gossipsub.add_explicit_peer(peer_id)
+    }
+
+    pub fn remove_explicit_peer(&mut self, peer_id: &PeerId) {
+        self.gossipsub.remove_explicit_peer(peer_id)
+    }
+
     pub fn block_peer(&mut self, peer_id: PeerId) {
         self.blocked_peer.block_peer(peer_id)
     }
+
+    pub fn unblock_peer(&mut self, peer_id: PeerId) {
+        self.blocked_peer.unblock_peer(peer_id)
+    }
 }
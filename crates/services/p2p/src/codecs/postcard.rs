@@ -12,9 +12,13 @@ use crate::{
         RequestMessage,
         ResponseMessage,
         REQUEST_RESPONSE_PROTOCOL_ID,
+        REQUEST_RESPONSE_PROTOCOL_ID_V2,
+        REQUEST_RESPONSE_PROTOCOL_ID_V3,
+        REQUEST_RESPONSE_PROTOCOL_ID_V4,
     },
 };
 use async_trait::async_trait;
+use fuel_core_types::fuel_tx::Transaction;
 use futures::{
     AsyncRead,
     AsyncReadExt,
@@ -25,7 +29,70 @@ use serde::{
     Deserialize,
     Serialize,
 };
-use std::io;
+use std::{
+    borrow::Cow,
+    io::{
+        self,
+        Read,
+    },
+};
+use thiserror::Error;
+
+/// The first byte of the payload compressed with zstd.
+/// Postcard encodes the enum variant index as the first byte of the uncompressed messages,
+/// and it is never this large, so the uncompressed messages don't need any header
+/// and stay compatible with the nodes that don't support the compression.
+/// All payloads must start with the enum variant index, see [`GossipedTxBatch`].
+const ZSTD_COMPRESSED_TAG: u8 = 0xFF;
+/// The zstd compression level used for the payloads
+const ZSTD_COMPRESSION_LEVEL: i32 = 0;
+/// The first byte of the versioned envelope, followed by the version byte and the message.
+/// The messages of the first version are sent without the envelope,
+/// so it is never the first byte of them, like the [`ZSTD_COMPRESSED_TAG`].
+const VERSIONED_ENVELOPE_TAG: u8 = 0xFE;
+/// The size of the tag and the version byte of the versioned envelope
+const VERSIONED_ENVELOPE_SIZE: usize = 2;
+
+/// The message uses the version of the wire format unknown to the node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("The message uses the unknown wire version {0}")]
+pub struct UnknownWireVersion(pub u8);
+
+impl UnknownWireVersion {
+    /// Returns the unknown version if it caused the decoding error
+    pub fn from_io_error(err: &io::Error) -> Option<Self> {
+        err.get_ref()?.downcast_ref::<Self>().copied()
+    }
+}
+
+/// The payload, compressed or decompressed, exceeds the max size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("The payload exceeds the max size of {max_size} bytes")]
+pub struct PayloadTooLarge {
+    pub max_size: usize,
+}
+
+impl PayloadTooLarge {
+    /// Returns the exceeded limit if it caused the decoding error
+    pub fn from_io_error(err: &io::Error) -> Option<Self> {
+        err.get_ref()?.downcast_ref::<Self>().copied()
+    }
+}
+
+impl From<PayloadTooLarge> for io::Error {
+    fn from(err: PayloadTooLarge) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+/// The batch of the gossiped transactions.
+/// The bare list would start with its length, which collides with the
+/// [`ZSTD_COMPRESSED_TAG`] and the [`VERSIONED_ENVELOPE_TAG`] for some lengths,
+/// so the list is wrapped into the enum to start with the variant index.
+#[derive(Serialize, Deserialize)]
+enum GossipedTxBatch<T> {
+    Transactions(Vec<T>),
+}
 
 /// Helper method for decoding data
 /// Reusable across `RequestResponseCodec` and `GossipsubCodec`
@@ -45,6 +112,17 @@ pub struct PostcardCodec {
     /// Necessary in order to avoid DoS attacks
     /// Currently the size mostly depends on the max size of the Block
     max_response_size: usize,
+    /// The payloads larger than the threshold are compressed.
+    /// The compression is disabled if it is `None`.
+    compression_threshold: Option<usize>,
+    /// The latest version of the protocol supported by the node
+    max_protocol: MessageExchangePostcardProtocol,
+    /// Max size of the gossiped transaction, checked before the decoding.
+    /// Only the decompressed size is limited by the max block size if it is `None`.
+    max_tx_gossip_size: Option<usize>,
+    /// Max size of the gossiped transactions batch, checked before the decoding.
+    /// Only the decompressed size is limited by the max block size if it is `None`.
+    max_tx_batch_gossip_size: Option<usize>,
 }
 
 impl PostcardCodec {
@@ -56,10 +134,163 @@ impl PostcardCodec {
 
         Self {
             max_response_size: max_block_size,
+            compression_threshold: None,
+            max_protocol: MessageExchangePostcardProtocol::LATEST,
+            max_tx_gossip_size: None,
+            max_tx_batch_gossip_size: None,
+        }
+    }
+
+    /// Limits the size of the messages gossiped on the topic.
+    /// The larger messages fail to decode with [`PayloadTooLarge`].
+    pub fn with_max_gossip_size(mut self, tag: GossipTopicTag, max_size: usize) -> Self {
+        match tag {
+            GossipTopicTag::NewTx => self.max_tx_gossip_size = Some(max_size),
+            GossipTopicTag::NewTxBatch => self.max_tx_batch_gossip_size = Some(max_size),
+        }
+        self
+    }
+
+    fn max_gossip_size(&self, tag: GossipTopicTag) -> Option<usize> {
+        match tag {
+            GossipTopicTag::NewTx => self.max_tx_gossip_size,
+            GossipTopicTag::NewTxBatch => self.max_tx_batch_gossip_size,
+        }
+    }
+
+    /// Limits the supported versions of the protocol by the `max_protocol`.
+    pub fn with_max_protocol(
+        mut self,
+        max_protocol: MessageExchangePostcardProtocol,
+    ) -> Self {
+        self.max_protocol = max_protocol;
+        self
+    }
+
+    /// The latest version of the protocol supported by the node
+    pub fn max_protocol(&self) -> MessageExchangePostcardProtocol {
+        self.max_protocol
+    }
+
+    /// Returns `true` if the response of the `encoded_size` bytes, without the envelope,
+    /// fits into the max response size of the peers, in any version of the protocol
+    pub fn fits_response(&self, encoded_size: usize) -> bool {
+        encoded_size.saturating_add(VERSIONED_ENVELOPE_SIZE) <= self.max_response_size
+    }
+
+    /// Wraps the message into the envelope of the `protocol` version
+    fn wrap(protocol: MessageExchangePostcardProtocol, data: Vec<u8>) -> Vec<u8> {
+        match protocol {
+            MessageExchangePostcardProtocol::V1 => data,
+            MessageExchangePostcardProtocol::V2
+            | MessageExchangePostcardProtocol::V3
+            | MessageExchangePostcardProtocol::V4 => {
+                let mut wrapped = Vec::with_capacity(
+                    data.len().saturating_add(VERSIONED_ENVELOPE_SIZE),
+                );
+                wrapped.push(VERSIONED_ENVELOPE_TAG);
+                wrapped.push(protocol.version());
+                wrapped.extend(data);
+                wrapped
+            }
+        }
+    }
+
+    /// Returns the message from the envelope.
+    /// Fails with [`UnknownWireVersion`] if the version isn't supported by the node.
+    fn unwrap<'a>(&self, data: &'a [u8]) -> Result<&'a [u8], io::Error> {
+        match data {
+            [VERSIONED_ENVELOPE_TAG, version, message @ ..] => {
+                match MessageExchangePostcardProtocol::from_version(*version) {
+                    Some(protocol) if protocol <= self.max_protocol => Ok(message),
+                    _ => Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        UnknownWireVersion(*version),
+                    )),
+                }
+            }
+            _ => Ok(data),
+        }
+    }
+
+    /// Compresses the payloads larger than the `threshold`.
+    pub fn with_compression_threshold(mut self, threshold: Option<usize>) -> Self {
+        self.compression_threshold = threshold;
+        self
+    }
+
+    fn compress(&self, data: Vec<u8>) -> Result<Vec<u8>, io::Error> {
+        match self.compression_threshold {
+            Some(threshold) if data.len() > threshold => {
+                let mut compressed = vec![ZSTD_COMPRESSED_TAG];
+                zstd::stream::copy_encode(
+                    data.as_slice(),
+                    &mut compressed,
+                    ZSTD_COMPRESSION_LEVEL,
+                )?;
+                Ok(compressed)
+            }
+            _ => Ok(data),
+        }
+    }
+
+    /// Compresses the request or response sent with the `protocol`.
+    /// The peers speaking only the first version can't decompress the payloads.
+    fn compress_for(
+        &self,
+        protocol: MessageExchangePostcardProtocol,
+        data: Vec<u8>,
+    ) -> Result<Vec<u8>, io::Error> {
+        match protocol {
+            MessageExchangePostcardProtocol::V1 => Ok(data),
+            MessageExchangePostcardProtocol::V2
+            | MessageExchangePostcardProtocol::V3
+            | MessageExchangePostcardProtocol::V4 => self.compress(data),
+        }
+    }
+
+    /// Decompresses the payload if it is compressed.
+    /// The decompressed size is limited by the `max_size`,
+    /// so the small payload can't expand into the huge one.
+    fn decompress<'a>(
+        data: &'a [u8],
+        max_size: usize,
+    ) -> Result<Cow<'a, [u8]>, io::Error> {
+        match data.split_first() {
+            Some((&ZSTD_COMPRESSED_TAG, compressed)) => {
+                let mut decompressed = Vec::new();
+                zstd::stream::read::Decoder::new(compressed)?
+                    .take((max_size as u64).saturating_add(1))
+                    .read_to_end(&mut decompressed)?;
+
+                if decompressed.len() > max_size {
+                    return Err(PayloadTooLarge { max_size }.into())
+                }
+                Ok(Cow::Owned(decompressed))
+            }
+            _ => Ok(Cow::Borrowed(data)),
         }
     }
 }
 
+/// Reads the whole message from the socket.
+/// Fails with [`PayloadTooLarge`] instead of truncating the message above the `max_size`.
+async fn read_limited<T>(socket: &mut T, max_size: usize) -> io::Result<Vec<u8>>
+where
+    T: AsyncRead + Unpin + Send,
+{
+    let mut message = Vec::new();
+    socket
+        .take((max_size as u64).saturating_add(1))
+        .read_to_end(&mut message)
+        .await?;
+
+    if message.len() > max_size {
+        return Err(PayloadTooLarge { max_size }.into())
+    }
+    Ok(message)
+}
+
 /// Since Postcard does not support async reads or writes out of the box
 /// We prefix Request & Response Messages with the length of the data in bytes
 /// We expect the substream to be properly closed when response channel is dropped.
@@ -81,12 +312,11 @@ impl request_response::Codec for PostcardCodec {
     where
         T: AsyncRead + Unpin + Send,
     {
-        let mut response = Vec::new();
-        socket
-            .take(self.max_response_size as u64)
-            .read_to_end(&mut response)
-            .await?;
-        deserialize(&response)
+        let request = read_limited(socket, self.max_response_size).await?;
+        deserialize(&Self::decompress(
+            self.unwrap(&request)?,
+            self.max_response_size,
+        )?)
     }
 
     async fn read_response<T>(
@@ -97,39 +327,49 @@ impl request_response::Codec for PostcardCodec {
     where
         T: AsyncRead + Unpin + Send,
     {
-        let mut response = Vec::new();
-        socket
-            .take(self.max_response_size as u64)
-            .read_to_end(&mut response)
-            .await?;
-
-        deserialize(&response)
+        let response = read_limited(socket, self.max_response_size).await?;
+        deserialize(&Self::decompress(
+            self.unwrap(&response)?,
+            self.max_response_size,
+        )?)
     }
 
     async fn write_request<T>(
         &mut self,
-        _protocol: &Self::Protocol,
+        protocol: &Self::Protocol,
         socket: &mut T,
         req: Self::Request,
     ) -> io::Result<()>
     where
         T: futures::AsyncWrite + Unpin + Send,
     {
-        let encoded_data = serialize(&req)?;
+        let encoded_data =
+            Self::wrap(*protocol, self.compress_for(*protocol, serialize(&req)?)?);
         socket.write_all(&encoded_data).await?;
         Ok(())
     }
 
     async fn write_response<T>(
         &mut self,
-        _protocol: &Self::Protocol,
+        protocol: &Self::Protocol,
         socket: &mut T,
         res: Self::Response,
     ) -> io::Result<()>
     where
         T: futures::AsyncWrite + Unpin + Send,
     {
-        let encoded_data = serialize(&res)?;
+        // The peer fails to read the larger response, so it isn't written at all
+        let max_size = self.max_response_size;
+        let serialized = serialize(&res)?;
+        if serialized.len() > max_size {
+            return Err(PayloadTooLarge { max_size }.into())
+        }
+        // The response uses the version of the protocol negotiated for the request
+        let encoded_data =
+            Self::wrap(*protocol, self.compress_for(*protocol, serialized)?);
+        if encoded_data.len() > max_size {
+            return Err(PayloadTooLarge { max_size }.into())
+        }
         socket.write_all(&encoded_data).await?;
         Ok(())
     }
@@ -139,12 +379,21 @@ impl GossipsubCodec for PostcardCodec {
     type RequestMessage = GossipsubBroadcastRequest;
     type ResponseMessage = GossipsubMessage;
 
+    /// The gossip is published in the first version of the wire format,
+    /// so all nodes on the topic can decode it.
+    /// The nodes decode the later versions to allow switching to them in the future.
     fn encode(&self, data: Self::RequestMessage) -> Result<Vec<u8>, io::Error> {
         let encoded_data = match data {
             GossipsubBroadcastRequest::NewTx(tx) => postcard::to_stdvec(&*tx),
+            GossipsubBroadcastRequest::NewTxBatch(txs) => {
+                let txs: Vec<&Transaction> = txs.iter().map(AsRef::as_ref).collect();
+                postcard::to_stdvec(&GossipedTxBatch::Transactions(txs))
+            }
         };
 
-        encoded_data.map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))
+        let encoded_data = encoded_data
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+        self.compress(encoded_data)
     }
 
     fn decode(
@@ -152,8 +401,23 @@ impl GossipsubCodec for PostcardCodec {
         encoded_data: &[u8],
         gossipsub_tag: GossipTopicTag,
     ) -> Result<Self::ResponseMessage, io::Error> {
+        // The size is checked before the decompression and the decoding,
+        // so the peer can't make the node decode the huge message
+        let max_size = self.max_gossip_size(gossipsub_tag);
+        let encoded_data = self.unwrap(encoded_data)?;
+        if let Some(max_size) = max_size {
+            if encoded_data.len() > max_size {
+                return Err(PayloadTooLarge { max_size }.into())
+            }
+        }
+        let encoded_data =
+            Self::decompress(encoded_data, max_size.unwrap_or(self.max_response_size))?;
         let decoded_response = match gossipsub_tag {
-            GossipTopicTag::NewTx => GossipsubMessage::NewTx(deserialize(encoded_data)?),
+            GossipTopicTag::NewTx => GossipsubMessage::NewTx(deserialize(&encoded_data)?),
+            GossipTopicTag::NewTxBatch => {
+                let GossipedTxBatch::Transactions(txs) = deserialize(&encoded_data)?;
+                GossipsubMessage::NewTxBatch(txs)
+            }
         };
 
         Ok(decoded_response)
@@ -161,24 +425,90 @@ impl GossipsubCodec for PostcardCodec {
 }
 
 impl NetworkCodec for PostcardCodec {
-    fn get_req_res_protocol(&self) -> <Self as request_response::Codec>::Protocol {
-        MessageExchangePostcardProtocol {}
+    fn get_req_res_protocols(&self) -> Vec<<Self as request_response::Codec>::Protocol> {
+        MessageExchangePostcardProtocol::ALL
+            .into_iter()
+            .rev()
+            .filter(|protocol| *protocol <= self.max_protocol)
+            .collect()
     }
 }
 
-#[derive(Default, Debug, Clone)]
-pub struct MessageExchangePostcardProtocol;
+/// The versions of the request-response protocol.
+/// The version is negotiated with the peer for each request.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MessageExchangePostcardProtocol {
+    /// The messages are sent without the envelope
+    #[default]
+    V1,
+    /// The messages are wrapped into the versioned envelope
+    V2,
+    /// Adds the request for the tip of the peer
+    V3,
+    /// Adds the chunked transfer of the blocks too large for one response
+    V4,
+}
+
+impl MessageExchangePostcardProtocol {
+    /// All versions of the protocol, from the oldest to the latest
+    pub const ALL: [Self; 4] = [Self::V1, Self::V2, Self::V3, Self::V4];
+    /// The latest version of the protocol
+    pub const LATEST: Self = Self::V4;
+
+    fn version(&self) -> u8 {
+        match self {
+            Self::V1 => 1,
+            Self::V2 => 2,
+            Self::V3 => 3,
+            Self::V4 => 4,
+        }
+    }
+
+    /// Parses the protocol advertised by the peer via the identify protocol
+    pub fn from_protocol_id(protocol_id: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|protocol| protocol.as_ref() == protocol_id)
+    }
+
+    /// Returns `true` if the peers speaking this version can decode the request
+    pub fn supports(&self, request: &RequestMessage) -> bool {
+        match request {
+            RequestMessage::SealedHeaders(_) | RequestMessage::Transactions(_) => true,
+            RequestMessage::Tip => *self >= Self::V3,
+            RequestMessage::BlockChunk { .. } => *self >= Self::V4,
+        }
+    }
+
+    fn from_version(version: u8) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|protocol| protocol.version() == version)
+    }
+}
 
 impl AsRef<str> for MessageExchangePostcardProtocol {
     fn as_ref(&self) -> &str {
-        REQUEST_RESPONSE_PROTOCOL_ID
+        match self {
+            Self::V1 => REQUEST_RESPONSE_PROTOCOL_ID,
+            Self::V2 => REQUEST_RESPONSE_PROTOCOL_ID_V2,
+            Self::V3 => REQUEST_RESPONSE_PROTOCOL_ID_V3,
+            Self::V4 => REQUEST_RESPONSE_PROTOCOL_ID_V4,
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::request_response::messages::MAX_REQUEST_SIZE;
+    use crate::request_response::messages::{
+        BlockChunk,
+        ResponseMessageError,
+        BLOCK_CHUNK_FRAMING_SIZE,
+        MAX_REQUEST_SIZE,
+    };
+    use fuel_core_types::services::p2p::Transactions;
+    use std::sync::Arc;
 
     #[test]
     fn test_request_size_fits() {
@@ -186,4 +516,445 @@ mod tests {
         let m = RequestMessage::Transactions(arbitrary_range);
         assert!(postcard::to_stdvec(&m).unwrap().len() <= MAX_REQUEST_SIZE);
     }
+
+    #[test]
+    fn test_not_found_response_roundtrip() {
+        let m = ResponseMessage::Error(ResponseMessageError::NotFound);
+        let encoded = serialize(&m).unwrap();
+        let decoded: ResponseMessage = deserialize(&encoded).unwrap();
+        assert!(matches!(
+            decoded,
+            ResponseMessage::Error(ResponseMessageError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn gossip_message_above_threshold_is_compressed_and_decoded() {
+        // The max size limits the decompressed message, so it should fit the transaction
+        let codec = PostcardCodec::new(1024 * 1024).with_compression_threshold(Some(0));
+        let tx = Transaction::default_test_tx();
+
+        let encoded = codec
+            .encode(GossipsubBroadcastRequest::NewTx(Arc::new(tx.clone())))
+            .unwrap();
+        assert_eq!(encoded[0], ZSTD_COMPRESSED_TAG);
+
+        let GossipsubMessage::NewTx(decoded) =
+            codec.decode(&encoded, GossipTopicTag::NewTx).unwrap()
+        else {
+            panic!("Expected a single transaction")
+        };
+        assert_eq!(decoded, tx);
+    }
+
+    #[test]
+    fn gossip_transaction_batch_roundtrip() {
+        let codec = PostcardCodec::new(MAX_REQUEST_SIZE);
+        let txs = vec![Transaction::default_test_tx(); 3];
+
+        let encoded = codec
+            .encode(GossipsubBroadcastRequest::NewTxBatch(
+                txs.iter().cloned().map(Arc::new).collect(),
+            ))
+            .unwrap();
+
+        let GossipsubMessage::NewTxBatch(decoded) =
+            codec.decode(&encoded, GossipTopicTag::NewTxBatch).unwrap()
+        else {
+            panic!("Expected a batch of transactions")
+        };
+        assert_eq!(decoded, txs);
+    }
+
+    #[test]
+    fn gossip_transaction_batch_length_is_not_read_as_tag() {
+        // Bare lists of 254 and 255 items would start with the envelope and zstd tags
+        let codec = PostcardCodec::new(1024 * 1024);
+        for count in [254, 255] {
+            let txs = vec![Transaction::default_test_tx(); count];
+
+            let encoded = codec
+                .encode(GossipsubBroadcastRequest::NewTxBatch(
+                    txs.iter().cloned().map(Arc::new).collect(),
+                ))
+                .unwrap();
+            assert_ne!(encoded[0], VERSIONED_ENVELOPE_TAG);
+            assert_ne!(encoded[0], ZSTD_COMPRESSED_TAG);
+
+            let GossipsubMessage::NewTxBatch(decoded) =
+                codec.decode(&encoded, GossipTopicTag::NewTxBatch).unwrap()
+            else {
+                panic!("Expected a batch of transactions")
+            };
+            assert_eq!(decoded, txs);
+        }
+    }
+
+    #[test]
+    fn gossip_message_below_threshold_is_not_compressed() {
+        let codec = PostcardCodec::new(MAX_REQUEST_SIZE)
+            .with_compression_threshold(Some(MAX_REQUEST_SIZE));
+        let tx = Transaction::default_test_tx();
+
+        let encoded = codec
+            .encode(GossipsubBroadcastRequest::NewTx(Arc::new(tx.clone())))
+            .unwrap();
+
+        // The message is the same as without the compression support
+        assert_eq!(encoded, postcard::to_stdvec(&tx).unwrap());
+    }
+
+    #[tokio::test]
+    async fn compressed_response_roundtrip() {
+        let mut codec =
+            PostcardCodec::new(1024 * 1024).with_compression_threshold(Some(1024));
+        let txs = vec![Transaction::default_test_tx(); 100];
+        let response =
+            ResponseMessage::Transactions(Some(vec![Transactions(txs.clone())]));
+        let uncompressed_size = serialize(&response).unwrap().len();
+
+        let mut socket = futures::io::Cursor::new(Vec::new());
+        codec
+            .write_response(&MessageExchangePostcardProtocol::V2, &mut socket, response)
+            .await
+            .unwrap();
+        let encoded = socket.into_inner();
+        assert_eq!(encoded[VERSIONED_ENVELOPE_SIZE], ZSTD_COMPRESSED_TAG);
+        assert!(encoded.len() < uncompressed_size);
+
+        let decoded = codec
+            .read_response(
+                &MessageExchangePostcardProtocol::V2,
+                &mut futures::io::Cursor::new(encoded),
+            )
+            .await
+            .unwrap();
+        let ResponseMessage::Transactions(Some(decoded)) = decoded else {
+            panic!("Unexpected response: {decoded:?}")
+        };
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].0, txs);
+    }
+
+    #[tokio::test]
+    async fn payloads_for_v1_peers_are_not_compressed() {
+        let mut codec =
+            PostcardCodec::new(1024 * 1024).with_compression_threshold(Some(0));
+        let protocol = MessageExchangePostcardProtocol::V1;
+        let request = RequestMessage::Transactions(0..1);
+        let response = ResponseMessage::Transactions(Some(vec![Transactions(vec![
+            Transaction::default_test_tx(),
+        ])]));
+
+        let mut socket = futures::io::Cursor::new(Vec::new());
+        codec
+            .write_request(&protocol, &mut socket, request.clone())
+            .await
+            .unwrap();
+        let encoded = socket.into_inner();
+        assert_ne!(encoded[0], ZSTD_COMPRESSED_TAG);
+        assert_eq!(encoded, serialize(&request).unwrap());
+
+        let mut socket = futures::io::Cursor::new(Vec::new());
+        codec
+            .write_response(&protocol, &mut socket, response.clone())
+            .await
+            .unwrap();
+        let encoded = socket.into_inner();
+        assert_ne!(encoded[0], ZSTD_COMPRESSED_TAG);
+        assert_eq!(encoded, serialize(&response).unwrap());
+    }
+
+    #[tokio::test]
+    async fn response_above_max_size_is_not_written() {
+        let txs = vec![Transaction::default_test_tx(); 10];
+        let response = ResponseMessage::Transactions(Some(vec![Transactions(txs)]));
+        let encoded_size = serialize(&response).unwrap().len();
+        let max_size = encoded_size.saturating_add(VERSIONED_ENVELOPE_SIZE);
+
+        // The response of the max size is written in any version of the protocol
+        let mut codec = PostcardCodec::new(max_size);
+        assert!(codec.fits_response(encoded_size));
+        for protocol in MessageExchangePostcardProtocol::ALL {
+            let mut socket = futures::io::Cursor::new(Vec::new());
+            codec
+                .write_response(&protocol, &mut socket, response.clone())
+                .await
+                .unwrap();
+        }
+
+        // The larger response fails on the serving side instead of the peer
+        let max_size = encoded_size.saturating_sub(1);
+        let mut codec = PostcardCodec::new(max_size);
+        assert!(!codec.fits_response(encoded_size));
+        let mut socket = futures::io::Cursor::new(Vec::new());
+        let err = codec
+            .write_response(&MessageExchangePostcardProtocol::V1, &mut socket, response)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            PayloadTooLarge::from_io_error(&err),
+            Some(PayloadTooLarge { max_size })
+        );
+        assert!(socket.into_inner().is_empty());
+    }
+
+    #[tokio::test]
+    async fn response_above_max_size_is_reported_instead_of_truncated() {
+        let txs = vec![Transaction::default_test_tx(); 10];
+        let response = ResponseMessage::Transactions(Some(vec![Transactions(txs)]));
+        let encoded = serialize(&response).unwrap();
+
+        // The response of the max size is read
+        let mut codec = PostcardCodec::new(encoded.len());
+        let decoded = codec
+            .read_response(
+                &MessageExchangePostcardProtocol::V1,
+                &mut futures::io::Cursor::new(encoded.clone()),
+            )
+            .await
+            .unwrap();
+        assert!(matches!(decoded, ResponseMessage::Transactions(Some(_))));
+
+        // The larger response isn't cut at the max size
+        let max_size = encoded.len().saturating_sub(1);
+        let mut codec = PostcardCodec::new(max_size);
+        let err = codec
+            .read_response(
+                &MessageExchangePostcardProtocol::V1,
+                &mut futures::io::Cursor::new(encoded),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            PayloadTooLarge::from_io_error(&err),
+            Some(PayloadTooLarge { max_size })
+        );
+    }
+
+    #[test]
+    fn decompressed_size_is_limited_by_max_size() {
+        // The tiny payload that expands far beyond the max size
+        let max_size = 1024;
+        let expanded_size = 64 * 1024 * 1024;
+        let mut payload = vec![ZSTD_COMPRESSED_TAG];
+        zstd::stream::copy_encode(
+            io::repeat(0).take(expanded_size),
+            &mut payload,
+            ZSTD_COMPRESSION_LEVEL,
+        )
+        .unwrap();
+        assert!(payload.len() < 64 * 1024);
+
+        let codec = PostcardCodec::new(max_size);
+        let result = codec.decode(&payload, GossipTopicTag::NewTx);
+
+        let err = result.expect_err("The payload should exceed the max size");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn gossip_message_above_topic_max_size_is_rejected_before_decoding() {
+        let tx = Transaction::default_test_tx();
+        let encoded = postcard::to_stdvec(&tx).unwrap();
+        let codec = PostcardCodec::new(MAX_REQUEST_SIZE)
+            .with_max_gossip_size(GossipTopicTag::NewTx, encoded.len());
+
+        // The message of the max size is decoded
+        assert!(codec.decode(&encoded, GossipTopicTag::NewTx).is_ok());
+
+        // The garbage above the max size is rejected without the decoding
+        let oversized = vec![0; encoded.len().saturating_add(1)];
+        let err = codec.decode(&oversized, GossipTopicTag::NewTx).unwrap_err();
+        assert_eq!(
+            PayloadTooLarge::from_io_error(&err),
+            Some(PayloadTooLarge {
+                max_size: encoded.len()
+            })
+        );
+
+        // Other topics keep their own limits
+        let batch =
+            postcard::to_stdvec(&GossipedTxBatch::Transactions(vec![tx; 2])).unwrap();
+        assert!(batch.len() > encoded.len());
+        assert!(codec.decode(&batch, GossipTopicTag::NewTxBatch).is_ok());
+    }
+
+    #[test]
+    fn compressed_gossip_message_is_limited_by_topic_max_size() {
+        let max_size = 1024;
+        let mut payload = vec![ZSTD_COMPRESSED_TAG];
+        zstd::stream::copy_encode(
+            io::repeat(0).take(64 * 1024),
+            &mut payload,
+            ZSTD_COMPRESSION_LEVEL,
+        )
+        .unwrap();
+        assert!(payload.len() < max_size);
+
+        let codec = PostcardCodec::new(MAX_REQUEST_SIZE)
+            .with_max_gossip_size(GossipTopicTag::NewTxBatch, max_size);
+        let err = codec
+            .decode(&payload, GossipTopicTag::NewTxBatch)
+            .unwrap_err();
+
+        assert_eq!(
+            PayloadTooLarge::from_io_error(&err),
+            Some(PayloadTooLarge { max_size })
+        );
+    }
+
+    #[test]
+    fn latest_protocol_is_preferred() {
+        let codec = PostcardCodec::new(MAX_REQUEST_SIZE);
+        assert_eq!(
+            codec.get_req_res_protocols(),
+            vec![
+                MessageExchangePostcardProtocol::V4,
+                MessageExchangePostcardProtocol::V3,
+                MessageExchangePostcardProtocol::V2,
+                MessageExchangePostcardProtocol::V1
+            ]
+        );
+
+        let codec = codec.with_max_protocol(MessageExchangePostcardProtocol::V1);
+        assert_eq!(
+            codec.get_req_res_protocols(),
+            vec![MessageExchangePostcardProtocol::V1]
+        );
+    }
+
+    #[tokio::test]
+    async fn response_uses_the_envelope_of_the_negotiated_protocol() {
+        let mut codec = PostcardCodec::new(MAX_REQUEST_SIZE);
+        let response = || ResponseMessage::Error(ResponseMessageError::NotFound);
+        let serialized = serialize(&response()).unwrap();
+
+        for protocol in MessageExchangePostcardProtocol::ALL {
+            let mut socket = futures::io::Cursor::new(Vec::new());
+            codec
+                .write_response(&protocol, &mut socket, response())
+                .await
+                .unwrap();
+            let encoded = socket.into_inner();
+
+            match protocol {
+                MessageExchangePostcardProtocol::V1 => assert_eq!(encoded, serialized),
+                MessageExchangePostcardProtocol::V2 => {
+                    assert_eq!(encoded[..2], [VERSIONED_ENVELOPE_TAG, 2]);
+                    assert_eq!(encoded[2..], serialized);
+                }
+                MessageExchangePostcardProtocol::V3 => {
+                    assert_eq!(encoded[..2], [VERSIONED_ENVELOPE_TAG, 3]);
+                    assert_eq!(encoded[2..], serialized);
+                }
+                MessageExchangePostcardProtocol::V4 => {
+                    assert_eq!(encoded[..2], [VERSIONED_ENVELOPE_TAG, 4]);
+                    assert_eq!(encoded[2..], serialized);
+                }
+            }
+
+            let decoded = codec
+                .read_response(&protocol, &mut futures::io::Cursor::new(encoded))
+                .await
+                .unwrap();
+            assert!(matches!(
+                decoded,
+                ResponseMessage::Error(ResponseMessageError::NotFound)
+            ));
+        }
+    }
+
+    #[test]
+    fn tip_request_is_supported_since_v3() {
+        let tip = RequestMessage::Tip;
+        let headers = RequestMessage::SealedHeaders(0..1);
+
+        assert!(!MessageExchangePostcardProtocol::V1.supports(&tip));
+        assert!(!MessageExchangePostcardProtocol::V2.supports(&tip));
+        assert!(MessageExchangePostcardProtocol::V3.supports(&tip));
+        assert!(MessageExchangePostcardProtocol::ALL
+            .iter()
+            .all(|protocol| protocol.supports(&headers)));
+        assert_eq!(
+            MessageExchangePostcardProtocol::from_protocol_id(
+                REQUEST_RESPONSE_PROTOCOL_ID_V2
+            ),
+            Some(MessageExchangePostcardProtocol::V2)
+        );
+        assert_eq!(
+            MessageExchangePostcardProtocol::from_protocol_id("/fuel/req_res/9.9.9"),
+            None
+        );
+    }
+
+    #[test]
+    fn block_chunk_request_is_supported_since_v4() {
+        let chunk = RequestMessage::BlockChunk {
+            block_height: 1,
+            chunk_index: 0,
+        };
+
+        assert!(!MessageExchangePostcardProtocol::V3.supports(&chunk));
+        assert!(MessageExchangePostcardProtocol::V4.supports(&chunk));
+        assert!(postcard::to_stdvec(&chunk).unwrap().len() <= MAX_REQUEST_SIZE);
+    }
+
+    #[tokio::test]
+    async fn block_chunk_of_max_size_fits_the_response() {
+        let chunk_size = 1024;
+        let max_size = chunk_size + BLOCK_CHUNK_FRAMING_SIZE;
+        let response = ResponseMessage::BlockChunk(BlockChunk {
+            block_id: Default::default(),
+            chunk_count: u32::MAX,
+            data: vec![u8::MAX; chunk_size],
+        });
+
+        let mut codec = PostcardCodec::new(max_size);
+        let mut socket = futures::io::Cursor::new(Vec::new());
+        codec
+            .write_response(
+                &MessageExchangePostcardProtocol::LATEST,
+                &mut socket,
+                response,
+            )
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn gossip_message_with_unknown_version_fails_to_decode() {
+        let tx = Transaction::default_test_tx();
+        let v2_message = PostcardCodec::wrap(
+            MessageExchangePostcardProtocol::V2,
+            postcard::to_stdvec(&tx).unwrap(),
+        );
+        let mut unknown_message = v2_message.clone();
+        unknown_message[1] = 99;
+
+        let codec = PostcardCodec::new(MAX_REQUEST_SIZE);
+        let GossipsubMessage::NewTx(decoded) =
+            codec.decode(&v2_message, GossipTopicTag::NewTx).unwrap()
+        else {
+            panic!("Expected a single transaction")
+        };
+        assert_eq!(decoded, tx);
+        let err = codec
+            .decode(&unknown_message, GossipTopicTag::NewTx)
+            .unwrap_err();
+        assert_eq!(
+            UnknownWireVersion::from_io_error(&err),
+            Some(UnknownWireVersion(99))
+        );
+
+        let v1_codec = PostcardCodec::new(MAX_REQUEST_SIZE)
+            .with_max_protocol(MessageExchangePostcardProtocol::V1);
+        let err = v1_codec
+            .decode(&v2_message, GossipTopicTag::NewTx)
+            .unwrap_err();
+        assert_eq!(
+            UnknownWireVersion::from_io_error(&err),
+            Some(UnknownWireVersion(2))
+        );
+    }
 }
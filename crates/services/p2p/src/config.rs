@@ -1,10 +1,25 @@
 use crate::{
-    gossipsub::config::default_gossipsub_config,
+    gossipsub::{
+        config::{
+            default_gossipsub_config,
+            GossipMessageIdStrategy,
+            GossipsubScoringConfig,
+            MAX_MESH_SIZE,
+            MESH_SIZE,
+            MIN_MESH_SIZE,
+        },
+        messages::GossipTopicTag,
+    },
     heartbeat,
     peer_manager::ConnectionState,
+    peer_reputation::PeerReputationConfig,
+    request_response::messages::BLOCK_CHUNK_FRAMING_SIZE,
     TryPeerId,
 };
+use anyhow::Context;
 use fuel_core_types::blockchain::consensus::Genesis;
+use ip_network::IpNetwork;
+use semver::VersionReq;
 
 use libp2p::{
     core::{
@@ -29,16 +44,26 @@ use libp2p::{
 use libp2p_mplex::MplexConfig;
 use std::{
     collections::HashSet,
+    io::{
+        self,
+        Write,
+    },
     net::{
         IpAddr,
         Ipv4Addr,
     },
+    path::Path,
     sync::{
         Arc,
         RwLock,
     },
-    time::Duration,
+    time::{
+        Duration,
+        Instant,
+        SystemTime,
+    },
 };
+use thiserror::Error;
 
 use self::{
     connection_tracker::ConnectionTracker,
@@ -62,6 +87,12 @@ pub const MAX_RESPONSE_SIZE: usize = 18 * 1024 * 1024;
 /// Maximum number of headers per request.
 pub const MAX_HEADERS_PER_REQUEST: u32 = 100;
 
+/// Maximum number of inbound requests per second from a single peer.
+pub const MAX_REQUESTS_PER_SECOND: u32 = 20;
+
+/// Maximum number of inbound requests a single peer can send at once.
+pub const MAX_REQUESTS_BURST: u32 = 50;
+
 /// Adds a timeout to the setup and protocol upgrade process for all
 /// inbound and outbound connections established through the transport.
 const TRANSPORT_TIMEOUT: Duration = Duration::from_secs(20);
@@ -71,9 +102,13 @@ pub struct Config<State = Initialized> {
     /// The keypair used for for handshake during communication with other p2p nodes.
     pub keypair: Keypair,
 
-    /// Name of the Network
+    /// Name of the Network, the nodes with different names never connect
     pub network_name: String,
 
+    /// The gossip topics the node subscribes to at the start.
+    /// The node can publish to other topics and change the subscriptions later.
+    pub subscribed_topics: HashSet<GossipTopicTag>,
+
     /// Checksum is a hash(sha256) of [`Genesis`] - chain id.
     pub checksum: Checksum,
 
@@ -86,13 +121,124 @@ pub struct Config<State = Initialized> {
     /// The TCP port that Swarm listens on
     pub tcp_port: u16,
 
+    /// Additional addresses for Swarm to listen on, besides the `address` with the `tcp_port`.
+    /// It allows listening on IPv4 and IPv6 at the same time.
+    pub listen_addresses: Vec<Multiaddr>,
+
     /// Max Size of a Block in bytes
     pub max_block_size: usize,
+    /// The gossip messages and the responses larger than the threshold are compressed.
+    /// The compression is disabled if it is `None`.
+    pub compression_threshold: Option<usize>,
+    /// The broadcasted transactions are buffered for up to this interval
+    /// and published as a single message. The batching is disabled if it is `None`.
+    pub tx_batch_interval: Option<Duration>,
+    /// Max size of the transactions batch in bytes, the full batch is published immediately
+    pub tx_batch_max_size: usize,
+    /// Max number of the gossip messages held until the consumers are ready after the start.
+    /// The gossip is delivered immediately if it is `None`.
+    pub startup_gossip_buffer_size: Option<usize>,
+    /// Max number of the transactions received via gossip remembered to drop their duplicates
+    pub gossip_duplicates_cache_size: usize,
+    /// The transaction received via gossip again within this window isn't passed to the consumers
+    pub gossip_duplicates_ttl: Duration,
+    /// Max number of the gossiped transactions waiting for the slowest subscriber
+    pub tx_broadcast_capacity: usize,
+    /// What is dropped when the slowest subscriber of the transactions is
+    /// `tx_broadcast_capacity` behind
+    pub tx_overflow_policy: OverflowPolicy,
+    /// Max number of the block heights of the peers waiting for the slowest subscriber
+    pub block_height_broadcast_capacity: usize,
+    /// What is dropped when the slowest subscriber of the block heights is
+    /// `block_height_broadcast_capacity` behind
+    pub block_height_overflow_policy: OverflowPolicy,
+    /// Max number of the requests of the other services waiting for the p2p task
+    pub request_channel_capacity: usize,
+    /// What happens to the new request when `request_channel_capacity` requests are waiting.
+    /// Only `Block` and `DropNewest` are supported.
+    pub request_overflow_policy: OverflowPolicy,
+    /// Max number of the network events waiting for the slowest subscriber
+    pub network_event_broadcast_capacity: usize,
+    /// Max size of the gossiped transaction in bytes.
+    /// The larger messages are rejected without the decoding.
+    pub max_tx_gossip_size: usize,
+    /// Max size of the gossiped transactions batch in bytes.
+    /// It should fit the `tx_batch_max_size` of the peers plus one more transaction.
+    pub max_tx_batch_gossip_size: usize,
     pub max_headers_per_request: u32,
+    /// Size in bytes of the chunks of the block too large for one response.
+    /// The chunk with its framing should fit into the `max_block_size` of the peers.
+    pub block_chunk_size: usize,
+    /// Max number of the chunks of the blocks requested from a single peer at once
+    pub max_block_chunks_in_flight: usize,
+    /// The time to receive all chunks of the block, before the transfer is abandoned
+    pub block_chunks_timeout: Duration,
+    /// Max size in bytes of the block reassembled from the chunks
+    pub max_chunked_block_size: usize,
+    /// The rate of inbound requests per second allowed from a single peer
+    pub max_requests_per_second: u32,
+    /// The number of inbound requests a single peer can send at once
+    pub max_requests_burst: u32,
+    /// Drops the requests above the rate limit instead of answering them with an error
+    pub drop_rate_limited_requests: bool,
+    /// Max number of the response bytes sent to a single peer per minute.
+    /// The block requests above it are answered with `TooManyRequests` until the minute ends.
+    pub max_response_bytes_per_peer_per_minute: Option<u64>,
+    /// The number of distinct peers the same outbound request for headers is sent to.
+    /// The first successful response is used, the rest are ignored.
+    pub redundant_request_factor: u32,
+    /// Max number of the outbound requests awaiting the response.
+    /// The requests above it are rejected with `TooManyInflight` instead of being sent.
+    pub max_outbound_requests: Option<usize>,
+    /// Max number of the database lookups for the inbound requests running at once
+    pub max_concurrent_db_lookups: usize,
+    /// Max number of the database lookups waiting for their turn.
+    /// The inbound requests above the limit are rejected.
+    pub max_queued_db_lookups: usize,
+    /// Capacity of the channel delivering the results of the database lookups
+    pub db_lookup_responses_capacity: usize,
+    /// How long the service waits for the already received requests
+    /// to be answered during the shutdown
+    pub shutdown_grace_period: Duration,
+    /// How many times the service retries to start the network
+    /// if the previous attempt failed, e.g. because the port is busy
+    pub max_start_retries: u32,
+    /// The delay before the first retry to start the network.
+    /// It is doubled after each failed attempt.
+    pub start_retry_backoff: Duration,
+    /// The number of consecutive failures to pass the gossiped data to a consumer
+    /// after which the consumer is reported as gone. Zero disables the report.
+    pub consumer_gone_threshold: u32,
+    /// The number of the connected peers required for the node to report
+    /// that it has enough peers. Zero makes the node ready from the start.
+    pub min_peers_for_ready: usize,
+    /// How long the request for the headers waits for a peer that can serve it,
+    /// if none is connected. The requests fail right away if it is `None`.
+    pub request_queue_timeout: Option<Duration>,
+    /// Max number of the requests waiting for a peer. The oldest request
+    /// is rejected when a new one doesn't fit.
+    pub request_queue_capacity: usize,
+    /// The remembered peers not seen for longer than this age
+    /// aren't dialed on the startup and are removed from the database.
+    pub known_peers_max_age: Duration,
+    /// Max number of the most recently seen remembered peers dialed on the startup.
+    /// Zero disables the dialing.
+    pub max_known_peers_to_dial: usize,
+    /// How often the connected peers are saved to the database.
+    /// They are also saved during the shutdown.
+    pub known_peers_save_interval: Duration,
+    /// The same warning, repeated for every message or request, is logged
+    /// at most once per this interval. Zero logs every warning.
+    pub warn_logs_interval: Duration,
+    /// Advertises to the peers that the node serves all blocks since the genesis
+    pub is_archival: bool,
 
     // `DiscoveryBehaviour` related fields
     pub bootstrap_nodes: Vec<Multiaddr>,
+    /// Discovers the peers on the local network, including the ones of other networks
     pub enable_mdns: bool,
+    /// Discovers the peers beyond the bootstrap nodes via the Kademlia DHT
+    pub enable_kademlia: bool,
     pub allow_private_addresses: bool,
     pub random_walk: Option<Duration>,
     pub connection_idle_timeout: Option<Duration>,
@@ -102,6 +248,25 @@ pub struct Config<State = Initialized> {
     pub reserved_nodes: Vec<Multiaddr>,
     /// Should the node only accept connection requests from the Reserved Nodes
     pub reserved_nodes_only_mode: bool,
+    /// The delay before the second redial of a disconnected reserved or bootstrap node,
+    /// doubled for each following redial
+    pub redial_backoff_base: Duration,
+    /// The upper bound of the delay between redials of a disconnected node
+    pub redial_backoff_max: Duration,
+    /// The warning is logged once the reserved or bootstrap node fails this number of dials in a row
+    pub redial_warn_after_failures: u32,
+
+    // Connection gating
+    /// If set, the node connects only to these peers, in both directions.
+    /// The reserved and bootstrap nodes should be in the list as well.
+    pub allowed_peers: Option<Vec<PeerId>>,
+    /// The node doesn't connect to the addresses in these IP ranges, in both directions
+    pub denied_ips: Vec<IpNetwork>,
+    /// If set, the identified peers running the fuel-core version
+    /// not matching the requirement are disconnected
+    pub min_peer_version: Option<VersionReq>,
+    /// Disconnect the peers with the unknown version as well, if `min_peer_version` is set
+    pub strict_peer_version: bool,
 
     // `PeerManager` fields
     /// Max number of unique peers connected
@@ -111,6 +276,14 @@ pub struct Config<State = Initialized> {
     /// Max number of connections per single peer
     /// The total number of connections will be `(max_peers_connected + reserved_nodes.len()) * max_connections_per_peer`
     pub max_connections_per_peer: u32,
+    /// Max number of established inbound connections, unlimited if `None`
+    pub max_inbound_connections: Option<u32>,
+    /// Max number of established outbound connections, unlimited if `None`
+    pub max_outbound_connections: Option<u32>,
+    /// Non-reserved peers that send no gossip, requests or responses for this period
+    /// are disconnected, even if the protocols keep their connections alive.
+    /// Disabled if `None`.
+    pub idle_connection_timeout: Option<Duration>,
     /// The interval at which identification requests are sent to
     /// the remote on established connections after the first request
     pub identify_interval: Option<Duration>,
@@ -120,6 +293,20 @@ pub struct Config<State = Initialized> {
 
     // `Gossipsub` config
     pub gossipsub_config: gossipsub::Config,
+    /// How the ids of the gossip messages are computed.
+    /// Overrides the message id function of the `gossipsub_config`.
+    pub gossip_message_id_strategy: GossipMessageIdStrategy,
+    /// The ideal number of peers in the gossipsub mesh (`D`).
+    /// Overrides the value from the `gossipsub_config`.
+    pub ideal_mesh_size: usize,
+    /// The minimum number of peers in the gossipsub mesh (`D_low`).
+    /// Overrides the value from the `gossipsub_config`.
+    pub min_mesh_size: usize,
+    /// The maximum number of peers in the gossipsub mesh (`D_high`).
+    /// Overrides the value from the `gossipsub_config`.
+    pub max_mesh_size: usize,
+    /// The gossipsub peer scoring parameters
+    pub gossipsub_scoring_config: GossipsubScoringConfig,
 
     pub heartbeat_config: heartbeat::Config,
 
@@ -136,6 +323,15 @@ pub struct Config<State = Initialized> {
     /// Max time since a given peer has sent a heartbeat before getting reputation penalty
     pub heartbeat_max_time_since_last: Duration,
 
+    /// Application-level reputation of peers based on the gossiped messages
+    pub peer_reputation_config: PeerReputationConfig,
+
+    /// The security protocol used to authenticate and encrypt connections
+    pub transport_security: TransportSecurity,
+
+    /// The strategy used to pick the peer for an outbound request
+    pub peer_selection_strategy: PeerSelectionStrategy,
+
     /// Enables prometheus metrics for this fuel-service
     pub metrics: bool,
 
@@ -160,36 +356,261 @@ impl Config<NotInitialized> {
         Ok(Config {
             keypair: self.keypair,
             network_name: self.network_name,
+            subscribed_topics: self.subscribed_topics,
             checksum: genesis.root()?.into(),
             address: self.address,
             public_address: self.public_address,
             tcp_port: self.tcp_port,
+            listen_addresses: self.listen_addresses,
             max_block_size: self.max_block_size,
+            compression_threshold: self.compression_threshold,
+            tx_batch_interval: self.tx_batch_interval,
+            tx_batch_max_size: self.tx_batch_max_size,
+            startup_gossip_buffer_size: self.startup_gossip_buffer_size,
+            gossip_duplicates_cache_size: self.gossip_duplicates_cache_size,
+            gossip_duplicates_ttl: self.gossip_duplicates_ttl,
+            tx_broadcast_capacity: self.tx_broadcast_capacity,
+            tx_overflow_policy: self.tx_overflow_policy,
+            block_height_broadcast_capacity: self.block_height_broadcast_capacity,
+            block_height_overflow_policy: self.block_height_overflow_policy,
+            request_channel_capacity: self.request_channel_capacity,
+            request_overflow_policy: self.request_overflow_policy,
+            network_event_broadcast_capacity: self.network_event_broadcast_capacity,
+            max_tx_gossip_size: self.max_tx_gossip_size,
+            max_tx_batch_gossip_size: self.max_tx_batch_gossip_size,
             max_headers_per_request: self.max_headers_per_request,
+            block_chunk_size: self.block_chunk_size,
+            max_block_chunks_in_flight: self.max_block_chunks_in_flight,
+            block_chunks_timeout: self.block_chunks_timeout,
+            max_chunked_block_size: self.max_chunked_block_size,
+            max_requests_per_second: self.max_requests_per_second,
+            max_requests_burst: self.max_requests_burst,
+            drop_rate_limited_requests: self.drop_rate_limited_requests,
+            max_response_bytes_per_peer_per_minute: self
+                .max_response_bytes_per_peer_per_minute,
+            redundant_request_factor: self.redundant_request_factor,
+            max_outbound_requests: self.max_outbound_requests,
+            max_concurrent_db_lookups: self.max_concurrent_db_lookups,
+            max_queued_db_lookups: self.max_queued_db_lookups,
+            db_lookup_responses_capacity: self.db_lookup_responses_capacity,
+            shutdown_grace_period: self.shutdown_grace_period,
+            max_start_retries: self.max_start_retries,
+            start_retry_backoff: self.start_retry_backoff,
+            consumer_gone_threshold: self.consumer_gone_threshold,
+            min_peers_for_ready: self.min_peers_for_ready,
+            request_queue_timeout: self.request_queue_timeout,
+            request_queue_capacity: self.request_queue_capacity,
+            known_peers_max_age: self.known_peers_max_age,
+            max_known_peers_to_dial: self.max_known_peers_to_dial,
+            known_peers_save_interval: self.known_peers_save_interval,
+            warn_logs_interval: self.warn_logs_interval,
+            is_archival: self.is_archival,
             bootstrap_nodes: self.bootstrap_nodes,
             enable_mdns: self.enable_mdns,
+            enable_kademlia: self.enable_kademlia,
             max_peers_connected: self.max_peers_connected,
             max_connections_per_peer: self.max_connections_per_peer,
+            max_inbound_connections: self.max_inbound_connections,
+            max_outbound_connections: self.max_outbound_connections,
+            idle_connection_timeout: self.idle_connection_timeout,
             allow_private_addresses: self.allow_private_addresses,
             random_walk: self.random_walk,
             connection_idle_timeout: self.connection_idle_timeout,
             reserved_nodes: self.reserved_nodes,
             reserved_nodes_only_mode: self.reserved_nodes_only_mode,
+            redial_backoff_base: self.redial_backoff_base,
+            redial_backoff_max: self.redial_backoff_max,
+            redial_warn_after_failures: self.redial_warn_after_failures,
+            allowed_peers: self.allowed_peers,
+            denied_ips: self.denied_ips,
+            min_peer_version: self.min_peer_version,
+            strict_peer_version: self.strict_peer_version,
             identify_interval: self.identify_interval,
             info_interval: self.info_interval,
             gossipsub_config: self.gossipsub_config,
+            gossip_message_id_strategy: self.gossip_message_id_strategy,
+            ideal_mesh_size: self.ideal_mesh_size,
+            min_mesh_size: self.min_mesh_size,
+            max_mesh_size: self.max_mesh_size,
+            gossipsub_scoring_config: self.gossipsub_scoring_config,
             heartbeat_config: self.heartbeat_config,
             set_request_timeout: self.set_request_timeout,
             set_connection_keep_alive: self.set_connection_keep_alive,
             heartbeat_check_interval: self.heartbeat_check_interval,
             heartbeat_max_avg_interval: self.heartbeat_max_time_since_last,
             heartbeat_max_time_since_last: self.heartbeat_max_time_since_last,
+            peer_reputation_config: self.peer_reputation_config,
+            transport_security: self.transport_security,
+            peer_selection_strategy: self.peer_selection_strategy,
             metrics: self.metrics,
             state: Initialized(()),
         })
     }
 }
 
+impl<State> Config<State> {
+    /// Checks the consistency of the configuration values.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let Self {
+            ideal_mesh_size,
+            min_mesh_size,
+            max_mesh_size,
+            ref gossipsub_scoring_config,
+            ref peer_reputation_config,
+            known_peers_save_interval,
+            tx_overflow_policy,
+            block_height_overflow_policy,
+            request_overflow_policy,
+            max_block_size,
+            block_chunk_size,
+            max_concurrent_db_lookups,
+            db_lookup_responses_capacity,
+            ..
+        } = *self;
+
+        if !(min_mesh_size <= ideal_mesh_size && ideal_mesh_size <= max_mesh_size) {
+            return Err(ConfigError::InvalidMeshSize {
+                min_mesh_size,
+                ideal_mesh_size,
+                max_mesh_size,
+            })
+        }
+
+        gossipsub_scoring_config
+            .validate()
+            .map_err(ConfigError::InvalidGossipsubScoring)?;
+
+        // The ban expiry is tracked in memory and persisted as the wall-clock time
+        let ban_duration = peer_reputation_config.ban_duration;
+        if !fits_current_time(ban_duration) {
+            return Err(ConfigError::DurationTooLarge {
+                name: "ban_duration",
+                duration: ban_duration,
+            })
+        }
+
+        if !fits_current_time(known_peers_save_interval) {
+            return Err(ConfigError::DurationTooLarge {
+                name: "known_peers_save_interval",
+                duration: known_peers_save_interval,
+            })
+        }
+
+        // The broadcasts are sent from the p2p event loop, which can't wait for the consumers
+        for (channel, policy) in [
+            ("transactions", tx_overflow_policy),
+            ("block_heights", block_height_overflow_policy),
+        ] {
+            if policy == OverflowPolicy::Block {
+                return Err(ConfigError::UnsupportedOverflowPolicy { channel, policy })
+            }
+        }
+
+        if block_chunk_size == 0
+            || block_chunk_size.saturating_add(BLOCK_CHUNK_FRAMING_SIZE) > max_block_size
+        {
+            return Err(ConfigError::InvalidBlockChunkSize {
+                block_chunk_size,
+                max_block_size,
+            })
+        }
+
+        // No lookup would ever run without the permits,
+        // and the channel of the results can't be created without the capacity
+        for (name, value) in [
+            ("max_concurrent_db_lookups", max_concurrent_db_lookups),
+            ("db_lookup_responses_capacity", db_lookup_responses_capacity),
+        ] {
+            if value == 0 {
+                return Err(ConfigError::ZeroValue { name })
+            }
+        }
+
+        // The sender of the request can't remove the requests queued before it
+        if request_overflow_policy == OverflowPolicy::DropOldest {
+            return Err(ConfigError::UnsupportedOverflowPolicy {
+                channel: "requests",
+                policy: request_overflow_policy,
+            })
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns `true` if the `duration` can be added to the current time of both clocks
+fn fits_current_time(duration: Duration) -> bool {
+    SystemTime::now().checked_add(duration).is_some()
+        && Instant::now().checked_add(duration).is_some()
+}
+
+/// Errors that can occur during validation of the `Config`
+#[derive(Debug, Eq, PartialEq, Error)]
+pub enum ConfigError {
+    #[error("The mesh size should satisfy `min({min_mesh_size}) <= ideal({ideal_mesh_size}) <= max({max_mesh_size})`")]
+    InvalidMeshSize {
+        min_mesh_size: usize,
+        ideal_mesh_size: usize,
+        max_mesh_size: usize,
+    },
+    #[error("Invalid gossipsub scoring config: {0}")]
+    InvalidGossipsubScoring(String),
+    #[error("The `{name}` of {duration:?} is too large to be added to the current time")]
+    DurationTooLarge {
+        name: &'static str,
+        duration: Duration,
+    },
+    #[error("The `{name}` should be greater than zero")]
+    ZeroValue { name: &'static str },
+    #[error("The block chunk size of {block_chunk_size} bytes with its framing doesn't fit the max block size of {max_block_size} bytes")]
+    InvalidBlockChunkSize {
+        block_chunk_size: usize,
+        max_block_size: usize,
+    },
+    #[error(
+        "The `{policy:?}` overflow policy isn't supported by the `{channel}` channel"
+    )]
+    UnsupportedOverflowPolicy {
+        channel: &'static str,
+        policy: OverflowPolicy,
+    },
+}
+
+/// The security protocol negotiated on top of the raw transport.
+/// All connections of the node use the same protocol.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TransportSecurity {
+    /// The Noise protocol with the `XX` handshake pattern
+    #[default]
+    Noise,
+}
+
+/// The strategy used to pick a peer among the ones able to serve an outbound request.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PeerSelectionStrategy {
+    /// Picks a random peer
+    #[default]
+    Random,
+    /// The peers take turns, in the order of their ids
+    RoundRobin,
+    /// Picks the peer that answered its latest request the fastest.
+    /// Peers that haven't answered any request yet are tried first.
+    LowestLatency,
+}
+
+/// What happens to the new data when the slowest consumer of the channel is too far behind.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OverflowPolicy {
+    /// The oldest data is overwritten, so the slowest consumer skips it
+    #[default]
+    DropOldest,
+    /// The new data isn't passed to the consumers until the slowest one catches up
+    DropNewest,
+    /// The sender waits until the consumer frees a slot.
+    /// Only the requests to the p2p task support it.
+    Block,
+}
+
 /// Takes secret key bytes generated outside of libp2p.
 /// And converts it into libp2p's `Keypair::Secp256k1`.
 pub fn convert_to_libp2p_keypair(
@@ -201,6 +622,44 @@ pub fn convert_to_libp2p_keypair(
     Ok(keypair.into())
 }
 
+/// Loads the keypair from the file with the hex encoded secret key.
+/// If the file doesn't exist, the new keypair is generated and written to it,
+/// so the peer id of the node stays the same across the restarts.
+/// Fails if the file exists but can't be read or doesn't contain a valid key.
+pub fn load_or_generate_keypair(path: &Path) -> anyhow::Result<Keypair> {
+    match std::fs::read_to_string(path) {
+        Ok(secret) => hex::decode(secret.trim())
+            .map_err(anyhow::Error::from)
+            .and_then(convert_to_libp2p_keypair)
+            .with_context(|| {
+                format!("The p2p keypair file `{}` is corrupted", path.display())
+            }),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            let keypair = secp256k1::Keypair::generate();
+            let secret_key = keypair.secret().to_bytes();
+            write_secret_file(path, hex::encode(secret_key).as_bytes()).with_context(
+                || format!("Failed to write the p2p keypair to `{}`", path.display()),
+            )?;
+            Ok(keypair.into())
+        }
+        Err(err) => Err(err).with_context(|| {
+            format!("Failed to read the p2p keypair from `{}`", path.display())
+        }),
+    }
+}
+
+/// Creates the file readable only by the owner
+fn write_secret_file(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(data)
+}
+
 impl Config<NotInitialized> {
     pub fn default(network_name: &str) -> Self {
         let keypair = Keypair::generate_secp256k1();
@@ -208,28 +667,89 @@ impl Config<NotInitialized> {
         Self {
             keypair,
             network_name: network_name.into(),
+            subscribed_topics: GossipTopicTag::ALL.into_iter().collect(),
             checksum: Default::default(),
             address: IpAddr::V4(Ipv4Addr::from([0, 0, 0, 0])),
             public_address: None,
             tcp_port: 0,
+            listen_addresses: vec![],
             max_block_size: MAX_RESPONSE_SIZE,
+            compression_threshold: None,
+            tx_batch_interval: None,
+            tx_batch_max_size: 64 * 1024,
+            startup_gossip_buffer_size: None,
+            gossip_duplicates_cache_size: 1024,
+            gossip_duplicates_ttl: Duration::from_secs(30),
+            tx_broadcast_capacity: 1024 * 10,
+            tx_overflow_policy: OverflowPolicy::default(),
+            block_height_broadcast_capacity: 1024 * 10,
+            block_height_overflow_policy: OverflowPolicy::default(),
+            request_channel_capacity: 1024 * 10,
+            request_overflow_policy: OverflowPolicy::Block,
+            network_event_broadcast_capacity: 1024 * 10,
+            max_tx_gossip_size: 256 * 1024,
+            max_tx_batch_gossip_size: 1024 * 1024,
             max_headers_per_request: MAX_HEADERS_PER_REQUEST,
+            block_chunk_size: 1024 * 1024,
+            max_block_chunks_in_flight: 4,
+            block_chunks_timeout: Duration::from_secs(60),
+            max_chunked_block_size: 64 * 1024 * 1024,
+            max_requests_per_second: MAX_REQUESTS_PER_SECOND,
+            max_requests_burst: MAX_REQUESTS_BURST,
+            drop_rate_limited_requests: false,
+            max_response_bytes_per_peer_per_minute: None,
+            redundant_request_factor: 1,
+            max_outbound_requests: None,
+            max_concurrent_db_lookups: 16,
+            max_queued_db_lookups: 1024,
+            db_lookup_responses_capacity: 100,
+            shutdown_grace_period: Duration::from_secs(2),
+            max_start_retries: 5,
+            start_retry_backoff: Duration::from_secs(1),
+            consumer_gone_threshold: 10,
+            min_peers_for_ready: 1,
+            request_queue_timeout: None,
+            request_queue_capacity: 100,
+            known_peers_max_age: Duration::from_secs(60 * 60 * 24 * 7),
+            max_known_peers_to_dial: 20,
+            known_peers_save_interval: Duration::from_secs(60 * 5),
+            warn_logs_interval: Duration::from_secs(10),
+            is_archival: false,
             bootstrap_nodes: vec![],
             enable_mdns: false,
+            enable_kademlia: true,
             max_peers_connected: 50,
             max_connections_per_peer: 3,
+            max_inbound_connections: None,
+            max_outbound_connections: None,
+            idle_connection_timeout: None,
             allow_private_addresses: true,
             random_walk: Some(Duration::from_millis(500)),
             connection_idle_timeout: Some(Duration::from_secs(120)),
             reserved_nodes: vec![],
             reserved_nodes_only_mode: false,
+            redial_backoff_base: Duration::from_secs(1),
+            redial_backoff_max: Duration::from_secs(60),
+            redial_warn_after_failures: 5,
+            allowed_peers: None,
+            denied_ips: vec![],
+            min_peer_version: None,
+            strict_peer_version: false,
             gossipsub_config: default_gossipsub_config(),
+            gossip_message_id_strategy: GossipMessageIdStrategy::default(),
+            ideal_mesh_size: MESH_SIZE,
+            min_mesh_size: MIN_MESH_SIZE,
+            max_mesh_size: MAX_MESH_SIZE,
+            gossipsub_scoring_config: GossipsubScoringConfig::default(),
             heartbeat_config: heartbeat::Config::default(),
             set_request_timeout: REQ_RES_TIMEOUT,
             set_connection_keep_alive: REQ_RES_TIMEOUT,
             heartbeat_check_interval: Duration::from_secs(10),
             heartbeat_max_avg_interval: Duration::from_secs(20),
             heartbeat_max_time_since_last: Duration::from_secs(40),
+            peer_reputation_config: PeerReputationConfig::default(),
+            transport_security: TransportSecurity::default(),
+            peer_selection_strategy: PeerSelectionStrategy::default(),
             info_interval: Some(Duration::from_secs(3)),
             identify_interval: Some(Duration::from_secs(5)),
             metrics: false,
@@ -249,7 +769,7 @@ impl Config<Initialized> {
 
 /// Transport for libp2p communication:
 /// TCP/IP, Websocket
-/// Noise as encryption layer
+/// Noise as encryption layer, see [`TransportSecurity`]
 /// mplex or yamux for multiplexing
 pub(crate) fn build_transport_function(
     p2p_config: &Config,
@@ -274,8 +794,11 @@ pub(crate) fn build_transport_function(
         }
         .upgrade(libp2p::core::upgrade::Version::V1Lazy);
 
-        let noise_authenticated =
-            noise::Config::new(keypair).expect("Noise key generation failed");
+        let security = match p2p_config.transport_security {
+            TransportSecurity::Noise => {
+                noise::Config::new(keypair).expect("Noise key generation failed")
+            }
+        };
 
         let multiplex_config = {
             let mplex_config = MplexConfig::default();
@@ -291,9 +814,10 @@ pub(crate) fn build_transport_function(
             let guarded_node = GuardedNode::new(&p2p_config.reserved_nodes);
 
             let fuel_authenticated = FuelAuthenticated::new(
-                noise_authenticated,
+                security,
                 guarded_node,
                 p2p_config.checksum,
+                p2p_config.network_name.clone(),
             );
 
             transport
@@ -308,9 +832,10 @@ pub(crate) fn build_transport_function(
             );
 
             let fuel_authenticated = FuelAuthenticated::new(
-                noise_authenticated,
+                security,
                 connection_tracker,
                 p2p_config.checksum,
+                p2p_config.network_name.clone(),
             );
 
             transport
@@ -332,3 +857,42 @@ fn peer_ids_set_from(multiaddr: &[Multiaddr]) -> HashSet<PeerId> {
         .map(|address| address.try_to_peer_id().unwrap())
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_keypair_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("p2p-keypair-{}", rand::random::<u64>()))
+    }
+
+    #[test]
+    fn generated_keypair_is_loaded_after_restart() {
+        let path = temp_keypair_path();
+
+        let generated = load_or_generate_keypair(&path).unwrap();
+        let loaded = load_or_generate_keypair(&path).unwrap();
+
+        assert_eq!(generated.public(), loaded.public());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn corrupted_keypair_file_is_rejected() {
+        let path = temp_keypair_path();
+        std::fs::write(&path, "not a secret key").unwrap();
+
+        let err = load_or_generate_keypair(&path).unwrap_err();
+
+        assert!(err.to_string().contains(&path.display().to_string()));
+        // The file isn't overwritten
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "not a secret key");
+        std::fs::remove_file(path).unwrap();
+    }
+}
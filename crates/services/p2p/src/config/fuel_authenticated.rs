@@ -30,6 +30,8 @@ pub(crate) struct FuelAuthenticated<A: Approver> {
     noise_authenticated: noise::Config,
     approver: A,
     checksum: Checksum,
+    /// The nodes of different networks can't connect even if they find each other
+    network_name: String,
 }
 
 impl<A: Approver> FuelAuthenticated<A> {
@@ -37,11 +39,13 @@ impl<A: Approver> FuelAuthenticated<A> {
         noise_authenticated: noise::Config,
         approver: A,
         checksum: Checksum,
+        network_name: String,
     ) -> Self {
         Self {
             noise_authenticated,
             approver,
             checksum,
+            network_name,
         }
     }
 }
@@ -57,7 +61,12 @@ impl<A: Approver> UpgradeInfo for FuelAuthenticated<A> {
             .next()
             .expect("Noise always has a protocol info");
 
-        std::iter::once(format!("{}/{}", noise, hex::encode(self.checksum.as_ref())))
+        std::iter::once(format!(
+            "{}/{}/{}",
+            noise,
+            hex::encode(self.checksum.as_ref()),
+            self.network_name
+        ))
     }
 }
 
@@ -0,0 +1,199 @@
+use crate::config::Config;
+use fuel_core_metrics::p2p_metrics::p2p_metrics;
+use ip_network::IpNetwork;
+use libp2p::{
+    core::Endpoint,
+    multiaddr::Protocol,
+    swarm::{
+        dummy,
+        ConnectionDenied,
+        ConnectionId,
+        FromSwarm,
+        NetworkBehaviour,
+        THandler,
+        THandlerInEvent,
+        THandlerOutEvent,
+        ToSwarm,
+    },
+    Multiaddr,
+    PeerId,
+};
+use std::{
+    collections::HashSet,
+    net::IpAddr,
+    task::{
+        Context,
+        Poll,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+use thiserror::Error;
+use void::Void;
+
+/// The denied connections are logged at most once per this interval
+const DENIED_LOG_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The reason the connection is denied
+#[derive(Debug, Error)]
+pub enum ConnectionGateError {
+    #[error("The peer {0} isn't in the allowed peers")]
+    PeerNotAllowed(PeerId),
+    #[error("The address {0} is in the denied IP ranges")]
+    AddressDenied(Multiaddr),
+}
+
+/// `Behaviour` that denies the connections with the peers outside of the `allowed_peers`
+/// and with the addresses in the `denied_ips` ranges, in both directions.
+/// The connections are denied before any protocol of the node runs on them.
+pub struct Behaviour {
+    /// All peers are allowed if it is `None`
+    allowed_peers: Option<HashSet<PeerId>>,
+    denied_ips: Vec<IpNetwork>,
+    metrics: bool,
+    last_log: Option<Instant>,
+    /// The number of the denied connections since the last log
+    not_logged: u64,
+}
+
+impl Behaviour {
+    pub(crate) fn new(config: &Config) -> Self {
+        Self {
+            allowed_peers: config
+                .allowed_peers
+                .as_ref()
+                .map(|peers| peers.iter().copied().collect()),
+            denied_ips: config.denied_ips.clone(),
+            metrics: config.metrics,
+            last_log: None,
+            not_logged: 0,
+        }
+    }
+
+    fn check_peer(&mut self, peer_id: &PeerId) -> Result<(), ConnectionDenied> {
+        match &self.allowed_peers {
+            Some(allowed_peers) if !allowed_peers.contains(peer_id) => {
+                Err(self.deny(ConnectionGateError::PeerNotAllowed(*peer_id)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_address(&mut self, address: &Multiaddr) -> Result<(), ConnectionDenied> {
+        if self.is_denied(address) {
+            Err(self.deny(ConnectionGateError::AddressDenied(address.clone())))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn is_denied(&self, address: &Multiaddr) -> bool {
+        let ip = address.iter().find_map(|protocol| match protocol {
+            Protocol::Ip4(ip) => Some(IpAddr::V4(ip)),
+            Protocol::Ip6(ip) => Some(IpAddr::V6(ip)),
+            _ => None,
+        });
+        ip.is_some_and(|ip| self.denied_ips.iter().any(|network| network.contains(ip)))
+    }
+
+    /// Counts the denied connection and logs it, unless another one was logged recently
+    fn deny(&mut self, error: ConnectionGateError) -> ConnectionDenied {
+        if self.metrics {
+            p2p_metrics().denied_connections.inc();
+        }
+
+        let now = Instant::now();
+        let log = match self.last_log {
+            Some(last_log) => now.duration_since(last_log) >= DENIED_LOG_INTERVAL,
+            None => true,
+        };
+        if log {
+            tracing::debug!(
+                target: "fuel-p2p",
+                "Denied the connection: {error}, {} more connections were denied since the last log",
+                self.not_logged
+            );
+            self.last_log = Some(now);
+            self.not_logged = 0;
+        } else {
+            self.not_logged = self.not_logged.saturating_add(1);
+        }
+
+        ConnectionDenied::new(error)
+    }
+}
+
+impl NetworkBehaviour for Behaviour {
+    type ConnectionHandler = dummy::ConnectionHandler;
+    type ToSwarm = Void;
+
+    fn handle_pending_inbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        _local_addr: &Multiaddr,
+        remote_addr: &Multiaddr,
+    ) -> Result<(), ConnectionDenied> {
+        self.check_address(remote_addr)
+    }
+
+    fn handle_established_inbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        peer: PeerId,
+        _local_addr: &Multiaddr,
+        _remote_addr: &Multiaddr,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        self.check_peer(&peer)?;
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn handle_pending_outbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        maybe_peer: Option<PeerId>,
+        addresses: &[Multiaddr],
+        _effective_role: Endpoint,
+    ) -> Result<Vec<Multiaddr>, ConnectionDenied> {
+        if let Some(peer) = maybe_peer {
+            self.check_peer(&peer)?;
+        }
+        // The behaviour can only add the addresses to dial, not remove them,
+        // so the whole dial is refused if any of its addresses is denied
+        for address in addresses {
+            self.check_address(address)?;
+        }
+        Ok(vec![])
+    }
+
+    fn handle_established_outbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        peer: PeerId,
+        addr: &Multiaddr,
+        _role_override: Endpoint,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        self.check_peer(&peer)?;
+        self.check_address(addr)?;
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn on_swarm_event(&mut self, _event: FromSwarm) {}
+
+    fn on_connection_handler_event(
+        &mut self,
+        _peer_id: PeerId,
+        _connection_id: ConnectionId,
+        event: THandlerOutEvent<Self>,
+    ) {
+        void::unreachable(event)
+    }
+
+    fn poll(
+        &mut self,
+        _cx: &mut Context<'_>,
+    ) -> Poll<ToSwarm<Self::ToSwarm, THandlerInEvent<Self>>> {
+        Poll::Pending
+    }
+}
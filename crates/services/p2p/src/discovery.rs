@@ -1,18 +1,26 @@
 use self::mdns_wrapper::MdnsWrapper;
+use fuel_core_metrics::p2p_metrics::p2p_metrics;
 use futures::FutureExt;
 use libp2p::{
-    core::Endpoint,
+    core::{
+        transport::ListenerId,
+        Endpoint,
+    },
     kad::{
         self,
         store::MemoryStore,
+        Mode,
     },
     mdns,
     swarm::{
         derive_prelude::{
             ConnectionClosed,
             ConnectionEstablished,
+            ExpiredListenAddr,
             FromSwarm,
+            NewListenAddr,
         },
+        dial_opts::DialOpts,
         ConnectionDenied,
         ConnectionId,
         NetworkBehaviour,
@@ -28,7 +36,10 @@ use libp2p::swarm::{
     ToSwarm,
 };
 use std::{
-    collections::HashSet,
+    collections::{
+        HashSet,
+        VecDeque,
+    },
     pin::Pin,
     task::{
         Context,
@@ -45,17 +56,42 @@ const SIXTY_SECONDS: Duration = Duration::from_secs(60);
 
 pub type Event = kad::Event;
 
+/// The discovery mechanisms active now and the peers found by them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiscoveryStatus {
+    pub mdns_enabled: bool,
+    pub kad_enabled: bool,
+    /// The number of distinct peers found on the local network
+    pub mdns_discovered_peers: usize,
+    /// The number of peers in the Kademlia routing table
+    pub kad_routing_table_size: usize,
+}
+
 /// NetworkBehavior for discovery of nodes
 pub struct Behaviour {
+    local_peer_id: PeerId,
+
     /// Track the connected peers
     connected_peers: HashSet<PeerId>,
 
+    /// The addresses the node listens on, announced by mDNS once it is enabled
+    listen_addresses: Vec<(ListenerId, Multiaddr)>,
+
     /// For discovery on local network, optionally available
     mdns: MdnsWrapper,
 
+    /// The peers found by mDNS
+    mdns_discovered_peers: HashSet<PeerId>,
+
+    /// The mDNS-found peers are dialed directly while Kademlia is disabled
+    pending_dials: VecDeque<DialOpts>,
+
     /// Kademlia with MemoryStore
     kademlia: kad::Behaviour<MemoryStore>,
 
+    /// Whether the node queries the DHT and serves it to other peers
+    kad_enabled: bool,
+
     /// If enabled, the Stream that will fire after the delay expires,
     /// starting new random walk
     next_kad_random_walk: Option<Pin<Box<tokio::time::Sleep>>>,
@@ -65,6 +101,9 @@ pub struct Behaviour {
 
     /// Maximum amount of allowed peers
     max_peers_connected: usize,
+
+    /// Reports the size of the routing table to the metrics
+    metrics: bool,
 }
 
 impl Behaviour {
@@ -72,6 +111,71 @@ impl Behaviour {
     pub fn add_address(&mut self, peer_id: &PeerId, address: Multiaddr) {
         self.kademlia.add_address(peer_id, address);
     }
+
+    /// The number of peers in the Kademlia routing table
+    pub fn routing_table_size(&mut self) -> usize {
+        self.kademlia
+            .kbuckets()
+            .map(|bucket| bucket.num_entries())
+            .sum()
+    }
+
+    /// Starts or stops the discovery on the local network.
+    /// The stopped mDNS doesn't announce the node anymore.
+    pub fn set_mdns(&mut self, enabled: bool) {
+        let running = matches!(self.mdns, MdnsWrapper::Ready(_));
+        match (running, enabled) {
+            (false, true) => {
+                self.mdns = MdnsWrapper::new(self.local_peer_id);
+                // The new mDNS only learns about the addresses it should announce from the swarm events
+                for (listener_id, addr) in &self.listen_addresses {
+                    self.mdns
+                        .on_swarm_event(&FromSwarm::NewListenAddr(NewListenAddr {
+                            listener_id: *listener_id,
+                            addr,
+                        }));
+                }
+            }
+            (true, false) => {
+                self.mdns = MdnsWrapper::disabled();
+                self.pending_dials.clear();
+            }
+            _ => {}
+        }
+    }
+
+    /// Starts or stops querying the DHT and serving it to other peers.
+    /// The known addresses stay in the routing table.
+    pub fn set_kad(&mut self, enabled: bool) {
+        if self.kad_enabled == enabled {
+            return
+        }
+        self.kad_enabled = enabled;
+        if enabled {
+            self.kademlia.set_mode(Some(Mode::Server));
+            if let Err(e) = self.kademlia.bootstrap() {
+                tracing::warn!("Kademlia bootstrap failed: {}", e);
+            }
+        } else {
+            self.kademlia.set_mode(Some(Mode::Client));
+        }
+    }
+
+    /// Starts the query of the DHT for the peers closest to a random key.
+    /// Returns `None` if Kademlia is disabled.
+    pub fn find_peers(&mut self) -> Option<kad::QueryId> {
+        self.kad_enabled
+            .then(|| self.kademlia.get_closest_peers(PeerId::random()))
+    }
+
+    pub fn status(&mut self) -> DiscoveryStatus {
+        DiscoveryStatus {
+            mdns_enabled: matches!(self.mdns, MdnsWrapper::Ready(_)),
+            kad_enabled: self.kad_enabled,
+            mdns_discovered_peers: self.mdns_discovered_peers.len(),
+            kad_routing_table_size: self.routing_table_size(),
+        }
+    }
 }
 
 impl NetworkBehaviour for Behaviour {
@@ -156,6 +260,13 @@ impl NetworkBehaviour for Behaviour {
                     trace!("Disconnected from {:?}", peer_id);
                 }
             }
+            FromSwarm::NewListenAddr(NewListenAddr { listener_id, addr }) => {
+                self.listen_addresses.push((*listener_id, (*addr).clone()));
+            }
+            FromSwarm::ExpiredListenAddr(ExpiredListenAddr { listener_id, addr }) => {
+                self.listen_addresses
+                    .retain(|(id, address)| id != listener_id || address != *addr);
+            }
             _ => (),
         }
         self.mdns.on_swarm_event(&event);
@@ -181,7 +292,9 @@ impl NetworkBehaviour for Behaviour {
         // if random walk is enabled poll the stream that will fire when random walk is scheduled
         if let Some(next_kad_random_query) = self.next_kad_random_walk.as_mut() {
             while next_kad_random_query.poll_unpin(cx).is_ready() {
-                if self.connected_peers.len() < self.max_peers_connected {
+                if self.kad_enabled
+                    && self.connected_peers.len() < self.max_peers_connected
+                {
                     let random_peer_id = PeerId::random();
                     self.kademlia.get_closest_peers(random_peer_id);
                 }
@@ -199,15 +312,42 @@ impl NetworkBehaviour for Behaviour {
 
         // poll sub-behaviors
         if let Poll::Ready(kad_action) = self.kademlia.poll(cx) {
+            if self.metrics {
+                if let ToSwarm::GenerateEvent(kad::Event::RoutingUpdated { .. }) =
+                    &kad_action
+                {
+                    let size =
+                        i64::try_from(self.routing_table_size()).unwrap_or(i64::MAX);
+                    p2p_metrics().kad_routing_table_size.set(size);
+                }
+            }
             return Poll::Ready(kad_action)
         };
 
+        if let Some(opts) = self.pending_dials.pop_front() {
+            return Poll::Ready(ToSwarm::Dial { opts })
+        }
+
         while let Poll::Ready(mdns_event) = self.mdns.poll(cx) {
             match mdns_event {
                 ToSwarm::GenerateEvent(mdns::Event::Discovered(list)) => {
                     for (peer_id, multiaddr) in list {
+                        self.mdns_discovered_peers.insert(peer_id);
+                        // Without the random walk nothing else dials the found peers
+                        if !self.kad_enabled
+                            && !self.connected_peers.contains(&peer_id)
+                            && self.connected_peers.len() < self.max_peers_connected
+                        {
+                            let opts = DialOpts::peer_id(peer_id)
+                                .addresses(vec![multiaddr.clone()])
+                                .build();
+                            self.pending_dials.push_back(opts);
+                        }
                         self.kademlia.add_address(&peer_id, multiaddr);
                     }
+                    if let Some(opts) = self.pending_dials.pop_front() {
+                        return Poll::Ready(ToSwarm::Dial { opts })
+                    }
                 }
                 ToSwarm::CloseConnection {
                     peer_id,
@@ -293,6 +433,20 @@ mod tests {
         (swarm, listen_addr, peer_id)
     }
 
+    #[tokio::test]
+    async fn routing_table_contains_bootstrap_nodes() {
+        let (_, first_peer_addr, first_peer_id) = build_fuel_discovery(vec![]);
+        let bootstrap_addr: Multiaddr = format!("{first_peer_addr}/p2p/{first_peer_id}")
+            .parse()
+            .unwrap();
+
+        let (mut without_bootstrap, _, _) = build_fuel_discovery(vec![]);
+        let (mut with_bootstrap, _, _) = build_fuel_discovery(vec![bootstrap_addr]);
+
+        assert_eq!(without_bootstrap.behaviour_mut().routing_table_size(), 0);
+        assert_eq!(with_bootstrap.behaviour_mut().routing_table_size(), 1);
+    }
+
     // builds 25 discovery swarms,
     // initially, only connects first_swarm to the rest of the swarms
     // after that each swarm uses kademlia to discover other swarms
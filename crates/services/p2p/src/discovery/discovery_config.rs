@@ -16,7 +16,10 @@ use libp2p::{
     PeerId,
 };
 use std::{
-    collections::HashSet,
+    collections::{
+        HashSet,
+        VecDeque,
+    },
     time::Duration,
 };
 use tracing::warn;
@@ -29,9 +32,11 @@ pub struct Config {
     reserved_nodes_only_mode: bool,
     random_walk: Option<Duration>,
     with_mdns: bool,
+    with_kad: bool,
     network_name: String,
     max_peers_connected: usize,
     connection_idle_timeout: Duration,
+    metrics: bool,
 }
 
 impl Config {
@@ -44,8 +49,10 @@ impl Config {
             random_walk: None,
             max_peers_connected: std::usize::MAX,
             with_mdns: false,
+            with_kad: true,
             network_name,
             connection_idle_timeout: Duration::from_secs(10),
+            metrics: false,
         }
     }
 
@@ -92,11 +99,23 @@ impl Config {
         self
     }
 
+    /// Whether the node queries the DHT and serves it to other peers from the start
+    pub fn enable_kad(&mut self, value: bool) -> &mut Self {
+        self.with_kad = value;
+        self
+    }
+
     pub fn with_random_walk(&mut self, value: Duration) -> &mut Self {
         self.random_walk = Some(value);
         self
     }
 
+    /// Reports the size of the Kademlia routing table to the metrics
+    pub fn enable_metrics(&mut self, value: bool) -> &mut Self {
+        self.metrics = value;
+        self
+    }
+
     pub fn finish(self) -> Behaviour {
         let Config {
             local_peer_id,
@@ -105,6 +124,8 @@ impl Config {
             max_peers_connected,
             reserved_nodes,
             reserved_nodes_only_mode,
+            with_kad,
+            metrics,
             ..
         } = self;
 
@@ -118,7 +139,9 @@ impl Config {
 
         let mut kademlia =
             kad::Behaviour::with_config(local_peer_id, memory_store, kademlia_config);
-        kademlia.set_mode(Some(Mode::Server));
+        // The disabled node still keeps the known addresses in the routing table
+        let mode = if with_kad { Mode::Server } else { Mode::Client };
+        kademlia.set_mode(Some(mode));
 
         // bootstrap nodes need to have their peer_id defined in the Multiaddr
         let bootstrap_nodes = bootstrap_nodes
@@ -143,8 +166,10 @@ impl Config {
             kademlia.add_address(peer_id, address.clone());
         }
 
-        if let Err(e) = kademlia.bootstrap() {
-            warn!("Kademlia bootstrap failed: {}", e);
+        if with_kad {
+            if let Err(e) = kademlia.bootstrap() {
+                warn!("Kademlia bootstrap failed: {}", e);
+            }
         }
 
         let next_kad_random_walk = {
@@ -168,12 +193,18 @@ impl Config {
         };
 
         Behaviour {
+            local_peer_id,
             connected_peers: HashSet::new(),
+            listen_addresses: vec![],
+            mdns_discovered_peers: HashSet::new(),
+            pending_dials: VecDeque::new(),
+            kad_enabled: with_kad,
             kademlia,
             next_kad_random_walk,
             duration_to_next_kad: Duration::from_secs(1),
             max_peers_connected,
             mdns,
+            metrics,
         }
     }
 }
@@ -0,0 +1,145 @@
+use crate::{
+    request_response::messages::ResponseError,
+    service::BroadcastError,
+};
+use fuel_core_storage::Error as StorageError;
+use libp2p::{
+    gossipsub::{
+        MessageId,
+        PublishError,
+    },
+    request_response::OutboundFailure,
+    swarm::DialError,
+    Multiaddr,
+    PeerId,
+    TransportError,
+};
+use thiserror::Error;
+use tokio::sync::{
+    mpsc,
+    oneshot,
+};
+
+/// Errors returned by the p2p service to its users
+#[derive(Debug, Error)]
+pub enum P2pError {
+    /// The service isn't running, so the request can't be processed
+    #[error("The p2p service is not running")]
+    ChannelClosed,
+    /// Too many requests are awaiting the processing by the service
+    #[error("The p2p service request queue is full")]
+    QueueFull,
+    #[error("Failed to listen on the `{address}`: {source}")]
+    BindFailed {
+        address: Multiaddr,
+        #[source]
+        source: TransportError<std::io::Error>,
+    },
+    #[error("No addresses to listen on")]
+    NoListenAddresses,
+    /// Every listener was closed before getting its address
+    #[error("All p2p listeners are closed")]
+    ListenersClosed,
+    #[error("The p2p service didn't get a listen address in time")]
+    ListenTimeout,
+    #[error("Invalid peer id {0:?}")]
+    InvalidPeerId(Vec<u8>),
+    #[error("Cannot retrieve the data for an empty range of block heights")]
+    EmptyRange,
+    /// None of the connected peers served the headers of the range
+    #[error("No peer served the headers of the block heights {0:?}")]
+    HeadersUnavailable(std::ops::Range<u32>),
+    /// The request wasn't sent, because the requested peer isn't connected
+    #[error("The requested peer is not connected")]
+    PeerNotConnected,
+    /// No connected peer could serve the request in time
+    #[error("No connected peer could serve the request")]
+    NoPeers,
+    /// The request wasn't sent, because the peer speaks an older version of the protocol
+    #[error("The peer doesn't support the request")]
+    UnsupportedRequest,
+    /// The response is too large for the peer to send, so a smaller range should be requested
+    #[error("The response of {size} bytes exceeds the max response size")]
+    ResponseTooLarge { size: u64 },
+    /// The response came from a peer other than the requested one
+    #[error("Requested the peer {requested}, but {responded} responded")]
+    UnexpectedResponder {
+        requested: PeerId,
+        responded: PeerId,
+    },
+    /// The peer didn't respond in time
+    #[error("The request to the peer timed out")]
+    RequestTimeout,
+    /// The peer responded that it doesn't have the requested data
+    #[error("The peer {0} doesn't have the requested data")]
+    NotFound(PeerId),
+    #[error("Invalid response from peer: {0}")]
+    InvalidResponse(#[source] ResponseError),
+    /// Not all chunks of the block were received in time
+    #[error("The chunks of the block weren't received in time")]
+    BlockChunksTimeout,
+    /// The received chunks don't make up the requested block
+    #[error("Invalid chunks of the block: {0}")]
+    InvalidBlockChunks(&'static str),
+    #[error("Kademlia is disabled")]
+    KademliaDisabled,
+    #[error("Failed to dial {address}: {source}")]
+    DialFailed {
+        address: Multiaddr,
+        #[source]
+        source: Box<DialError>,
+    },
+    #[error(transparent)]
+    Broadcast(#[from] BroadcastError),
+    /// The gossip message can't be forwarded, because it isn't cached anymore
+    #[error("The message {0} is not in the gossipsub cache")]
+    MessageNotCached(MessageId),
+    #[error("Failed to forward the message {message_id}: {source}")]
+    ForwardFailed {
+        message_id: MessageId,
+        #[source]
+        source: PublishError,
+    },
+    #[error("Failed to read the local storage: {0}")]
+    Storage(StorageError),
+}
+
+impl From<ResponseError> for P2pError {
+    fn from(error: ResponseError) -> Self {
+        match error {
+            ResponseError::PeerNotConnected => Self::PeerNotConnected,
+            ResponseError::NoPeers => Self::NoPeers,
+            ResponseError::UnsupportedRequest => Self::UnsupportedRequest,
+            ResponseError::TooLarge { size } => Self::ResponseTooLarge { size },
+            ResponseError::P2P(OutboundFailure::Timeout) => Self::RequestTimeout,
+            error => Self::InvalidResponse(error),
+        }
+    }
+}
+
+impl From<StorageError> for P2pError {
+    fn from(error: StorageError) -> Self {
+        Self::Storage(error)
+    }
+}
+
+impl<T> From<mpsc::error::SendError<T>> for P2pError {
+    fn from(_: mpsc::error::SendError<T>) -> Self {
+        Self::ChannelClosed
+    }
+}
+
+impl<T> From<mpsc::error::TrySendError<T>> for P2pError {
+    fn from(error: mpsc::error::TrySendError<T>) -> Self {
+        match error {
+            mpsc::error::TrySendError::Full(_) => Self::QueueFull,
+            mpsc::error::TrySendError::Closed(_) => Self::ChannelClosed,
+        }
+    }
+}
+
+impl From<oneshot::error::RecvError> for P2pError {
+    fn from(_: oneshot::error::RecvError) -> Self {
+        Self::ChannelClosed
+    }
+}
@@ -22,9 +22,13 @@ use sha2::{
 };
 use std::time::Duration;
 
-use super::topics::{
-    GossipTopic,
-    NEW_TX_GOSSIP_TOPIC,
+use super::{
+    messages::GossipTopicTag,
+    topics::{
+        GossipTopic,
+        NEW_TX_BATCH_GOSSIP_TOPIC,
+        NEW_TX_GOSSIP_TOPIC,
+    },
 };
 
 // The number of slots in each epoch.
@@ -43,7 +47,13 @@ const DECAY_TO_ZERO: f64 = 0.8;
 const DECAY_INTERVAL: Duration = SLOT;
 
 // The target number of peers in each gossip mesh.
-const MESH_SIZE: usize = 8;
+pub const MESH_SIZE: usize = 8;
+
+// The minimum number of peers in each gossip mesh before grafting new peers.
+pub const MIN_MESH_SIZE: usize = 6;
+
+// The maximum number of peers in each gossip mesh before pruning existing peers.
+pub const MAX_MESH_SIZE: usize = 12;
 
 // The weight applied to the score for delivering new transactions.
 const NEW_TX_GOSSIP_WEIGHT: f64 = 0.05;
@@ -54,10 +64,180 @@ const NEW_TX_GOSSIP_WEIGHT: f64 = 0.05;
 // but the peer is not completely banned from the network.
 pub const GRAYLIST_THRESHOLD: f64 = -16000.0;
 
+/// The gossipsub peer scoring parameters that can be tuned per network.
+/// They are mapped onto libp2p's [`PeerScoreParams`] and [`PeerScoreThresholds`].
+#[derive(Clone, Debug)]
+pub struct GossipsubScoringConfig {
+    /// Peers below this score don't receive the gossip from the node
+    pub gossip_threshold: f64,
+    /// Peers below this score don't receive the messages published by the node
+    pub publish_threshold: f64,
+    /// Messages of the peers below this score are ignored
+    pub graylist_threshold: f64,
+    /// Peer exchange is accepted only from the peers above this score
+    pub accept_px_threshold: f64,
+    /// The median mesh score below which the opportunistic grafting is triggered
+    pub opportunistic_graft_threshold: f64,
+    /// The weight of the `NewTx` topic in the score of a peer
+    pub new_tx_topic_weight: f64,
+    /// The weight of each invalid message delivered in the `NewTx` topic
+    pub invalid_message_deliveries_weight: f64,
+    /// The weight of the penalty for the peers pruned from the mesh
+    /// without delivering enough messages, disabled if zero
+    pub mesh_failure_penalty_weight: f64,
+    /// The decay of the mesh failure penalty
+    pub mesh_failure_penalty_decay: f64,
+    /// The cap of the positive score that topics contribute
+    pub topic_score_cap: f64,
+}
+
+impl Default for GossipsubScoringConfig {
+    fn default() -> Self {
+        Self {
+            gossip_threshold: -4000.0,
+            publish_threshold: -8000.0,
+            graylist_threshold: GRAYLIST_THRESHOLD,
+            accept_px_threshold: 40.0,
+            opportunistic_graft_threshold: 5.0,
+            new_tx_topic_weight: NEW_TX_GOSSIP_WEIGHT,
+            // -200 per invalid message
+            invalid_message_deliveries_weight: -10.0 / NEW_TX_GOSSIP_WEIGHT,
+            mesh_failure_penalty_weight: 0.0,
+            mesh_failure_penalty_decay: 0.0,
+            topic_score_cap: 400.0,
+        }
+    }
+}
+
+impl GossipsubScoringConfig {
+    /// Checks the parameters the same way as gossipsub does, so the broken
+    /// config is rejected before the start of the service.
+    pub fn validate(&self) -> Result<(), String> {
+        let thresholds = self.peer_score_thresholds();
+        thresholds.validate()?;
+
+        let mut params = self.peer_score_params(&thresholds);
+        params.topics.insert(
+            GossipTopic::new(NEW_TX_GOSSIP_TOPIC).hash(),
+            self.topic_score_params(self.new_tx_topic_weight),
+        );
+        params.validate()
+    }
+
+    fn peer_score_thresholds(&self) -> PeerScoreThresholds {
+        PeerScoreThresholds {
+            gossip_threshold: self.gossip_threshold,
+            publish_threshold: self.publish_threshold,
+            graylist_threshold: self.graylist_threshold,
+            accept_px_threshold: self.accept_px_threshold,
+            opportunistic_graft_threshold: self.opportunistic_graft_threshold,
+        }
+    }
+
+    fn peer_score_params(&self, thresholds: &PeerScoreThresholds) -> PeerScoreParams {
+        let mut params = PeerScoreParams {
+            decay_interval: DECAY_INTERVAL,
+            decay_to_zero: DECAY_TO_ZERO,
+            retain_score: EPOCH
+                .checked_mul(100)
+                .expect("`EPOCH` is usually not more than a year"),
+            app_specific_weight: 0.0,
+            ip_colocation_factor_threshold: 50.0, // Allow up to 50 nodes per IP
+            behaviour_penalty_threshold: 6.0,
+            behaviour_penalty_decay: score_parameter_decay(
+                EPOCH
+                    .checked_mul(10)
+                    .expect("`EPOCH` is usually not more than a year"),
+            ),
+            ..Default::default()
+        };
+
+        let target_value =
+            params.behaviour_penalty_decay - params.behaviour_penalty_threshold;
+
+        params.behaviour_penalty_weight =
+            thresholds.gossip_threshold / target_value.powi(2);
+
+        params.topic_score_cap = self.topic_score_cap;
+        params.ip_colocation_factor_weight = -params.topic_score_cap;
+
+        params
+    }
+
+    fn topic_score_params(&self, topic_weight: f64) -> TopicScoreParams {
+        let mut params = TopicScoreParams::default();
+
+        params.topic_weight = topic_weight;
+
+        // The "quantum" of time spent in the mesh, set to the duration of a slot.
+        // This is the smallest unit of time for which we track a peer's presence in the mesh.
+        params.time_in_mesh_quantum = SLOT;
+        params.time_in_mesh_cap = 3600.0 / params.time_in_mesh_quantum.as_secs_f64();
+        params.time_in_mesh_weight = 0.5;
+
+        // The decay time for the first message delivered score, set to 100 times the epoch duration.
+        // This means that the score given for first message deliveries will decay over this time period.
+        params.first_message_deliveries_decay = score_parameter_decay(
+            EPOCH
+                .checked_mul(100)
+                .expect("`EPOCH` is usually not more than a year"),
+        );
+        params.first_message_deliveries_cap = 1000.0;
+        params.first_message_deliveries_weight = 0.5;
+
+        params.mesh_message_deliveries_weight = 0.0;
+        params.mesh_message_deliveries_threshold = 0.0;
+        params.mesh_message_deliveries_decay = 0.0;
+        params.mesh_message_deliveries_cap = 0.0;
+        params.mesh_message_deliveries_window = Duration::from_secs(0);
+        params.mesh_message_deliveries_activation = Duration::from_secs(0);
+        params.mesh_failure_penalty_decay = self.mesh_failure_penalty_decay;
+        params.mesh_failure_penalty_weight = self.mesh_failure_penalty_weight;
+
+        params.invalid_message_deliveries_weight = self.invalid_message_deliveries_weight;
+        params.invalid_message_deliveries_decay = score_parameter_decay(
+            EPOCH
+                .checked_mul(50)
+                .expect("`EPOCH` is usually not more than a year"),
+        );
+
+        params
+    }
+}
+
+/// How the id of the gossip message is computed.
+/// Gossipsub deduplicates the messages by their ids.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum GossipMessageIdStrategy {
+    /// The hash of the message data, so the same payload published by different peers
+    /// or published twice is delivered once
+    #[default]
+    Sha256OfData,
+    /// The publisher and the sequence number of the message,
+    /// so every published message is delivered, even with the same payload
+    SourceAndSequence,
+}
+
+impl GossipMessageIdStrategy {
+    pub fn message_id(&self, message: &gossipsub::Message) -> MessageId {
+        match self {
+            Self::Sha256OfData => MessageId::from(&Sha256::digest(&message.data)[..]),
+            Self::SourceAndSequence => {
+                let mut id = message
+                    .source
+                    .map(|peer_id| peer_id.to_bytes())
+                    .unwrap_or_default();
+                id.extend(message.sequence_number.unwrap_or_default().to_be_bytes());
+                MessageId::from(id)
+            }
+        }
+    }
+}
+
 /// Creates `GossipsubConfigBuilder` with few of the Gossipsub values already defined
 pub fn default_gossipsub_builder() -> gossipsub::ConfigBuilder {
     let gossip_message_id = move |message: &gossipsub::Message| {
-        MessageId::from(&Sha256::digest(&message.data)[..])
+        GossipMessageIdStrategy::Sha256OfData.message_id(message)
     };
 
     let mut builder = gossipsub::ConfigBuilder::default();
@@ -75,8 +255,8 @@ pub fn default_gossipsub_builder() -> gossipsub::ConfigBuilder {
 pub(crate) fn default_gossipsub_config() -> gossipsub::Config {
     default_gossipsub_builder()
         .mesh_n(MESH_SIZE)
-        .mesh_n_low(6)
-        .mesh_n_high(12)
+        .mesh_n_low(MIN_MESH_SIZE)
+        .mesh_n_high(MAX_MESH_SIZE)
         .gossip_lazy(6)
         .history_length(5)
         .history_gossip(3)
@@ -87,92 +267,32 @@ pub(crate) fn default_gossipsub_config() -> gossipsub::Config {
         .expect("valid gossipsub configuration")
 }
 
-fn initialize_topic_score_params(topic_weight: f64) -> TopicScoreParams {
-    let mut params = TopicScoreParams::default();
-
-    params.topic_weight = topic_weight;
-
-    // The "quantum" of time spent in the mesh, set to the duration of a slot.
-    // This is the smallest unit of time for which we track a peer's presence in the mesh.
-    params.time_in_mesh_quantum = SLOT;
-    params.time_in_mesh_cap = 3600.0 / params.time_in_mesh_quantum.as_secs_f64();
-    params.time_in_mesh_weight = 0.5;
-
-    // The decay time for the first message delivered score, set to 100 times the epoch duration.
-    // This means that the score given for first message deliveries will decay over this time period.
-    params.first_message_deliveries_decay = score_parameter_decay(
-        EPOCH
-            .checked_mul(100)
-            .expect("`EPOCH` is usually not more than a year"),
-    );
-    params.first_message_deliveries_cap = 1000.0;
-    params.first_message_deliveries_weight = 0.5;
-
-    params.mesh_message_deliveries_weight = 0.0;
-    params.mesh_message_deliveries_threshold = 0.0;
-    params.mesh_message_deliveries_decay = 0.0;
-    params.mesh_message_deliveries_cap = 0.0;
-    params.mesh_message_deliveries_window = Duration::from_secs(0);
-    params.mesh_message_deliveries_activation = Duration::from_secs(0);
-    params.mesh_failure_penalty_decay = 0.0;
-    params.mesh_failure_penalty_weight = 0.0;
-
-    params.invalid_message_deliveries_weight = -10.0 / params.topic_weight; // -200 per invalid message
-    params.invalid_message_deliveries_decay = score_parameter_decay(
-        EPOCH
-            .checked_mul(50)
-            .expect("`EPOCH` is usually not more than a year"),
-    );
-
-    params
-}
-
 fn score_parameter_decay(decay_time: Duration) -> f64 {
     let ticks = decay_time.as_secs_f64() / DECAY_INTERVAL.as_secs_f64();
     DECAY_TO_ZERO.powf(1.0 / ticks)
 }
 
-fn initialize_peer_score_params(thresholds: &PeerScoreThresholds) -> PeerScoreParams {
-    let mut params = PeerScoreParams {
-        decay_interval: DECAY_INTERVAL,
-        decay_to_zero: DECAY_TO_ZERO,
-        retain_score: EPOCH
-            .checked_mul(100)
-            .expect("`EPOCH` is usually not more than a year"),
-        app_specific_weight: 0.0,
-        ip_colocation_factor_threshold: 50.0, // Allow up to 50 nodes per IP
-        behaviour_penalty_threshold: 6.0,
-        behaviour_penalty_decay: score_parameter_decay(
-            EPOCH
-                .checked_mul(10)
-                .expect("`EPOCH` is usually not more than a year"),
-        ),
-        ..Default::default()
-    };
-
-    let target_value =
-        params.behaviour_penalty_decay - params.behaviour_penalty_threshold;
-
-    params.behaviour_penalty_weight = thresholds.gossip_threshold / target_value.powi(2);
-
-    params.topic_score_cap = 400.0;
-    params.ip_colocation_factor_weight = -params.topic_score_cap;
-
-    params
-}
-
-fn initialize_peer_score_thresholds() -> PeerScoreThresholds {
-    PeerScoreThresholds {
-        gossip_threshold: -4000.0,
-        publish_threshold: -8000.0,
-        graylist_threshold: GRAYLIST_THRESHOLD,
-        accept_px_threshold: 40.0,
-        opportunistic_graft_threshold: 5.0,
-    }
+/// Applies the mesh degree parameters and the message id strategy
+/// of the `P2pConfig` on top of its `GossipsubConfig`
+fn build_gossipsub_config(p2p_config: &Config) -> anyhow::Result<gossipsub::Config> {
+    let message_id_strategy = p2p_config.gossip_message_id_strategy;
+    let gossipsub_config =
+        gossipsub::ConfigBuilder::from(p2p_config.gossipsub_config.clone())
+            .mesh_n(p2p_config.ideal_mesh_size)
+            .mesh_n_low(p2p_config.min_mesh_size)
+            .mesh_n_high(p2p_config.max_mesh_size)
+            .message_id_fn(move |message| message_id_strategy.message_id(message))
+            .build()?;
+
+    Ok(gossipsub_config)
 }
 
 /// Given a `P2pConfig` containing `GossipsubConfig` creates a Gossipsub Behaviour
-pub(crate) fn build_gossipsub_behaviour(p2p_config: &Config) -> gossipsub::Behaviour {
+pub(crate) fn build_gossipsub_behaviour(
+    p2p_config: &Config,
+) -> anyhow::Result<gossipsub::Behaviour> {
+    let gossipsub_config = build_gossipsub_config(p2p_config)?;
+
     let mut gossipsub = if p2p_config.metrics {
         // Move to Metrics related feature flag
         let mut p2p_registry = prometheus_client::registry::Registry::default();
@@ -181,7 +301,7 @@ pub(crate) fn build_gossipsub_behaviour(p2p_config: &Config) -> gossipsub::Behav
 
         let mut gossipsub = gossipsub::Behaviour::new_with_metrics(
             MessageAuthenticity::Signed(p2p_config.keypair.clone()),
-            p2p_config.gossipsub_config.clone(),
+            gossipsub_config,
             &mut p2p_registry,
             metrics_config,
         )
@@ -199,7 +319,7 @@ pub(crate) fn build_gossipsub_behaviour(p2p_config: &Config) -> gossipsub::Behav
     } else {
         let mut gossipsub = gossipsub::Behaviour::new(
             MessageAuthenticity::Signed(p2p_config.keypair.clone()),
-            p2p_config.gossipsub_config.clone(),
+            gossipsub_config,
         )
         .expect("gossipsub initialized");
 
@@ -215,29 +335,84 @@ pub(crate) fn build_gossipsub_behaviour(p2p_config: &Config) -> gossipsub::Behav
         gossipsub.add_explicit_peer(&peer_id);
     }
 
-    gossipsub
+    Ok(gossipsub)
 }
 
 fn initialize_gossipsub(gossipsub: &mut gossipsub::Behaviour, p2p_config: &Config) {
-    let peer_score_thresholds = initialize_peer_score_thresholds();
-    let peer_score_params = initialize_peer_score_params(&peer_score_thresholds);
+    let scoring_config = &p2p_config.gossipsub_scoring_config;
+    let peer_score_thresholds = scoring_config.peer_score_thresholds();
+    let peer_score_params = scoring_config.peer_score_params(&peer_score_thresholds);
 
     gossipsub
         .with_peer_score(peer_score_params, peer_score_thresholds)
         .expect("gossipsub initialized with peer score");
 
-    let topics = vec![(NEW_TX_GOSSIP_TOPIC, NEW_TX_GOSSIP_WEIGHT)];
+    let topics = vec![
+        (
+            GossipTopicTag::NewTx,
+            NEW_TX_GOSSIP_TOPIC,
+            scoring_config.new_tx_topic_weight,
+        ),
+        (
+            GossipTopicTag::NewTxBatch,
+            NEW_TX_BATCH_GOSSIP_TOPIC,
+            scoring_config.new_tx_topic_weight,
+        ),
+    ];
 
     // subscribe to gossipsub topics with the network name suffix
-    for (topic, weight) in topics {
+    for (tag, topic, weight) in topics {
         let t: GossipTopic = Topic::new(format!("{}/{}", topic, p2p_config.network_name));
 
         gossipsub
-            .set_topic_params(t.clone(), initialize_topic_score_params(weight))
+            .set_topic_params(t.clone(), scoring_config.topic_score_params(weight))
             .expect("First time initializing Topic Score");
 
-        gossipsub
-            .subscribe(&t)
-            .expect("Subscription to Topic: {topic} successful");
+        if p2p_config.subscribed_topics.contains(&tag) {
+            gossipsub
+                .subscribe(&t)
+                .expect("Subscription to Topic: {topic} successful");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libp2p::PeerId;
+
+    fn message(source: PeerId, sequence_number: u64) -> gossipsub::Message {
+        gossipsub::Message {
+            source: Some(source),
+            data: vec![1, 2, 3],
+            sequence_number: Some(sequence_number),
+            topic: GossipTopic::new("topic").hash(),
+        }
+    }
+
+    #[test]
+    fn same_payload_has_same_id_with_content_hash() {
+        let first = message(PeerId::random(), 1);
+        let second = message(PeerId::random(), 2);
+
+        let strategy = GossipMessageIdStrategy::Sha256OfData;
+
+        assert_eq!(strategy.message_id(&first), strategy.message_id(&second));
+    }
+
+    #[test]
+    fn same_payload_has_different_ids_with_source_and_sequence() {
+        let source = PeerId::random();
+        let first = message(source, 1);
+        let second = message(source, 2);
+        let from_other_source = message(PeerId::random(), 1);
+
+        let strategy = GossipMessageIdStrategy::SourceAndSequence;
+
+        assert_ne!(strategy.message_id(&first), strategy.message_id(&second));
+        assert_ne!(
+            strategy.message_id(&first),
+            strategy.message_id(&from_other_source)
+        );
     }
 }
@@ -1,7 +1,15 @@
-use std::sync::Arc;
+use std::{
+    str::FromStr,
+    sync::Arc,
+};
 
 use fuel_core_types::fuel_tx::Transaction;
 
+use super::topics::{
+    NEW_TX_BATCH_GOSSIP_TOPIC,
+    NEW_TX_GOSSIP_TOPIC,
+};
+
 use serde::{
     Deserialize,
     Serialize,
@@ -9,9 +17,35 @@ use serde::{
 
 /// Used to inform `GossipsubCodec` to which GossipsubMessage decode to
 /// GossipTopicTag is decided by checking received TopicHash from the peer
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum GossipTopicTag {
     NewTx,
+    NewTxBatch,
+}
+
+impl GossipTopicTag {
+    /// All gossip topics used by the node
+    pub const ALL: [Self; 2] = [Self::NewTx, Self::NewTxBatch];
+
+    /// The name of the topic, without the network name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NewTx => NEW_TX_GOSSIP_TOPIC,
+            Self::NewTxBatch => NEW_TX_BATCH_GOSSIP_TOPIC,
+        }
+    }
+}
+
+impl FromStr for GossipTopicTag {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            NEW_TX_GOSSIP_TOPIC => Ok(Self::NewTx),
+            NEW_TX_BATCH_GOSSIP_TOPIC => Ok(Self::NewTxBatch),
+            _ => Err(anyhow::anyhow!("Unknown gossip topic `{s}`")),
+        }
+    }
 }
 
 /// Takes `Arc<T>` and wraps it in a matching GossipsubBroadcastRequest
@@ -20,9 +54,12 @@ pub enum GossipTopicTag {
 #[derive(Debug, Clone)]
 pub enum GossipsubBroadcastRequest {
     NewTx(Arc<Transaction>),
+    /// Several transactions published as a single message
+    NewTxBatch(Vec<Arc<Transaction>>),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum GossipsubMessage {
     NewTx(Transaction),
+    NewTxBatch(Vec<Transaction>),
 }
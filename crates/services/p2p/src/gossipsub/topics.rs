@@ -11,6 +11,7 @@ use super::messages::{
 
 pub type GossipTopic = Sha256Topic;
 pub const NEW_TX_GOSSIP_TOPIC: &str = "new_tx";
+pub const NEW_TX_BATCH_GOSSIP_TOPIC: &str = "new_tx_batch";
 
 /// Holds used Gossipsub Topics
 /// Each field contains TopicHash and GossipTopic itself
@@ -18,14 +19,18 @@ pub const NEW_TX_GOSSIP_TOPIC: &str = "new_tx";
 #[derive(Debug)]
 pub struct GossipsubTopics {
     new_tx_topic: (TopicHash, GossipTopic),
+    new_tx_batch_topic: (TopicHash, GossipTopic),
 }
 
 impl GossipsubTopics {
     pub fn new(network_name: &str) -> Self {
         let new_tx_topic = Topic::new(format!("{NEW_TX_GOSSIP_TOPIC}/{network_name}"));
+        let new_tx_batch_topic =
+            Topic::new(format!("{NEW_TX_BATCH_GOSSIP_TOPIC}/{network_name}"));
 
         Self {
             new_tx_topic: (new_tx_topic.hash(), new_tx_topic),
+            new_tx_batch_topic: (new_tx_batch_topic.hash(), new_tx_batch_topic),
         }
     }
 
@@ -34,14 +39,36 @@ impl GossipsubTopics {
         &self,
         incoming_topic: &TopicHash,
     ) -> Option<GossipTopicTag> {
-        let GossipsubTopics { new_tx_topic } = &self;
+        let GossipsubTopics {
+            new_tx_topic,
+            new_tx_batch_topic,
+        } = &self;
 
         match incoming_topic {
             hash if hash == &new_tx_topic.0 => Some(GossipTopicTag::NewTx),
+            hash if hash == &new_tx_batch_topic.0 => Some(GossipTopicTag::NewTxBatch),
             _ => None,
         }
     }
 
+    /// Returns the topic matching the `GossipTopicTag`
+    pub fn get_topic(&self, tag: GossipTopicTag) -> GossipTopic {
+        match tag {
+            GossipTopicTag::NewTx => self.new_tx_topic.1.clone(),
+            GossipTopicTag::NewTxBatch => self.new_tx_batch_topic.1.clone(),
+        }
+    }
+
+    /// Returns all topics used by the node
+    pub fn all_topics(&self) -> Vec<GossipTopic> {
+        let GossipsubTopics {
+            new_tx_topic,
+            new_tx_batch_topic,
+        } = &self;
+
+        vec![new_tx_topic.1.clone(), new_tx_batch_topic.1.clone()]
+    }
+
     /// Given a `GossipsubBroadcastRequest` retruns a `GossipTopic`
     /// which is broadcast over the network with the serialized inner value of `GossipsubBroadcastRequest`
     pub fn get_gossipsub_topic(
@@ -50,6 +77,7 @@ impl GossipsubTopics {
     ) -> GossipTopic {
         match outgoing_request {
             GossipsubBroadcastRequest::NewTx(_) => self.new_tx_topic.1.clone(),
+            GossipsubBroadcastRequest::NewTxBatch(_) => self.new_tx_batch_topic.1.clone(),
         }
     }
 }
@@ -0,0 +1,226 @@
+//! The chain the node follows is advertised to the peers via the identify agent version,
+//! so the nodes of different chains don't stay connected even if their networks collide.
+//! The fuel-core version at the start of the agent version allows to require
+//! the minimal version of the peers.
+
+use crate::{
+    behavior::IDENTIFY_PROTOCOL_VERSION,
+    config::Config,
+};
+use semver::{
+    Version,
+    VersionReq,
+};
+use thiserror::Error;
+
+/// The prefix of the agent version that carries the handshake
+const AGENT_PREFIX: &str = "fuel-core/";
+
+/// The separator of the handshake fields in the agent version
+const FIELD_SEPARATOR: &str = "; ";
+
+/// The reason the peer is rejected by the handshake
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum HandshakeMismatch {
+    #[error("The peer doesn't advertise its chain, its agent version is `{0}`")]
+    Missing(String),
+    #[error("The peer uses the protocol version `{0}`")]
+    ProtocolVersion(String),
+    #[error("The peer follows the chain with the genesis `{0}`")]
+    Genesis(String),
+    #[error("The peer is on the network `{0}`")]
+    NetworkName(String),
+}
+
+/// The reason the peer is rejected by the version requirement
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum VersionMismatch {
+    #[error("The peer runs the version `{0}`")]
+    Unsupported(Version),
+    #[error("The version of the peer is unknown, its agent version is `{0}`")]
+    Unknown(String),
+}
+
+/// The chain of the node exchanged right after the connection is established.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainHandshake {
+    pub protocol_version: String,
+    /// The hex encoded commitment of the genesis block
+    pub genesis_id: String,
+    pub network_name: String,
+}
+
+impl ChainHandshake {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            protocol_version: IDENTIFY_PROTOCOL_VERSION.to_string(),
+            genesis_id: hex::encode(config.checksum.as_ref()),
+            network_name: config.network_name.clone(),
+        }
+    }
+
+    /// Encodes the handshake as
+    /// `fuel-core/<version> (<protocol version>; <genesis id>; <network name>)`.
+    /// The network name goes last, so it may contain the separator.
+    pub fn agent_version(&self) -> String {
+        format!(
+            "{AGENT_PREFIX}{} ({}{FIELD_SEPARATOR}{}{FIELD_SEPARATOR}{})",
+            env!("CARGO_PKG_VERSION"),
+            self.protocol_version,
+            self.genesis_id,
+            self.network_name
+        )
+    }
+
+    /// Decodes the handshake from the agent version of the peer.
+    /// Returns `None` if the peer doesn't advertise it.
+    pub fn parse(agent_version: &str) -> Option<Self> {
+        let (_, handshake) =
+            agent_version.strip_prefix(AGENT_PREFIX)?.split_once(" (")?;
+        let handshake = handshake.strip_suffix(')')?;
+
+        let mut fields = handshake.splitn(3, FIELD_SEPARATOR);
+        let protocol_version = fields.next()?.to_string();
+        let genesis_id = fields.next()?.to_string();
+        let network_name = fields.next()?.to_string();
+        Some(Self {
+            protocol_version,
+            genesis_id,
+            network_name,
+        })
+    }
+
+    /// Checks that the peer with the `agent_version` follows the same chain.
+    pub fn check(&self, agent_version: &str) -> Result<(), HandshakeMismatch> {
+        let Some(peer) = Self::parse(agent_version) else {
+            return Err(HandshakeMismatch::Missing(agent_version.to_string()))
+        };
+
+        if peer.protocol_version != self.protocol_version {
+            Err(HandshakeMismatch::ProtocolVersion(peer.protocol_version))
+        } else if peer.genesis_id != self.genesis_id {
+            Err(HandshakeMismatch::Genesis(peer.genesis_id))
+        } else if peer.network_name != self.network_name {
+            Err(HandshakeMismatch::NetworkName(peer.network_name))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Returns the fuel-core version from the agent version of the peer,
+/// or `None` if it isn't a fuel-core node or the version can't be parsed.
+pub fn agent_semver(agent_version: &str) -> Option<Version> {
+    let version = agent_version.strip_prefix(AGENT_PREFIX)?;
+    let version = version
+        .split_once(' ')
+        .map_or(version, |(version, _)| version);
+    Version::parse(version).ok()
+}
+
+/// The fuel-core version required from the peers.
+#[derive(Debug, Clone)]
+pub struct VersionRequirement {
+    pub requirement: VersionReq,
+    /// Whether the peers with the unknown version are rejected
+    pub strict: bool,
+}
+
+impl VersionRequirement {
+    /// Checks that the peer with the `agent_version` runs the supported version.
+    /// The peers with the unknown version pass unless the requirement is strict.
+    pub fn check(&self, agent_version: &str) -> Result<(), VersionMismatch> {
+        match agent_semver(agent_version) {
+            Some(version) if !self.requirement.matches(&version) => {
+                Err(VersionMismatch::Unsupported(version))
+            }
+            None if self.strict => {
+                Err(VersionMismatch::Unknown(agent_version.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    fn handshake() -> ChainHandshake {
+        let mut config = Config::default_initialized("handshake; with separator");
+        config.checksum = [1u8; 32].into();
+        ChainHandshake::new(&config)
+    }
+
+    #[test]
+    fn agent_version_is_parsed_back() {
+        let handshake = handshake();
+
+        let parsed = ChainHandshake::parse(&handshake.agent_version());
+
+        assert_eq!(parsed, Some(handshake));
+    }
+
+    #[test]
+    fn check__rejects_different_genesis() {
+        let handshake = handshake();
+        let mut peer = handshake.clone();
+        peer.genesis_id = hex::encode([2u8; 32]);
+
+        let result = handshake.check(&peer.agent_version());
+
+        assert_eq!(result, Err(HandshakeMismatch::Genesis(peer.genesis_id)));
+    }
+
+    #[test]
+    fn check__rejects_peer_without_handshake() {
+        let handshake = handshake();
+
+        let result = handshake.check("rust-libp2p/0.44.2");
+
+        assert_eq!(
+            result,
+            Err(HandshakeMismatch::Missing("rust-libp2p/0.44.2".to_string()))
+        );
+    }
+
+    #[test]
+    fn agent_semver__is_read_from_agent_version() {
+        let agent_version = handshake().agent_version();
+
+        let version = agent_semver(&agent_version);
+
+        assert_eq!(
+            version,
+            Some(Version::parse(env!("CARGO_PKG_VERSION")).unwrap())
+        );
+        assert_eq!(
+            agent_semver("fuel-core/0.21.0"),
+            Some(Version::new(0, 21, 0))
+        );
+        assert_eq!(agent_semver("rust-libp2p/0.44.2"), None);
+    }
+
+    #[test]
+    fn version_requirement__rejects_unknown_version_only_if_strict() {
+        let mut requirement = VersionRequirement {
+            requirement: ">=0.22.0".parse().unwrap(),
+            strict: false,
+        };
+
+        assert_eq!(
+            requirement.check("fuel-core/0.21.0"),
+            Err(VersionMismatch::Unsupported(Version::new(0, 21, 0)))
+        );
+        assert_eq!(requirement.check("fuel-core/0.22.1"), Ok(()));
+        assert_eq!(requirement.check("rust-libp2p/0.44.2"), Ok(()));
+
+        requirement.strict = true;
+        assert_eq!(
+            requirement.check("rust-libp2p/0.44.2"),
+            Err(VersionMismatch::Unknown("rust-libp2p/0.44.2".to_string()))
+        );
+    }
+}
@@ -4,12 +4,17 @@
 pub mod behavior;
 pub mod codecs;
 pub mod config;
+pub mod connection_gater;
 pub mod discovery;
+pub mod error;
 pub mod gossipsub;
+pub mod handshake;
 pub mod heartbeat;
+pub mod log_limiter;
 pub mod p2p_service;
 pub mod peer_manager;
 pub mod peer_report;
+pub mod peer_reputation;
 pub mod ports;
 pub mod request_response;
 pub mod service;
@@ -17,6 +22,9 @@ pub mod service;
 pub use gossipsub::config as gossipsub_config;
 pub use heartbeat::Config;
 
+pub use ip_network::IpNetwork;
+pub use semver::VersionReq;
+
 pub use libp2p::{
     multiaddr::Protocol,
     Multiaddr,
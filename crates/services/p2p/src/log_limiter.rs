@@ -0,0 +1,126 @@
+//! Limits the rate of the repeated warnings. A misbehaving or a lagging peer
+//! can trigger the same warning thousands of times, flooding the logs.
+
+use std::{
+    collections::HashMap,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+/// The kinds of the warnings limited independently of each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WarnEvent {
+    /// The reported gossip message isn't in the gossipsub cache anymore
+    MessageNotCached,
+    /// The gossip message failed to decode
+    GossipDecodeFailed,
+    /// The gossip message was received on an unknown topic
+    UnknownGossipTopic,
+    /// The peer answered the request with the response of another type
+    InvalidResponseType,
+}
+
+/// Lets through one warning of each kind per interval
+/// and counts the warnings suppressed in between
+#[derive(Debug)]
+pub struct WarnRateLimiter {
+    /// Zero disables the limiting
+    interval: Duration,
+    windows: HashMap<WarnEvent, Window>,
+}
+
+#[derive(Debug)]
+struct Window {
+    logged_at: Instant,
+    suppressed: u64,
+}
+
+impl WarnRateLimiter {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            windows: HashMap::new(),
+        }
+    }
+
+    /// Returns the number of the warnings of this kind suppressed since the last
+    /// logged one if the warning should be logged, or `None` if it is suppressed.
+    pub fn check(&mut self, event: WarnEvent) -> Option<u64> {
+        self.check_at(event, Instant::now())
+    }
+
+    fn check_at(&mut self, event: WarnEvent, now: Instant) -> Option<u64> {
+        match self.windows.get_mut(&event) {
+            Some(window)
+                if now.saturating_duration_since(window.logged_at) < self.interval =>
+            {
+                window.suppressed = window.suppressed.saturating_add(1);
+                None
+            }
+            Some(window) => {
+                window.logged_at = now;
+                Some(core::mem::take(&mut window.suppressed))
+            }
+            None => {
+                self.windows.insert(
+                    event,
+                    Window {
+                        logged_at: now,
+                        suppressed: 0,
+                    },
+                );
+                Some(0)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_warning_is_logged_once_per_interval() {
+        let interval = Duration::from_secs(10);
+        let mut limiter = WarnRateLimiter::new(interval);
+        let start = Instant::now();
+
+        assert_eq!(
+            limiter.check_at(WarnEvent::MessageNotCached, start),
+            Some(0)
+        );
+        for _ in 0..5 {
+            assert_eq!(limiter.check_at(WarnEvent::MessageNotCached, start), None);
+        }
+        // Other kinds of warnings are limited separately
+        assert_eq!(
+            limiter.check_at(WarnEvent::GossipDecodeFailed, start),
+            Some(0)
+        );
+
+        let after_interval = start.checked_add(interval).unwrap();
+        assert_eq!(
+            limiter.check_at(WarnEvent::MessageNotCached, after_interval),
+            Some(5)
+        );
+        assert_eq!(
+            limiter.check_at(WarnEvent::MessageNotCached, after_interval),
+            None
+        );
+    }
+
+    #[test]
+    fn zero_interval_logs_every_warning() {
+        let mut limiter = WarnRateLimiter::new(Duration::ZERO);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(
+                limiter.check_at(WarnEvent::UnknownGossipTopic, now),
+                Some(0)
+            );
+        }
+    }
+}
@@ -2,35 +2,63 @@ use crate::{
     behavior::{
         FuelBehaviour,
         FuelBehaviourEvent,
+        ARCHIVAL_IDENTIFY_PROTOCOL_VERSION,
     },
     codecs::{
-        postcard::PostcardCodec,
+        postcard::{
+            MessageExchangePostcardProtocol,
+            PayloadTooLarge,
+            PostcardCodec,
+            UnknownWireVersion,
+        },
         GossipsubCodec,
     },
     config::{
         build_transport_function,
         Config,
     },
+    discovery::DiscoveryStatus,
+    error::P2pError,
     gossipsub::{
         messages::{
+            GossipTopicTag,
             GossipsubBroadcastRequest,
             GossipsubMessage as FuelGossipsubMessage,
         },
         topics::GossipsubTopics,
     },
+    handshake::{
+        ChainHandshake,
+        VersionRequirement,
+    },
     heartbeat,
+    log_limiter::{
+        WarnEvent,
+        WarnRateLimiter,
+    },
     peer_manager::{
+        BandwidthProtocol,
+        ConnectionDirection,
         PeerManager,
         Punisher,
+        RedialConfig,
+        TrafficDirection,
     },
     peer_report::PeerReportEvent,
-    request_response::messages::{
-        RequestError,
-        RequestMessage,
-        ResponseError,
-        ResponseMessage,
-        ResponseSendError,
-        ResponseSender,
+    request_response::{
+        messages::{
+            RequestError,
+            RequestMessage,
+            ResponseError,
+            ResponseMessage,
+            ResponseMessageError,
+            ResponseSendError,
+            ResponseSender,
+        },
+        rate_limiter::{
+            RateLimiter,
+            ResponseBytesLimiter,
+        },
     },
     TryPeerId,
 };
@@ -41,6 +69,7 @@ use fuel_core_types::{
 };
 use futures::prelude::*;
 use libp2p::{
+    core::transport::ListenerId,
     gossipsub::{
         self,
         MessageAcceptance,
@@ -49,14 +78,21 @@ use libp2p::{
         TopicHash,
     },
     identify,
+    kad,
     multiaddr::Protocol,
     request_response::{
         self,
         InboundRequestId,
+        OutboundFailure,
         OutboundRequestId,
         ResponseChannel,
     },
-    swarm::SwarmEvent,
+    swarm::{
+        dial_opts::DialOpts,
+        ConnectionId,
+        DialError,
+        SwarmEvent,
+    },
     Multiaddr,
     PeerId,
     Swarm,
@@ -64,10 +100,19 @@ use libp2p::{
 };
 use rand::seq::IteratorRandom;
 use std::{
-    collections::HashMap,
+    collections::{
+        HashMap,
+        HashSet,
+    },
     time::Duration,
 };
-use tokio::sync::broadcast;
+use tokio::{
+    sync::{
+        broadcast,
+        oneshot,
+    },
+    time::Instant,
+};
 use tracing::{
     debug,
     warn,
@@ -76,6 +121,38 @@ use tracing::{
 /// Maximum amount of peer's addresses that we are ready to store per peer
 const MAX_IDENTIFY_ADDRESSES: usize = 10;
 
+/// The penalty for the peer that exceeded the rate of inbound requests
+const RATE_LIMITED_REQUEST_PENALTY: AppScore = -1.0;
+
+/// The penalty for the peer that gossiped the message with the unknown wire version
+const UNKNOWN_WIRE_VERSION_PENALTY: AppScore = -1.0;
+
+/// The window of `max_response_bytes_per_peer_per_minute`
+const RESPONSE_BYTES_WINDOW: Duration = Duration::from_secs(60);
+
+/// Returns the size of the message encoded by the postcard codec, without its version byte
+fn encoded_size<T: serde::Serialize>(message: &T) -> usize {
+    postcard::experimental::serialized_size(message).unwrap_or_default()
+}
+
+fn saturating_u64(bytes: usize) -> u64 {
+    u64::try_from(bytes).unwrap_or(u64::MAX)
+}
+
+/// The response above the max size is reported like the `TooLarge` response of the peer,
+/// so the requester asks for less. Only the lower bound of its size is known.
+fn outbound_failure_into_response_error(failure: OutboundFailure) -> ResponseError {
+    match failure {
+        OutboundFailure::Io(err) => match PayloadTooLarge::from_io_error(&err) {
+            Some(PayloadTooLarge { max_size }) => ResponseError::TooLarge {
+                size: saturating_u64(max_size).saturating_add(1),
+            },
+            None => ResponseError::P2P(OutboundFailure::Io(err)),
+        },
+        failure => ResponseError::P2P(failure),
+    }
+}
+
 impl Punisher for Swarm<FuelBehaviour> {
     fn ban_peer(&mut self, peer_id: PeerId) {
         self.behaviour_mut().block_peer(peer_id)
@@ -88,11 +165,8 @@ pub struct FuelP2PService {
     /// Store the local peer id
     pub local_peer_id: PeerId,
 
-    /// IP address for Swarm to listen on
-    local_address: std::net::IpAddr,
-
-    /// The TCP port that Swarm listens on
-    tcp_port: u16,
+    /// The addresses for Swarm to listen on
+    listen_addresses: Vec<Multiaddr>,
 
     /// Swarm handler for FuelBehaviour
     swarm: Swarm<FuelBehaviour>,
@@ -103,13 +177,17 @@ pub struct FuelP2PService {
     /// Whenever a response (or an error) is received from the p2p network,
     /// the request is removed from this table, and the channel is used to
     /// send the result to the caller.
-    outbound_requests_table: HashMap<OutboundRequestId, ResponseSender>,
+    outbound_requests_table: HashMap<OutboundRequestId, OutboundRequest>,
 
     /// Holds active inbound requests and associated oneshot channels.
     /// Whenever we're done processing the request, it's removed from this table,
     /// and the channel is used to send the result to libp2p, which will forward it
     /// to the peer that requested it.
-    inbound_requests_table: HashMap<InboundRequestId, ResponseChannel<ResponseMessage>>,
+    inbound_requests_table: HashMap<InboundRequestId, InboundRequest>,
+
+    /// Holds the inbound requests whose responses are handed to libp2p,
+    /// but aren't written to the peers yet, with the spans and the receive times of the requests.
+    responses_in_flight: HashMap<InboundRequestId, (tracing::Span, Instant)>,
 
     /// NetworkCodec used as `<GossipsubCodec>` for encoding and decoding of Gossipsub messages    
     network_codec: PostcardCodec,
@@ -122,6 +200,62 @@ pub struct FuelP2PService {
 
     /// Holds peers' information, and manages existing connections
     peer_manager: PeerManager,
+
+    /// Limits the rate of inbound requests per peer
+    inbound_rate_limiter: RateLimiter,
+
+    /// Whether the requests above the rate limit are dropped without the response
+    drop_rate_limited_requests: bool,
+
+    /// Limits the response bytes sent to each peer per minute
+    response_bytes_limiter: Option<ResponseBytesLimiter>,
+
+    /// The outbound requests above this number awaiting the response are rejected
+    max_outbound_requests: Option<usize>,
+
+    /// Non-reserved peers without substantive traffic for this period are disconnected
+    idle_connection_timeout: Option<Duration>,
+
+    /// The address of the node as observed by the latest identified inbound peer.
+    /// It is the external address if the node is behind NAT.
+    observed_address: Option<Multiaddr>,
+
+    /// The manual dials waiting for the connection to be established or to fail
+    pending_dials: HashMap<ConnectionId, oneshot::Sender<Result<PeerId, DialError>>>,
+
+    /// The DHT queries for the peers waiting for the result, with the max number of peers
+    /// to return
+    pending_peer_searches:
+        HashMap<kad::QueryId, (usize, oneshot::Sender<Option<Vec<PeerId>>>)>,
+
+    /// The chain of the node, the identified peers following another one are disconnected
+    chain_handshake: ChainHandshake,
+
+    /// The identified peers running the version not matching the requirement are disconnected
+    version_requirement: Option<VersionRequirement>,
+
+    /// Limits the rate of the warnings repeated for every message or request
+    warn_limiter: WarnRateLimiter,
+}
+
+/// The request sent to a peer that awaits the response
+#[derive(Debug)]
+struct OutboundRequest {
+    on_response: ResponseSender,
+    sent_at: Instant,
+    /// The span of the request lifecycle, so its logs can be correlated
+    span: tracing::Span,
+}
+
+/// The request received from a peer that awaits the response
+#[derive(Debug)]
+struct InboundRequest {
+    peer_id: PeerId,
+    channel: ResponseChannel<ResponseMessage>,
+    received_at: Instant,
+    /// The span lasts until the response is written to the peer,
+    /// so its duration is the time it took to serve the request
+    span: tracing::Span,
 }
 
 #[derive(Debug)]
@@ -160,6 +294,15 @@ pub enum FuelP2PEvent {
         peer_id: PeerId,
         block_height: BlockHeight,
     },
+    /// The gossip message received from the peer failed to decode
+    GossipDecodeError {
+        peer_id: PeerId,
+        topic_hash: TopicHash,
+    },
+    /// The set of the addresses the node listens on or the observed address has changed
+    ListenAddrChanged,
+    /// The peer follows another chain and was disconnected
+    ChainMismatch(PeerId),
 }
 
 impl FuelP2PService {
@@ -167,14 +310,16 @@ impl FuelP2PService {
         reserved_peers_updates: broadcast::Sender<usize>,
         config: Config,
         codec: PostcardCodec,
-    ) -> Self {
+    ) -> anyhow::Result<Self> {
+        config.validate()?;
+
         let gossipsub_data =
             GossipsubData::with_topics(GossipsubTopics::new(&config.network_name));
         let network_metadata = NetworkMetadata { gossipsub_data };
 
         // configure and build P2P Service
         let (transport_function, connection_state) = build_transport_function(&config);
-        let behaviour = FuelBehaviour::new(&config, codec.clone());
+        let behaviour = FuelBehaviour::new(&config, codec.clone())?;
 
         let mut swarm = SwarmBuilder::with_existing_identity(config.keypair.clone())
             .with_tokio()
@@ -199,20 +344,54 @@ impl FuelP2PService {
             swarm.add_external_address(public_address);
         }
 
-        let reserved_peers = config
+        let listen_addresses = {
+            let mut m = Multiaddr::from(config.address);
+            m.push(Protocol::Tcp(config.tcp_port));
+            std::iter::once(m)
+                .chain(config.listen_addresses.iter().cloned())
+                .collect()
+        };
+
+        let reserved_peers: HashSet<_> = config
             .reserved_nodes
             .iter()
             .filter_map(|m| m.try_to_peer_id())
             .collect();
 
-        Self {
+        // The bootstrap nodes aren't dialed in the reserved nodes only mode
+        let bootstrap_peers = if config.reserved_nodes_only_mode {
+            HashSet::new()
+        } else {
+            config
+                .bootstrap_nodes
+                .iter()
+                .filter_map(|m| m.try_to_peer_id())
+                .filter(|peer_id| !reserved_peers.contains(peer_id))
+                .collect()
+        };
+        let redial_config = RedialConfig {
+            base: config.redial_backoff_base,
+            max: config.redial_backoff_max,
+            warn_after_failures: config.redial_warn_after_failures,
+        };
+        let chain_handshake = ChainHandshake::new(&config);
+        let version_requirement =
+            config
+                .min_peer_version
+                .clone()
+                .map(|requirement| VersionRequirement {
+                    requirement,
+                    strict: config.strict_peer_version,
+                });
+
+        Ok(Self {
             local_peer_id,
-            local_address: config.address,
-            tcp_port: config.tcp_port,
+            listen_addresses,
             swarm,
             network_codec: codec,
             outbound_requests_table: HashMap::default(),
             inbound_requests_table: HashMap::default(),
+            responses_in_flight: HashMap::default(),
             network_metadata,
             metrics,
             peer_manager: PeerManager::new(
@@ -220,49 +399,117 @@ impl FuelP2PService {
                 reserved_peers,
                 connection_state,
                 config.max_peers_connected as usize,
+                config.peer_selection_strategy,
+                bootstrap_peers,
+                redial_config,
             ),
-        }
+            inbound_rate_limiter: RateLimiter::new(
+                config.max_requests_per_second,
+                config.max_requests_burst,
+            ),
+            drop_rate_limited_requests: config.drop_rate_limited_requests,
+            response_bytes_limiter: config.max_response_bytes_per_peer_per_minute.map(
+                |max_bytes| ResponseBytesLimiter::new(max_bytes, RESPONSE_BYTES_WINDOW),
+            ),
+            max_outbound_requests: config.max_outbound_requests,
+            idle_connection_timeout: config.idle_connection_timeout,
+            observed_address: None,
+            pending_dials: HashMap::new(),
+            pending_peer_searches: HashMap::new(),
+            chain_handshake,
+            version_requirement,
+            warn_limiter: WarnRateLimiter::new(config.warn_logs_interval),
+        })
     }
 
-    pub async fn start(&mut self) -> anyhow::Result<()> {
-        // set up node's address to listen on
-        let listen_multiaddr = {
-            let mut m = Multiaddr::from(self.local_address);
-            m.push(Protocol::Tcp(self.tcp_port));
-            m
-        };
+    /// Starts listening on all configured addresses.
+    /// The addresses that can't be bound are skipped, the start fails only if none is bound.
+    pub async fn start(&mut self) -> Result<(), P2pError> {
         let peer_id = self.local_peer_id;
+        let mut listeners = HashSet::new();
+        let mut last_error = None;
 
-        tracing::info!(
-            "The p2p service starts on the `{listen_multiaddr}` with `{peer_id}`"
-        );
+        for listen_multiaddr in self.listen_addresses.clone() {
+            tracing::info!(
+                "The p2p service starts on the `{listen_multiaddr}` with `{peer_id}`"
+            );
+            match self.swarm.listen_on(listen_multiaddr.clone()) {
+                Ok(listener) => {
+                    listeners.insert(listener);
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed to listen on the `{listen_multiaddr}`: {err:?}"
+                    );
+                    last_error = Some(P2pError::BindFailed {
+                        address: listen_multiaddr,
+                        source: err,
+                    });
+                }
+            }
+        }
 
-        // start listening at the given address
-        self.swarm.listen_on(listen_multiaddr)?;
+        if listeners.is_empty() {
+            return Err(last_error.unwrap_or(P2pError::NoListenAddresses))
+        }
 
         // Wait for listener addresses.
-        tokio::time::timeout(Duration::from_secs(5), self.await_listeners_address())
-            .await
-            .map_err(|_| {
-                anyhow::anyhow!("P2PService should get a new address within 5 seconds")
-            })?;
-        Ok(())
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            self.await_listeners_address(listeners),
+        )
+        .await
+        .map_err(|_| P2pError::ListenTimeout)?
     }
 
-    async fn await_listeners_address(&mut self) {
-        loop {
-            if let SwarmEvent::NewListenAddr { .. } = self.swarm.select_next_some().await
-            {
-                break;
+    /// Waits until every listener gets its address or is closed
+    async fn await_listeners_address(
+        &mut self,
+        mut listeners: HashSet<ListenerId>,
+    ) -> Result<(), P2pError> {
+        let mut bound = false;
+        while !listeners.is_empty() {
+            match self.swarm.select_next_some().await {
+                SwarmEvent::NewListenAddr {
+                    listener_id,
+                    address,
+                } => {
+                    tracing::info!("Listening for p2p traffic on `{address}`");
+                    bound = true;
+                    listeners.remove(&listener_id);
+                }
+                SwarmEvent::ListenerClosed {
+                    listener_id,
+                    addresses,
+                    reason,
+                } => {
+                    tracing::warn!(
+                        "p2p listener(s) `{addresses:?}` closed with `{reason:?}`"
+                    );
+                    listeners.remove(&listener_id);
+                }
+                _ => {}
             }
         }
+
+        if bound {
+            Ok(())
+        } else {
+            Err(P2pError::ListenersClosed)
+        }
     }
 
-    #[cfg(feature = "test-helpers")]
+    /// Returns the addresses the node listens on and the address observed by the peers,
+    /// including the peer id of the node
     pub fn multiaddrs(&self) -> Vec<Multiaddr> {
         let local_peer = self.local_peer_id;
+        let observed_address = self
+            .observed_address
+            .iter()
+            .filter(|addr| !self.swarm.listeners().any(|listener| listener == *addr));
         self.swarm
             .listeners()
+            .chain(observed_address)
             .map(|addr| {
                 format!("{addr}/p2p/{local_peer}")
                     .parse()
@@ -275,6 +522,28 @@ impl FuelP2PService {
         self.peer_manager.get_peers_ids()
     }
 
+    /// Returns `true` if the peer speaks a version of the request-response protocol
+    /// that has the request. The versions are advertised via the identify protocol,
+    /// so the peer that didn't identify itself yet is checked only against the node's version.
+    fn peer_supports_request(&self, peer_id: &PeerId, request: &RequestMessage) -> bool {
+        let max_protocol = self.network_codec.max_protocol();
+        let peer_protocols = self
+            .peer_manager
+            .get_peer_info(peer_id)
+            .map(|peer_info| &peer_info.protocols)
+            .filter(|protocols| !protocols.is_empty());
+        let Some(peer_protocols) = peer_protocols else {
+            return max_protocol.supports(request)
+        };
+
+        peer_protocols
+            .iter()
+            .filter_map(|protocol| {
+                MessageExchangePostcardProtocol::from_protocol_id(protocol.as_ref())
+            })
+            .any(|protocol| protocol <= max_protocol && protocol.supports(request))
+    }
+
     pub fn publish_message(
         &mut self,
         message: GossipsubBroadcastRequest,
@@ -285,13 +554,26 @@ impl FuelP2PService {
             .topics
             .get_gossipsub_topic(&message);
 
-        match self.network_codec.encode(message) {
-            Ok(encoded_data) => self
-                .swarm
-                .behaviour_mut()
-                .publish_message(topic, encoded_data),
-            Err(e) => Err(PublishError::TransformFailed(e)),
+        let encoded_data = self
+            .network_codec
+            .encode(message)
+            .map_err(PublishError::TransformFailed)?;
+        let size = encoded_data.len();
+        let recipients = self.swarm.behaviour().topic_peers(&topic);
+        let message_id = self
+            .swarm
+            .behaviour_mut()
+            .publish_message(topic, encoded_data)?;
+
+        for peer_id in recipients {
+            self.record_bandwidth(
+                &peer_id,
+                BandwidthProtocol::Gossip,
+                TrafficDirection::Outbound,
+                size,
+            );
         }
+        Ok(message_id)
     }
 
     /// Sends RequestMessage to a peer
@@ -304,26 +586,80 @@ impl FuelP2PService {
         on_response: ResponseSender,
     ) -> Result<OutboundRequestId, RequestError> {
         let peer_id = match peer_id {
-            Some(peer_id) => peer_id,
-            _ => {
-                let peers = self.get_peers_ids_iter();
-                let peers_count = self.peer_manager.total_peers_connected();
-
-                if peers_count == 0 {
-                    return Err(RequestError::NoPeersConnected);
+            Some(peer_id) => {
+                if !self.swarm.is_connected(&peer_id) {
+                    debug!(target: "fuel-p2p", "The requested peer {peer_id} is not connected, rejecting the request");
+                    on_response.send_error(peer_id, ResponseError::PeerNotConnected);
+                    return Err(RequestError::PeerNotConnected(peer_id))
                 }
-
+                if !self.peer_supports_request(&peer_id, &message_request) {
+                    debug!(target: "fuel-p2p", "The peer {peer_id} doesn't support the {message_request:?} request, rejecting it");
+                    on_response.send_error(peer_id, ResponseError::UnsupportedRequest);
+                    return Err(RequestError::UnsupportedRequest(peer_id))
+                }
+                peer_id
+            }
+            _ => {
                 let mut range = rand::thread_rng();
-                *peers.choose(&mut range).unwrap()
+                let supports_request = |peer_id: &&PeerId| {
+                    self.peer_supports_request(peer_id, &message_request)
+                };
+                // Reserved peers are trusted, so they are preferred over others
+                let reserved_peer = self
+                    .peer_manager
+                    .get_connected_reserved_peers()
+                    .filter(supports_request)
+                    .choose(&mut range);
+                let peer = reserved_peer.or_else(|| {
+                    self.get_peers_ids_iter()
+                        .filter(supports_request)
+                        .choose(&mut range)
+                });
+
+                *peer.ok_or(RequestError::NoPeersConnected)?
             }
         };
 
-        let request_id = self
-            .swarm
-            .behaviour_mut()
-            .send_request_msg(message_request, &peer_id);
+        if self
+            .max_outbound_requests
+            .is_some_and(|max| self.outbound_requests_table.len() >= max)
+        {
+            debug!(target: "fuel-p2p", "Too many outbound requests are in flight, rejecting the request to {peer_id}");
+            on_response.send_error(peer_id, ResponseError::TooManyInflight);
+            return Err(RequestError::TooManyInflight)
+        }
+
+        let span = tracing::debug_span!(
+            target: "fuel-p2p",
+            "p2p_request",
+            request_id = tracing::field::Empty,
+            %peer_id,
+            request = message_request.kind(),
+            block_height_range = ?message_request.block_height_range(),
+        );
 
-        self.outbound_requests_table.insert(request_id, on_response);
+        self.record_bandwidth(
+            &peer_id,
+            BandwidthProtocol::RequestResponse,
+            TrafficDirection::Outbound,
+            encoded_size(&message_request),
+        );
+        let request_id = span.in_scope(|| {
+            self.swarm
+                .behaviour_mut()
+                .send_request_msg(message_request, &peer_id)
+        });
+        span.record("request_id", tracing::field::display(request_id));
+        span.in_scope(|| debug!(target: "fuel-p2p", "The request is sent"));
+
+        self.outbound_requests_table.insert(
+            request_id,
+            OutboundRequest {
+                on_response,
+                sent_at: Instant::now(),
+                span,
+            },
+        );
 
         Ok(request_id)
     }
@@ -332,12 +668,28 @@ impl FuelP2PService {
     pub fn send_response_msg(
         &mut self,
         request_id: InboundRequestId,
-        message: ResponseMessage,
+        mut message: ResponseMessage,
     ) -> Result<(), ResponseSendError> {
-        let Some(channel) = self.inbound_requests_table.remove(&request_id) else {
+        let Some(InboundRequest {
+            peer_id,
+            channel,
+            received_at,
+            span,
+        }) = self.inbound_requests_table.remove(&request_id)
+        else {
             debug!("ResponseChannel for {:?} does not exist!", request_id);
             return Err(ResponseSendError::ResponseChannelDoesNotExist);
         };
+        let entered = span.enter();
+        let mut size = encoded_size(&message);
+        if !self.network_codec.fits_response(size) {
+            // The peer can't read the response, so it is told to request less instead
+            warn!(target: "fuel-p2p", size, "The response exceeds the max response size");
+            message = ResponseMessage::Error(ResponseMessageError::TooLarge {
+                size: saturating_u64(size),
+            });
+            size = encoded_size(&message);
+        }
 
         if self
             .swarm
@@ -348,10 +700,66 @@ impl FuelP2PService {
             debug!("Failed to send ResponseMessage for {:?}", request_id);
             return Err(ResponseSendError::SendingResponseFailed);
         }
+        debug!(target: "fuel-p2p", size, "The response is handed over for sending");
+        drop(entered);
+        self.responses_in_flight
+            .insert(request_id, (span, received_at));
+
+        self.record_bandwidth(
+            &peer_id,
+            BandwidthProtocol::RequestResponse,
+            TrafficDirection::Outbound,
+            size,
+        );
+        if let Some(limiter) = &mut self.response_bytes_limiter {
+            limiter.record(peer_id, saturating_u64(size));
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if some inbound requests aren't answered yet,
+    /// or their responses aren't written to the peers yet
+    pub fn has_pending_inbound_requests(&self) -> bool {
+        !self.inbound_requests_table.is_empty() || !self.responses_in_flight.is_empty()
+    }
 
+    /// Subscribes to or unsubscribes from the gossipsub topic.
+    /// The node can publish to the topic regardless of the subscription.
+    pub fn set_topic_subscription(
+        &mut self,
+        tag: GossipTopicTag,
+        subscribe: bool,
+    ) -> anyhow::Result<()> {
+        let topic = self.network_metadata.gossipsub_data.topics.get_topic(tag);
+        let behaviour = self.swarm.behaviour_mut();
+        if subscribe {
+            behaviour
+                .subscribe(&topic)
+                .map_err(|e| anyhow::anyhow!("Failed to subscribe to {topic}: {e:?}"))?;
+        } else {
+            behaviour.unsubscribe(&topic).map_err(|e| {
+                anyhow::anyhow!("Failed to unsubscribe from {topic}: {e:?}")
+            })?;
+        }
         Ok(())
     }
 
+    /// Returns `true` if the node is subscribed to the gossipsub topic
+    pub fn is_subscribed(&self, tag: GossipTopicTag) -> bool {
+        let topic = self.network_metadata.gossipsub_data.topics.get_topic(tag);
+        self.swarm.behaviour().is_subscribed(&topic)
+    }
+
+    /// Unsubscribes from all gossipsub topics, so the peers stop sending the gossip
+    pub fn unsubscribe_from_all_topics(&mut self) {
+        for topic in self.network_metadata.gossipsub_data.topics.all_topics() {
+            if let Err(e) = self.swarm.behaviour_mut().unsubscribe(&topic) {
+                warn!(target: "fuel-p2p", "Failed to unsubscribe from {topic}: {e:?}");
+            }
+        }
+    }
+
     pub fn update_block_height(&mut self, block_height: BlockHeight) {
         self.swarm.behaviour_mut().update_block_height(block_height)
     }
@@ -372,24 +780,173 @@ impl FuelP2PService {
             }
         }
 
-        if let Some(gossip_score) = self
+        let should_check_score = matches!(acceptance, MessageAcceptance::Reject);
+        match self.swarm.behaviour_mut().report_message_validation_result(
+            msg_id,
+            &propagation_source,
+            acceptance,
+        ) {
+            Ok(true) => {
+                debug!(target: "fuel-p2p", "Sent a report for MessageId: {} from PeerId: {}", msg_id, propagation_source);
+                let gossip_score = should_check_score
+                    .then(|| self.get_peer_score(&propagation_source))
+                    .flatten();
+                if let Some(gossip_score) = gossip_score {
+                    self.peer_manager.handle_gossip_score_update(
+                        propagation_source,
+                        gossip_score,
+                        &mut self.swarm,
+                    );
+                }
+            }
+            Ok(false) => {
+                if let Some(suppressed) =
+                    self.warn_limiter.check(WarnEvent::MessageNotCached)
+                {
+                    warn!(target: "fuel-p2p", suppressed, "Message with MessageId: {} not found in the Gossipsub Message Cache", msg_id);
+                }
+            }
+            Err(e) => {
+                tracing::error!(target: "fuel-p2p", "Failed to report Message with MessageId: {} with Error: {:?}", msg_id, e);
+                self.count_gossip_report_error()
+            }
+        }
+    }
+
+    /// Propagates the already received message to the mesh peers without re-encoding it.
+    /// Fails if the message isn't in the gossipsub cache anymore.
+    pub fn forward_gossip(&mut self, msg_id: &MessageId) -> Result<(), P2pError> {
+        let local_peer_id = self.local_peer_id;
+        match self
             .swarm
             .behaviour_mut()
-            .report_message_validation_result(msg_id, &propagation_source, acceptance)
+            .forward_message(msg_id, &local_peer_id)
         {
-            self.peer_manager.handle_gossip_score_update(
-                propagation_source,
-                gossip_score,
-                &mut self.swarm,
-            );
+            Ok(true) => Ok(()),
+            Ok(false) => Err(P2pError::MessageNotCached(msg_id.clone())),
+            Err(source) => Err(P2pError::ForwardFailed {
+                message_id: msg_id.clone(),
+                source,
+            }),
         }
     }
 
-    #[cfg(test)]
+    /// Accounts the bytes exchanged with the peer in its info and the metrics
+    fn record_bandwidth(
+        &mut self,
+        peer_id: &PeerId,
+        protocol: BandwidthProtocol,
+        direction: TrafficDirection,
+        bytes: usize,
+    ) {
+        let bytes = saturating_u64(bytes);
+        self.peer_manager
+            .handle_bandwidth(peer_id, protocol, direction, bytes);
+        if self.metrics {
+            p2p_metrics().count_bandwidth(protocol.as_str(), direction.as_str(), bytes);
+        }
+    }
+
+    /// Counts the gossip message reports that failed to be processed
+    pub fn count_gossip_report_error(&self) {
+        if self.metrics {
+            p2p_metrics().gossip_report_errors.inc();
+        }
+    }
+
+    /// Returns the gossipsub score of the peer, if the peer scoring is enabled
     pub fn get_peer_score(&self, peer_id: &PeerId) -> Option<f64> {
         self.swarm.behaviour().get_peer_score(peer_id)
     }
 
+    /// Disconnects the peer and refuses any connection with it
+    /// until `unban_peer` is called. Reserved peers are never banned.
+    pub fn ban_peer(&mut self, peer_id: PeerId) {
+        if self.peer_manager.is_reserved(&peer_id) {
+            return
+        }
+        self.swarm.behaviour_mut().block_peer(peer_id)
+    }
+
+    /// Adds the peer to the reserved ones and dials it if it isn't connected yet.
+    /// The connection with the reserved peer is restored whenever it is lost.
+    pub fn add_reserved_peer(&mut self, peer_id: PeerId, address: Multiaddr) {
+        self.peer_manager.add_reserved_peer(peer_id);
+
+        let behaviour = self.swarm.behaviour_mut();
+        behaviour.unblock_peer(peer_id);
+        behaviour.add_explicit_peer(&peer_id);
+        behaviour.add_addresses_to_discovery(&peer_id, vec![address.clone()]);
+
+        if !self.swarm.is_connected(&peer_id) {
+            let opts = DialOpts::peer_id(peer_id).addresses(vec![address]).build();
+            if let Err(err) = self.swarm.dial(opts) {
+                debug!(target: "fuel-p2p", "Failed to dial reserved peer {:?}: {:?}", peer_id, err);
+            }
+        }
+    }
+
+    /// Turns the reserved peer into a regular one.
+    /// The peer is disconnected if there is no free slot for it.
+    pub fn remove_reserved_peer(&mut self, peer_id: PeerId) {
+        self.swarm.behaviour_mut().remove_explicit_peer(&peer_id);
+
+        if self.peer_manager.remove_reserved_peer(&peer_id) {
+            let _ = self.swarm.disconnect_peer_id(peer_id);
+        }
+    }
+
+    /// Dials the address. The peer id of the dialed node is sent to the `channel`
+    /// once the connection is established, or the error if the dial fails.
+    pub fn dial(
+        &mut self,
+        address: Multiaddr,
+        channel: oneshot::Sender<Result<PeerId, DialError>>,
+    ) {
+        let opts = DialOpts::unknown_peer_id().address(address).build();
+        let connection_id = opts.connection_id();
+        match self.swarm.dial(opts) {
+            Ok(()) => {
+                self.pending_dials.insert(connection_id, channel);
+            }
+            Err(err) => {
+                let _ = channel.send(Err(err));
+            }
+        }
+    }
+
+    /// Queries the DHT for the peers closest to a random key. Up to `count` found peers
+    /// are sent to the `channel` once the query finishes, or `None` if Kademlia is disabled.
+    pub fn find_peers(
+        &mut self,
+        count: usize,
+        channel: oneshot::Sender<Option<Vec<PeerId>>>,
+    ) {
+        match self.swarm.behaviour_mut().find_peers() {
+            Some(query_id) => {
+                self.pending_peer_searches
+                    .insert(query_id, (count, channel));
+            }
+            None => {
+                let _ = channel.send(None);
+            }
+        }
+    }
+
+    /// Starts or stops the discovery mechanisms, the `None` ones stay as they are
+    pub fn set_discovery(&mut self, mdns: Option<bool>, kad: Option<bool>) {
+        self.swarm.behaviour_mut().set_discovery(mdns, kad)
+    }
+
+    pub fn discovery_status(&mut self) -> DiscoveryStatus {
+        self.swarm.behaviour_mut().discovery_status()
+    }
+
+    /// Allows connections with the previously banned peer
+    pub fn unban_peer(&mut self, peer_id: PeerId) {
+        self.swarm.behaviour_mut().unblock_peer(peer_id)
+    }
+
     /// Report application score
     /// If application peer score is below allowed threshold
     /// the peer is banned
@@ -411,7 +968,7 @@ impl FuelP2PService {
         level = "debug",
         fields(
             local_peer_id = %self.local_peer_id,
-            local_address = %self.local_address
+            listen_addresses = ?self.listen_addresses
         ),
         ret
     )]
@@ -428,7 +985,11 @@ impl FuelP2PService {
             }
             SwarmEvent::NewListenAddr { address, .. } => {
                 tracing::info!("Listening for p2p traffic on `{address}`");
-                None
+                Some(FuelP2PEvent::ListenAddrChanged)
+            }
+            SwarmEvent::ExpiredListenAddr { address, .. } => {
+                tracing::info!("Stopped listening for p2p traffic on `{address}`");
+                Some(FuelP2PEvent::ListenAddrChanged)
             }
             SwarmEvent::ListenerClosed {
                 addresses, reason, ..
@@ -436,6 +997,29 @@ impl FuelP2PService {
                 tracing::info!(
                     "p2p listener(s) `{addresses:?}` closed with `{reason:?}`"
                 );
+                Some(FuelP2PEvent::ListenAddrChanged)
+            }
+            SwarmEvent::ConnectionEstablished {
+                peer_id,
+                connection_id,
+                ..
+            } => {
+                if let Some(channel) = self.pending_dials.remove(&connection_id) {
+                    let _ = channel.send(Ok(peer_id));
+                }
+                None
+            }
+            SwarmEvent::OutgoingConnectionError {
+                connection_id,
+                peer_id,
+                error,
+            } => {
+                if let Some(peer_id) = peer_id {
+                    self.peer_manager.handle_dial_failure(&peer_id);
+                }
+                if let Some(channel) = self.pending_dials.remove(&connection_id) {
+                    let _ = channel.send(Err(error));
+                }
                 None
             }
             _ => None,
@@ -446,6 +1030,10 @@ impl FuelP2PService {
         &self.peer_manager
     }
 
+    pub fn peer_manager_mut(&mut self) -> &mut PeerManager {
+        &mut self.peer_manager
+    }
+
     fn handle_behaviour_event(
         &mut self,
         event: FuelBehaviourEvent,
@@ -458,10 +1046,31 @@ impl FuelP2PService {
             }
             FuelBehaviourEvent::Identify(event) => self.handle_identify_event(event),
             FuelBehaviourEvent::Heartbeat(event) => self.handle_heartbeat_event(event),
+            FuelBehaviourEvent::Discovery(event) => self.handle_discovery_event(event),
             _ => None,
         }
     }
 
+    fn handle_discovery_event(&mut self, event: kad::Event) -> Option<FuelP2PEvent> {
+        if let kad::Event::OutboundQueryProgressed {
+            id,
+            result: kad::QueryResult::GetClosestPeers(result),
+            ..
+        } = event
+        {
+            if let Some((count, channel)) = self.pending_peer_searches.remove(&id) {
+                // The timed out query still reports the peers found before the timeout
+                let mut peers = match result {
+                    Ok(kad::GetClosestPeersOk { peers, .. }) => peers,
+                    Err(kad::GetClosestPeersError::Timeout { peers, .. }) => peers,
+                };
+                peers.truncate(count);
+                let _ = channel.send(Some(peers));
+            }
+        }
+        None
+    }
+
     fn handle_gossipsub_event(
         &mut self,
         event: gossipsub::Event,
@@ -472,6 +1081,22 @@ impl FuelP2PService {
             message_id,
         } = event
         {
+            let span = tracing::debug_span!(
+                target: "fuel-p2p",
+                "p2p_gossip",
+                topic = %message.topic,
+                %message_id,
+                peer_id = %propagation_source,
+            );
+            let _entered = span.enter();
+            self.peer_manager.handle_peer_activity(&propagation_source);
+            self.record_bandwidth(
+                &propagation_source,
+                BandwidthProtocol::Gossip,
+                TrafficDirection::Inbound,
+                message.data.len(),
+            );
+
             if let Some(correct_topic) = self
                 .network_metadata
                 .gossipsub_data
@@ -487,18 +1112,72 @@ impl FuelP2PService {
                             message: decoded_message,
                         })
                     }
+                    Err(err) if UnknownWireVersion::from_io_error(&err).is_some() => {
+                        // The peer may run the newer version of the node,
+                        // so the message is ignored instead of being rejected
+                        debug!(target: "fuel-p2p", "Ignoring the message {message_id} from {propagation_source}: {err}");
+
+                        self.report_message_validation_result(
+                            &message_id,
+                            propagation_source,
+                            MessageAcceptance::Ignore,
+                        );
+                        self.report_peer(
+                            propagation_source,
+                            UNKNOWN_WIRE_VERSION_PENALTY,
+                            "p2p",
+                        );
+
+                        if self.metrics {
+                            p2p_metrics().gossip_unknown_version_errors.inc();
+                        }
+                    }
+                    Err(err) if PayloadTooLarge::from_io_error(&err).is_some() => {
+                        // The message isn't logged, because it is huge
+                        debug!(target: "fuel-p2p", "Rejecting the message {message_id} from {propagation_source}: {err}");
+
+                        self.report_message_validation_result(
+                            &message_id,
+                            propagation_source,
+                            MessageAcceptance::Reject,
+                        );
+
+                        if self.metrics {
+                            p2p_metrics().count_oversized_gossip(correct_topic.as_str());
+                        }
+
+                        return Some(FuelP2PEvent::GossipDecodeError {
+                            peer_id: propagation_source,
+                            topic_hash: message.topic,
+                        })
+                    }
                     Err(err) => {
-                        warn!(target: "fuel-p2p", "Failed to decode a message. ID: {}, Message: {:?} with error: {:?}", message_id, &message.data, err);
+                        if let Some(suppressed) =
+                            self.warn_limiter.check(WarnEvent::GossipDecodeFailed)
+                        {
+                            warn!(target: "fuel-p2p", suppressed, "Failed to decode a message. ID: {}, Message: {:?} with error: {:?}", message_id, &message.data, err);
+                        }
 
                         self.report_message_validation_result(
                             &message_id,
                             propagation_source,
                             MessageAcceptance::Reject,
                         );
+
+                        if self.metrics {
+                            p2p_metrics().gossip_decode_errors.inc();
+                        }
+
+                        return Some(FuelP2PEvent::GossipDecodeError {
+                            peer_id: propagation_source,
+                            topic_hash: message.topic,
+                        })
                     }
                 }
-            } else {
-                warn!(target: "fuel-p2p", "GossipTopicTag does not exist for {:?}", &message.topic);
+            } else if let Some(suppressed) =
+                self.warn_limiter.check(WarnEvent::UnknownGossipTopic)
+            {
+                warn!(target: "fuel-p2p", suppressed, "GossipTopicTag does not exist for {:?}", &message.topic);
             }
         }
         None
@@ -510,35 +1189,63 @@ impl FuelP2PService {
     ) -> Option<FuelP2PEvent> {
         match event {
             PeerReportEvent::PerformDecay => {
-                self.peer_manager.batch_update_score_with_decay()
+                self.peer_manager.batch_update_score_with_decay();
+                let behaviour = self.swarm.behaviour();
+                self.peer_manager
+                    .update_gossipsub_scores(|peer_id| behaviour.get_peer_score(peer_id));
             }
             PeerReportEvent::CheckReservedNodesHealth => {
-                let disconnected_peers: Vec<_> = self
-                    .peer_manager
-                    .get_disconnected_reserved_peers()
-                    .copied()
-                    .collect();
+                let disconnected_peers = self.peer_manager.reserved_peers_to_redial();
 
                 for peer_id in disconnected_peers {
                     debug!(target: "fuel-p2p", "Trying to reconnect to reserved peer {:?}", peer_id);
 
                     let _ = self.swarm.dial(peer_id);
                 }
+
+                for peer_id in self.peer_manager.bootstrap_peers_to_redial() {
+                    debug!(target: "fuel-p2p", "Trying to reconnect to bootstrap peer {:?}", peer_id);
+
+                    let _ = self.swarm.dial(peer_id);
+                }
+            }
+            PeerReportEvent::CheckIdlePeers => {
+                if let Some(timeout) = self.idle_connection_timeout {
+                    for peer_id in self.peer_manager.idle_peers(timeout) {
+                        debug!(target: "fuel-p2p", "Disconnecting idle peer {:?}", peer_id);
+
+                        let _ = self.swarm.disconnect_peer_id(peer_id);
+                    }
+                }
             }
             PeerReportEvent::PeerConnected {
                 peer_id,
                 initial_connection,
+                direction,
             } => {
-                if self
-                    .peer_manager
-                    .handle_peer_connected(&peer_id, initial_connection)
-                {
-                    let _ = self.swarm.disconnect_peer_id(peer_id);
-                } else if initial_connection {
-                    return Some(FuelP2PEvent::PeerConnected(peer_id));
-                }
+                match self.peer_manager.handle_peer_connected(
+                    &peer_id,
+                    initial_connection,
+                    direction,
+                ) {
+                    Some(peer_to_disconnect) if peer_to_disconnect == peer_id => {
+                        let _ = self.swarm.disconnect_peer_id(peer_id);
+                    }
+                    Some(evicted_peer) => {
+                        let _ = self.swarm.disconnect_peer_id(evicted_peer);
+                        return Some(FuelP2PEvent::PeerConnected(peer_id));
+                    }
+                    None if initial_connection => {
+                        return Some(FuelP2PEvent::PeerConnected(peer_id));
+                    }
+                    None => {}
+                }
             }
             PeerReportEvent::PeerDisconnected { peer_id } => {
+                self.inbound_rate_limiter.remove_peer(&peer_id);
+                if let Some(limiter) = &mut self.response_bytes_limiter {
+                    limiter.remove_peer(&peer_id);
+                }
                 if self.peer_manager.handle_peer_disconnect(peer_id) {
                     let _ = self.swarm.dial(peer_id);
                 }
@@ -548,6 +1255,16 @@ impl FuelP2PService {
         None
     }
 
+    fn warn_invalid_response_type(&mut self, request_id: OutboundRequestId) {
+        if let Some(suppressed) = self.warn_limiter.check(WarnEvent::InvalidResponseType)
+        {
+            warn!(
+                suppressed,
+                "Invalid response type received for request {:?}", request_id
+            );
+        }
+    }
+
     fn handle_request_response_event(
         &mut self,
         event: request_response::Event<RequestMessage, ResponseMessage>,
@@ -559,7 +1276,65 @@ impl FuelP2PService {
                     channel,
                     request_id,
                 } => {
-                    self.inbound_requests_table.insert(request_id, channel);
+                    self.peer_manager.handle_peer_activity(&peer);
+                    self.record_bandwidth(
+                        &peer,
+                        BandwidthProtocol::RequestResponse,
+                        TrafficDirection::Inbound,
+                        encoded_size(&request),
+                    );
+
+                    if !self.inbound_rate_limiter.try_acquire(peer) {
+                        debug!(target: "fuel-p2p", "Peer {:?} has exceeded the rate of requests", peer);
+                        // Dropping of the channel closes the stream without the response
+                        if !self.drop_rate_limited_requests {
+                            let response = ResponseMessage::Error(
+                                ResponseMessageError::TooManyRequests,
+                            );
+                            let _ = self
+                                .swarm
+                                .behaviour_mut()
+                                .send_response_msg(channel, response);
+                        }
+                        self.report_peer(peer, RATE_LIMITED_REQUEST_PENALTY, "p2p");
+                        return None;
+                    }
+
+                    let over_response_bytes = self
+                        .response_bytes_limiter
+                        .as_mut()
+                        .is_some_and(|limiter| limiter.is_exhausted(&peer));
+                    if over_response_bytes && request.is_block_request() {
+                        debug!(target: "fuel-p2p", "Peer {:?} has exceeded the response bytes per minute", peer);
+                        let response =
+                            ResponseMessage::Error(ResponseMessageError::TooManyRequests);
+                        let _ = self
+                            .swarm
+                            .behaviour_mut()
+                            .send_response_msg(channel, response);
+                        return None;
+                    }
+
+                    let span = tracing::debug_span!(
+                        target: "fuel-p2p",
+                        "p2p_inbound_request",
+                        %request_id,
+                        peer_id = %peer,
+                        request = request.kind(),
+                        block_height_range = ?request.block_height_range(),
+                    );
+                    span.in_scope(
+                        || debug!(target: "fuel-p2p", "The request is received"),
+                    );
+                    self.inbound_requests_table.insert(
+                        request_id,
+                        InboundRequest {
+                            peer_id: peer,
+                            channel,
+                            received_at: Instant::now(),
+                            span,
+                        },
+                    );
 
                     return Some(FuelP2PEvent::InboundRequestMessage {
                         request_id,
@@ -570,22 +1345,38 @@ impl FuelP2PService {
                     request_id,
                     response,
                 } => {
-                    let Some(channel) = self.outbound_requests_table.remove(&request_id)
+                    self.peer_manager.handle_peer_activity(&peer);
+                    self.record_bandwidth(
+                        &peer,
+                        BandwidthProtocol::RequestResponse,
+                        TrafficDirection::Inbound,
+                        encoded_size(&response),
+                    );
+
+                    let Some(OutboundRequest {
+                        on_response: channel,
+                        sent_at,
+                        span,
+                    }) = self.outbound_requests_table.remove(&request_id)
                     else {
                         debug!("Send channel not found for {:?}", request_id);
                         return None;
                     };
+                    let _entered = span.enter();
+                    let response_time = sent_at.elapsed();
+                    debug!(target: "fuel-p2p", "The response is received in {:?}", response_time);
+                    self.peer_manager.handle_response_time(&peer, response_time);
 
                     let send_ok = match channel {
                         ResponseSender::SealedHeaders(c) => match response {
                             ResponseMessage::SealedHeaders(v) => {
                                 c.send((peer, Ok(v))).is_ok()
                             }
+                            ResponseMessage::Error(e) => {
+                                c.send((peer, Err(e.into()))).is_ok()
+                            }
                             _ => {
-                                warn!(
-                                    "Invalid response type received for request {:?}",
-                                    request_id
-                                );
+                                self.warn_invalid_response_type(request_id);
                                 c.send((peer, Err(ResponseError::TypeMismatch))).is_ok()
                             }
                         },
@@ -593,11 +1384,33 @@ impl FuelP2PService {
                             ResponseMessage::Transactions(v) => {
                                 c.send((peer, Ok(v))).is_ok()
                             }
+                            ResponseMessage::Error(e) => {
+                                c.send((peer, Err(e.into()))).is_ok()
+                            }
+                            _ => {
+                                self.warn_invalid_response_type(request_id);
+                                c.send((peer, Err(ResponseError::TypeMismatch))).is_ok()
+                            }
+                        },
+                        ResponseSender::Tip(c) => match response {
+                            ResponseMessage::Tip(v) => c.send((peer, Ok(v))).is_ok(),
+                            ResponseMessage::Error(e) => {
+                                c.send((peer, Err(e.into()))).is_ok()
+                            }
+                            _ => {
+                                self.warn_invalid_response_type(request_id);
+                                c.send((peer, Err(ResponseError::TypeMismatch))).is_ok()
+                            }
+                        },
+                        ResponseSender::BlockChunk(c) => match response {
+                            ResponseMessage::BlockChunk(v) => {
+                                c.send((peer, Ok(v))).is_ok()
+                            }
+                            ResponseMessage::Error(e) => {
+                                c.send((peer, Err(e.into()))).is_ok()
+                            }
                             _ => {
-                                warn!(
-                                    "Invalid response type received for request {:?}",
-                                    request_id
-                                );
+                                self.warn_invalid_response_type(request_id);
                                 c.send((peer, Err(ResponseError::TypeMismatch))).is_ok()
                             }
                         },
@@ -613,27 +1426,49 @@ impl FuelP2PService {
                 error,
                 request_id,
             } => {
-                tracing::error!("RequestResponse inbound error for peer: {:?} with id: {:?} and error: {:?}", peer, request_id, error);
-
                 // Drop the channel, as we can't send a response
-                let _ = self.inbound_requests_table.remove(&request_id);
+                let span = self
+                    .inbound_requests_table
+                    .remove(&request_id)
+                    .map(|request| request.span)
+                    .or_else(|| {
+                        self.responses_in_flight
+                            .remove(&request_id)
+                            .map(|(span, _)| span)
+                    })
+                    .unwrap_or_else(tracing::Span::none);
+                let _entered = span.enter();
+                tracing::error!("RequestResponse inbound error for peer: {:?} with id: {:?} and error: {:?}", peer, request_id, error);
+            }
+            request_response::Event::ResponseSent { request_id, .. } => {
+                if let Some((span, received_at)) =
+                    self.responses_in_flight.remove(&request_id)
+                {
+                    let elapsed = received_at.elapsed();
+                    span.in_scope(
+                        || debug!(target: "fuel-p2p", ?elapsed, "The response is sent"),
+                    );
+                }
             }
             request_response::Event::OutboundFailure {
                 peer,
                 error,
                 request_id,
             } => {
+                let request = self.outbound_requests_table.remove(&request_id);
+                let span = request
+                    .as_ref()
+                    .map(|request| request.span.clone())
+                    .unwrap_or_else(tracing::Span::none);
+                let _entered = span.enter();
                 tracing::error!("RequestResponse outbound error for peer: {:?} with id: {:?} and error: {:?}", peer, request_id, error);
 
-                if let Some(channel) = self.outbound_requests_table.remove(&request_id) {
-                    match channel {
-                        ResponseSender::SealedHeaders(c) => {
-                            let _ = c.send((peer, Err(ResponseError::P2P(error))));
-                        }
-                        ResponseSender::Transactions(c) => {
-                            let _ = c.send((peer, Err(ResponseError::P2P(error))));
-                        }
-                    };
+                if let Some(OutboundRequest {
+                    on_response: channel,
+                    ..
+                }) = request
+                {
+                    channel.send_error(peer, outbound_failure_into_response_error(error));
                 }
             }
             _ => {}
@@ -644,12 +1479,31 @@ impl FuelP2PService {
     fn handle_identify_event(&mut self, event: identify::Event) -> Option<FuelP2PEvent> {
         match event {
             identify::Event::Received { peer_id, info } => {
+                if let Err(mismatch) = self.chain_handshake.check(&info.agent_version) {
+                    if self.metrics {
+                        p2p_metrics().chain_mismatches.inc();
+                    }
+                    warn!(target: "fuel-p2p", "Disconnecting {peer_id} that follows another chain: {mismatch}");
+                    let _ = self.swarm.disconnect_peer_id(peer_id);
+                    return Some(FuelP2PEvent::ChainMismatch(peer_id))
+                }
+
+                if let Some(requirement) = &self.version_requirement {
+                    if let Err(mismatch) = requirement.check(&info.agent_version) {
+                        tracing::info!(target: "fuel-p2p", "Disconnecting {peer_id}: {mismatch}");
+                        let _ = self.swarm.disconnect_peer_id(peer_id);
+                        return None
+                    }
+                }
+
                 if self.metrics {
                     p2p_metrics().unique_peers.inc();
                 }
 
                 let mut addresses = info.listen_addrs;
                 let agent_version = info.agent_version;
+                let is_archival =
+                    info.protocol_version == ARCHIVAL_IDENTIFY_PROTOCOL_VERSION;
 
                 if addresses.len() > MAX_IDENTIFY_ADDRESSES {
                     let protocol_version = info.protocol_version;
@@ -665,11 +1519,31 @@ impl FuelP2PService {
                     &peer_id,
                     addresses.clone(),
                     agent_version,
+                    info.protocols,
+                    is_archival,
                 );
 
                 self.swarm
                     .behaviour_mut()
                     .add_addresses_to_discovery(&peer_id, addresses);
+
+                // The outbound connections use the ephemeral ports,
+                // so only the peers that dialed the node observe its listen address
+                let peer_dialed_us = self
+                    .peer_manager
+                    .get_peer_info(&peer_id)
+                    .and_then(|peer_info| peer_info.direction)
+                    == Some(ConnectionDirection::Inbound);
+                let observed_address = info.observed_addr;
+                if peer_dialed_us
+                    && self.observed_address.as_ref() != Some(&observed_address)
+                {
+                    tracing::info!(
+                        "The node is observed at `{observed_address}` by `{peer_id}`"
+                    );
+                    self.observed_address = Some(observed_address);
+                    return Some(FuelP2PEvent::ListenAddrChanged)
+                }
             }
             identify::Event::Sent { .. } => {}
             identify::Event::Pushed { .. } => {}
@@ -688,8 +1562,14 @@ impl FuelP2PService {
             peer_id,
             latest_block_height,
         } = event;
-        self.peer_manager
+        let is_fresh = self
+            .peer_manager
             .handle_peer_info_updated(&peer_id, latest_block_height);
+        // The stale height would make the sync go back to the blocks the peer already has
+        if !is_fresh {
+            debug!(target: "fuel-p2p", "Ignoring the stale block height {latest_block_height} of the peer {peer_id}");
+            return None
+        }
 
         Some(FuelP2PEvent::PeerInfoUpdated {
             peer_id,
@@ -702,32 +1582,62 @@ impl FuelP2PService {
 #[cfg(test)]
 mod tests {
     use super::{
+        outbound_failure_into_response_error,
         FuelP2PService,
+        MessageAcceptance,
+        MessageId,
+        OutboundFailure,
         PublishError,
     };
     use crate::{
-        codecs::postcard::PostcardCodec,
-        config::Config,
+        codecs::postcard::{
+            MessageExchangePostcardProtocol,
+            PayloadTooLarge,
+            PostcardCodec,
+        },
+        config::{
+            Config,
+            ConfigError,
+            OverflowPolicy,
+            TransportSecurity,
+        },
+        connection_gater::ConnectionGateError,
+        error::P2pError,
         gossipsub::{
+            config::{
+                GossipMessageIdStrategy,
+                GossipsubScoringConfig,
+            },
             messages::{
+                GossipTopicTag,
                 GossipsubBroadcastRequest,
                 GossipsubMessage,
             },
             topics::{
                 GossipTopic,
+                NEW_TX_BATCH_GOSSIP_TOPIC,
                 NEW_TX_GOSSIP_TOPIC,
             },
         },
         p2p_service::FuelP2PEvent,
-        peer_manager::PeerInfo,
+        peer_manager::{
+            ConnectionDirection,
+            PeerInfo,
+        },
         request_response::messages::{
+            RequestError,
             RequestMessage,
             ResponseError,
             ResponseMessage,
+            ResponseMessageError,
             ResponseSender,
         },
-        service::to_message_acceptance,
+        service::{
+            to_message_acceptance,
+            TaskP2PService,
+        },
     };
+    use fuel_core_metrics::p2p_metrics::p2p_metrics;
     use fuel_core_types::{
         blockchain::{
             consensus::{
@@ -753,7 +1663,10 @@ mod tests {
     use libp2p::{
         gossipsub::Topic,
         identity::Keypair,
+        multiaddr::Protocol,
         swarm::{
+            dial_opts::DialOpts,
+            DialError,
             ListenError,
             SwarmEvent,
         },
@@ -762,7 +1675,10 @@ mod tests {
     };
     use rand::Rng;
     use std::{
-        collections::HashSet,
+        collections::{
+            HashMap,
+            HashSet,
+        },
         ops::Range,
         sync::Arc,
         time::Duration,
@@ -778,14 +1694,20 @@ mod tests {
     type P2PService = FuelP2PService;
 
     /// helper function for building FuelP2PService
-    async fn build_service_from_config(mut p2p_config: Config) -> P2PService {
+    async fn build_service_from_config(p2p_config: Config) -> P2PService {
+        let codec = PostcardCodec::new(p2p_config.max_block_size);
+        build_service_with_codec(p2p_config, codec).await
+    }
+
+    async fn build_service_with_codec(
+        mut p2p_config: Config,
+        codec: PostcardCodec,
+    ) -> P2PService {
         p2p_config.keypair = Keypair::generate_secp256k1(); // change keypair for each Node
-        let max_block_size = p2p_config.max_block_size;
         let (sender, _) =
             broadcast::channel(p2p_config.reserved_nodes.len().saturating_add(1));
 
-        let mut service =
-            FuelP2PService::new(sender, p2p_config, PostcardCodec::new(max_block_size));
+        let mut service = FuelP2PService::new(sender, p2p_config, codec).unwrap();
         service.start().await.unwrap();
         service
     }
@@ -820,100 +1742,720 @@ mod tests {
         });
     }
 
+    #[tokio::test]
+    #[instrument]
+    async fn topic_subscription_can_be_changed_at_runtime() {
+        let mut p2p_config =
+            Config::default_initialized("topic_subscription_can_be_changed_at_runtime");
+        p2p_config.subscribed_topics = HashSet::new();
+        let mut node = build_service_from_config(p2p_config).await;
+        assert!(!node.is_subscribed(GossipTopicTag::NewTx));
+
+        node.set_topic_subscription(GossipTopicTag::NewTx, true)
+            .unwrap();
+        assert!(node.is_subscribed(GossipTopicTag::NewTx));
+
+        node.set_topic_subscription(GossipTopicTag::NewTx, false)
+            .unwrap();
+        assert!(!node.is_subscribed(GossipTopicTag::NewTx));
+    }
+
+    #[test]
+    fn gossip_topic_tag_is_parsed_from_the_topic_name() {
+        assert_eq!(
+            NEW_TX_GOSSIP_TOPIC.parse::<GossipTopicTag>().unwrap(),
+            GossipTopicTag::NewTx
+        );
+        assert!("unknown_topic".parse::<GossipTopicTag>().is_err());
+    }
+
     #[tokio::test]
     #[instrument]
     async fn p2p_service_works() {
         build_service_from_config(Config::default_initialized("p2p_service_works")).await;
     }
 
-    // Single sentry node connects to multiple reserved nodes and `max_peers_allowed` amount of non-reserved nodes.
-    // It also tries to dial extra non-reserved nodes to establish the connection.
-    // A single reserved node is not started immediately with the rest of the nodes.
-    // Once sentry node establishes the connection with the allowed number of nodes
-    // we start the reserved node, and await for it to establish the connection.
-    // This test proves that there is always an available slot for the reserved node to connect to.
-    #[tokio::test(flavor = "multi_thread")]
+    #[tokio::test]
     #[instrument]
-    async fn reserved_nodes_reconnect_works() {
-        let p2p_config = Config::default_initialized("reserved_nodes_reconnect_works");
+    async fn p2p_service_rejects_invalid_mesh_size() {
+        let mut p2p_config =
+            Config::default_initialized("p2p_service_rejects_invalid_mesh_size");
+        p2p_config.min_mesh_size = 8;
+        p2p_config.ideal_mesh_size = 6;
+        p2p_config.max_mesh_size = 12;
+        let max_block_size = p2p_config.max_block_size;
+        let (sender, _) = broadcast::channel(1);
 
-        // total amount will be `max_peers_allowed` + `reserved_nodes.len()`
-        let max_peers_allowed: usize = 3;
+        let result =
+            FuelP2PService::new(sender, p2p_config, PostcardCodec::new(max_block_size));
 
-        let (bootstrap_nodes, bootstrap_multiaddrs) =
-            setup_bootstrap_nodes(&p2p_config, max_peers_allowed.saturating_mul(5)).await;
-        let (mut reserved_nodes, reserved_multiaddrs) =
-            setup_bootstrap_nodes(&p2p_config, max_peers_allowed).await;
+        let err = result.err().expect("Invalid mesh size should be rejected");
+        assert_eq!(
+            err.downcast_ref::<ConfigError>(),
+            Some(&ConfigError::InvalidMeshSize {
+                min_mesh_size: 8,
+                ideal_mesh_size: 6,
+                max_mesh_size: 12,
+            })
+        );
+    }
 
-        let mut sentry_node = {
-            let mut p2p_config = p2p_config.clone();
-            p2p_config.max_peers_connected = max_peers_allowed as u32;
+    #[tokio::test]
+    #[instrument]
+    async fn p2p_service_rejects_graylist_threshold_above_publish_threshold() {
+        let mut p2p_config = Config::default_initialized(
+            "p2p_service_rejects_graylist_threshold_above_publish_threshold",
+        );
+        p2p_config.gossipsub_scoring_config.publish_threshold = -100.0;
+        p2p_config.gossipsub_scoring_config.graylist_threshold = -10.0;
+        let max_block_size = p2p_config.max_block_size;
+        let (sender, _) = broadcast::channel(1);
 
-            p2p_config.bootstrap_nodes = bootstrap_multiaddrs;
+        let result =
+            FuelP2PService::new(sender, p2p_config, PostcardCodec::new(max_block_size));
 
-            p2p_config.reserved_nodes = reserved_multiaddrs;
+        let err = result
+            .err()
+            .expect("Graylist threshold above publish threshold should be rejected");
+        assert!(matches!(
+            err.downcast_ref::<ConfigError>(),
+            Some(ConfigError::InvalidGossipsubScoring(_))
+        ));
+    }
 
-            build_service_from_config(p2p_config).await
+    #[tokio::test]
+    #[instrument]
+    async fn p2p_service_rejects_ban_duration_beyond_system_time() {
+        let mut p2p_config = Config::default_initialized(
+            "p2p_service_rejects_ban_duration_beyond_system_time",
+        );
+        p2p_config.peer_reputation_config.ban_duration = Duration::MAX;
+        let max_block_size = p2p_config.max_block_size;
+        let (sender, _) = broadcast::channel(1);
+
+        let result =
+            FuelP2PService::new(sender, p2p_config, PostcardCodec::new(max_block_size));
+
+        let err = result
+            .err()
+            .expect("Too long ban duration should be rejected");
+        assert_eq!(
+            err.downcast_ref::<ConfigError>(),
+            Some(&ConfigError::DurationTooLarge {
+                name: "ban_duration",
+                duration: Duration::MAX,
+            })
+        );
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn p2p_service_rejects_known_peers_save_interval_beyond_current_time() {
+        let mut p2p_config = Config::default_initialized(
+            "p2p_service_rejects_known_peers_save_interval_beyond_current_time",
+        );
+        p2p_config.known_peers_save_interval = Duration::MAX;
+        let max_block_size = p2p_config.max_block_size;
+        let (sender, _) = broadcast::channel(1);
+
+        let result =
+            FuelP2PService::new(sender, p2p_config, PostcardCodec::new(max_block_size));
+
+        let err = result
+            .err()
+            .expect("Too long known peers save interval should be rejected");
+        assert_eq!(
+            err.downcast_ref::<ConfigError>(),
+            Some(&ConfigError::DurationTooLarge {
+                name: "known_peers_save_interval",
+                duration: Duration::MAX,
+            })
+        );
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn p2p_service_rejects_unsupported_overflow_policies() {
+        let mut p2p_config = Config::default_initialized(
+            "p2p_service_rejects_unsupported_overflow_policies",
+        );
+        p2p_config.request_overflow_policy = OverflowPolicy::DropOldest;
+        let max_block_size = p2p_config.max_block_size;
+        let (sender, _) = broadcast::channel(1);
+
+        let result =
+            FuelP2PService::new(sender, p2p_config, PostcardCodec::new(max_block_size));
+
+        let err = result
+            .err()
+            .expect("The requests can't drop the oldest queued request");
+        assert_eq!(
+            err.downcast_ref::<ConfigError>(),
+            Some(&ConfigError::UnsupportedOverflowPolicy {
+                channel: "requests",
+                policy: OverflowPolicy::DropOldest,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn p2p_service_rejects_block_chunks_above_max_block_size() {
+        let mut p2p_config = Config::default_initialized(
+            "p2p_service_rejects_block_chunks_above_max_block_size",
+        );
+        let max_block_size = p2p_config.max_block_size;
+        p2p_config.block_chunk_size = max_block_size;
+        let (sender, _) = broadcast::channel(1);
+
+        let result =
+            FuelP2PService::new(sender, p2p_config, PostcardCodec::new(max_block_size));
+
+        let err = result
+            .err()
+            .expect("The chunk with its framing doesn't fit into one response");
+        assert_eq!(
+            err.downcast_ref::<ConfigError>(),
+            Some(&ConfigError::InvalidBlockChunkSize {
+                block_chunk_size: max_block_size,
+                max_block_size,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn p2p_service_rejects_zero_db_lookup_limits() {
+        let zero_limits: [(&str, fn(&mut Config)); 2] = [
+            ("max_concurrent_db_lookups", |config| {
+                config.max_concurrent_db_lookups = 0
+            }),
+            ("db_lookup_responses_capacity", |config| {
+                config.db_lookup_responses_capacity = 0
+            }),
+        ];
+        for (name, set_zero) in zero_limits {
+            let mut p2p_config =
+                Config::default_initialized("p2p_service_rejects_zero_db_lookup_limits");
+            set_zero(&mut p2p_config);
+            let max_block_size = p2p_config.max_block_size;
+            let (sender, _) = broadcast::channel(1);
+
+            let result = FuelP2PService::new(
+                sender,
+                p2p_config,
+                PostcardCodec::new(max_block_size),
+            );
+
+            let err = result.err().expect("The zero limit should be rejected");
+            assert_eq!(
+                err.downcast_ref::<ConfigError>(),
+                Some(&ConfigError::ZeroValue { name })
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn stale_heartbeat_is_not_reported() {
+        let mut node = build_service_from_config(Config::default_initialized(
+            "stale_heartbeat_is_not_reported",
+        ))
+        .await;
+        let peer_id = PeerId::random();
+        node.peer_manager.handle_peer_connected(
+            &peer_id,
+            true,
+            ConnectionDirection::Inbound,
+        );
+        let heartbeat = |height: u32| crate::heartbeat::Event {
+            peer_id,
+            latest_block_height: height.into(),
         };
 
-        // pop() a single reserved node, so it's not run with the rest of the nodes
-        let mut reserved_node = reserved_nodes.pop();
-        let reserved_node_peer_id = reserved_node.as_ref().unwrap().local_peer_id;
+        let event = node.handle_heartbeat_event(heartbeat(10));
+        assert!(matches!(
+            event,
+            Some(FuelP2PEvent::PeerInfoUpdated { block_height, .. }) if block_height == 10.into()
+        ));
 
-        let all_node_services: Vec<_> = bootstrap_nodes
-            .into_iter()
-            .chain(reserved_nodes.into_iter())
-            .collect();
+        // The lower height is stale, so it isn't passed to the sync
+        assert!(node.handle_heartbeat_event(heartbeat(5)).is_none());
+        let peer_info = node.peer_manager.get_peer_info(&peer_id).unwrap();
+        assert_eq!(peer_info.heartbeat_data.block_height, Some(10.into()));
 
-        let mut all_nodes_ids: Vec<PeerId> = all_node_services
-            .iter()
-            .map(|service| service.local_peer_id)
-            .collect();
+        // The same height is still reported
+        assert!(node.handle_heartbeat_event(heartbeat(10)).is_some());
+    }
 
-        let (stop_sender, _) = watch::channel(());
-        all_node_services.into_iter().for_each(|node| {
-            spawn(&stop_sender, node);
-        });
+    #[test]
+    fn oversized_response_is_reported_as_too_large() {
+        let err = std::io::Error::from(PayloadTooLarge { max_size: 10 });
+
+        let error = outbound_failure_into_response_error(OutboundFailure::Io(err));
+
+        assert!(matches!(error, ResponseError::TooLarge { size: 11 }));
+    }
+
+    #[test]
+    fn default_gossipsub_scoring_config_is_valid() {
+        assert_eq!(GossipsubScoringConfig::default().validate(), Ok(()));
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn nodes_with_configured_transport_security_exchange_gossip() {
+        let mut p2p_config = Config::default_initialized(
+            "nodes_with_configured_transport_security_exchange_gossip",
+        );
+        p2p_config.transport_security = TransportSecurity::Noise;
+
+        // Node A
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+
+        // Node B
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config.clone()).await;
+
+        let mut message_sent = false;
 
         loop {
             tokio::select! {
-                sentry_node_event = sentry_node.next_event() => {
-                    // we've connected to all other peers
-                    if sentry_node.peer_manager.total_peers_connected() > max_peers_allowed {
-                        // if the `reserved_node` is not included,
-                        // create and insert it, to be polled with rest of the nodes
-                        if !all_nodes_ids
-                        .iter()
-                        .any(|local_peer_id| local_peer_id == &reserved_node_peer_id) {
-                            if let Some(node) = reserved_node {
-                                all_nodes_ids.push(node.local_peer_id);
-                                spawn(&stop_sender, node);
-                                reserved_node = None;
-                            }
+                node_a_event = node_a.next_event() => {
+                    if let Some(FuelP2PEvent::PeerInfoUpdated { .. }) = node_a_event {
+                        if !message_sent {
+                            message_sent = true;
+                            let tx = Arc::new(Transaction::default_test_tx());
+                            node_a.publish_message(GossipsubBroadcastRequest::NewTx(tx)).unwrap();
                         }
                     }
-                    if let Some(FuelP2PEvent::PeerConnected(peer_id)) = sentry_node_event {
-                        // we connected to the desired reserved node
-                        if peer_id == reserved_node_peer_id {
+                },
+                node_b_event = node_b.next_event() => {
+                    // The message is only delivered over the secured connection
+                    if let Some(FuelP2PEvent::GossipsubMessage { message, .. }) = node_b_event {
+                        let GossipsubMessage::NewTx(tx) = message else {
+                            panic!("Expected a single transaction")
+                        };
+                        assert_eq!(tx, Transaction::default_test_tx());
+                        break
+                    }
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn peers_connect_over_each_listen_address() {
+        let mut p2p_config =
+            Config::default_initialized("peers_connect_over_each_listen_address");
+
+        // Node A listens on IPv4 and IPv6
+        let mut node_a_config = p2p_config.clone();
+        node_a_config.address = "127.0.0.1".parse().unwrap();
+        node_a_config.listen_addresses = vec!["/ip6/::1/tcp/0".parse().unwrap()];
+        let mut node_a = build_service_from_config(node_a_config).await;
+        let node_a_peer_id = node_a.local_peer_id;
+        let node_a_addresses = node_a.multiaddrs();
+        let is_ip6 =
+            |address: &Multiaddr| matches!(address.iter().next(), Some(Protocol::Ip6(_)));
+        assert_eq!(node_a_addresses.len(), 2);
+        assert_eq!(node_a_addresses.iter().filter(|a| is_ip6(a)).count(), 1);
+
+        for address in node_a_addresses {
+            // Node B knows only one address of Node A
+            p2p_config.reserved_nodes = vec![address];
+            let mut node_b = build_service_from_config(p2p_config.clone()).await;
+
+            loop {
+                tokio::select! {
+                    node_b_event = node_b.next_event() => {
+                        if let Some(FuelP2PEvent::PeerConnected(peer_id)) = node_b_event {
+                            assert_eq!(peer_id, node_a_peer_id);
                             break
                         }
+                    },
+                    node_a_event = node_a.next_event() => {
+                        tracing::info!("Node A Event: {:?}", node_a_event);
                     }
-                },
+                }
             }
         }
-        stop_sender.send(()).unwrap();
     }
 
-    // We start with two nodes, node_a and node_b, bootstrapped with `bootstrap_nodes_count` other nodes.
-    // Yet node_a and node_b are only allowed to connect to specified amount of nodes.
     #[tokio::test]
     #[instrument]
-    async fn max_peers_connected_works() {
-        let p2p_config = Config::default_initialized("max_peers_connected_works");
-
-        let bootstrap_nodes_count = 20;
-        let node_a_max_peers_allowed: usize = 3;
-        let node_b_max_peers_allowed: usize = 5;
+    async fn unsupported_listen_address_does_not_prevent_start() {
+        let mut p2p_config = Config::default_initialized(
+            "unsupported_listen_address_does_not_prevent_start",
+        );
+        p2p_config.address = "127.0.0.1".parse().unwrap();
+        p2p_config.listen_addresses =
+            vec!["/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap()];
+
+        let node = build_service_from_config(p2p_config.clone()).await;
+        assert_eq!(node.multiaddrs().len(), 1);
+
+        // The start fails if none of the addresses is bound
+        let busy_port = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        p2p_config.tcp_port = busy_port.local_addr().unwrap().port();
+        p2p_config.listen_addresses = vec![];
+        let (sender, _) = broadcast::channel(1);
+        let codec = PostcardCodec::new(p2p_config.max_block_size);
+        let mut service = FuelP2PService::new(sender, p2p_config, codec).unwrap();
+        assert!(matches!(
+            service.start().await,
+            Err(P2pError::BindFailed { .. })
+        ));
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn bootstrap_node_started_later_is_connected_without_restart() {
+        let mut p2p_config = Config::default_initialized(
+            "bootstrap_node_started_later_is_connected_without_restart",
+        );
+        p2p_config.address = "127.0.0.1".parse().unwrap();
+        // Disables the random walk, so only the redials dial the bootstrap node
+        p2p_config.random_walk = None;
+
+        // Node A doesn't exist yet, only its address and peer id are known
+        let node_a_keypair = Keypair::generate_secp256k1();
+        let node_a_peer_id = node_a_keypair.public().to_peer_id();
+        let node_a_port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let node_a_address: Multiaddr =
+            format!("/ip4/127.0.0.1/tcp/{node_a_port}/p2p/{node_a_peer_id}")
+                .parse()
+                .unwrap();
+
+        // Node B fails to dial Node A
+        let mut node_b_config = p2p_config.clone();
+        node_b_config.bootstrap_nodes = vec![node_a_address];
+        let mut node_b = build_service_from_config(node_b_config).await;
+        let _ = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                node_b.next_event().await;
+            }
+        })
+        .await;
+        assert!(!node_b.get_peers_ids_iter().any(|id| *id == node_a_peer_id));
+
+        // Node A starts on the known address
+        p2p_config.keypair = node_a_keypair;
+        p2p_config.tcp_port = node_a_port;
+        let (sender, _) = broadcast::channel(1);
+        let codec = PostcardCodec::new(p2p_config.max_block_size);
+        let mut node_a = FuelP2PService::new(sender, p2p_config, codec).unwrap();
+        node_a.start().await.unwrap();
+
+        // Node B redials Node A
+        let connected = tokio::time::timeout(Duration::from_secs(20), async {
+            loop {
+                tokio::select! {
+                    node_b_event = node_b.next_event() => {
+                        if let Some(FuelP2PEvent::PeerConnected(peer_id)) = node_b_event {
+                            if peer_id == node_a_peer_id {
+                                break
+                            }
+                        }
+                    },
+                    _ = node_a.swarm.select_next_some() => {},
+                };
+            }
+        })
+        .await;
+        assert!(connected.is_ok(), "Node B should connect to Node A");
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn dial_returns_peer_id_of_listening_node_or_error() {
+        let mut p2p_config = Config::default_initialized(
+            "dial_returns_peer_id_of_listening_node_or_error",
+        );
+        p2p_config.address = "127.0.0.1".parse().unwrap();
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+        let mut node_b = build_service_from_config(p2p_config).await;
+        // The listen address without the `/p2p/` suffix of the peer id
+        let mut node_a_address = node_a.multiaddrs().remove(0);
+        node_a_address.pop();
+
+        // The listening node is dialed without knowing its peer id
+        let (sender, mut receiver) = oneshot::channel();
+        node_b.dial(node_a_address, sender);
+        let result = loop {
+            tokio::select! {
+                result = &mut receiver => break result.unwrap(),
+                _ = node_a.swarm.select_next_some() => {},
+                _ = node_b.next_event() => {},
+            }
+        };
+        assert_eq!(result.unwrap(), node_a.local_peer_id);
+
+        // Nothing listens on the dead address
+        let dead_port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let dead_address: Multiaddr =
+            format!("/ip4/127.0.0.1/tcp/{dead_port}").parse().unwrap();
+        let (sender, mut receiver) = oneshot::channel();
+        node_b.dial(dead_address, sender);
+        let result = loop {
+            tokio::select! {
+                result = &mut receiver => break result.unwrap(),
+                _ = node_b.next_event() => {},
+            }
+        };
+        assert!(matches!(result, Err(DialError::Transport(_))));
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn reserved_node_reconnects_after_forced_disconnect() {
+        let mut p2p_config = Config::default_initialized(
+            "reserved_node_reconnects_after_forced_disconnect",
+        );
+
+        // Node A
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+        let node_a_peer_id = node_a.local_peer_id;
+
+        // Node B treats Node A as a reserved node
+        p2p_config.reserved_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config).await;
+
+        let mut connections = 0;
+
+        loop {
+            tokio::select! {
+                node_b_event = node_b.next_event() => {
+                    if let Some(FuelP2PEvent::PeerConnected(peer_id)) = node_b_event {
+                        assert_eq!(peer_id, node_a_peer_id);
+                        connections = connections.saturating_add(1);
+                        if connections == 1 {
+                            // Force the disconnect, Node B should restore the connection
+                            let _ = node_b.swarm.disconnect_peer_id(peer_id);
+                        } else {
+                            break;
+                        }
+                    }
+
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                },
+                node_a_event = node_a.next_event() => {
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                }
+            }
+        }
+
+        assert!(node_b.peer_manager.is_reserved(&node_a_peer_id));
+        assert_eq!(node_b.peer_manager.total_peers_connected(), 1);
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn added_reserved_peer_is_dialed_and_kept_connected() {
+        let p2p_config = Config::default_initialized(
+            "added_reserved_peer_is_dialed_and_kept_connected",
+        );
+
+        // Node A
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+        let node_a_peer_id = node_a.local_peer_id;
+
+        // Node B doesn't know about Node A at the startup
+        let mut node_b = build_service_from_config(p2p_config).await;
+        let node_a_address = node_a.multiaddrs().pop().unwrap();
+        node_b.add_reserved_peer(node_a_peer_id, node_a_address);
+
+        let mut connections = 0;
+
+        loop {
+            tokio::select! {
+                node_b_event = node_b.next_event() => {
+                    if let Some(FuelP2PEvent::PeerConnected(peer_id)) = node_b_event {
+                        assert_eq!(peer_id, node_a_peer_id);
+                        connections = connections.saturating_add(1);
+                        if connections == 1 {
+                            // Force the disconnect, Node B should restore the connection
+                            let _ = node_b.swarm.disconnect_peer_id(peer_id);
+                        } else {
+                            break;
+                        }
+                    }
+
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                },
+                node_a_event = node_a.next_event() => {
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                }
+            }
+        }
+
+        assert!(node_b.peer_manager.is_reserved(&node_a_peer_id));
+        assert_eq!(node_b.peer_manager.peer_counts().reserved_peers, 1);
+
+        // when
+        node_b.remove_reserved_peer(node_a_peer_id);
+
+        // then
+        assert!(!node_b.peer_manager.is_reserved(&node_a_peer_id));
+        assert_eq!(node_b.peer_manager.peer_counts().non_reserved_peers, 1);
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn idle_non_reserved_peer_is_disconnected_and_reserved_one_stays() {
+        let mut p2p_config = Config::default_initialized(
+            "idle_non_reserved_peer_is_disconnected_and_reserved_one_stays",
+        );
+
+        // Node B is a reserved node of Node A
+        let node_b = build_service_from_config(p2p_config.clone()).await;
+        let node_b_peer_id = node_b.local_peer_id;
+        let node_b_addresses = node_b.multiaddrs();
+
+        // Node A disconnects the idle peers
+        let mut node_a_config = p2p_config.clone();
+        node_a_config.reserved_nodes = node_b_addresses;
+        node_a_config.idle_connection_timeout = Some(Duration::from_secs(2));
+        let mut node_a = build_service_from_config(node_a_config).await;
+
+        // Node C is a regular peer connected to Node A
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let node_c = build_service_from_config(p2p_config).await;
+        let node_c_peer_id = node_c.local_peer_id;
+
+        let (stop_sender, _) = watch::channel(());
+        spawn(&stop_sender, node_b);
+        spawn(&stop_sender, node_c);
+
+        let wait_for_idle_disconnect = async {
+            let mut node_c_connected = false;
+            loop {
+                match node_a.next_event().await {
+                    Some(FuelP2PEvent::PeerConnected(peer_id))
+                        if peer_id == node_c_peer_id =>
+                    {
+                        node_c_connected = true;
+                    }
+                    Some(FuelP2PEvent::PeerDisconnected(peer_id)) => {
+                        assert_ne!(peer_id, node_b_peer_id);
+                        if peer_id == node_c_peer_id && node_c_connected {
+                            break
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(20), wait_for_idle_disconnect)
+            .await
+            .expect("The idle peer should be disconnected");
+
+        let reserved_peers: Vec<_> = node_a
+            .peer_manager
+            .get_connected_reserved_peers()
+            .copied()
+            .collect();
+        assert_eq!(reserved_peers, vec![node_b_peer_id]);
+        assert!(!node_a
+            .peer_manager
+            .get_peers_ids()
+            .any(|peer_id| *peer_id == node_c_peer_id));
+    }
+
+    // Single sentry node connects to multiple reserved nodes and `max_peers_allowed` amount of non-reserved nodes.
+    // It also tries to dial extra non-reserved nodes to establish the connection.
+    // A single reserved node is not started immediately with the rest of the nodes.
+    // Once sentry node establishes the connection with the allowed number of nodes
+    // we start the reserved node, and await for it to establish the connection.
+    // This test proves that there is always an available slot for the reserved node to connect to.
+    #[tokio::test(flavor = "multi_thread")]
+    #[instrument]
+    async fn reserved_nodes_reconnect_works() {
+        let p2p_config = Config::default_initialized("reserved_nodes_reconnect_works");
+
+        // total amount will be `max_peers_allowed` + `reserved_nodes.len()`
+        let max_peers_allowed: usize = 3;
+
+        let (bootstrap_nodes, bootstrap_multiaddrs) =
+            setup_bootstrap_nodes(&p2p_config, max_peers_allowed.saturating_mul(5)).await;
+        let (mut reserved_nodes, reserved_multiaddrs) =
+            setup_bootstrap_nodes(&p2p_config, max_peers_allowed).await;
+
+        let mut sentry_node = {
+            let mut p2p_config = p2p_config.clone();
+            p2p_config.max_peers_connected = max_peers_allowed as u32;
+
+            p2p_config.bootstrap_nodes = bootstrap_multiaddrs;
+
+            p2p_config.reserved_nodes = reserved_multiaddrs;
+
+            build_service_from_config(p2p_config).await
+        };
+
+        // pop() a single reserved node, so it's not run with the rest of the nodes
+        let mut reserved_node = reserved_nodes.pop();
+        let reserved_node_peer_id = reserved_node.as_ref().unwrap().local_peer_id;
+
+        let all_node_services: Vec<_> = bootstrap_nodes
+            .into_iter()
+            .chain(reserved_nodes.into_iter())
+            .collect();
+
+        let mut all_nodes_ids: Vec<PeerId> = all_node_services
+            .iter()
+            .map(|service| service.local_peer_id)
+            .collect();
+
+        let (stop_sender, _) = watch::channel(());
+        all_node_services.into_iter().for_each(|node| {
+            spawn(&stop_sender, node);
+        });
+
+        loop {
+            tokio::select! {
+                sentry_node_event = sentry_node.next_event() => {
+                    // we've connected to all other peers
+                    if sentry_node.peer_manager.total_peers_connected() > max_peers_allowed {
+                        // if the `reserved_node` is not included,
+                        // create and insert it, to be polled with rest of the nodes
+                        if !all_nodes_ids
+                        .iter()
+                        .any(|local_peer_id| local_peer_id == &reserved_node_peer_id) {
+                            if let Some(node) = reserved_node {
+                                all_nodes_ids.push(node.local_peer_id);
+                                spawn(&stop_sender, node);
+                                reserved_node = None;
+                            }
+                        }
+                    }
+                    if let Some(FuelP2PEvent::PeerConnected(peer_id)) = sentry_node_event {
+                        // we connected to the desired reserved node
+                        if peer_id == reserved_node_peer_id {
+                            break
+                        }
+                    }
+                },
+            }
+        }
+        stop_sender.send(()).unwrap();
+    }
+
+    // We start with two nodes, node_a and node_b, bootstrapped with `bootstrap_nodes_count` other nodes.
+    // Yet node_a and node_b are only allowed to connect to specified amount of nodes.
+    #[tokio::test]
+    #[instrument]
+    async fn max_peers_connected_works() {
+        let p2p_config = Config::default_initialized("max_peers_connected_works");
+
+        let bootstrap_nodes_count = 20;
+        let node_a_max_peers_allowed: usize = 3;
+        let node_b_max_peers_allowed: usize = 5;
 
         let (mut nodes, nodes_multiaddrs) =
             setup_bootstrap_nodes(&p2p_config, bootstrap_nodes_count).await;
@@ -1112,122 +2654,701 @@ mod tests {
         }
     }
 
-    // Simulates 2 p2p nodes that are on the same network but their Fuel Upgrade checksum is different
-    // (different chain id or chain config)
-    // So they are not able to connect
     #[tokio::test]
     #[instrument]
-    async fn nodes_cannot_connect_due_to_different_checksum() {
-        use libp2p::TransportError;
-        // Node A
-        let mut p2p_config =
-            Config::default_initialized("nodes_cannot_connect_due_to_different_checksum");
+    async fn nodes_are_not_discovered_via_mdns_once_it_is_disabled() {
+        let mut p2p_config = Config::default_initialized(
+            "nodes_are_not_discovered_via_mdns_once_it_is_disabled",
+        );
+        p2p_config.enable_mdns = true;
+
+        // Node A stops the discovery on the local network
         let mut node_a = build_service_from_config(p2p_config.clone()).await;
+        node_a.set_discovery(Some(false), None);
+        let status = node_a.discovery_status();
+        assert!(!status.mdns_enabled);
+        assert!(status.kad_enabled);
+        let discovered_before = status.mdns_discovered_peers;
 
-        // different checksum
-        p2p_config.checksum = [1u8; 32].into();
-        p2p_config.bootstrap_nodes = node_a.multiaddrs();
-        // Node B
+        // Node B appears on the local network
         let mut node_b = build_service_from_config(p2p_config).await;
+        let node_b_id = node_b.local_peer_id;
+
+        let connected = tokio::time::timeout(Duration::from_secs(3), async {
+            loop {
+                tokio::select! {
+                    node_a_event = node_a.next_event() => {
+                        if let Some(FuelP2PEvent::PeerConnected(peer_id)) = node_a_event {
+                            if peer_id == node_b_id {
+                                break
+                            }
+                        }
+                    },
+                    _ = node_b.swarm.select_next_some() => {},
+                };
+            }
+        })
+        .await;
+        assert!(connected.is_err(), "Node B shouldn't be discovered");
+        assert_eq!(
+            node_a.discovery_status().mdns_discovered_peers,
+            discovered_before
+        );
 
+        // Node A finds Node B once mDNS is enabled again.
+        // Kademlia is disabled, so Node B is dialed right after it is discovered.
+        node_a.set_discovery(Some(true), Some(false));
         loop {
             tokio::select! {
-                node_a_event = node_a.swarm.select_next_some() => {
-                    tracing::info!("Node A Event: {:?}", node_a_event);
-                    if let SwarmEvent::IncomingConnectionError { error: ListenError::Transport(TransportError::Other(_)), .. } = node_a_event {
-                        break
-                    }
-                },
-                node_b_event = node_b.next_event() => {
-                    if let Some(FuelP2PEvent::PeerConnected(_)) = node_b_event {
-                        panic!("Node B should not connect to Node A!")
+                node_a_event = node_a.next_event() => {
+                    if let Some(FuelP2PEvent::PeerConnected(peer_id)) = node_a_event {
+                        if peer_id == node_b_id {
+                            break
+                        }
                     }
-                    tracing::info!("Node B Event: {:?}", node_b_event);
                 },
-
+                _ = node_b.swarm.select_next_some() => {},
             };
         }
+        let status = node_a.discovery_status();
+        assert!(status.mdns_enabled);
+        assert!(!status.kad_enabled);
+        assert!(status.mdns_discovered_peers > discovered_before);
     }
 
-    // Simulates 3 p2p nodes, Node B & Node C are bootstrapped with Node A
-    // Using Identify Protocol Node C should be able to identify and connect to Node B
     #[tokio::test]
     #[instrument]
-    async fn nodes_connected_via_identify() {
-        // Node A
-        let mut p2p_config = Config::default_initialized("nodes_connected_via_identify");
-
-        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+    async fn find_peers_returns_peer_known_only_to_bootstrap_node() {
+        let mut p2p_config = Config::default_initialized(
+            "find_peers_returns_peer_known_only_to_bootstrap_node",
+        );
+        // Only the explicit queries look for the peers beyond the bootstrap node
+        p2p_config.random_walk = None;
 
-        // Node B
-        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        // Node A is the only bootstrap node of Node B and Node C
+        let (bootstrap_nodes, bootstrap_multiaddrs) =
+            setup_bootstrap_nodes(&p2p_config, 1).await;
+        p2p_config.bootstrap_nodes = bootstrap_multiaddrs;
+        let node_c = build_service_from_config(p2p_config.clone()).await;
+        let node_c_id = node_c.local_peer_id;
         let mut node_b = build_service_from_config(p2p_config.clone()).await;
 
-        // Node C
-        let mut node_c = build_service_from_config(p2p_config).await;
-
-        loop {
+        let (stop_sender, _) = watch::channel(());
+        for node in bootstrap_nodes {
+            spawn(&stop_sender, node);
+        }
+        spawn(&stop_sender, node_c);
+
+        // Node B queries the DHT until Node A has learned about Node C
+        let mut retry = tokio::time::interval(Duration::from_millis(500));
+        let mut searches = Vec::new();
+        loop {
+            tokio::select! {
+                _ = node_b.next_event() => {},
+                _ = retry.tick() => {
+                    let (sender, receiver) = oneshot::channel();
+                    node_b.find_peers(10, sender);
+                    searches.push(receiver);
+                }
+            }
+            let node_c_found = searches.iter_mut().any(|search| {
+                matches!(search.try_recv(), Ok(Some(peers)) if peers.contains(&node_c_id))
+            });
+            if node_c_found {
+                break
+            }
+        }
+
+        // The node with the disabled Kademlia doesn't query the DHT
+        p2p_config.enable_kademlia = false;
+        let mut node_d = build_service_from_config(p2p_config).await;
+        let (sender, receiver) = oneshot::channel();
+        node_d.find_peers(10, sender);
+        assert_eq!(receiver.await.unwrap(), None);
+        assert!(!node_d.discovery_status().kad_enabled);
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn inbound_connections_above_limit_are_refused() {
+        let mut p2p_config =
+            Config::default_initialized("inbound_connections_above_limit_are_refused");
+        let max_inbound_connections = 1;
+
+        // Node A
+        let mut node_a = build_service_from_config(Config {
+            max_inbound_connections: Some(max_inbound_connections),
+            ..p2p_config.clone()
+        })
+        .await;
+
+        // Node B and Node C connect to Node A
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config.clone()).await;
+        let mut node_c = build_service_from_config(p2p_config).await;
+
+        let mut inbound_connections = 0u32;
+
+        loop {
+            tokio::select! {
+                node_a_event = node_a.swarm.select_next_some() => {
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                    match node_a_event {
+                        SwarmEvent::ConnectionEstablished { endpoint, .. } if endpoint.is_listener() => {
+                            inbound_connections = inbound_connections.saturating_add(1);
+                        }
+                        SwarmEvent::ConnectionClosed { endpoint, .. } if endpoint.is_listener() => {
+                            inbound_connections = inbound_connections.saturating_sub(1);
+                        }
+                        SwarmEvent::IncomingConnectionError { error: ListenError::Denied { .. }, .. } => {
+                            assert_eq!(inbound_connections, max_inbound_connections);
+                            break
+                        }
+                        _ => {}
+                    }
+                },
+                node_b_event = node_b.next_event() => {
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                },
+                node_c_event = node_c.next_event() => {
+                    tracing::info!("Node C Event: {:?}", node_c_event);
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn inbound_connection_from_not_allowed_peer_is_denied() {
+        let mut p2p_config = Config::default_initialized(
+            "inbound_connection_from_not_allowed_peer_is_denied",
+        );
+
+        // Node A allows only some other peer
+        let mut node_a = build_service_from_config(Config {
+            allowed_peers: Some(vec![PeerId::random()]),
+            metrics: true,
+            ..p2p_config.clone()
+        })
+        .await;
+        let denied_before = p2p_metrics().denied_connections.get();
+
+        // Node B connects to Node A
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config).await;
+        let node_b_peer_id = node_b.local_peer_id;
+
+        loop {
+            tokio::select! {
+                node_a_event = node_a.swarm.select_next_some() => {
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                    match node_a_event {
+                        SwarmEvent::ConnectionEstablished { peer_id, .. } => {
+                            panic!("Not allowed peer {peer_id} is connected")
+                        }
+                        SwarmEvent::IncomingConnectionError { error: ListenError::Denied { cause }, .. } => {
+                            assert!(matches!(
+                                cause.downcast_ref::<ConnectionGateError>(),
+                                Some(ConnectionGateError::PeerNotAllowed(peer_id)) if *peer_id == node_b_peer_id
+                            ));
+                            break
+                        }
+                        _ => {}
+                    }
+                },
+                node_b_event = node_b.next_event() => {
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                }
+            }
+        }
+        assert!(p2p_metrics().denied_connections.get() > denied_before);
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn dial_to_not_allowed_peer_or_denied_ip_is_refused() {
+        let mut p2p_config = Config::default_initialized(
+            "dial_to_not_allowed_peer_or_denied_ip_is_refused",
+        );
+        p2p_config.address = "127.0.0.1".parse().unwrap();
+
+        let node_a = build_service_from_config(p2p_config.clone()).await;
+        let node_a_address = node_a.multiaddrs().pop().unwrap();
+
+        // Node B allows only some other peer
+        let mut node_b = build_service_from_config(Config {
+            allowed_peers: Some(vec![PeerId::random()]),
+            ..p2p_config.clone()
+        })
+        .await;
+        let result = node_b.swarm.dial(
+            DialOpts::peer_id(node_a.local_peer_id)
+                .addresses(vec![node_a_address.clone()])
+                .build(),
+        );
+        let Err(DialError::Denied { cause }) = result else {
+            panic!("The dial should be denied, got {result:?}")
+        };
+        assert!(matches!(
+            cause.downcast_ref::<ConnectionGateError>(),
+            Some(ConnectionGateError::PeerNotAllowed(_))
+        ));
+
+        // Node C denies the local addresses
+        let mut node_c = build_service_from_config(Config {
+            denied_ips: vec!["127.0.0.0/8".parse().unwrap()],
+            ..p2p_config
+        })
+        .await;
+        let result = node_c.swarm.dial(node_a_address.clone());
+        let Err(DialError::Denied { cause }) = result else {
+            panic!("The dial should be denied, got {result:?}")
+        };
+        assert!(matches!(
+            cause.downcast_ref::<ConnectionGateError>(),
+            Some(ConnectionGateError::AddressDenied(_))
+        ));
+
+        // The denied address isn't dialed along with the allowed one
+        let allowed_address: Multiaddr = "/ip4/10.0.0.1/tcp/4001".parse().unwrap();
+        let result = node_c.swarm.dial(
+            DialOpts::peer_id(node_a.local_peer_id)
+                .addresses(vec![allowed_address, node_a_address.clone()])
+                .build(),
+        );
+        let Err(DialError::Denied { cause }) = result else {
+            panic!("The dial should be denied, got {result:?}")
+        };
+        assert!(matches!(
+            cause.downcast_ref::<ConnectionGateError>(),
+            Some(ConnectionGateError::AddressDenied(address)) if *address == node_a_address
+        ));
+    }
+
+    // Simulates 2 p2p nodes that are on the same network but their Fuel Upgrade checksum is different
+    // (different chain id or chain config)
+    // So they are not able to connect
+    #[tokio::test]
+    #[instrument]
+    async fn nodes_cannot_connect_due_to_different_checksum() {
+        use libp2p::TransportError;
+        // Node A
+        let mut p2p_config =
+            Config::default_initialized("nodes_cannot_connect_due_to_different_checksum");
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+
+        // different checksum
+        p2p_config.checksum = [1u8; 32].into();
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        // Node B
+        let mut node_b = build_service_from_config(p2p_config).await;
+
+        loop {
+            tokio::select! {
+                node_a_event = node_a.swarm.select_next_some() => {
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                    if let SwarmEvent::IncomingConnectionError { error: ListenError::Transport(TransportError::Other(_)), .. } = node_a_event {
+                        break
+                    }
+                },
+                node_b_event = node_b.next_event() => {
+                    if let Some(FuelP2PEvent::PeerConnected(_)) = node_b_event {
+                        panic!("Node B should not connect to Node A!")
+                    }
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                },
+
+            };
+        }
+    }
+
+    // Simulates 2 p2p nodes that share the genesis but not the network name,
+    // the transport upgrade doesn't let them connect
+    #[tokio::test]
+    #[instrument]
+    async fn nodes_cannot_connect_due_to_different_network_name() {
+        use libp2p::TransportError;
+        // Node A
+        let mut p2p_config = Config::default_initialized(
+            "nodes_cannot_connect_due_to_different_network_name",
+        );
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+
+        // Node B
+        p2p_config.network_name = "another_network".to_string();
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config).await;
+
+        loop {
+            tokio::select! {
+                node_a_event = node_a.swarm.select_next_some() => {
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                    if let SwarmEvent::IncomingConnectionError { error: ListenError::Transport(TransportError::Other(_)), .. } = node_a_event {
+                        break
+                    }
+                },
+                node_b_event = node_b.next_event() => {
+                    if let Some(FuelP2PEvent::PeerConnected(_)) = node_b_event {
+                        panic!("Node B should not connect to Node A!")
+                    }
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                },
+            };
+        }
+    }
+
+    // mDNS doesn't know about the networks, so the nodes of different networks
+    // find each other on the local network, but they are not able to connect
+    #[tokio::test]
+    #[instrument]
+    async fn nodes_of_different_networks_are_not_connected_via_mdns() {
+        let mut p2p_config = Config::default_initialized(
+            "nodes_of_different_networks_are_not_connected_via_mdns",
+        );
+        p2p_config.enable_mdns = true;
+        // Kademlia is disabled, so the peers are dialed right after they are discovered
+        p2p_config.enable_kademlia = false;
+
+        // Node A
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+        let node_a_id = node_a.local_peer_id;
+
+        // Node B
+        p2p_config.network_name = "another_network".to_string();
+        let mut node_b = build_service_from_config(p2p_config).await;
+        let node_b_id = node_b.local_peer_id;
+
+        // Wait until one of the nodes fails to dial the other one
+        loop {
+            tokio::select! {
+                node_a_event = node_a.swarm.select_next_some() => {
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                    match node_a_event {
+                        SwarmEvent::ConnectionEstablished { peer_id, .. } if peer_id == node_b_id => {
+                            panic!("Node A should not connect to Node B!")
+                        }
+                        SwarmEvent::OutgoingConnectionError { peer_id: Some(peer_id), .. } if peer_id == node_b_id => {
+                            break
+                        }
+                        _ => {}
+                    }
+                },
+                node_b_event = node_b.swarm.select_next_some() => {
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                    match node_b_event {
+                        SwarmEvent::ConnectionEstablished { peer_id, .. } if peer_id == node_a_id => {
+                            panic!("Node B should not connect to Node A!")
+                        }
+                        SwarmEvent::OutgoingConnectionError { peer_id: Some(peer_id), .. } if peer_id == node_a_id => {
+                            break
+                        }
+                        _ => {}
+                    }
+                },
+            };
+        }
+
+        assert!(!node_a.swarm.is_connected(&node_b_id));
+        assert!(!node_b.swarm.is_connected(&node_a_id));
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn peers_not_matching_min_peer_version_are_disconnected() {
+        // Node A
+        let mut p2p_config = Config::default_initialized(
+            "peers_not_matching_min_peer_version_are_disconnected",
+        );
+        let mut node_a = build_service_from_config(Config {
+            min_peer_version: Some(">=99.0.0".parse().unwrap()),
+            ..p2p_config.clone()
+        })
+        .await;
+
+        // Node B
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config).await;
+        let node_b_peer_id = node_b.local_peer_id;
+
+        loop {
+            tokio::select! {
+                node_a_event = node_a.next_event() => {
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                    if let Some(FuelP2PEvent::PeerDisconnected(peer_id)) = node_a_event {
+                        assert_eq!(peer_id, node_b_peer_id);
+                        break
+                    }
+                },
+                node_b_event = node_b.next_event() => {
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                },
+            };
+        }
+    }
+
+    // Simulates 3 p2p nodes, Node B & Node C are bootstrapped with Node A
+    // Using Identify Protocol Node C should be able to identify and connect to Node B
+    #[tokio::test]
+    #[instrument]
+    async fn nodes_connected_via_identify() {
+        // Node A
+        let mut p2p_config = Config::default_initialized("nodes_connected_via_identify");
+
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+
+        // Node B
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config.clone()).await;
+
+        // Node C
+        let mut node_c = build_service_from_config(p2p_config).await;
+
+        loop {
+            tokio::select! {
+                node_a_event = node_a.next_event() => {
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                },
+                node_b_event = node_b.next_event() => {
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                },
+
+                node_c_event = node_c.next_event() => {
+                    if let Some(FuelP2PEvent::PeerConnected(peer_id)) = node_c_event {
+                        // we have connected to Node B!
+                        if peer_id == node_b.local_peer_id {
+                            break
+                        }
+                    }
+
+                    tracing::info!("Node C Event: {:?}", node_c_event);
+                }
+            };
+        }
+    }
+
+    // Simulates 2 p2p nodes that connect to each other and consequently exchange Peer Info
+    // On successful connection, node B updates its latest BlockHeight
+    // and shares it with Peer A via Heartbeat protocol
+    #[tokio::test]
+    #[instrument]
+    async fn address_observed_by_inbound_peer_is_reported() {
+        let mut p2p_config =
+            Config::default_initialized("address_observed_by_inbound_peer_is_reported");
+
+        // Node A is dialed by Node B
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let node_b = build_service_from_config(p2p_config).await;
+
+        let (stop_sender, _) = watch::channel(());
+        spawn(&stop_sender, node_b);
+
+        let wait_for_observed_address = async {
+            loop {
+                if let Some(FuelP2PEvent::ListenAddrChanged) = node_a.next_event().await {
+                    if node_a.observed_address.is_some() {
+                        break
+                    }
+                }
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(10), wait_for_observed_address)
+            .await
+            .expect("Node B should report the observed address of Node A");
+
+        // Node B dialed the listen address of Node A
+        let observed_address = node_a.observed_address.clone().unwrap();
+        assert!(node_a
+            .swarm
+            .listeners()
+            .any(|addr| *addr == observed_address));
+        let local_peer_id = node_a.local_peer_id;
+        let expected: Multiaddr = format!("{observed_address}/p2p/{local_peer_id}")
+            .parse()
+            .unwrap();
+        assert!(node_a.multiaddrs().contains(&expected));
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn peer_info_updates_work() {
+        let mut p2p_config = Config::default_initialized("peer_info_updates_work");
+
+        // Node A
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+
+        // Node B
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config).await;
+
+        let latest_block_height = 40_u32.into();
+
+        loop {
+            tokio::select! {
+                node_a_event = node_a.next_event() => {
+                    if let Some(FuelP2PEvent::PeerInfoUpdated { peer_id, block_height: _ }) = node_a_event {
+                        if let Some(PeerInfo {  heartbeat_data, client_version, .. }) = node_a.peer_manager.get_peer_info(&peer_id) {
+                            // Exits after it verifies that:
+                            // 1. Peer Addresses are known
+                            // 2. Client Version is known
+                            // 3. Node has responded with their latest BlockHeight
+                            if client_version.is_some() && heartbeat_data.block_height == Some(latest_block_height) {
+                                break;
+                            }
+                        }
+                    }
+
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                },
+                node_b_event = node_b.next_event() => {
+                    if let Some(FuelP2PEvent::PeerConnected(_)) = node_b_event {
+                        // we've connected to Peer A
+                        // let's update our BlockHeight
+                        node_b.update_block_height(latest_block_height);
+                    }
+
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                }
+            }
+        }
+    }
+
+    // Simulates 2 p2p nodes that connect to each other and exchange Identify info,
+    // after which node A is able to query the addresses and protocols of node B
+    #[tokio::test]
+    #[instrument]
+    async fn peer_info_is_available_after_identify() {
+        let mut p2p_config =
+            Config::default_initialized("peer_info_is_available_after_identify");
+
+        // Node A
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+
+        // Node B
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config).await;
+        let node_b_peer_id = node_b.local_peer_id;
+
+        // Unknown peers have no info
+        assert!(TaskP2PService::get_peer_info(&node_a, &PeerId::random()).is_none());
+
+        loop {
+            tokio::select! {
+                node_a_event = node_a.next_event() => {
+                    if let Some(PeerInfo { peer_addresses, protocols, client_version, .. }) =
+                        TaskP2PService::get_peer_info(&node_a, &node_b_peer_id) {
+                        // Identify info has been received
+                        if client_version.is_some() {
+                            assert!(!peer_addresses.is_empty());
+                            assert!(!protocols.is_empty());
+                            break;
+                        }
+                    }
+
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                },
+                node_b_event = node_b.next_event() => {
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                }
+            }
+        }
+    }
+
+    // Simulates 2 p2p nodes that connect to each other,
+    // after which each of them reports the other one in its list of peers
+    #[tokio::test]
+    #[instrument]
+    async fn connected_nodes_report_each_other_in_peer_info() {
+        let mut p2p_config =
+            Config::default_initialized("connected_nodes_report_each_other_in_peer_info");
+
+        // Node A
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+        let node_a_peer_id = node_a.local_peer_id;
+
+        // Node B dials Node A
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config).await;
+        let node_b_peer_id = node_b.local_peer_id;
+
+        let reports_peer =
+            |node: &FuelP2PService,
+             peer_id: &PeerId,
+             expected_direction: ConnectionDirection| {
+                TaskP2PService::get_all_peer_info(node).into_iter().any(
+                    |(id, peer_info)| {
+                        id == peer_id
+                            && peer_info.direction == Some(expected_direction)
+                            && peer_info.gossipsub_score.is_some()
+                    },
+                )
+            };
+
+        loop {
+            if reports_peer(&node_a, &node_b_peer_id, ConnectionDirection::Inbound)
+                && reports_peer(&node_b, &node_a_peer_id, ConnectionDirection::Outbound)
+            {
+                break
+            }
+
             tokio::select! {
                 node_a_event = node_a.next_event() => {
                     tracing::info!("Node A Event: {:?}", node_a_event);
                 },
                 node_b_event = node_b.next_event() => {
                     tracing::info!("Node B Event: {:?}", node_b_event);
-                },
-
-                node_c_event = node_c.next_event() => {
-                    if let Some(FuelP2PEvent::PeerConnected(peer_id)) = node_c_event {
-                        // we have connected to Node B!
-                        if peer_id == node_b.local_peer_id {
-                            break
-                        }
-                    }
-
-                    tracing::info!("Node C Event: {:?}", node_c_event);
                 }
-            };
+            }
         }
     }
 
-    // Simulates 2 p2p nodes that connect to each other and consequently exchange Peer Info
-    // On successful connection, node B updates its latest BlockHeight
-    // and shares it with Peer A via Heartbeat protocol
+    // Simulates 2 p2p nodes where Node B publishes a malformed gossip message,
+    // which Node A fails to decode and reports as a `GossipDecodeError`
     #[tokio::test]
     #[instrument]
-    async fn peer_info_updates_work() {
-        let mut p2p_config = Config::default_initialized("peer_info_updates_work");
+    async fn malformed_gossip_message_produces_decode_error() {
+        let mut p2p_config =
+            Config::default_initialized("malformed_gossip_message_produces_decode_error");
+        let selected_topic: GossipTopic = Topic::new(format!(
+            "{}/{}",
+            NEW_TX_GOSSIP_TOPIC, p2p_config.network_name
+        ));
+        let decode_errors_before = p2p_metrics().gossip_decode_errors.get();
 
         // Node A
+        p2p_config.metrics = true;
         let mut node_a = build_service_from_config(p2p_config.clone()).await;
 
         // Node B
+        p2p_config.metrics = false;
         p2p_config.bootstrap_nodes = node_a.multiaddrs();
         let mut node_b = build_service_from_config(p2p_config).await;
+        let node_b_peer_id = node_b.local_peer_id;
 
-        let latest_block_height = 40_u32.into();
+        let mut message_sent = false;
 
         loop {
             tokio::select! {
                 node_a_event = node_a.next_event() => {
-                    if let Some(FuelP2PEvent::PeerInfoUpdated { peer_id, block_height: _ }) = node_a_event {
-                        if let Some(PeerInfo {  heartbeat_data, client_version, .. }) = node_a.peer_manager.get_peer_info(&peer_id) {
-                            // Exits after it verifies that:
-                            // 1. Peer Addresses are known
-                            // 2. Client Version is known
-                            // 3. Node has responded with their latest BlockHeight
-                            if client_version.is_some() && heartbeat_data.block_height == Some(latest_block_height) {
-                                break;
-                            }
-                        }
+                    if let Some(FuelP2PEvent::GossipDecodeError { peer_id, topic_hash }) = node_a_event {
+                        assert_eq!(peer_id, node_b_peer_id);
+                        assert_eq!(topic_hash, selected_topic.hash());
+                        assert!(p2p_metrics().gossip_decode_errors.get() > decode_errors_before);
+                        break
                     }
 
                     tracing::info!("Node A Event: {:?}", node_a_event);
                 },
                 node_b_event = node_b.next_event() => {
-                    if let Some(FuelP2PEvent::PeerConnected(_)) = node_b_event {
-                        // we've connected to Peer A
-                        // let's update our BlockHeight
-                        node_b.update_block_height(latest_block_height);
+                    if let Some(FuelP2PEvent::PeerInfoUpdated { .. }) = node_b_event {
+                        if !message_sent {
+                            // the payload is not a valid encoding of any gossip message
+                            message_sent = node_b
+                                .swarm
+                                .behaviour_mut()
+                                .publish_message(selected_topic.clone(), b"malformed".to_vec())
+                                .is_ok();
+                        }
                     }
 
                     tracing::info!("Node B Event: {:?}", node_b_event);
@@ -1386,6 +3507,7 @@ mod tests {
         let selected_topic: GossipTopic = {
             let topic = match broadcast_request {
                 GossipsubBroadcastRequest::NewTx(_) => NEW_TX_GOSSIP_TOPIC,
+                GossipsubBroadcastRequest::NewTxBatch(_) => NEW_TX_BATCH_GOSSIP_TOPIC,
             };
 
             Topic::new(format!("{}/{}", topic, p2p_config.network_name))
@@ -1447,6 +3569,12 @@ mod tests {
                                     panic!("Wrong GossipsubMessage")
                                 }
                             }
+                            GossipsubMessage::NewTxBatch(txs) => {
+                                if txs.iter().any(|tx| tx != &Transaction::default_test_tx()) {
+                                    tracing::error!("Wrong p2p message {:?}", message);
+                                    panic!("Wrong GossipsubMessage")
+                                }
+                            }
                         }
 
                         // Node B received the correct message
@@ -1486,6 +3614,146 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    #[instrument]
+    async fn same_tx_published_by_two_nodes_is_delivered_once() {
+        let deliveries = deliveries_of_tx_published_by_two_nodes(
+            GossipMessageIdStrategy::Sha256OfData,
+        )
+        .await;
+        assert_eq!(deliveries, 1);
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn same_tx_published_by_two_nodes_is_delivered_twice_with_source_and_sequence()
+    {
+        let deliveries = deliveries_of_tx_published_by_two_nodes(
+            GossipMessageIdStrategy::SourceAndSequence,
+        )
+        .await;
+        assert_eq!(deliveries, 2);
+    }
+
+    /// Nodes A and B publish the same transaction to Node C. Node C doesn't report
+    /// the validation result, so the transaction isn't forwarded between A and B.
+    /// Returns the number of times Node C received the transaction.
+    async fn deliveries_of_tx_published_by_two_nodes(
+        message_id_strategy: GossipMessageIdStrategy,
+    ) -> usize {
+        let mut p2p_config =
+            Config::default_initialized("deliveries_of_tx_published_by_two_nodes");
+        p2p_config.gossip_message_id_strategy = message_id_strategy;
+
+        // Node C
+        let mut node_c = build_service_from_config(p2p_config.clone()).await;
+
+        // Nodes A and B are connected only to Node C
+        p2p_config.bootstrap_nodes = node_c.multiaddrs();
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+        let mut node_b = build_service_from_config(p2p_config).await;
+        node_a
+            .swarm
+            .behaviour_mut()
+            .block_peer(node_b.local_peer_id);
+        node_b
+            .swarm
+            .behaviour_mut()
+            .block_peer(node_a.local_peer_id);
+
+        let tx =
+            GossipsubBroadcastRequest::NewTx(Arc::new(Transaction::default_test_tx()));
+        let mut published_by_a = false;
+        let mut published_by_b = false;
+        let mut deliveries = 0usize;
+        // Node C keeps receiving for a while after both nodes published
+        let mut deadline = None;
+
+        loop {
+            if published_by_a && published_by_b && deadline.is_none() {
+                deadline =
+                    tokio::time::Instant::now().checked_add(Duration::from_secs(2));
+            }
+            let wait_for_deadline = async move {
+                match deadline {
+                    Some(deadline) => tokio::time::sleep_until(deadline).await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            tokio::select! {
+                _ = wait_for_deadline => break,
+                node_a_event = node_a.next_event() => {
+                    if let Some(FuelP2PEvent::PeerInfoUpdated { .. }) = node_a_event {
+                        if !published_by_a {
+                            published_by_a = true;
+                            node_a.publish_message(tx.clone()).unwrap();
+                        }
+                    }
+                },
+                node_b_event = node_b.next_event() => {
+                    if let Some(FuelP2PEvent::PeerInfoUpdated { .. }) = node_b_event {
+                        if !published_by_b {
+                            published_by_b = true;
+                            node_b.publish_message(tx.clone()).unwrap();
+                        }
+                    }
+                },
+                node_c_event = node_c.next_event() => {
+                    if let Some(FuelP2PEvent::GossipsubMessage { .. }) = node_c_event {
+                        deliveries = deliveries.saturating_add(1);
+                    }
+                }
+            }
+        }
+
+        deliveries
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn received_gossip_message_can_be_forwarded_again() {
+        let mut p2p_config =
+            Config::default_initialized("received_gossip_message_can_be_forwarded_again");
+
+        // Node A
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+
+        // Node B
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config).await;
+
+        let mut message_sent = false;
+        loop {
+            tokio::select! {
+                node_a_event = node_a.next_event() => {
+                    if let Some(FuelP2PEvent::PeerInfoUpdated { .. }) = node_a_event {
+                        if !message_sent {
+                            message_sent = true;
+                            let tx = Arc::new(Transaction::default_test_tx());
+                            node_a.publish_message(GossipsubBroadcastRequest::NewTx(tx)).unwrap();
+                        }
+                    }
+                },
+                node_b_event = node_b.next_event() => {
+                    if let Some(FuelP2PEvent::GossipsubMessage { message_id, peer_id, .. }) = node_b_event {
+                        node_b.report_message_validation_result(&message_id, peer_id, MessageAcceptance::Accept);
+
+                        // The cached message is forwarded again
+                        node_b.forward_gossip(&message_id).unwrap();
+                        // The unknown message can't be forwarded
+                        let unknown_message_id = MessageId::new(b"unknown");
+                        assert!(matches!(
+                            node_b.forward_gossip(&unknown_message_id),
+                            Err(P2pError::MessageNotCached(id)) if id == unknown_message_id
+                        ));
+                        break
+                    }
+                }
+            }
+        }
+    }
+
     fn arbitrary_headers_for_range(range: Range<u32>) -> Vec<SealedBlockHeader> {
         let mut blocks = Vec::new();
         for i in range {
@@ -1572,6 +3840,7 @@ mod tests {
                                             }
                                         });
                                     }
+                                    _ => unreachable!("Only the headers and the transactions are requested"),
                                 }
                             }
                         }
@@ -1593,6 +3862,7 @@ mod tests {
                                 let transactions = vec![Transactions(txs)];
                                 let _ = node_b.send_response_msg(*request_id, ResponseMessage::Transactions(Some(transactions)));
                             }
+                            _ => {}
                         }
                     }
 
@@ -1602,6 +3872,222 @@ mod tests {
         }
     }
 
+    /// Captures the fields of the request spans and the events logged inside them
+    #[derive(Clone)]
+    struct RequestSpansCapture {
+        span_name: &'static str,
+        fields: Arc<std::sync::Mutex<HashMap<String, String>>>,
+        events: Arc<std::sync::atomic::AtomicUsize>,
+        closed: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl RequestSpansCapture {
+        fn new(span_name: &'static str) -> Self {
+            Self {
+                span_name,
+                fields: Default::default(),
+                events: Default::default(),
+                closed: Default::default(),
+            }
+        }
+    }
+
+    impl tracing::field::Visit for RequestSpansCapture {
+        fn record_debug(
+            &mut self,
+            field: &tracing::field::Field,
+            value: &dyn std::fmt::Debug,
+        ) {
+            self.fields
+                .lock()
+                .unwrap()
+                .insert(field.name().to_string(), format!("{value:?}"));
+        }
+    }
+
+    impl<S> tracing_subscriber::Layer<S> for RequestSpansCapture
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            _id: &tracing::span::Id,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            if attrs.metadata().name() == self.span_name {
+                attrs.record(&mut self.clone());
+            }
+        }
+
+        fn on_record(
+            &self,
+            id: &tracing::span::Id,
+            values: &tracing::span::Record<'_>,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            if ctx
+                .span(id)
+                .is_some_and(|span| span.name() == self.span_name)
+            {
+                values.record(&mut self.clone());
+            }
+        }
+
+        fn on_close(
+            &self,
+            id: tracing::span::Id,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            if ctx
+                .span(&id)
+                .is_some_and(|span| span.name() == self.span_name)
+            {
+                self.closed
+                    .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            }
+        }
+
+        fn on_event(
+            &self,
+            event: &tracing::Event<'_>,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            if ctx
+                .event_span(event)
+                .is_some_and(|span| span.name() == self.span_name)
+            {
+                self.events
+                    .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn served_inbound_request_is_logged_inside_one_span() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let capture = RequestSpansCapture::new("p2p_inbound_request");
+        let _guard = tracing::subscriber::set_default(
+            tracing_subscriber::registry().with(capture.clone()),
+        );
+
+        let mut p2p_config = Config::default_initialized(
+            "served_inbound_request_is_logged_inside_one_span",
+        );
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+        let node_a_peer_id = node_a.local_peer_id;
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config).await;
+        let node_b_peer_id = node_b.local_peer_id;
+
+        let (sender, mut receiver) = oneshot::channel();
+        let mut sender = Some(sender);
+        let mut response_received = false;
+        let mut inbound_request_id = None;
+        // Node B closes the span once the response is written to Node A
+        while !response_received || node_b.has_pending_inbound_requests() {
+            tokio::select! {
+                response = &mut receiver, if !response_received => {
+                    let (_, response) = response.unwrap();
+                    assert!(response.is_ok());
+                    response_received = true;
+                }
+                node_a_event = node_a.next_event() => {
+                    if let Some(FuelP2PEvent::PeerInfoUpdated { .. }) = node_a_event {
+                        if let Some(sender) = sender.take() {
+                            let request = RequestMessage::SealedHeaders(2..6);
+                            node_a.send_request_msg(Some(node_b_peer_id), request, ResponseSender::SealedHeaders(sender)).unwrap();
+                        }
+                    }
+                },
+                node_b_event = node_b.next_event() => {
+                    if let Some(FuelP2PEvent::InboundRequestMessage { request_id, request_message: RequestMessage::SealedHeaders(range) }) = node_b_event {
+                        inbound_request_id = Some(request_id);
+                        let sealed_headers = arbitrary_headers_for_range(range);
+                        let _ = node_b.send_response_msg(request_id, ResponseMessage::SealedHeaders(Some(sealed_headers)));
+                    }
+                }
+            }
+        }
+
+        let fields = capture.fields.lock().unwrap().clone();
+        assert_eq!(
+            fields.get("request_id"),
+            Some(&inbound_request_id.unwrap().to_string())
+        );
+        assert_eq!(fields.get("peer_id"), Some(&node_a_peer_id.to_string()));
+        assert_eq!(
+            fields.get("request").map(String::as_str),
+            Some("\"sealed_headers\"")
+        );
+        // The request is received, the response is handed over and sent inside the span
+        assert!(capture.events.load(std::sync::atomic::Ordering::SeqCst) >= 3);
+        assert_eq!(capture.closed.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn completed_request_is_logged_inside_its_span() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let capture = RequestSpansCapture::new("p2p_request");
+        let _guard = tracing::subscriber::set_default(
+            tracing_subscriber::registry().with(capture.clone()),
+        );
+
+        let mut p2p_config =
+            Config::default_initialized("completed_request_is_logged_inside_its_span");
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config).await;
+        let node_b_peer_id = node_b.local_peer_id;
+
+        let (sender, mut receiver) = oneshot::channel();
+        let mut sender = Some(sender);
+        let mut request_id = None;
+        loop {
+            tokio::select! {
+                response = &mut receiver => {
+                    let (_, response) = response.unwrap();
+                    assert!(response.is_ok());
+                    break
+                }
+                node_a_event = node_a.next_event() => {
+                    if let Some(FuelP2PEvent::PeerInfoUpdated { .. }) = node_a_event {
+                        if let Some(sender) = sender.take() {
+                            let request = RequestMessage::SealedHeaders(2..6);
+                            let id = node_a.send_request_msg(Some(node_b_peer_id), request, ResponseSender::SealedHeaders(sender)).unwrap();
+                            request_id = Some(id);
+                        }
+                    }
+                },
+                node_b_event = node_b.next_event() => {
+                    if let Some(FuelP2PEvent::InboundRequestMessage { request_id, request_message: RequestMessage::SealedHeaders(range) }) = node_b_event {
+                        let sealed_headers = arbitrary_headers_for_range(range);
+                        let _ = node_b.send_response_msg(request_id, ResponseMessage::SealedHeaders(Some(sealed_headers)));
+                    }
+                }
+            }
+        }
+
+        let fields = capture.fields.lock().unwrap().clone();
+        assert_eq!(
+            fields.get("request_id"),
+            Some(&request_id.unwrap().to_string())
+        );
+        assert_eq!(fields.get("peer_id"), Some(&node_b_peer_id.to_string()));
+        assert_eq!(
+            fields.get("request").map(String::as_str),
+            Some("\"sealed_headers\"")
+        );
+        assert_eq!(
+            fields.get("block_height_range").map(String::as_str),
+            Some("2..6")
+        );
+        // The request is sent and its response is received inside the span
+        assert!(capture.events.load(std::sync::atomic::Ordering::SeqCst) >= 2);
+    }
+
     #[tokio::test]
     #[instrument]
     async fn request_response_works_with_transactions() {
@@ -1609,11 +4095,125 @@ mod tests {
         request_response_works_with(RequestMessage::Transactions(arbitrary_range)).await
     }
 
-    #[tokio::test]
-    #[instrument]
-    async fn request_response_works_with_sealed_headers_range_inclusive() {
-        let arbitrary_range = 2..6;
-        request_response_works_with(RequestMessage::SealedHeaders(arbitrary_range)).await
+    #[tokio::test]
+    #[instrument]
+    async fn request_response_works_with_sealed_headers_range_inclusive() {
+        let arbitrary_range = 2..6;
+        request_response_works_with(RequestMessage::SealedHeaders(arbitrary_range)).await
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn request_response_works_between_v1_only_and_v2_nodes() {
+        let mut p2p_config = Config::default_initialized(
+            "request_response_works_between_v1_only_and_v2_nodes",
+        );
+
+        // Node A supports only the first version of the protocol
+        let v1_codec = PostcardCodec::new(p2p_config.max_block_size)
+            .with_max_protocol(MessageExchangePostcardProtocol::V1);
+        let mut node_a = build_service_with_codec(p2p_config.clone(), v1_codec).await;
+
+        // Node B supports both versions
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config.clone()).await;
+        let node_a_peer_id = node_a.local_peer_id;
+
+        let range = 2..6;
+        let mut response_receiver = None;
+
+        loop {
+            tokio::select! {
+                response = async { response_receiver.as_mut().unwrap().await }, if response_receiver.is_some() => {
+                    let (_, response) = response.unwrap();
+                    let sealed_headers = response.unwrap().unwrap();
+                    let expected = arbitrary_headers_for_range(range.clone());
+                    assert_eq!(sealed_headers.len(), expected.len());
+                    assert!(expected.iter().zip(sealed_headers.iter()).all(|(a, b)| eq_except_metadata(a, b)));
+                    break;
+                }
+                node_a_event = node_a.next_event() => {
+                    // Node A answers the request over the first version
+                    if let Some(FuelP2PEvent::InboundRequestMessage { request_id, request_message: RequestMessage::SealedHeaders(range) }) = node_a_event {
+                        let sealed_headers = arbitrary_headers_for_range(range);
+                        let _ = node_a.send_response_msg(request_id, ResponseMessage::SealedHeaders(Some(sealed_headers)));
+                    }
+                }
+                node_b_event = node_b.next_event() => {
+                    if let Some(FuelP2PEvent::PeerInfoUpdated { peer_id, .. }) = node_b_event {
+                        if peer_id == node_a_peer_id && response_receiver.is_none() {
+                            let (sender, receiver) = oneshot::channel();
+                            node_b.send_request_msg(
+                                Some(node_a_peer_id),
+                                RequestMessage::SealedHeaders(range.clone()),
+                                ResponseSender::SealedHeaders(sender),
+                            ).unwrap();
+                            response_receiver = Some(receiver);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn request_unknown_to_older_peer_is_rejected_as_unsupported() {
+        let mut p2p_config = Config::default_initialized(
+            "request_unknown_to_older_peer_is_rejected_as_unsupported",
+        );
+
+        // Node A speaks the version of the protocol without the tip request
+        let v2_codec = PostcardCodec::new(p2p_config.max_block_size)
+            .with_max_protocol(MessageExchangePostcardProtocol::V2);
+        let mut node_a = build_service_with_codec(p2p_config.clone(), v2_codec).await;
+        let node_a_peer_id = node_a.local_peer_id;
+
+        // Node B supports all versions
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config).await;
+
+        // Node B learns the versions of Node A from the identify protocol
+        let node_a_is_identified = |node_b: &P2PService| {
+            node_b
+                .peer_manager
+                .get_peer_info(&node_a_peer_id)
+                .is_some_and(|peer_info| !peer_info.protocols.is_empty())
+        };
+        while !node_a_is_identified(&node_b) {
+            tokio::select! {
+                node_a_event = node_a.next_event() => {
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                }
+                node_b_event = node_b.next_event() => {
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                }
+            }
+        }
+
+        // The tip request isn't sent to Node A
+        let (sender, receiver) = oneshot::channel();
+        let result = node_b.send_request_msg(
+            Some(node_a_peer_id),
+            RequestMessage::Tip,
+            ResponseSender::Tip(sender),
+        );
+        assert!(matches!(
+            result,
+            Err(RequestError::UnsupportedRequest(peer_id)) if peer_id == node_a_peer_id
+        ));
+        let (_, response) = receiver.await.unwrap();
+        assert!(matches!(response, Err(ResponseError::UnsupportedRequest)));
+
+        // The requests known to Node A are still sent
+        let (sender, _receiver) = oneshot::channel();
+        assert!(node_b
+            .send_request_msg(
+                Some(node_a_peer_id),
+                RequestMessage::SealedHeaders(0..1),
+                ResponseSender::SealedHeaders(sender),
+            )
+            .is_ok());
     }
 
     /// We send a request for transactions, but it's responded by only headers
@@ -1693,6 +4293,513 @@ mod tests {
         }
     }
 
+    /// The peer responds with the transactions exceeding the max response size
+    #[tokio::test]
+    #[instrument]
+    async fn response_above_max_size_is_replaced_with_too_large_error() {
+        let mut p2p_config = Config::default_initialized(
+            "response_above_max_size_is_replaced_with_too_large_error",
+        );
+        p2p_config.max_block_size = 1024;
+        p2p_config.block_chunk_size = 512;
+        let max_block_size = p2p_config.max_block_size;
+
+        // Node A
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+
+        // Node B
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config.clone()).await;
+
+        let (tx_test_end, mut rx_test_end) = mpsc::channel::<bool>(1);
+
+        let mut request_sent = false;
+
+        loop {
+            tokio::select! {
+                message_sent = rx_test_end.recv() => {
+                    assert!(message_sent.unwrap(), "Received incorrect or missing message");
+                    break;
+                }
+                node_a_event = node_a.next_event() => {
+                    if let Some(FuelP2PEvent::PeerInfoUpdated { peer_id, block_height: _ }) = node_a_event {
+                        if node_a.peer_manager.get_peer_info(&peer_id).is_some() && !request_sent {
+                            request_sent = true;
+
+                            let (tx_orchestrator, rx_orchestrator) = oneshot::channel();
+                            assert!(node_a.send_request_msg(None, RequestMessage::Transactions(0..2), ResponseSender::Transactions(tx_orchestrator)).is_ok());
+                            let tx_test_end = tx_test_end.clone();
+
+                            tokio::spawn(async move {
+                                match rx_orchestrator.await {
+                                    // The requester learns the size instead of timing out
+                                    Ok((_, Err(ResponseError::TooLarge { size }))) if size > saturating_u64(max_block_size) => {
+                                        let _ = tx_test_end.send(true).await;
+                                    },
+                                    other => {
+                                        let _ = tx_test_end.send(false).await;
+                                        panic!("Unexpected response: {:?}", other);
+                                    },
+                                }
+                            });
+                        }
+                    }
+
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                },
+                node_b_event = node_b.next_event() => {
+                    if let Some(FuelP2PEvent::InboundRequestMessage{ request_id, request_message: _ }) = &node_b_event {
+                        let txs = vec![Transaction::default_test_tx(); 100];
+                        let response = ResponseMessage::Transactions(Some(vec![Transactions(txs)]));
+                        assert!(encoded_size(&response) > max_block_size);
+                        assert!(node_b.send_response_msg(*request_id, response).is_ok());
+                    }
+
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                }
+            };
+        }
+    }
+
+    async fn send_requests_above_rate_limit(
+        mut p2p_config: Config,
+    ) -> Vec<Result<Option<Vec<Transactions>>, ResponseError>> {
+        // The budget is not refilled during the test
+        p2p_config.max_requests_per_second = 0;
+        p2p_config.max_requests_burst = 2;
+        let requests = 4;
+
+        // Node A
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+
+        // Node B
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config.clone()).await;
+
+        let (tx_response, mut rx_response) = mpsc::channel(requests);
+        let mut responses = vec![];
+        let mut request_sent = false;
+
+        loop {
+            tokio::select! {
+                response = rx_response.recv() => {
+                    responses.push(response.unwrap());
+                    if responses.len() == requests {
+                        break;
+                    }
+                }
+                node_a_event = node_a.next_event() => {
+                    if let Some(FuelP2PEvent::PeerInfoUpdated { peer_id, block_height: _ }) = node_a_event {
+                        if node_a.peer_manager.get_peer_info(&peer_id).is_some() && !request_sent {
+                            request_sent = true;
+
+                            for _ in 0..requests {
+                                let (tx_orchestrator, rx_orchestrator) = oneshot::channel();
+                                assert!(node_a.send_request_msg(Some(peer_id), RequestMessage::Transactions(0..2), ResponseSender::Transactions(tx_orchestrator)).is_ok());
+                                let tx_response = tx_response.clone();
+                                tokio::spawn(async move {
+                                    let (_, response) = rx_orchestrator.await.unwrap();
+                                    let _ = tx_response.send(response).await;
+                                });
+                            }
+                        }
+                    }
+
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                },
+                node_b_event = node_b.next_event() => {
+                    // Node B receives only the requests within the budget of Node A
+                    if let Some(FuelP2PEvent::InboundRequestMessage{ request_id, request_message: _ }) = &node_b_event {
+                        let _ = node_b.send_response_msg(*request_id, ResponseMessage::Error(ResponseMessageError::NotFound));
+                    }
+
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                }
+            };
+        }
+
+        responses
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn outbound_requests_above_max_inflight_are_rejected() {
+        let mut p2p_config = Config::default_initialized(
+            "outbound_requests_above_max_inflight_are_rejected",
+        );
+        let max_outbound_requests = 2;
+
+        // Node A
+        let mut node_a = build_service_from_config(Config {
+            max_outbound_requests: Some(max_outbound_requests),
+            ..p2p_config.clone()
+        })
+        .await;
+
+        // Node B
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config).await;
+
+        let mut receivers = vec![];
+        loop {
+            tokio::select! {
+                node_a_event = node_a.next_event() => {
+                    if let Some(FuelP2PEvent::PeerInfoUpdated { peer_id, block_height: _ }) = node_a_event {
+                        if node_a.peer_manager.get_peer_info(&peer_id).is_some() {
+                            for i in 0..=max_outbound_requests {
+                                let (tx_orchestrator, rx_orchestrator) = oneshot::channel();
+                                let result = node_a.send_request_msg(Some(peer_id), RequestMessage::Transactions(0..2), ResponseSender::Transactions(tx_orchestrator));
+                                if i < max_outbound_requests {
+                                    assert!(result.is_ok());
+                                } else {
+                                    assert!(matches!(result, Err(RequestError::TooManyInflight)));
+                                }
+                                receivers.push(rx_orchestrator);
+                            }
+                            break
+                        }
+                    }
+
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                },
+                node_b_event = node_b.next_event() => {
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                }
+            };
+        }
+
+        // The rejected request is answered right away
+        let rejected = receivers.pop().unwrap();
+        let (_, response) = rejected.await.unwrap();
+        assert!(matches!(response, Err(ResponseError::TooManyInflight)));
+
+        // The first requests proceed
+        let mut served = 0;
+        while served < max_outbound_requests {
+            tokio::select! {
+                node_a_event = node_a.next_event() => {
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                },
+                node_b_event = node_b.next_event() => {
+                    if let Some(FuelP2PEvent::InboundRequestMessage{ request_id, request_message: _ }) = &node_b_event {
+                        let _ = node_b.send_response_msg(*request_id, ResponseMessage::Error(ResponseMessageError::NotFound));
+                        served = served.saturating_add(1);
+                    }
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                }
+            };
+        }
+        for mut receiver in receivers {
+            let response = tokio::time::timeout(Duration::from_secs(5), async {
+                loop {
+                    tokio::select! {
+                        response = &mut receiver => break response.unwrap().1,
+                        node_a_event = node_a.next_event() => {
+                            tracing::info!("Node A Event: {:?}", node_a_event);
+                        },
+                        node_b_event = node_b.next_event() => {
+                            tracing::info!("Node B Event: {:?}", node_b_event);
+                        }
+                    }
+                }
+            })
+            .await
+            .expect("The request should be answered");
+            assert!(matches!(response, Err(ResponseError::NotFound)));
+        }
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn request_is_sent_to_the_targeted_peer() {
+        let mut p2p_config =
+            Config::default_initialized("request_is_sent_to_the_targeted_peer");
+
+        // Node A requests the headers
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+
+        // Node B has the headers, Node C doesn't
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config.clone()).await;
+        let node_b_id = node_b.local_peer_id;
+        let mut node_c = build_service_from_config(p2p_config).await;
+        let node_c_id = node_c.local_peer_id;
+
+        // The request to the peer that isn't connected is rejected right away
+        let unknown_peer = PeerId::random();
+        let (sender, receiver) = oneshot::channel();
+        let result = node_a.send_request_msg(
+            Some(unknown_peer),
+            RequestMessage::SealedHeaders(0..2),
+            ResponseSender::SealedHeaders(sender),
+        );
+        assert!(
+            matches!(result, Err(RequestError::PeerNotConnected(peer_id)) if peer_id == unknown_peer)
+        );
+        let (peer_id, response) = receiver.await.unwrap();
+        assert_eq!(peer_id, unknown_peer);
+        assert!(matches!(response, Err(ResponseError::PeerNotConnected)));
+
+        let is_connected = |node_a: &P2PService, peer_id: &PeerId| {
+            node_a.peer_manager.get_peer_info(peer_id).is_some()
+        };
+        while !is_connected(&node_a, &node_b_id) || !is_connected(&node_a, &node_c_id) {
+            tokio::select! {
+                node_a_event = node_a.next_event() => {
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                },
+                node_b_event = node_b.next_event() => {
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                },
+                node_c_event = node_c.next_event() => {
+                    tracing::info!("Node C Event: {:?}", node_c_event);
+                }
+            };
+        }
+
+        for targeted_peer in [node_b_id, node_c_id, node_b_id] {
+            let (sender, mut receiver) = oneshot::channel();
+            assert!(node_a
+                .send_request_msg(
+                    Some(targeted_peer),
+                    RequestMessage::SealedHeaders(0..2),
+                    ResponseSender::SealedHeaders(sender),
+                )
+                .is_ok());
+
+            let (peer_id, response) = loop {
+                tokio::select! {
+                    response = &mut receiver => break response.unwrap(),
+                    node_a_event = node_a.next_event() => {
+                        tracing::info!("Node A Event: {:?}", node_a_event);
+                    },
+                    node_b_event = node_b.next_event() => {
+                        if let Some(FuelP2PEvent::InboundRequestMessage { request_id, request_message: RequestMessage::SealedHeaders(range) }) = node_b_event {
+                            let sealed_headers = arbitrary_headers_for_range(range);
+                            let _ = node_b.send_response_msg(request_id, ResponseMessage::SealedHeaders(Some(sealed_headers)));
+                        }
+                    },
+                    node_c_event = node_c.next_event() => {
+                        if let Some(FuelP2PEvent::InboundRequestMessage { request_id, .. }) = node_c_event {
+                            let _ = node_c.send_response_msg(request_id, ResponseMessage::Error(ResponseMessageError::NotFound));
+                        }
+                    }
+                };
+            };
+
+            assert_eq!(peer_id, targeted_peer);
+            if targeted_peer == node_b_id {
+                let expected = arbitrary_headers_for_range(0..2);
+                let sealed_headers = response.unwrap().unwrap();
+                assert_eq!(sealed_headers.len(), expected.len());
+                assert!(expected
+                    .iter()
+                    .zip(sealed_headers.iter())
+                    .all(|(a, b)| eq_except_metadata(a, b)));
+            } else {
+                assert!(matches!(response, Err(ResponseError::NotFound)));
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn requests_above_rate_limit_are_rejected() {
+        let p2p_config =
+            Config::default_initialized("requests_above_rate_limit_are_rejected");
+
+        let responses = send_requests_above_rate_limit(p2p_config).await;
+
+        let served = responses
+            .iter()
+            .filter(|response| matches!(response, Err(ResponseError::NotFound)))
+            .count();
+        let rejected = responses
+            .iter()
+            .filter(|response| matches!(response, Err(ResponseError::TooManyRequests)))
+            .count();
+        assert_eq!(served, 2);
+        assert_eq!(rejected, 2);
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn requests_above_rate_limit_are_dropped_when_configured() {
+        let mut p2p_config = Config::default_initialized(
+            "requests_above_rate_limit_are_dropped_when_configured",
+        );
+        p2p_config.drop_rate_limited_requests = true;
+
+        let responses = send_requests_above_rate_limit(p2p_config).await;
+
+        let served = responses
+            .iter()
+            .filter(|response| matches!(response, Err(ResponseError::NotFound)))
+            .count();
+        let dropped = responses
+            .iter()
+            .filter(|response| matches!(response, Err(ResponseError::P2P(_))))
+            .count();
+        assert_eq!(served, 2);
+        assert_eq!(dropped, 2);
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn block_requests_above_response_bytes_are_rejected_until_window_ends() {
+        let mut p2p_config = Config::default_initialized(
+            "block_requests_above_response_bytes_are_rejected_until_window_ends",
+        );
+
+        // Node A sends a single response per minute
+        p2p_config.max_response_bytes_per_peer_per_minute = Some(1);
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+        let node_a_id = node_a.local_peer_id;
+
+        // Node B
+        p2p_config.max_response_bytes_per_peer_per_minute = None;
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config.clone()).await;
+
+        let mut requests = [
+            RequestMessage::Transactions(0..2),
+            RequestMessage::Transactions(0..2),
+            RequestMessage::Tip,
+        ]
+        .into_iter();
+        let (tx_response, mut rx_response) = mpsc::channel(1);
+        let mut responses = vec![];
+        let mut awaiting_response = false;
+
+        loop {
+            // The requests are sent one by one, so the limit is exceeded by the first response
+            if !awaiting_response
+                && node_b.peer_manager.get_peer_info(&node_a_id).is_some()
+            {
+                let Some(request) = requests.next() else {
+                    break
+                };
+                let tx_response = tx_response.clone();
+                let response_sender = if let RequestMessage::Tip = request {
+                    let (sender, receiver) = oneshot::channel();
+                    tokio::spawn(async move {
+                        let (_, response) = receiver.await.unwrap();
+                        let _ = tx_response.send(response.map(|_| ())).await;
+                    });
+                    ResponseSender::Tip(sender)
+                } else {
+                    let (sender, receiver) = oneshot::channel();
+                    tokio::spawn(async move {
+                        let (_, response) = receiver.await.unwrap();
+                        let _ = tx_response.send(response.map(|_| ())).await;
+                    });
+                    ResponseSender::Transactions(sender)
+                };
+                assert!(node_b
+                    .send_request_msg(Some(node_a_id), request, response_sender)
+                    .is_ok());
+                awaiting_response = true;
+            }
+
+            tokio::select! {
+                response = rx_response.recv() => {
+                    responses.push(response.unwrap());
+                    awaiting_response = false;
+                }
+                node_a_event = node_a.next_event() => {
+                    if let Some(FuelP2PEvent::InboundRequestMessage { request_id, .. }) = node_a_event {
+                        let _ = node_a.send_response_msg(request_id, ResponseMessage::Error(ResponseMessageError::NotFound));
+                    }
+                },
+                node_b_event = node_b.next_event() => {
+                    tracing::info!("Node B Event: {:?}", node_b_event);
+                }
+            };
+        }
+
+        // The next block request is rejected after the first response, the tip is still served
+        assert!(matches!(responses[0], Err(ResponseError::NotFound)));
+        assert!(matches!(responses[1], Err(ResponseError::TooManyRequests)));
+        assert!(matches!(responses[2], Err(ResponseError::NotFound)));
+
+        // The bytes of the requests and the responses are accounted for the peer
+        let bandwidth = node_b
+            .peer_manager
+            .get_peer_info(&node_a_id)
+            .unwrap()
+            .bandwidth;
+        assert!(bandwidth.request_response.inbound > 0);
+        assert!(bandwidth.request_response.outbound > 0);
+        assert_eq!(
+            bandwidth.total().outbound,
+            bandwidth.request_response.outbound
+        );
+    }
+
+    #[tokio::test]
+    #[instrument]
+    async fn response_time_of_the_delayed_peer_matches_its_delay() {
+        let mut p2p_config = Config::default_initialized(
+            "response_time_of_the_delayed_peer_matches_its_delay",
+        );
+        let delay = Duration::from_millis(300);
+
+        // Node A
+        let mut node_a = build_service_from_config(p2p_config.clone()).await;
+
+        // Node B answers each request after the delay
+        p2p_config.bootstrap_nodes = node_a.multiaddrs();
+        let mut node_b = build_service_from_config(p2p_config.clone()).await;
+        let node_b_id = node_b.local_peer_id;
+
+        let mut receiver = None;
+        let mut delayed_response = None;
+        loop {
+            if receiver.is_none()
+                && node_a.peer_manager.get_peer_info(&node_b_id).is_some()
+            {
+                let (sender, response_receiver) = oneshot::channel();
+                assert!(node_a
+                    .send_request_msg(
+                        Some(node_b_id),
+                        RequestMessage::Tip,
+                        ResponseSender::Tip(sender),
+                    )
+                    .is_ok());
+                receiver = Some(response_receiver);
+            }
+
+            tokio::select! {
+                response = async { receiver.as_mut().unwrap().await }, if receiver.is_some() => {
+                    let (_, response) = response.unwrap();
+                    assert!(matches!(response, Err(ResponseError::NotFound)));
+                    break
+                }
+                _ = async { tokio::time::sleep_until(delayed_response.unwrap().1).await }, if delayed_response.is_some() => {
+                    let (request_id, _) = delayed_response.take().unwrap();
+                    let _ = node_b.send_response_msg(request_id, ResponseMessage::Error(ResponseMessageError::NotFound));
+                }
+                node_a_event = node_a.next_event() => {
+                    tracing::info!("Node A Event: {:?}", node_a_event);
+                },
+                node_b_event = node_b.next_event() => {
+                    if let Some(FuelP2PEvent::InboundRequestMessage { request_id, .. }) = node_b_event {
+                        delayed_response = Some((request_id, tokio::time::Instant::now() + delay));
+                    }
+                }
+            };
+        }
+
+        let latency = node_a
+            .peer_manager
+            .get_peer_info(&node_b_id)
+            .unwrap()
+            .average_response_time
+            .unwrap();
+        assert!(latency >= delay, "{latency:?}");
+        assert!(latency < delay + Duration::from_secs(1), "{latency:?}");
+    }
+
     #[tokio::test]
     #[instrument]
     async fn req_res_outbound_timeout_works() {
@@ -11,8 +11,12 @@ use fuel_core_types::{
 use libp2p::{
     Multiaddr,
     PeerId,
+    StreamProtocol,
+};
+use rand::{
+    seq::IteratorRandom,
+    Rng,
 };
-use rand::seq::IteratorRandom;
 use std::{
     collections::{
         HashMap,
@@ -22,13 +26,17 @@ use std::{
         Arc,
         RwLock,
     },
+    time::Duration,
 };
+use tokio::time::Instant;
 use tracing::{
     debug,
     info,
+    warn,
 };
 
 use crate::{
+    config::PeerSelectionStrategy,
     gossipsub_config::GRAYLIST_THRESHOLD,
     peer_manager::heartbeat_data::HeartbeatData,
 };
@@ -38,13 +46,139 @@ pub mod heartbeat_data;
 /// At this point we better just ban the peer
 const MIN_GOSSIPSUB_SCORE_BEFORE_BAN: AppScore = GRAYLIST_THRESHOLD;
 
+/// The delay before the redial of a disconnected reserved or bootstrap peer
+const MIN_REDIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// The upper bound of the delay between redials of a disconnected reserved or bootstrap peer
+const MAX_REDIAL_BACKOFF: Duration = Duration::from_secs(60);
+/// The share of the redial delay cut randomly, so the nodes don't redial the same peer at once
+const REDIAL_JITTER: f64 = 0.1;
+/// The weight of the previous average in the average response time of a peer,
+/// the latest response time has the weight of one
+const RESPONSE_TIME_AVERAGE_WEIGHT: u32 = 4;
+
+/// The side that initiated the connection with a peer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionDirection {
+    /// The peer dialed us
+    Inbound,
+    /// We dialed the peer
+    Outbound,
+}
+
+/// The protocol that carried the bytes exchanged with a peer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BandwidthProtocol {
+    /// The gossipsub messages
+    Gossip,
+    /// The requests and the responses
+    RequestResponse,
+}
+
+impl BandwidthProtocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BandwidthProtocol::Gossip => "gossip",
+            BandwidthProtocol::RequestResponse => "request_response",
+        }
+    }
+}
+
+/// The direction of the bytes exchanged with a peer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrafficDirection {
+    /// The bytes received from the peer
+    Inbound,
+    /// The bytes sent to the peer
+    Outbound,
+}
+
+impl TrafficDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrafficDirection::Inbound => "inbound",
+            TrafficDirection::Outbound => "outbound",
+        }
+    }
+}
+
+/// The number of bytes received from and sent to a peer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ByteCounters {
+    pub inbound: u64,
+    pub outbound: u64,
+}
+
+impl ByteCounters {
+    fn add(&mut self, direction: TrafficDirection, bytes: u64) {
+        let counter = match direction {
+            TrafficDirection::Inbound => &mut self.inbound,
+            TrafficDirection::Outbound => &mut self.outbound,
+        };
+        *counter = counter.saturating_add(bytes);
+    }
+}
+
+/// The bytes exchanged with a peer per protocol, as encoded by the codec.
+/// The framing of the transport and the multiplexer isn't included.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BandwidthUsage {
+    pub gossip: ByteCounters,
+    pub request_response: ByteCounters,
+}
+
+impl BandwidthUsage {
+    pub fn record(
+        &mut self,
+        protocol: BandwidthProtocol,
+        direction: TrafficDirection,
+        bytes: u64,
+    ) {
+        match protocol {
+            BandwidthProtocol::Gossip => self.gossip.add(direction, bytes),
+            BandwidthProtocol::RequestResponse => {
+                self.request_response.add(direction, bytes)
+            }
+        }
+    }
+
+    /// Returns the bytes exchanged over all protocols
+    pub fn total(&self) -> ByteCounters {
+        ByteCounters {
+            inbound: self
+                .gossip
+                .inbound
+                .saturating_add(self.request_response.inbound),
+            outbound: self
+                .gossip
+                .outbound
+                .saturating_add(self.request_response.outbound),
+        }
+    }
+}
+
 // Info about a single Peer that we're connected to
 #[derive(Debug, Clone)]
 pub struct PeerInfo {
     pub peer_addresses: HashSet<Multiaddr>,
     pub client_version: Option<String>,
+    /// Protocols supported by the peer, as reported by the identify protocol
+    pub protocols: Vec<StreamProtocol>,
     pub heartbeat_data: HeartbeatData,
     pub score: AppScore,
+    /// The gossipsub score of the peer, refreshed on every reputation decay
+    pub gossipsub_score: Option<f64>,
+    /// The direction of the first connection established with the peer
+    pub direction: Option<ConnectionDirection>,
+    /// The time it took the peer to answer our latest request
+    pub last_response_time: Option<Duration>,
+    /// The moving average of the time it took the peer to answer our requests
+    pub average_response_time: Option<Duration>,
+    /// The peer serves all blocks since the genesis, as reported by the identify protocol
+    pub is_archival: bool,
+    /// The last time the peer sent us gossip, a request or a response
+    pub last_activity: Instant,
+    /// The bytes exchanged with the peer since the connection was established
+    pub bandwidth: BandwidthUsage,
 }
 
 impl PeerInfo {
@@ -52,8 +186,16 @@ impl PeerInfo {
         Self {
             peer_addresses: HashSet::new(),
             client_version: None,
+            protocols: Vec::new(),
             heartbeat_data: HeartbeatData::new(heartbeat_avg_window),
             score: DEFAULT_APP_SCORE,
+            gossipsub_score: None,
+            direction: None,
+            last_response_time: None,
+            average_response_time: None,
+            is_archival: false,
+            last_activity: Instant::now(),
+            bandwidth: BandwidthUsage::default(),
         }
     }
 }
@@ -68,6 +210,14 @@ pub struct PeerManager {
     connection_state: Arc<RwLock<ConnectionState>>,
     max_non_reserved_peers: usize,
     reserved_peers_updates: tokio::sync::broadcast::Sender<usize>,
+    /// The bootstrap peers redialed until they are connected
+    bootstrap_peers: HashSet<PeerId>,
+    redial_config: RedialConfig,
+    /// Redial schedule of the disconnected reserved and bootstrap peers
+    redial_backoff: HashMap<PeerId, RedialBackoff>,
+    peer_selection_strategy: PeerSelectionStrategy,
+    /// The peer picked for the latest outbound request
+    last_selected_peer: Option<PeerId>,
 }
 
 impl PeerManager {
@@ -76,6 +226,9 @@ impl PeerManager {
         reserved_peers: HashSet<PeerId>,
         connection_state: Arc<RwLock<ConnectionState>>,
         max_non_reserved_peers: usize,
+        peer_selection_strategy: PeerSelectionStrategy,
+        bootstrap_peers: HashSet<PeerId>,
+        redial_config: RedialConfig,
     ) -> Self {
         Self {
             score_config: ScoreConfig::default(),
@@ -85,6 +238,11 @@ impl PeerManager {
             connection_state,
             max_non_reserved_peers,
             reserved_peers_updates,
+            bootstrap_peers,
+            redial_config,
+            redial_backoff: HashMap::new(),
+            peer_selection_strategy,
+            last_selected_peer: None,
         }
     }
 
@@ -109,11 +267,13 @@ impl PeerManager {
         }
     }
 
+    /// Records the heartbeat of the peer.
+    /// Returns `false` if the heartbeat is stale, so its block height shouldn't be announced.
     pub fn handle_peer_info_updated(
         &mut self,
         peer_id: &PeerId,
         block_height: BlockHeight,
-    ) {
+    ) -> bool {
         if let Some(time_elapsed) = self
             .get_peer_info(peer_id)
             .map(|info| info.heartbeat_data.duration_since_last_heartbeat())
@@ -122,19 +282,26 @@ impl PeerManager {
         }
 
         let peers = self.get_assigned_peer_table_mut(peer_id);
-        update_heartbeat(peers, peer_id, block_height);
+        update_heartbeat(peers, peer_id, block_height)
     }
 
-    /// Returns `true` signaling that the peer should be disconnected
+    /// Returns the peer that should be disconnected: either the connected peer itself,
+    /// or the evicted peer that was replaced by the connected one.
     pub fn handle_peer_connected(
         &mut self,
         peer_id: &PeerId,
         initial_connection: bool,
-    ) -> bool {
+        direction: ConnectionDirection,
+    ) -> Option<PeerId> {
         if initial_connection {
-            self.handle_initial_connection(peer_id)
+            let peer_to_disconnect = self.handle_initial_connection(peer_id);
+            if peer_to_disconnect != Some(*peer_id) {
+                let peers = self.get_assigned_peer_table_mut(peer_id);
+                insert_connection_direction(peers, peer_id, direction);
+            }
+            peer_to_disconnect
         } else {
-            false
+            None
         }
     }
 
@@ -143,10 +310,14 @@ impl PeerManager {
         peer_id: &PeerId,
         addresses: Vec<Multiaddr>,
         agent_version: String,
+        protocols: Vec<StreamProtocol>,
+        is_archival: bool,
     ) {
         let peers = self.get_assigned_peer_table_mut(peer_id);
         insert_client_version(peers, peer_id, agent_version);
         insert_peer_addresses(peers, peer_id, addresses);
+        insert_protocols(peers, peer_id, protocols);
+        insert_archival(peers, peer_id, is_archival);
     }
 
     pub fn batch_update_score_with_decay(&mut self) {
@@ -155,6 +326,19 @@ impl PeerManager {
         }
     }
 
+    pub fn update_gossipsub_scores<F>(&mut self, gossipsub_score: F)
+    where
+        F: Fn(&PeerId) -> Option<f64>,
+    {
+        for (peer_id, peer_info) in self
+            .non_reserved_connected_peers
+            .iter_mut()
+            .chain(self.reserved_connected_peers.iter_mut())
+        {
+            peer_info.gossipsub_score = gossipsub_score(peer_id);
+        }
+    }
+
     pub fn update_app_score<T: Punisher>(
         &mut self,
         peer_id: PeerId,
@@ -202,10 +386,44 @@ impl PeerManager {
             .chain(self.reserved_connected_peers.iter())
     }
 
-    pub fn get_disconnected_reserved_peers(&self) -> impl Iterator<Item = &PeerId> {
-        self.reserved_peers
+    pub fn get_connected_reserved_peers(&self) -> impl Iterator<Item = &PeerId> {
+        self.reserved_connected_peers.keys()
+    }
+
+    /// Returns the disconnected reserved peers that should be redialed now.
+    /// Consecutive redials of the same peer are delayed exponentially.
+    pub fn reserved_peers_to_redial(&mut self) -> Vec<PeerId> {
+        let disconnected = self
+            .reserved_peers
             .iter()
-            .filter(|peer_id| !self.reserved_connected_peers.contains_key(peer_id))
+            .filter(|peer_id| !self.reserved_connected_peers.contains_key(peer_id));
+
+        due_redials(disconnected, &mut self.redial_backoff, &self.redial_config)
+    }
+
+    /// Returns the disconnected bootstrap peers that should be redialed now.
+    /// They aren't redialed while all non-reserved slots are taken.
+    pub fn bootstrap_peers_to_redial(&mut self) -> Vec<PeerId> {
+        if self.non_reserved_connected_peers.len() >= self.max_non_reserved_peers {
+            return vec![]
+        }
+        let disconnected = self.bootstrap_peers.iter().filter(|peer_id| {
+            !self.non_reserved_connected_peers.contains_key(peer_id)
+                && !self.reserved_connected_peers.contains_key(peer_id)
+        });
+
+        due_redials(disconnected, &mut self.redial_backoff, &self.redial_config)
+    }
+
+    /// Counts the failed dial of the reserved or bootstrap peer
+    pub fn handle_dial_failure(&mut self, peer_id: &PeerId) {
+        let Some(backoff) = self.redial_backoff.get_mut(peer_id) else {
+            return
+        };
+        backoff.failures = backoff.failures.saturating_add(1);
+        if backoff.failures == self.redial_config.warn_after_failures {
+            warn!(target: "fuel-p2p", "Failed to connect to {:?} {} times in a row, still retrying", peer_id, backoff.failures);
+        }
     }
 
     /// Handles on peer's last connection getting disconnected
@@ -232,30 +450,167 @@ impl PeerManager {
             false
         } else if self.reserved_connected_peers.remove(&peer_id).is_some() {
             self.send_reserved_peers_update();
+            // The peer is redialed immediately, so the next attempt is delayed
+            let now = Instant::now();
+            let mut backoff = RedialBackoff::new(now, &self.redial_config);
+            backoff.schedule_next(now, &self.redial_config);
+            self.redial_backoff.insert(peer_id, backoff);
             true
         } else {
             false
         }
     }
 
+    /// Marks the peer as reserved.
+    /// If the peer is already connected, it no longer takes a non-reserved slot.
+    pub fn add_reserved_peer(&mut self, peer_id: PeerId) {
+        if !self.reserved_peers.insert(peer_id) {
+            return
+        }
+
+        if let Some(peer_info) = self.non_reserved_connected_peers.remove(&peer_id) {
+            self.reserved_connected_peers.insert(peer_id, peer_info);
+            self.send_reserved_peers_update();
+            self.update_connection_state();
+        }
+    }
+
+    /// Treats the peer as a regular one.
+    /// Returns `true` if the peer is connected and there is no free non-reserved slot for it,
+    /// so it should be disconnected.
+    pub fn remove_reserved_peer(&mut self, peer_id: &PeerId) -> bool {
+        if !self.reserved_peers.remove(peer_id) {
+            return false
+        }
+        self.redial_backoff.remove(peer_id);
+
+        let Some(peer_info) = self.reserved_connected_peers.remove(peer_id) else {
+            return false
+        };
+        self.send_reserved_peers_update();
+
+        if self.non_reserved_connected_peers.len() >= self.max_non_reserved_peers {
+            return true
+        }
+        self.non_reserved_connected_peers
+            .insert(*peer_id, peer_info);
+        self.update_connection_state();
+        false
+    }
+
     /// Find a peer that is holding the given block height.
-    pub fn get_peer_id_with_height(&self, height: &BlockHeight) -> Option<PeerId> {
-        let mut range = rand::thread_rng();
+    pub fn get_peer_id_with_height(&mut self, height: &BlockHeight) -> Option<PeerId> {
         // TODO: Optimize the selection of the peer.
         //  We can store pair `(peer id, height)` for all nodes(reserved and not) in the
         //  https://docs.rs/sorted-vec/latest/sorted_vec/struct.SortedVec.html
-        self.non_reserved_connected_peers
+        let candidates = self
+            .non_reserved_connected_peers
             .iter()
             .chain(self.reserved_connected_peers.iter())
             .filter(|(_, peer_info)| {
                 peer_info.heartbeat_data.block_height >= Some(*height)
+            });
+
+        let selected_peer = match self.peer_selection_strategy {
+            PeerSelectionStrategy::Random => {
+                let mut range = rand::thread_rng();
+                candidates.map(|(peer_id, _)| *peer_id).choose(&mut range)
+            }
+            PeerSelectionStrategy::RoundRobin => {
+                let mut peer_ids =
+                    candidates.map(|(peer_id, _)| *peer_id).collect::<Vec<_>>();
+                peer_ids.sort();
+                // the peer following the last selected one, or the first one
+                peer_ids
+                    .iter()
+                    .find(|peer_id| Some(**peer_id) > self.last_selected_peer)
+                    .or_else(|| peer_ids.first())
+                    .copied()
+            }
+            PeerSelectionStrategy::LowestLatency => candidates
+                .min_by_key(|(_, peer_info)| {
+                    peer_info.last_response_time.unwrap_or(Duration::ZERO)
+                })
+                .map(|(peer_id, _)| *peer_id),
+        };
+
+        self.last_selected_peer = selected_peer;
+        selected_peer
+    }
+
+    /// Finds up to `count` distinct peers holding the given block height.
+    /// The first peer is picked with the configured strategy, the rest are random.
+    pub fn get_peers_ids_with_height(
+        &mut self,
+        height: &BlockHeight,
+        count: usize,
+    ) -> Vec<PeerId> {
+        let Some(selected_peer) = self.get_peer_id_with_height(height) else {
+            return vec![]
+        };
+
+        let mut range = rand::thread_rng();
+        let other_peers = self
+            .non_reserved_connected_peers
+            .iter()
+            .chain(self.reserved_connected_peers.iter())
+            .filter(|(peer_id, peer_info)| {
+                **peer_id != selected_peer
+                    && peer_info.heartbeat_data.block_height >= Some(*height)
             })
             .map(|(peer_id, _)| *peer_id)
-            .choose(&mut range)
+            .choose_multiple(&mut range, count.saturating_sub(1));
+
+        std::iter::once(selected_peer).chain(other_peers).collect()
+    }
+
+    /// Records the time it took the peer to answer our request
+    pub fn handle_response_time(&mut self, peer_id: &PeerId, response_time: Duration) {
+        let peers = self.get_assigned_peer_table_mut(peer_id);
+        if let Some(peer) = peers.get_mut(peer_id) {
+            peer.last_response_time = Some(response_time);
+            peer.average_response_time =
+                Some(peer.average_response_time.map_or(response_time, |average| {
+                    average_response_time(average, response_time)
+                }));
+        } else {
+            log_missing_peer(peer_id);
+        }
+    }
+
+    /// Records the substantive traffic from the peer: gossip, requests or responses
+    pub fn handle_peer_activity(&mut self, peer_id: &PeerId) {
+        let peers = self.get_assigned_peer_table_mut(peer_id);
+        if let Some(peer) = peers.get_mut(peer_id) {
+            peer.last_activity = Instant::now();
+        }
+    }
+
+    /// Accounts the bytes exchanged with the peer
+    pub fn handle_bandwidth(
+        &mut self,
+        peer_id: &PeerId,
+        protocol: BandwidthProtocol,
+        direction: TrafficDirection,
+        bytes: u64,
+    ) {
+        let peers = self.get_assigned_peer_table_mut(peer_id);
+        if let Some(peer) = peers.get_mut(peer_id) {
+            peer.bandwidth.record(protocol, direction, bytes);
+        }
+    }
+
+    /// Returns the non-reserved peers that had no substantive traffic for the `timeout`
+    pub fn idle_peers(&self, timeout: Duration) -> Vec<PeerId> {
+        self.non_reserved_connected_peers
+            .iter()
+            .filter(|(_, peer_info)| peer_info.last_activity.elapsed() >= timeout)
+            .map(|(peer_id, _)| *peer_id)
+            .collect()
     }
 
     /// Handles the first connnection established with a Peer    
-    fn handle_initial_connection(&mut self, peer_id: &PeerId) -> bool {
+    fn handle_initial_connection(&mut self, peer_id: &PeerId) -> Option<PeerId> {
         const HEARTBEAT_AVG_WINDOW: u32 = 10;
 
         // if the connected Peer is not from the reserved peers
@@ -263,8 +618,16 @@ impl PeerManager {
             let non_reserved_peers_connected = self.non_reserved_connected_peers.len();
             // check if all the slots are already taken
             if non_reserved_peers_connected >= self.max_non_reserved_peers {
-                // Too many peers already connected, disconnect the Peer
-                return true
+                let Some(evicted_peer) = self.eviction_candidate() else {
+                    // Too many peers already connected, disconnect the Peer
+                    return Some(*peer_id)
+                };
+
+                debug!(target: "fuel-p2p", "Evicting {:?} in favor of {:?}", evicted_peer, peer_id);
+                self.non_reserved_connected_peers.remove(&evicted_peer);
+                self.non_reserved_connected_peers
+                    .insert(*peer_id, PeerInfo::new(HEARTBEAT_AVG_WINDOW));
+                return Some(evicted_peer)
             }
 
             if non_reserved_peers_connected.saturating_add(1)
@@ -278,14 +641,51 @@ impl PeerManager {
 
             self.non_reserved_connected_peers
                 .insert(*peer_id, PeerInfo::new(HEARTBEAT_AVG_WINDOW));
+            self.redial_backoff.remove(peer_id);
         } else {
             self.reserved_connected_peers
                 .insert(*peer_id, PeerInfo::new(HEARTBEAT_AVG_WINDOW));
+            self.redial_backoff.remove(peer_id);
 
             self.send_reserved_peers_update();
         }
 
-        false
+        None
+    }
+
+    /// Finds the least valuable non-reserved peer that is worth replacing with a new one:
+    /// the peer with the lowest reputation, and the longest idle one among equals.
+    /// Peers with the default or higher reputation are never evicted.
+    fn eviction_candidate(&self) -> Option<PeerId> {
+        self.non_reserved_connected_peers
+            .iter()
+            .filter(|(_, peer_info)| peer_info.score < DEFAULT_APP_SCORE)
+            .min_by(|(_, a), (_, b)| {
+                a.score.total_cmp(&b.score).then_with(|| {
+                    let a_idle = a.heartbeat_data.duration_since_last_heartbeat();
+                    let b_idle = b.heartbeat_data.duration_since_last_heartbeat();
+                    b_idle.cmp(&a_idle)
+                })
+            })
+            .map(|(peer_id, _)| *peer_id)
+    }
+
+    pub fn peer_counts(&self) -> PeerCounts {
+        PeerCounts {
+            reserved_peers: self.reserved_connected_peers.len(),
+            non_reserved_peers: self.non_reserved_connected_peers.len(),
+            max_non_reserved_peers: self.max_non_reserved_peers,
+        }
+    }
+
+    fn update_connection_state(&self) {
+        if let Ok(mut connection_state) = self.connection_state.write() {
+            if self.non_reserved_connected_peers.len() < self.max_non_reserved_peers {
+                connection_state.allow_new_peers();
+            } else {
+                connection_state.deny_new_peers();
+            }
+        }
     }
 
     fn send_reserved_peers_update(&self) {
@@ -306,6 +706,16 @@ impl PeerManager {
     }
 }
 
+/// Weights the previous average with `RESPONSE_TIME_AVERAGE_WEIGHT`,
+/// so the single slow response doesn't outweigh the history of the peer
+fn average_response_time(average: Duration, response_time: Duration) -> Duration {
+    average
+        .saturating_mul(RESPONSE_TIME_AVERAGE_WEIGHT)
+        .saturating_add(response_time)
+        .checked_div(RESPONSE_TIME_AVERAGE_WEIGHT.saturating_add(1))
+        .unwrap_or(response_time)
+}
+
 fn insert_peer_addresses(
     peers: &mut HashMap<PeerId, PeerInfo>,
     peer_id: &PeerId,
@@ -320,6 +730,88 @@ fn insert_peer_addresses(
     }
 }
 
+/// The number of connected peers
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PeerCounts {
+    pub reserved_peers: usize,
+    pub non_reserved_peers: usize,
+    /// The limit of connected non-reserved peers
+    pub max_non_reserved_peers: usize,
+}
+
+/// The schedule of the redials of the disconnected reserved and bootstrap peers
+#[derive(Debug, Clone, Copy)]
+pub struct RedialConfig {
+    /// The delay before the second redial, doubled for each following one
+    pub base: Duration,
+    /// The upper bound of the delay between redials
+    pub max: Duration,
+    /// The warning is logged once the peer fails this number of dials in a row
+    pub warn_after_failures: u32,
+}
+
+impl Default for RedialConfig {
+    fn default() -> Self {
+        Self {
+            base: MIN_REDIAL_BACKOFF,
+            max: MAX_REDIAL_BACKOFF,
+            warn_after_failures: 5,
+        }
+    }
+}
+
+/// Schedules redials of a disconnected reserved or bootstrap peer
+#[derive(Debug, Clone, Copy)]
+struct RedialBackoff {
+    next_redial: Instant,
+    delay: Duration,
+    /// The number of failed dials since the peer was connected
+    failures: u32,
+}
+
+impl RedialBackoff {
+    fn new(now: Instant, config: &RedialConfig) -> Self {
+        Self {
+            next_redial: now,
+            delay: config.base,
+            failures: 0,
+        }
+    }
+
+    /// Delays the next redial and doubles the delay for the following one
+    fn schedule_next(&mut self, now: Instant, config: &RedialConfig) {
+        let jitter = self
+            .delay
+            .mul_f64(rand::thread_rng().gen_range(0.0..REDIAL_JITTER));
+        let delay = self.delay.saturating_sub(jitter);
+        self.next_redial = now.checked_add(delay).unwrap_or(now);
+        self.delay = self.delay.saturating_mul(2).min(config.max);
+    }
+}
+
+/// Returns the peers whose redial is due and schedules their next redials
+fn due_redials<'a>(
+    peers: impl Iterator<Item = &'a PeerId>,
+    backoffs: &mut HashMap<PeerId, RedialBackoff>,
+    config: &RedialConfig,
+) -> Vec<PeerId> {
+    let now = Instant::now();
+    let mut due = vec![];
+
+    for peer_id in peers {
+        let backoff = backoffs
+            .entry(*peer_id)
+            .or_insert_with(|| RedialBackoff::new(now, config));
+
+        if backoff.next_redial <= now {
+            backoff.schedule_next(now, config);
+            due.push(*peer_id);
+        }
+    }
+
+    due
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 pub struct ConnectionState {
     peers_allowed: bool,
@@ -349,11 +841,12 @@ fn update_heartbeat(
     peers: &mut HashMap<PeerId, PeerInfo>,
     peer_id: &PeerId,
     block_height: BlockHeight,
-) {
+) -> bool {
     if let Some(peer) = peers.get_mut(peer_id) {
-        peer.heartbeat_data.update(block_height);
+        peer.heartbeat_data.update(block_height)
     } else {
         log_missing_peer(peer_id);
+        true
     }
 }
 
@@ -369,6 +862,42 @@ fn insert_client_version(
     }
 }
 
+fn insert_protocols(
+    peers: &mut HashMap<PeerId, PeerInfo>,
+    peer_id: &PeerId,
+    protocols: Vec<StreamProtocol>,
+) {
+    if let Some(peer) = peers.get_mut(peer_id) {
+        peer.protocols = protocols;
+    } else {
+        log_missing_peer(peer_id);
+    }
+}
+
+fn insert_archival(
+    peers: &mut HashMap<PeerId, PeerInfo>,
+    peer_id: &PeerId,
+    is_archival: bool,
+) {
+    if let Some(peer) = peers.get_mut(peer_id) {
+        peer.is_archival = is_archival;
+    } else {
+        log_missing_peer(peer_id);
+    }
+}
+
+fn insert_connection_direction(
+    peers: &mut HashMap<PeerId, PeerInfo>,
+    peer_id: &PeerId,
+    direction: ConnectionDirection,
+) {
+    if let Some(peer) = peers.get_mut(peer_id) {
+        peer.direction = Some(direction);
+    } else {
+        log_missing_peer(peer_id);
+    }
+}
+
 fn log_missing_peer(peer_id: &PeerId) {
     debug!(target: "fuel-p2p", "Peer with PeerId: {:?} is not among the connected peers", peer_id)
 }
@@ -421,6 +950,9 @@ mod tests {
             reserved_peers.into_iter().collect(),
             connection_state,
             max_non_reserved_peers,
+            PeerSelectionStrategy::default(),
+            HashSet::new(),
+            RedialConfig::default(),
         )
     }
 
@@ -499,4 +1031,198 @@ mod tests {
             reserved_peers.len() + max_non_reserved_peers
         );
     }
+
+    #[test]
+    fn average_response_time_follows_the_latest_responses() {
+        let peer_id = PeerId::random();
+        let mut peer_manager = initialize_peer_manager(vec![], 1);
+        peer_manager.handle_initial_connection(&peer_id);
+
+        // the first response sets the average
+        peer_manager.handle_response_time(&peer_id, Duration::from_millis(100));
+        let peer_info = peer_manager.get_peer_info(&peer_id).unwrap();
+        assert_eq!(
+            peer_info.average_response_time,
+            Some(Duration::from_millis(100))
+        );
+
+        // a single slow response moves the average only by a fifth of the difference
+        peer_manager.handle_response_time(&peer_id, Duration::from_millis(600));
+        let peer_info = peer_manager.get_peer_info(&peer_id).unwrap();
+        assert_eq!(
+            peer_info.last_response_time,
+            Some(Duration::from_millis(600))
+        );
+        assert_eq!(
+            peer_info.average_response_time,
+            Some(Duration::from_millis(200))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reserved_peer_redials_are_delayed_exponentially() {
+        let reserved_peer = PeerId::random();
+        let mut peer_manager = initialize_peer_manager(vec![reserved_peer], 0);
+        peer_manager.handle_initial_connection(&reserved_peer);
+
+        // the peer is redialed immediately after the disconnect
+        assert!(peer_manager.handle_peer_disconnect(reserved_peer));
+        assert!(peer_manager.reserved_peers_to_redial().is_empty());
+
+        // the following redials are delayed by 1, 2 and 4 seconds
+        for delay in [1, 2, 4] {
+            tokio::time::advance(Duration::from_secs(delay.saturating_sub(1))).await;
+            assert!(peer_manager.reserved_peers_to_redial().is_empty());
+            tokio::time::advance(Duration::from_secs(1)).await;
+            assert_eq!(peer_manager.reserved_peers_to_redial(), vec![reserved_peer]);
+        }
+
+        // the backoff is reset once the peer is connected again
+        peer_manager.handle_initial_connection(&reserved_peer);
+        assert!(peer_manager.handle_peer_disconnect(reserved_peer));
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert_eq!(peer_manager.reserved_peers_to_redial(), vec![reserved_peer]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn bootstrap_peer_is_redialed_until_connected() {
+        let bootstrap_peer = PeerId::random();
+        let connection_state = ConnectionState::new();
+        let (sender, _) = tokio::sync::broadcast::channel(1);
+        let mut peer_manager = PeerManager::new(
+            sender,
+            HashSet::new(),
+            connection_state,
+            1,
+            PeerSelectionStrategy::default(),
+            [bootstrap_peer].into_iter().collect(),
+            RedialConfig::default(),
+        );
+
+        // the first dial fails, the redial is delayed
+        assert_eq!(
+            peer_manager.bootstrap_peers_to_redial(),
+            vec![bootstrap_peer]
+        );
+        peer_manager.handle_dial_failure(&bootstrap_peer);
+        assert!(peer_manager.bootstrap_peers_to_redial().is_empty());
+        tokio::time::advance(MIN_REDIAL_BACKOFF).await;
+        assert_eq!(
+            peer_manager.bootstrap_peers_to_redial(),
+            vec![bootstrap_peer]
+        );
+
+        // the connected peer isn't redialed
+        peer_manager.handle_initial_connection(&bootstrap_peer);
+        tokio::time::advance(MAX_REDIAL_BACKOFF).await;
+        assert!(peer_manager.bootstrap_peers_to_redial().is_empty());
+
+        // the redials resume once the connection is lost
+        assert!(!peer_manager.handle_peer_disconnect(bootstrap_peer));
+        assert_eq!(
+            peer_manager.bootstrap_peers_to_redial(),
+            vec![bootstrap_peer]
+        );
+    }
+
+    #[test]
+    fn peer_with_low_reputation_is_evicted_in_favor_of_new_peer() {
+        let max_non_reserved_peers = 3;
+        let reserved_peer = PeerId::random();
+        let mut peer_manager =
+            initialize_peer_manager(vec![reserved_peer], max_non_reserved_peers);
+        peer_manager.handle_initial_connection(&reserved_peer);
+
+        let peers = get_random_peers(max_non_reserved_peers);
+        for peer_id in &peers {
+            assert_eq!(peer_manager.handle_initial_connection(peer_id), None);
+        }
+
+        // all peers have a good reputation, so the new peer is refused
+        let new_peer = PeerId::random();
+        assert_eq!(
+            peer_manager.handle_initial_connection(&new_peer),
+            Some(new_peer)
+        );
+
+        // the peer with the lowest reputation is replaced
+        let bad_peer = peers[1];
+        for (peer_id, score) in [(peers[0], -1.), (bad_peer, -10.)] {
+            peer_manager
+                .non_reserved_connected_peers
+                .get_mut(&peer_id)
+                .unwrap()
+                .score = DEFAULT_APP_SCORE + score;
+        }
+        assert_eq!(
+            peer_manager.handle_initial_connection(&new_peer),
+            Some(bad_peer)
+        );
+        assert!(peer_manager.get_peer_info(&new_peer).is_some());
+        assert!(peer_manager.get_peer_info(&bad_peer).is_none());
+        assert_eq!(
+            peer_manager.peer_counts(),
+            PeerCounts {
+                reserved_peers: 1,
+                non_reserved_peers: max_non_reserved_peers,
+                max_non_reserved_peers,
+            }
+        );
+    }
+
+    #[test]
+    fn round_robin_selection_cycles_through_connected_peers() {
+        let mut peer_manager = initialize_peer_manager(vec![], 5);
+        peer_manager.peer_selection_strategy = PeerSelectionStrategy::RoundRobin;
+
+        let peers = get_random_peers(3);
+        for peer_id in &peers {
+            peer_manager.handle_initial_connection(peer_id);
+            peer_manager.handle_peer_info_updated(peer_id, BlockHeight::from(10));
+        }
+
+        let height = BlockHeight::from(5);
+        let selected_peers = (0..peers.len().saturating_mul(2))
+            .map(|_| peer_manager.get_peer_id_with_height(&height).unwrap())
+            .collect::<Vec<_>>();
+
+        // every peer is selected once before the cycle repeats
+        let (first_cycle, second_cycle) = selected_peers.split_at(peers.len());
+        assert_eq!(
+            first_cycle.iter().collect::<HashSet<_>>(),
+            peers.iter().collect::<HashSet<_>>()
+        );
+        assert_eq!(first_cycle, second_cycle);
+    }
+
+    #[test]
+    fn connected_peer_takes_reserved_slot_after_being_added_to_reserved_peers() {
+        let max_non_reserved_peers = 1;
+        let mut peer_manager = initialize_peer_manager(vec![], max_non_reserved_peers);
+        let peer_id = PeerId::random();
+        peer_manager.handle_initial_connection(&peer_id);
+        assert!(!peer_manager
+            .connection_state
+            .read()
+            .unwrap()
+            .available_slot());
+
+        // when
+        peer_manager.add_reserved_peer(peer_id);
+
+        // then
+        assert!(peer_manager.is_reserved(&peer_id));
+        assert!(peer_manager
+            .connection_state
+            .read()
+            .unwrap()
+            .available_slot());
+        let other_peer = PeerId::random();
+        assert_eq!(peer_manager.handle_initial_connection(&other_peer), None);
+
+        // the slot of the removed reserved peer is already taken
+        assert!(peer_manager.remove_reserved_peer(&peer_id));
+        assert!(!peer_manager.is_reserved(&peer_id));
+        assert!(peer_manager.get_peer_info(&peer_id).is_none());
+    }
 }
@@ -54,13 +54,18 @@ impl HeartbeatData {
         self.durations.push_front(new_duration);
     }
 
-    pub fn update(&mut self, block_height: BlockHeight) {
-        self.block_height = Some(block_height);
+    /// Records the heartbeat of the peer.
+    /// Stale heartbeats don't lower the known block height of the peer.
+    /// Returns `false` if the heartbeat is stale.
+    pub fn update(&mut self, block_height: BlockHeight) -> bool {
+        let is_stale = self.block_height > Some(block_height);
+        self.block_height = self.block_height.max(Some(block_height));
         let old_hearbeat = self.last_heartbeat;
         self.last_heartbeat = Instant::now();
         self.last_heartbeat_sys = SystemTime::now();
         let new_duration = self.last_heartbeat.saturating_duration_since(old_hearbeat);
         self.add_new_duration(new_duration);
+        !is_stale
     }
 }
 
@@ -99,4 +104,20 @@ mod tests {
             assert_eq!(actual, expected);
         }
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn update__ignores_stale_block_height() {
+        let mut heartbeat_data = HeartbeatData::new(10);
+        assert!(heartbeat_data.update(10.into()));
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert!(!heartbeat_data.update(5.into()));
+
+        assert_eq!(heartbeat_data.block_height, Some(10.into()));
+        // the stale heartbeat is still a sign of the peer being alive
+        assert_eq!(
+            heartbeat_data.duration_since_last_heartbeat(),
+            Duration::from_secs(0)
+        );
+    }
 }
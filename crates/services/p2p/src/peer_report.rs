@@ -1,4 +1,7 @@
-use crate::config::Config;
+use crate::{
+    config::Config,
+    peer_manager::ConnectionDirection,
+};
 use libp2p::{
     self,
     core::Endpoint,
@@ -34,8 +37,10 @@ use tokio::time::{
     Interval,
 };
 
-const HEALTH_CHECK_INTERVAL_IN_SECONDS: u64 = 10;
+// The redials of reserved nodes are additionally limited by the exponential backoff
+const HEALTH_CHECK_INTERVAL_IN_SECONDS: u64 = 1;
 const REPUTATION_DECAY_INTERVAL_IN_SECONDS: u64 = 1;
+const IDLE_PEERS_CHECK_INTERVAL_IN_SECONDS: u64 = 1;
 
 /// Events emitted by PeerReportBehavior
 #[derive(Debug, Clone)]
@@ -43,6 +48,7 @@ pub enum PeerReportEvent {
     PeerConnected {
         peer_id: PeerId,
         initial_connection: bool,
+        direction: ConnectionDirection,
     },
     PeerDisconnected {
         peer_id: PeerId,
@@ -51,6 +57,8 @@ pub enum PeerReportEvent {
     CheckReservedNodesHealth,
     /// Informs p2p service / PeerManager to perform reputation decay of connected nodes
     PerformDecay,
+    /// Informs p2p service to disconnect the idle non-reserved peers
+    CheckIdlePeers,
 }
 
 // `Behaviour` that reports events about peers
@@ -59,10 +67,12 @@ pub struct Behaviour {
     // regulary checks if reserved nodes are connected
     health_check: Interval,
     decay_interval: Interval,
+    // checks for the idle peers if `idle_connection_timeout` is set
+    idle_peers_check: Option<Interval>,
 }
 
 impl Behaviour {
-    pub(crate) fn new(_config: &Config) -> Self {
+    pub(crate) fn new(config: &Config) -> Self {
         Self {
             pending_events: VecDeque::default(),
             health_check: time::interval(Duration::from_secs(
@@ -71,6 +81,9 @@ impl Behaviour {
             decay_interval: time::interval(Duration::from_secs(
                 REPUTATION_DECAY_INTERVAL_IN_SECONDS,
             )),
+            idle_peers_check: config.idle_connection_timeout.map(|_| {
+                time::interval(Duration::from_secs(IDLE_PEERS_CHECK_INTERVAL_IN_SECONDS))
+            }),
         }
     }
 }
@@ -104,13 +117,20 @@ impl NetworkBehaviour for Behaviour {
             FromSwarm::ConnectionEstablished(connection_established) => {
                 let ConnectionEstablished {
                     peer_id,
+                    endpoint,
                     other_established,
                     ..
                 } = connection_established;
+                let direction = if endpoint.is_dialer() {
+                    ConnectionDirection::Outbound
+                } else {
+                    ConnectionDirection::Inbound
+                };
                 self.pending_events
                     .push_back(PeerReportEvent::PeerConnected {
                         peer_id,
                         initial_connection: other_established == 0,
+                        direction,
                     });
             }
             FromSwarm::ConnectionClosed(connection_closed) => {
@@ -156,6 +176,14 @@ impl NetworkBehaviour for Behaviour {
             ))
         }
 
+        if let Some(idle_peers_check) = &mut self.idle_peers_check {
+            if idle_peers_check.poll_tick(cx).is_ready() {
+                return Poll::Ready(ToSwarm::GenerateEvent(
+                    PeerReportEvent::CheckIdlePeers,
+                ))
+            }
+        }
+
         Poll::Pending
     }
 }
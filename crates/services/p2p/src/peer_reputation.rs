@@ -0,0 +1,212 @@
+use fuel_core_types::services::p2p::{
+    peer_reputation::AppScore,
+    GossipsubMessageAcceptance,
+};
+use libp2p::PeerId;
+use std::{
+    collections::HashMap,
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// Configuration of the application-level gossip reputation of peers
+#[derive(Debug, Clone)]
+pub struct PeerReputationConfig {
+    /// Score change for a gossiped message that was accepted
+    pub accept_reward: AppScore,
+    /// Score change for a gossiped message that was ignored
+    pub ignore_penalty: AppScore,
+    /// Score change for a gossiped message that was rejected
+    pub reject_penalty: AppScore,
+    /// The maximum score a peer can accumulate
+    pub max_score: AppScore,
+    /// Peers with a score below this threshold are disconnected and banned
+    pub ban_threshold: AppScore,
+    /// For how long the peer stays banned
+    pub ban_duration: Duration,
+    /// Each `decay_interval` scores are multiplied by this factor
+    pub decay_factor: AppScore,
+    /// The interval between score decays
+    pub decay_interval: Duration,
+}
+
+impl Default for PeerReputationConfig {
+    fn default() -> Self {
+        Self {
+            accept_reward: 0.1,
+            ignore_penalty: -1.0,
+            reject_penalty: -10.0,
+            max_score: 100.0,
+            ban_threshold: -50.0,
+            ban_duration: Duration::from_secs(60 * 10),
+            decay_factor: 0.99,
+            decay_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Accumulates the score of each peer based on the validation
+/// results of the gossiped messages they propagated.
+#[derive(Debug)]
+pub struct PeerReputation {
+    config: PeerReputationConfig,
+    scores: HashMap<PeerId, AppScore>,
+    /// Banned peers with the expiration time of the ban
+    banned_peers: HashMap<PeerId, Instant>,
+    next_decay_time: Instant,
+}
+
+impl PeerReputation {
+    pub fn new(config: PeerReputationConfig) -> Self {
+        let next_decay_time = next_time(Instant::now(), config.decay_interval);
+        Self {
+            config,
+            scores: HashMap::new(),
+            banned_peers: HashMap::new(),
+            next_decay_time,
+        }
+    }
+
+    /// Updates the score of the peer based on the `acceptance`.
+    /// Returns `true` signaling that the peer should be disconnected and banned.
+    pub fn report(
+        &mut self,
+        peer_id: PeerId,
+        acceptance: GossipsubMessageAcceptance,
+    ) -> bool {
+        if self.is_banned(&peer_id) {
+            // The peer is already banned
+            return false
+        }
+
+        let change = match acceptance {
+            GossipsubMessageAcceptance::Accept => self.config.accept_reward,
+            GossipsubMessageAcceptance::Ignore => self.config.ignore_penalty,
+            GossipsubMessageAcceptance::Reject => self.config.reject_penalty,
+        };
+
+        let score = self.scores.entry(peer_id).or_default();
+        *score = self.config.max_score.min(*score + change);
+
+        if *score < self.config.ban_threshold {
+            self.ban(peer_id, self.config.ban_duration);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Bans the peer for the `duration`, forgetting its score
+    pub fn ban(&mut self, peer_id: PeerId, duration: Duration) {
+        self.scores.remove(&peer_id);
+        let ban_expiration = next_time(Instant::now(), duration);
+        self.banned_peers.insert(peer_id, ban_expiration);
+    }
+
+    /// The ban is considered lifted as soon as it expires,
+    /// even if the `decay` hasn't been called yet.
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.banned_peers
+            .get(peer_id)
+            .is_some_and(|expiration| *expiration > Instant::now())
+    }
+
+    /// For how long the peer stays banned after crossing the threshold
+    pub fn ban_duration(&self) -> Duration {
+        self.config.ban_duration
+    }
+
+    /// The time when the next call of the `decay` is expected
+    pub fn next_decay_time(&self) -> Instant {
+        self.next_decay_time
+    }
+
+    /// Moves scores towards zero and lifts expired bans.
+    /// Returns peers whose ban has expired.
+    pub fn decay(&mut self) -> Vec<PeerId> {
+        let now = Instant::now();
+        self.next_decay_time = next_time(now, self.config.decay_interval);
+
+        let decay_factor = self.config.decay_factor;
+        self.scores.retain(|_, score| {
+            *score *= decay_factor;
+            // Scores close to zero are not worth tracking
+            score.abs() >= f64::EPSILON
+        });
+
+        let expired: Vec<_> = self
+            .banned_peers
+            .iter()
+            .filter(|(_, expiration)| **expiration <= now)
+            .map(|(peer_id, _)| *peer_id)
+            .collect();
+        for peer_id in &expired {
+            self.banned_peers.remove(peer_id);
+        }
+        expired
+    }
+
+    /// Returns the current scores of all tracked peers
+    pub fn scores(&self) -> Vec<(PeerId, AppScore)> {
+        self.scores
+            .iter()
+            .map(|(peer_id, score)| (*peer_id, *score))
+            .collect()
+    }
+}
+
+fn next_time(now: Instant, duration: Duration) -> Instant {
+    now.checked_add(duration)
+        .expect("The interval should be small enough to be added to the current time")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejected_messages_ban_the_peer_exactly_once() {
+        let mut reputation = PeerReputation::new(PeerReputationConfig::default());
+        let peer_id = PeerId::random();
+
+        // Much more reports than required to cross the threshold
+        let bans = (0..100)
+            .filter(|_| reputation.report(peer_id, GossipsubMessageAcceptance::Reject))
+            .count();
+
+        assert_eq!(bans, 1);
+        assert!(reputation.is_banned(&peer_id));
+    }
+
+    #[test]
+    fn accepted_messages_do_not_exceed_max_score() {
+        let config = PeerReputationConfig::default();
+        let max_score = config.max_score;
+        let mut reputation = PeerReputation::new(config);
+        let peer_id = PeerId::random();
+
+        for _ in 0..10_000 {
+            reputation.report(peer_id, GossipsubMessageAcceptance::Accept);
+        }
+
+        assert_eq!(reputation.scores(), vec![(peer_id, max_score)]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ban_expires_after_ban_duration() {
+        let config = PeerReputationConfig {
+            ban_duration: Duration::from_secs(10),
+            ..Default::default()
+        };
+        let mut reputation = PeerReputation::new(config);
+        let peer_id = PeerId::random();
+        while !reputation.report(peer_id, GossipsubMessageAcceptance::Reject) {}
+
+        tokio::time::advance(Duration::from_secs(5)).await;
+        assert!(reputation.decay().is_empty());
+
+        tokio::time::advance(Duration::from_secs(5)).await;
+        assert_eq!(reputation.decay(), vec![peer_id]);
+        assert!(!reputation.is_banned(&peer_id));
+    }
+}
@@ -3,10 +3,16 @@ use fuel_core_storage::Result as StorageResult;
 use fuel_core_types::{
     blockchain::{
         consensus::Genesis,
+        primitives::BlockId,
         SealedBlockHeader,
     },
     fuel_types::BlockHeight,
-    services::p2p::Transactions,
+    services::p2p::{
+        KnownPeer,
+        PeerBan,
+        PeerId,
+        Transactions,
+    },
 };
 use std::ops::Range;
 
@@ -22,6 +28,40 @@ pub trait P2pDb: Send + Sync {
     ) -> StorageResult<Option<Vec<Transactions>>>;
 
     fn get_genesis(&self) -> StorageResult<Genesis>;
+
+    /// Returns the height and the id of the latest block,
+    /// or `None` if the database has no blocks yet
+    fn get_current_tip(&self) -> StorageResult<Option<(BlockHeight, BlockId)>>;
+
+    /// Returns `true` if the block at the `height` is stored locally,
+    /// so it doesn't need to be requested from the peers
+    fn has_block(&self, height: &BlockHeight) -> StorageResult<bool>;
+}
+
+/// The state of the peers that outlives the restart of the node.
+/// Unlike the [`P2pDb`] views, it is written by the service.
+pub trait P2pPeerStore: Send + 'static {
+    /// Persists the ban of the peer, so it survives the restart of the node
+    fn insert_peer_ban(&mut self, peer_id: &PeerId, ban: &PeerBan) -> StorageResult<()>;
+
+    /// Forgets the ban of the peer, e.g. once it has expired
+    fn remove_peer_ban(&mut self, peer_id: &PeerId) -> StorageResult<()>;
+
+    /// Returns all persisted bans, including expired ones
+    fn load_peer_bans(&self) -> StorageResult<Vec<(PeerId, PeerBan)>>;
+
+    /// Remembers the peer, replacing its previous entry
+    fn insert_known_peer(
+        &mut self,
+        peer_id: &PeerId,
+        peer: &KnownPeer,
+    ) -> StorageResult<()>;
+
+    /// Forgets the peer, so it isn't dialed after the restart
+    fn remove_known_peer(&mut self, peer_id: &PeerId) -> StorageResult<()>;
+
+    /// Returns all remembered peers, including the ones not seen for a long time
+    fn load_known_peers(&self) -> StorageResult<Vec<(PeerId, KnownPeer)>>;
 }
 
 pub trait BlockHeightImporter: Send + Sync {
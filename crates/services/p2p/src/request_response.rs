@@ -1 +1,4 @@
+pub mod lookup_pool;
 pub mod messages;
+pub mod rate_limiter;
+pub mod response_cache;
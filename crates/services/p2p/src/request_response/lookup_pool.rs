@@ -0,0 +1,228 @@
+use fuel_core_metrics::p2p_metrics::p2p_metrics;
+use std::sync::{
+    atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+    Arc,
+};
+use tokio::sync::{
+    mpsc,
+    Semaphore,
+};
+
+/// Runs the blocking database lookups for the inbound requests in the background.
+/// Only `max_concurrent` lookups run at once, and up to `max_queued` lookups wait for their turn.
+/// The results are delivered through the bounded channel, see [`LookupPool::next_result`].
+#[derive(Debug)]
+pub struct LookupPool<T> {
+    semaphore: Arc<Semaphore>,
+    /// The number of spawned lookups whose results aren't taken yet
+    pending: Arc<AtomicUsize>,
+    max_pending: usize,
+    sender: mpsc::Sender<T>,
+    receiver: mpsc::Receiver<T>,
+    metrics: bool,
+}
+
+impl<T> LookupPool<T>
+where
+    T: Send + 'static,
+{
+    pub fn new(
+        max_concurrent: usize,
+        max_queued: usize,
+        results_capacity: usize,
+        metrics: bool,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel(results_capacity);
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            pending: Arc::new(AtomicUsize::new(0)),
+            max_pending: max_concurrent.saturating_add(max_queued),
+            sender,
+            receiver,
+            metrics,
+        }
+    }
+
+    /// Spawns the lookup. Returns `false` if the pool is full and the lookup is rejected.
+    /// The `failed` result is delivered instead if the lookup panics.
+    pub fn try_spawn<F>(&self, lookup: F, failed: T) -> bool
+    where
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let max_pending = self.max_pending;
+        let reserved = self
+            .pending
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |pending| {
+                (pending < max_pending).then(|| pending.saturating_add(1))
+            })
+            .is_ok();
+        if !reserved {
+            return false
+        }
+
+        let semaphore = self.semaphore.clone();
+        let pending = self.pending.clone();
+        let sender = self.sender.clone();
+        let metrics = self.metrics;
+        if metrics {
+            p2p_metrics().db_lookups_queued.inc();
+        }
+
+        tokio::spawn(async move {
+            let permit = semaphore
+                .acquire_owned()
+                .await
+                .expect("The semaphore is never closed");
+            if metrics {
+                p2p_metrics().db_lookups_queued.dec();
+                p2p_metrics().db_lookups_in_flight.inc();
+            }
+
+            let result = tokio::task::spawn_blocking(lookup).await;
+
+            drop(permit);
+            if metrics {
+                p2p_metrics().db_lookups_in_flight.dec();
+            }
+
+            // The lookup is pending until its result is taken from the channel,
+            // so the slow consumer of the results limits the number of the spawned tasks
+            let result = match result {
+                Ok(result) => result,
+                Err(err) => {
+                    tracing::error!(target: "fuel-p2p", "The database lookup failed: {err}");
+                    failed
+                }
+            };
+            let delivered = sender.send(result).await.is_ok();
+            if !delivered {
+                pending.fetch_sub(1, Ordering::SeqCst);
+            }
+        });
+
+        true
+    }
+
+    /// Returns the result of the next finished lookup.
+    /// It is cancel safe, so it can be used in `tokio::select!`.
+    pub async fn next_result(&mut self) -> Option<T> {
+        let result = self.receiver.recv().await;
+        if result.is_some() {
+            self.pending.fetch_sub(1, Ordering::SeqCst);
+        }
+        result
+    }
+
+    /// Returns `true` if some lookups are still running or their results aren't taken yet
+    pub fn has_pending(&self) -> bool {
+        self.pending.load(Ordering::SeqCst) > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn number_of_concurrent_lookups_is_bounded() {
+        let max_concurrent = 4;
+        let lookups = 1000;
+        let mut pool = LookupPool::new(max_concurrent, lookups, lookups, false);
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+
+        for _ in 0..lookups {
+            let running = running.clone();
+            let max_running = max_running.clone();
+            let spawned = pool.try_spawn(
+                move || {
+                    let now_running =
+                        running.fetch_add(1, Ordering::SeqCst).saturating_add(1);
+                    max_running.fetch_max(now_running, Ordering::SeqCst);
+                    // The artificial delay of the database
+                    std::thread::sleep(Duration::from_millis(1));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                },
+                (),
+            );
+            assert!(spawned);
+        }
+
+        for _ in 0..lookups {
+            pool.next_result().await.unwrap();
+        }
+        assert!(max_running.load(Ordering::SeqCst) <= max_concurrent);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn second_lookup_waits_for_the_first_one_with_single_permit() {
+        let (unblock_sender, unblock_receiver) = std::sync::mpsc::channel::<()>();
+        let second_started = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let mut pool = LookupPool::new(1, 1, 2, false);
+
+        assert!(pool.try_spawn(
+            move || {
+                let _ = unblock_receiver.recv();
+                "first"
+            },
+            "failed"
+        ));
+        let started = second_started.clone();
+        assert!(pool.try_spawn(
+            move || {
+                started.store(true, Ordering::SeqCst);
+                "second"
+            },
+            "failed"
+        ));
+
+        // The first lookup holds the only permit
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!second_started.load(Ordering::SeqCst));
+
+        unblock_sender.send(()).unwrap();
+        assert_eq!(pool.next_result().await, Some("first"));
+        assert_eq!(pool.next_result().await, Some("second"));
+        assert!(second_started.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn lookups_above_the_queue_limit_are_rejected() {
+        let (unblock_sender, unblock_receiver) = std::sync::mpsc::channel::<()>();
+        let unblock_receiver = Arc::new(std::sync::Mutex::new(unblock_receiver));
+        let mut pool = LookupPool::new(1, 1, 2, false);
+
+        let blocked_lookup = || {
+            let unblock_receiver = unblock_receiver.clone();
+            move || {
+                let _ = unblock_receiver.lock().unwrap().recv();
+            }
+        };
+        assert!(pool.try_spawn(blocked_lookup(), ()));
+        assert!(pool.try_spawn(blocked_lookup(), ()));
+        assert!(!pool.try_spawn(blocked_lookup(), ()));
+
+        unblock_sender.send(()).unwrap();
+        unblock_sender.send(()).unwrap();
+        pool.next_result().await.unwrap();
+        pool.next_result().await.unwrap();
+
+        // The pool has room again
+        assert!(pool.try_spawn(|| (), ()));
+        pool.next_result().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn panicked_lookup_delivers_the_failed_result() {
+        let mut pool = LookupPool::new(1, 1, 1, false);
+
+        assert!(pool.try_spawn(|| panic!("The database is broken"), "failed"));
+
+        assert_eq!(pool.next_result().await, Some("failed"));
+        assert!(!pool.has_pending());
+    }
+}
@@ -1,5 +1,9 @@
 use fuel_core_types::{
-    blockchain::SealedBlockHeader,
+    blockchain::{
+        primitives::BlockId,
+        SealedBlockHeader,
+    },
+    fuel_types::BlockHeight,
     services::p2p::Transactions,
 };
 use libp2p::{
@@ -15,6 +19,13 @@ use thiserror::Error;
 use tokio::sync::oneshot;
 
 pub(crate) const REQUEST_RESPONSE_PROTOCOL_ID: &str = "/fuel/req_res/0.0.1";
+pub(crate) const REQUEST_RESPONSE_PROTOCOL_ID_V2: &str = "/fuel/req_res/0.0.2";
+pub(crate) const REQUEST_RESPONSE_PROTOCOL_ID_V3: &str = "/fuel/req_res/0.0.3";
+pub(crate) const REQUEST_RESPONSE_PROTOCOL_ID_V4: &str = "/fuel/req_res/0.0.4";
+
+/// The upper bound of the bytes the [`ResponseMessage::BlockChunk`] adds to the data
+/// of the chunk, including the versioned envelope
+pub(crate) const BLOCK_CHUNK_FRAMING_SIZE: usize = 64;
 
 /// Max Size in Bytes of the Request Message
 #[cfg(test)]
@@ -24,12 +35,89 @@ pub(crate) const MAX_REQUEST_SIZE: usize = core::mem::size_of::<RequestMessage>(
 pub enum RequestMessage {
     SealedHeaders(Range<u32>),
     Transactions(Range<u32>),
+    /// The height and the id of the latest block of the peer
+    Tip,
+    /// The `chunk_index`-th chunk of the block at the `block_height`,
+    /// for the blocks too large for one response
+    BlockChunk {
+        block_height: u32,
+        chunk_index: u32,
+    },
+}
+
+impl RequestMessage {
+    /// The kind of the request, used in the logs
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RequestMessage::SealedHeaders(_) => "sealed_headers",
+            RequestMessage::Transactions(_) => "transactions",
+            RequestMessage::Tip => "tip",
+            RequestMessage::BlockChunk { .. } => "block_chunk",
+        }
+    }
+
+    /// The range of the requested blocks, if the request is for the block data
+    pub fn block_height_range(&self) -> Option<Range<u32>> {
+        match self {
+            RequestMessage::SealedHeaders(range)
+            | RequestMessage::Transactions(range) => Some(range.clone()),
+            RequestMessage::Tip => None,
+            RequestMessage::BlockChunk { block_height, .. } => {
+                Some(*block_height..block_height.saturating_add(1))
+            }
+        }
+    }
+
+    /// Returns `true` if the request asks for the block data
+    pub fn is_block_request(&self) -> bool {
+        matches!(
+            self,
+            RequestMessage::SealedHeaders(_)
+                | RequestMessage::Transactions(_)
+                | RequestMessage::BlockChunk { .. }
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ResponseMessage {
     SealedHeaders(Option<Vec<SealedBlockHeader>>),
     Transactions(Option<Vec<Transactions>>),
+    Tip(Option<(BlockHeight, BlockId)>),
+    BlockChunk(BlockChunk),
+    /// The responder was unable to serve the request
+    Error(ResponseMessageError),
+}
+
+/// The part of the encoded [`ChunkedBlock`].
+/// All chunks of the block are of the same size, except the last one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockChunk {
+    /// The id of the block the chunk belongs to
+    pub block_id: BlockId,
+    /// The number of the chunks the block is split into
+    pub chunk_count: u32,
+    pub data: Vec<u8>,
+}
+
+/// The block transferred in chunks, because it doesn't fit into one response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkedBlock {
+    pub header: SealedBlockHeader,
+    pub transactions: Transactions,
+}
+
+/// Errors that the responding peer sends back over the wire
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Error)]
+pub enum ResponseMessageError {
+    #[error("The requested data is not available on the peer")]
+    NotFound,
+    #[error("The requester has exceeded the allowed rate of requests")]
+    TooManyRequests,
+    /// The response of the `size` bytes exceeds the max response size,
+    /// so the requester should ask for a smaller range
+    #[error("The response of {size} bytes exceeds the max response size")]
+    TooLarge { size: u64 },
 }
 
 pub type OnResponse<T> = oneshot::Sender<(PeerId, Result<T, ResponseError>)>;
@@ -38,12 +126,40 @@ pub type OnResponse<T> = oneshot::Sender<(PeerId, Result<T, ResponseError>)>;
 pub enum ResponseSender {
     SealedHeaders(OnResponse<Option<Vec<SealedBlockHeader>>>),
     Transactions(OnResponse<Option<Vec<Transactions>>>),
+    Tip(OnResponse<Option<(BlockHeight, BlockId)>>),
+    BlockChunk(OnResponse<BlockChunk>),
+}
+
+impl ResponseSender {
+    /// Answers the request with the `error` on behalf of the `peer`
+    pub fn send_error(self, peer: PeerId, error: ResponseError) {
+        match self {
+            ResponseSender::SealedHeaders(c) => {
+                let _ = c.send((peer, Err(error)));
+            }
+            ResponseSender::Transactions(c) => {
+                let _ = c.send((peer, Err(error)));
+            }
+            ResponseSender::Tip(c) => {
+                let _ = c.send((peer, Err(error)));
+            }
+            ResponseSender::BlockChunk(c) => {
+                let _ = c.send((peer, Err(error)));
+            }
+        }
+    }
 }
 
 #[derive(Debug, Error)]
 pub enum RequestError {
     #[error("Not currently connected to any peers")]
     NoPeersConnected,
+    #[error("Too many outbound requests are awaiting the response")]
+    TooManyInflight,
+    #[error("The requested peer {0} is not connected")]
+    PeerNotConnected(PeerId),
+    #[error("The peer {0} doesn't support the request")]
+    UnsupportedRequest(PeerId),
 }
 
 #[derive(Debug, Error)]
@@ -54,6 +170,38 @@ pub enum ResponseError {
     /// The peer responded with an invalid response type
     #[error("Peer response message was of incorrect type")]
     TypeMismatch,
+    /// The peer doesn't have the requested data
+    #[error("Peer doesn't have the requested data")]
+    NotFound,
+    /// The peer refused to serve the request due to the rate limit
+    #[error("Peer has rate limited the request")]
+    TooManyRequests,
+    /// The response of the `size` bytes is too large for the peer to send
+    #[error("Peer's response of {size} bytes exceeds the max response size")]
+    TooLarge { size: u64 },
+    /// The request wasn't sent, because too many requests are awaiting the response
+    #[error("Too many outbound requests are awaiting the response")]
+    TooManyInflight,
+    /// The request wasn't sent, because the requested peer isn't connected
+    #[error("The requested peer is not connected")]
+    PeerNotConnected,
+    /// The request wasn't sent, because no connected peer could serve it in time
+    #[error("No connected peer could serve the request")]
+    NoPeers,
+    /// The request wasn't sent, because the peer speaks an older version
+    /// of the protocol that doesn't have this request
+    #[error("The peer doesn't support the request")]
+    UnsupportedRequest,
+}
+
+impl From<ResponseMessageError> for ResponseError {
+    fn from(error: ResponseMessageError) -> Self {
+        match error {
+            ResponseMessageError::NotFound => ResponseError::NotFound,
+            ResponseMessageError::TooManyRequests => ResponseError::TooManyRequests,
+            ResponseMessageError::TooLarge { size } => ResponseError::TooLarge { size },
+        }
+    }
 }
 
 /// Errors than can occur when attempting to send a response
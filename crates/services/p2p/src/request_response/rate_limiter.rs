@@ -0,0 +1,172 @@
+use libp2p::PeerId;
+use std::{
+    collections::HashMap,
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// Limits the number of inbound requests per peer with the token bucket algorithm.
+/// Each peer can send up to `burst` requests at once,
+/// and the budget is refilled with `rate` requests per second.
+#[derive(Debug)]
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    buckets: HashMap<PeerId, Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(rate: u32, burst: u32) -> Self {
+        Self {
+            rate: f64::from(rate),
+            burst: f64::from(burst),
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Consumes a token of the peer.
+    /// Returns `false` if the peer has exceeded its budget.
+    pub fn try_acquire(&mut self, peer_id: PeerId) -> bool {
+        let now = Instant::now();
+        let bucket = self.buckets.entry(peer_id).or_insert(Bucket {
+            tokens: self.burst,
+            last_refill: now,
+        });
+
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = self.burst.min(bucket.tokens + elapsed * self.rate);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Forgets the budget of the disconnected peer
+    pub fn remove_peer(&mut self, peer_id: &PeerId) {
+        self.buckets.remove(peer_id);
+    }
+}
+
+/// Limits the number of the response bytes sent to each peer within a fixed window.
+/// Once the peer exceeds the limit, its requests are rejected until the window ends.
+#[derive(Debug)]
+pub struct ResponseBytesLimiter {
+    max_bytes: u64,
+    window: Duration,
+    windows: HashMap<PeerId, ResponseWindow>,
+}
+
+#[derive(Debug)]
+struct ResponseWindow {
+    started_at: Instant,
+    bytes: u64,
+}
+
+impl ResponseBytesLimiter {
+    pub fn new(max_bytes: u64, window: Duration) -> Self {
+        Self {
+            max_bytes,
+            window,
+            windows: HashMap::new(),
+        }
+    }
+
+    /// Returns `true` if the peer has exceeded the limit in the current window
+    pub fn is_exhausted(&mut self, peer_id: &PeerId) -> bool {
+        let Some(peer_window) = self.windows.get(peer_id) else {
+            return false
+        };
+        if peer_window.started_at.elapsed() >= self.window {
+            self.windows.remove(peer_id);
+            return false
+        }
+        peer_window.bytes >= self.max_bytes
+    }
+
+    /// Accounts the bytes of the response sent to the peer
+    pub fn record(&mut self, peer_id: PeerId, bytes: u64) {
+        let now = Instant::now();
+        let peer_window = self.windows.entry(peer_id).or_insert(ResponseWindow {
+            started_at: now,
+            bytes: 0,
+        });
+        if now.duration_since(peer_window.started_at) >= self.window {
+            peer_window.started_at = now;
+            peer_window.bytes = 0;
+        }
+        peer_window.bytes = peer_window.bytes.saturating_add(bytes);
+    }
+
+    /// Forgets the window of the disconnected peer
+    pub fn remove_peer(&mut self, peer_id: &PeerId) {
+        self.windows.remove(peer_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn requests_above_burst_are_rejected_until_refill() {
+        let mut rate_limiter = RateLimiter::new(1, 3);
+        let peer_id = PeerId::random();
+
+        let accepted = (0..10)
+            .filter(|_| rate_limiter.try_acquire(peer_id))
+            .count();
+        assert_eq!(accepted, 3);
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert!(rate_limiter.try_acquire(peer_id));
+        assert!(!rate_limiter.try_acquire(peer_id));
+    }
+
+    #[test]
+    fn peers_have_independent_budgets() {
+        let mut rate_limiter = RateLimiter::new(1, 1);
+        let peer_a = PeerId::random();
+        let peer_b = PeerId::random();
+
+        assert!(rate_limiter.try_acquire(peer_a));
+        assert!(!rate_limiter.try_acquire(peer_a));
+        assert!(rate_limiter.try_acquire(peer_b));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn response_bytes_above_limit_are_rejected_until_window_ends() {
+        let mut limiter = ResponseBytesLimiter::new(100, Duration::from_secs(60));
+        let peer_id = PeerId::random();
+
+        limiter.record(peer_id, 60);
+        assert!(!limiter.is_exhausted(&peer_id));
+        limiter.record(peer_id, 60);
+        assert!(limiter.is_exhausted(&peer_id));
+
+        tokio::time::advance(Duration::from_secs(59)).await;
+        assert!(limiter.is_exhausted(&peer_id));
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert!(!limiter.is_exhausted(&peer_id));
+    }
+
+    #[test]
+    fn removed_peer_starts_with_a_new_window() {
+        let mut limiter = ResponseBytesLimiter::new(100, Duration::from_secs(60));
+        let peer_id = PeerId::random();
+
+        limiter.record(peer_id, 100);
+        assert!(limiter.is_exhausted(&peer_id));
+        limiter.remove_peer(&peer_id);
+        assert!(!limiter.is_exhausted(&peer_id));
+    }
+}
@@ -0,0 +1,131 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    hash::Hash,
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// How long the served data is reused for the same requests.
+/// It is short, so the cache never serves the data outdated by newer blocks for long.
+pub const RESPONSE_CACHE_TTL: Duration = Duration::from_secs(2);
+/// Max number of the requests with cached responses
+pub const RESPONSE_CACHE_CAPACITY: usize = 64;
+
+/// Short-lived cache of the data served to the peers,
+/// so duplicate requests arriving close to each other don't hit the database again.
+#[derive(Debug)]
+pub struct ResponseCache<K, V> {
+    ttl: Duration,
+    capacity: usize,
+    entries: HashMap<K, Entry<V>>,
+}
+
+#[derive(Debug)]
+struct Entry<V> {
+    value: V,
+    inserted_at: Instant,
+}
+
+impl<K, V> Default for ResponseCache<K, V> {
+    fn default() -> Self {
+        Self::new(RESPONSE_CACHE_TTL, RESPONSE_CACHE_CAPACITY)
+    }
+}
+
+impl<K, V> ResponseCache<K, V> {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            entries: HashMap::new(),
+        }
+    }
+}
+
+impl<K, V> ResponseCache<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    /// Returns the cached value if it hasn't expired yet
+    pub fn get<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let entry = self.entries.get(key)?;
+        if entry.inserted_at.elapsed() < self.ttl {
+            Some(entry.value.clone())
+        } else {
+            self.entries.remove(key);
+            None
+        }
+    }
+
+    /// Removes the value from the cache
+    pub fn remove<Q>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.entries.remove(key);
+    }
+
+    /// Caches the value, evicting the oldest entry if the cache is full
+    pub fn insert(&mut self, key: K, value: V) {
+        let ttl = self.ttl;
+        self.entries
+            .retain(|_, entry| entry.inserted_at.elapsed() < ttl);
+
+        if self.entries.len() >= self.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.inserted_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        if self.capacity > 0 {
+            self.entries.insert(
+                key,
+                Entry {
+                    value,
+                    inserted_at: Instant::now(),
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn cached_value_expires_after_ttl() {
+        let mut cache = ResponseCache::new(Duration::from_secs(1), 10);
+        cache.insert(1u32, "value");
+        assert_eq!(cache.get(&1), Some("value"));
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert_eq!(cache.get(&1), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn oldest_value_is_evicted_when_cache_is_full() {
+        let mut cache = ResponseCache::new(Duration::from_secs(10), 2);
+        cache.insert(1u32, "first");
+        tokio::time::advance(Duration::from_millis(1)).await;
+        cache.insert(2u32, "second");
+        tokio::time::advance(Duration::from_millis(1)).await;
+        cache.insert(3u32, "third");
+
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.get(&2), Some("second"));
+        assert_eq!(cache.get(&3), Some("third"));
+    }
+}
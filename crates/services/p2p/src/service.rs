@@ -3,8 +3,12 @@ use crate::{
     config::{
         Config,
         NotInitialized,
+        OverflowPolicy,
     },
+    discovery::DiscoveryStatus,
+    error::P2pError,
     gossipsub::messages::{
+        GossipTopicTag,
         GossipsubBroadcastRequest,
         GossipsubMessage,
     },
@@ -12,34 +16,60 @@ use crate::{
         FuelP2PEvent,
         FuelP2PService,
     },
-    peer_manager::PeerInfo,
+    peer_manager::{
+        PeerCounts,
+        PeerInfo,
+    },
+    peer_reputation::PeerReputation,
     ports::{
         BlockHeightImporter,
         P2pDb,
+        P2pPeerStore,
     },
-    request_response::messages::{
-        OnResponse,
-        RequestMessage,
-        ResponseMessage,
-        ResponseSender,
+    request_response::{
+        lookup_pool::LookupPool,
+        messages::{
+            BlockChunk,
+            ChunkedBlock,
+            OnResponse,
+            RequestMessage,
+            ResponseError,
+            ResponseMessage,
+            ResponseMessageError,
+            ResponseSender,
+        },
+        response_cache::ResponseCache,
     },
 };
 use anyhow::anyhow;
+use fuel_core_metrics::p2p_metrics::p2p_metrics;
 use fuel_core_services::{
-    stream::BoxStream,
+    stream::{
+        BoxStream,
+        IntoBoxStream,
+    },
     RunnableService,
     RunnableTask,
     ServiceRunner,
     StateWatcher,
 };
-use fuel_core_storage::transactional::AtomicView;
+use fuel_core_storage::{
+    transactional::AtomicView,
+    Error as StorageError,
+    Result as StorageResult,
+};
 use fuel_core_types::{
-    blockchain::SealedBlockHeader,
+    blockchain::{
+        primitives::BlockId,
+        SealedBlockHeader,
+    },
     fuel_tx::{
         Transaction,
+        TxId,
         UniqueIdentifier,
     },
     fuel_types::{
+        canonical::Serialize as _,
         BlockHeight,
         ChainId,
     },
@@ -52,6 +82,9 @@ use fuel_core_types::{
         GossipData,
         GossipsubMessageAcceptance,
         GossipsubMessageInfo,
+        GossipsubMessageReport,
+        KnownPeer,
+        PeerBan,
         PeerId as FuelPeerId,
         TransactionGossipData,
         Transactions,
@@ -59,23 +92,47 @@ use fuel_core_types::{
 };
 use futures::{
     future::BoxFuture,
+    stream::FuturesUnordered,
     StreamExt,
+    TryStreamExt,
 };
 use libp2p::{
-    gossipsub::MessageAcceptance,
+    gossipsub::{
+        MessageAcceptance,
+        MessageId,
+        PublishError,
+    },
     request_response::InboundRequestId,
+    swarm::DialError,
+    Multiaddr,
     PeerId,
 };
 use std::{
+    collections::{
+        HashMap,
+        HashSet,
+        VecDeque,
+    },
     fmt::Debug,
     ops::Range,
-    sync::Arc,
+    sync::{
+        atomic::{
+            AtomicBool,
+            AtomicU64,
+            Ordering,
+        },
+        Arc,
+        Mutex,
+    },
+    time::SystemTime,
 };
+use thiserror::Error;
 use tokio::{
     sync::{
         broadcast,
         mpsc,
         oneshot,
+        Semaphore,
     },
     time::{
         Duration,
@@ -84,19 +141,123 @@ use tokio::{
 };
 use tracing::warn;
 
-pub type Service<V> = ServiceRunner<UninitializedTask<V, SharedState>>;
+pub type Service<V, S> = ServiceRunner<UninitializedTask<V, SharedState, S>>;
+
+/// How long the transactions handed to the consumers via gossip are remembered
+const RECEIVED_TXS_TTL: Duration = Duration::from_secs(30);
+/// Max number of the remembered transactions handed to the consumers via gossip
+const RECEIVED_TXS_CAPACITY: usize = 1024;
+/// Max number of the batches awaiting the validation results
+const BATCH_REPORTS_CAPACITY: usize = 256;
+
+/// Why the broadcasted message wasn't published to the network
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BroadcastError {
+    /// No peers are subscribed to the topic yet, the broadcast can be retried later
+    #[error("No peers to publish the message to")]
+    InsufficientPeers,
+    /// The network has already seen the message
+    #[error("The message was already published")]
+    Duplicate,
+    #[error("Failed to publish the message: {0}")]
+    Other(String),
+}
+
+impl From<&PublishError> for BroadcastError {
+    fn from(error: &PublishError) -> Self {
+        match error {
+            PublishError::InsufficientPeers => Self::InsufficientPeers,
+            PublishError::Duplicate => Self::Duplicate,
+            error => Self::Other(error.to_string()),
+        }
+    }
+}
+
+/// Receives the result of publishing the broadcasted message
+type BroadcastResultSender = oneshot::Sender<Result<MessageId, BroadcastError>>;
 
 enum TaskRequest {
-    // Broadcast requests to p2p network
-    BroadcastTransaction(Arc<Transaction>),
+    // Broadcast requests to p2p network, the result is sent to the channel if any
+    BroadcastTransaction {
+        transaction: Arc<Transaction>,
+        channel: Option<BroadcastResultSender>,
+    },
     // Request to get one-off data from p2p network
     GetPeerIds(oneshot::Sender<Vec<PeerId>>),
     // Request to get information about all connected peers
     GetAllPeerInfo {
         channel: oneshot::Sender<Vec<(PeerId, PeerInfo)>>,
     },
+    // Request to get information about a single connected peer
+    GetPeerInfo {
+        peer_id: PeerId,
+        channel: oneshot::Sender<Option<PeerInfo>>,
+    },
+    // Request to get the number of connected peers
+    GetPeerCounts {
+        channel: oneshot::Sender<PeerCounts>,
+    },
+    // Request to get the addresses the node listens on
+    GetListenAddresses {
+        channel: oneshot::Sender<Vec<Multiaddr>>,
+    },
+    // Request to get the gossip reputation scores of peers
+    GetPeerReputationScores {
+        channel: oneshot::Sender<Vec<(PeerId, AppScore)>>,
+    },
+    // Request to get the average response time of the peers that answered our requests
+    GetPeerLatencies {
+        channel: oneshot::Sender<Vec<(PeerId, Duration)>>,
+    },
+    // Request to keep the connection with the peer at all times
+    AddReservedPeer {
+        peer_id: PeerId,
+        address: Multiaddr,
+    },
+    // Request to treat the reserved peer as a regular one
+    RemoveReservedPeer {
+        peer_id: PeerId,
+    },
+    // Request to join or leave the gossip topic
+    SetTopicSubscription {
+        topic: GossipTopicTag,
+        subscribe: bool,
+    },
+    // Request to propagate the received gossip message to the mesh peers again
+    ForwardGossip {
+        message_id: Vec<u8>,
+        channel: oneshot::Sender<Result<(), P2pError>>,
+    },
+    // Signals that the consumers of the gossip are ready to receive it
+    ConsumersReady,
+    // Request to start or stop the discovery mechanisms, the `None` ones stay as they are
+    SetDiscovery {
+        mdns: Option<bool>,
+        kad: Option<bool>,
+    },
+    // Request to get the active discovery mechanisms and the peers found by them
+    GetDiscoveryStatus {
+        channel: oneshot::Sender<DiscoveryStatus>,
+    },
+    // Request to connect to the address, responds with the peer id of the connected node
+    Dial {
+        address: Multiaddr,
+        channel: oneshot::Sender<Result<PeerId, DialError>>,
+    },
+    // Request to query the DHT for the peers, responds with `None` if Kademlia is disabled
+    FindPeers {
+        count: usize,
+        channel: oneshot::Sender<Option<Vec<PeerId>>>,
+    },
+    // Request to check whether the block is stored locally before fetching it from the peers
+    HasLocalBlock {
+        height: BlockHeight,
+        channel: oneshot::Sender<StorageResult<bool>>,
+    },
     GetSealedHeaders {
         block_height_range: Range<u32>,
+        // The peer is picked by the service if it is `None`
+        from_peer: Option<PeerId>,
         channel: OnResponse<Option<Vec<SealedBlockHeader>>>,
     },
     GetTransactions {
@@ -104,8 +265,18 @@ enum TaskRequest {
         from_peer: PeerId,
         channel: OnResponse<Option<Vec<Transactions>>>,
     },
+    GetTip {
+        from_peer: PeerId,
+        channel: OnResponse<Option<(BlockHeight, BlockId)>>,
+    },
+    GetBlockChunk {
+        block_height: u32,
+        chunk_index: u32,
+        from_peer: PeerId,
+        channel: OnResponse<BlockChunk>,
+    },
     // Responds back to the p2p network
-    RespondWithGossipsubMessageReport((GossipsubMessageInfo, GossipsubMessageAcceptance)),
+    RespondWithGossipsubMessageReport(GossipsubMessageReport),
     RespondWithPeerReport {
         peer_id: PeerId,
         score: AppScore,
@@ -116,7 +287,7 @@ enum TaskRequest {
 impl Debug for TaskRequest {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            TaskRequest::BroadcastTransaction(_) => {
+            TaskRequest::BroadcastTransaction { .. } => {
                 write!(f, "TaskRequest::BroadcastTransaction")
             }
             TaskRequest::GetPeerIds(_) => {
@@ -128,6 +299,12 @@ impl Debug for TaskRequest {
             TaskRequest::GetTransactions { .. } => {
                 write!(f, "TaskRequest::GetTransactions")
             }
+            TaskRequest::GetTip { .. } => {
+                write!(f, "TaskRequest::GetTip")
+            }
+            TaskRequest::GetBlockChunk { .. } => {
+                write!(f, "TaskRequest::GetBlockChunk")
+            }
             TaskRequest::RespondWithGossipsubMessageReport(_) => {
                 write!(f, "TaskRequest::RespondWithGossipsubMessageReport")
             }
@@ -135,8 +312,53 @@ impl Debug for TaskRequest {
                 write!(f, "TaskRequest::RespondWithPeerReport")
             }
             TaskRequest::GetAllPeerInfo { .. } => {
+                write!(f, "TaskRequest::GetAllPeerInfo")
+            }
+            TaskRequest::GetPeerInfo { .. } => {
                 write!(f, "TaskRequest::GetPeerInfo")
             }
+            TaskRequest::GetPeerCounts { .. } => {
+                write!(f, "TaskRequest::GetPeerCounts")
+            }
+            TaskRequest::GetListenAddresses { .. } => {
+                write!(f, "TaskRequest::GetListenAddresses")
+            }
+            TaskRequest::GetPeerReputationScores { .. } => {
+                write!(f, "TaskRequest::GetPeerReputationScores")
+            }
+            TaskRequest::GetPeerLatencies { .. } => {
+                write!(f, "TaskRequest::GetPeerLatencies")
+            }
+            TaskRequest::AddReservedPeer { .. } => {
+                write!(f, "TaskRequest::AddReservedPeer")
+            }
+            TaskRequest::RemoveReservedPeer { .. } => {
+                write!(f, "TaskRequest::RemoveReservedPeer")
+            }
+            TaskRequest::SetTopicSubscription { .. } => {
+                write!(f, "TaskRequest::SetTopicSubscription")
+            }
+            TaskRequest::ForwardGossip { .. } => {
+                write!(f, "TaskRequest::ForwardGossip")
+            }
+            TaskRequest::ConsumersReady => {
+                write!(f, "TaskRequest::ConsumersReady")
+            }
+            TaskRequest::SetDiscovery { .. } => {
+                write!(f, "TaskRequest::SetDiscovery")
+            }
+            TaskRequest::GetDiscoveryStatus { .. } => {
+                write!(f, "TaskRequest::GetDiscoveryStatus")
+            }
+            TaskRequest::Dial { .. } => {
+                write!(f, "TaskRequest::Dial")
+            }
+            TaskRequest::FindPeers { .. } => {
+                write!(f, "TaskRequest::FindPeers")
+            }
+            TaskRequest::HasLocalBlock { .. } => {
+                write!(f, "TaskRequest::HasLocalBlock")
+            }
         }
     }
 }
@@ -150,14 +372,22 @@ pub enum HeartBeatPeerReportReason {
 pub trait TaskP2PService: Send {
     fn get_peer_ids(&self) -> Vec<PeerId>;
     fn get_all_peer_info(&self) -> Vec<(&PeerId, &PeerInfo)>;
-    fn get_peer_id_with_height(&self, height: &BlockHeight) -> Option<PeerId>;
+    fn get_peer_info(&self, peer_id: &PeerId) -> Option<&PeerInfo>;
+    fn get_peer_counts(&self) -> PeerCounts;
+    fn listen_addresses(&self) -> Vec<Multiaddr>;
+    fn get_peer_id_with_height(&mut self, height: &BlockHeight) -> Option<PeerId>;
+    fn get_peers_ids_with_height(
+        &mut self,
+        height: &BlockHeight,
+        count: usize,
+    ) -> Vec<PeerId>;
 
     fn next_event(&mut self) -> BoxFuture<'_, Option<FuelP2PEvent>>;
 
     fn publish_message(
         &mut self,
         message: GossipsubBroadcastRequest,
-    ) -> anyhow::Result<()>;
+    ) -> Result<MessageId, PublishError>;
 
     fn send_request_msg(
         &mut self,
@@ -178,6 +408,8 @@ pub trait TaskP2PService: Send {
         acceptance: GossipsubMessageAcceptance,
     ) -> anyhow::Result<()>;
 
+    fn forward_gossip(&mut self, message_id: &MessageId) -> Result<(), P2pError>;
+
     fn report_peer(
         &mut self,
         peer_id: PeerId,
@@ -186,6 +418,36 @@ pub trait TaskP2PService: Send {
     ) -> anyhow::Result<()>;
 
     fn update_block_height(&mut self, height: BlockHeight) -> anyhow::Result<()>;
+
+    fn ban_peer(&mut self, peer_id: PeerId);
+
+    fn unban_peer(&mut self, peer_id: PeerId);
+
+    fn add_reserved_peer(&mut self, peer_id: PeerId, address: Multiaddr);
+
+    fn remove_reserved_peer(&mut self, peer_id: PeerId);
+
+    fn set_discovery(&mut self, mdns: Option<bool>, kad: Option<bool>);
+
+    fn discovery_status(&mut self) -> DiscoveryStatus;
+
+    fn dial(
+        &mut self,
+        address: Multiaddr,
+        channel: oneshot::Sender<Result<PeerId, DialError>>,
+    );
+
+    fn find_peers(&mut self, count: usize, channel: oneshot::Sender<Option<Vec<PeerId>>>);
+
+    fn has_pending_inbound_requests(&self) -> bool;
+
+    fn set_topic_subscription(
+        &mut self,
+        topic: GossipTopicTag,
+        subscribe: bool,
+    ) -> anyhow::Result<()>;
+
+    fn unsubscribe_from_all_topics(&mut self);
 }
 
 impl TaskP2PService for FuelP2PService {
@@ -197,8 +459,29 @@ impl TaskP2PService for FuelP2PService {
         self.peer_manager().get_all_peers().collect()
     }
 
-    fn get_peer_id_with_height(&self, height: &BlockHeight) -> Option<PeerId> {
-        self.peer_manager().get_peer_id_with_height(height)
+    fn get_peer_info(&self, peer_id: &PeerId) -> Option<&PeerInfo> {
+        self.peer_manager().get_peer_info(peer_id)
+    }
+
+    fn get_peer_counts(&self) -> PeerCounts {
+        self.peer_manager().peer_counts()
+    }
+
+    fn listen_addresses(&self) -> Vec<Multiaddr> {
+        self.multiaddrs()
+    }
+
+    fn get_peer_id_with_height(&mut self, height: &BlockHeight) -> Option<PeerId> {
+        self.peer_manager_mut().get_peer_id_with_height(height)
+    }
+
+    fn get_peers_ids_with_height(
+        &mut self,
+        height: &BlockHeight,
+        count: usize,
+    ) -> Vec<PeerId> {
+        self.peer_manager_mut()
+            .get_peers_ids_with_height(height, count)
     }
 
     fn next_event(&mut self) -> BoxFuture<'_, Option<FuelP2PEvent>> {
@@ -208,9 +491,8 @@ impl TaskP2PService for FuelP2PService {
     fn publish_message(
         &mut self,
         message: GossipsubBroadcastRequest,
-    ) -> anyhow::Result<()> {
-        self.publish_message(message)?;
-        Ok(())
+    ) -> Result<MessageId, PublishError> {
+        self.publish_message(message)
     }
 
     fn send_request_msg(
@@ -241,6 +523,10 @@ impl TaskP2PService for FuelP2PService {
         Ok(())
     }
 
+    fn forward_gossip(&mut self, message_id: &MessageId) -> Result<(), P2pError> {
+        self.forward_gossip(message_id)
+    }
+
     fn report_peer(
         &mut self,
         peer_id: PeerId,
@@ -255,6 +541,343 @@ impl TaskP2PService for FuelP2PService {
         self.update_block_height(height);
         Ok(())
     }
+
+    fn ban_peer(&mut self, peer_id: PeerId) {
+        self.ban_peer(peer_id)
+    }
+
+    fn unban_peer(&mut self, peer_id: PeerId) {
+        self.unban_peer(peer_id)
+    }
+
+    fn add_reserved_peer(&mut self, peer_id: PeerId, address: Multiaddr) {
+        self.add_reserved_peer(peer_id, address)
+    }
+
+    fn remove_reserved_peer(&mut self, peer_id: PeerId) {
+        self.remove_reserved_peer(peer_id)
+    }
+
+    fn set_discovery(&mut self, mdns: Option<bool>, kad: Option<bool>) {
+        self.set_discovery(mdns, kad)
+    }
+
+    fn discovery_status(&mut self) -> DiscoveryStatus {
+        self.discovery_status()
+    }
+
+    fn dial(
+        &mut self,
+        address: Multiaddr,
+        channel: oneshot::Sender<Result<PeerId, DialError>>,
+    ) {
+        self.dial(address, channel)
+    }
+
+    fn find_peers(
+        &mut self,
+        count: usize,
+        channel: oneshot::Sender<Option<Vec<PeerId>>>,
+    ) {
+        self.find_peers(count, channel)
+    }
+
+    fn has_pending_inbound_requests(&self) -> bool {
+        self.has_pending_inbound_requests()
+    }
+
+    fn set_topic_subscription(
+        &mut self,
+        topic: GossipTopicTag,
+        subscribe: bool,
+    ) -> anyhow::Result<()> {
+        self.set_topic_subscription(topic, subscribe)
+    }
+
+    fn unsubscribe_from_all_topics(&mut self) {
+        self.unsubscribe_from_all_topics()
+    }
+}
+
+/// The changes of the network state observed by the node.
+/// They are emitted after the service has updated its own view of the peers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkEvent {
+    /// A new peer has connected to the node
+    PeerConnected(PeerId),
+    /// The peer has disconnected from the node
+    PeerDisconnected(PeerId),
+    /// The peer has reported its latest block height
+    PeerInfoUpdated {
+        peer_id: PeerId,
+        block_height: BlockHeight,
+    },
+    /// The node listens on the new set of addresses, or the peers observe it at the new address
+    ListenAddrChanged(Vec<Multiaddr>),
+    /// Nobody consumes the data passed to the channel anymore
+    ConsumerGone(ConsumerChannel),
+    /// The number of the connected peers crossed the `min_peers_for_ready`.
+    /// It is `true` if the node has enough peers now.
+    PeersReady(bool),
+}
+
+/// The channels between the p2p task and the other services
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsumerChannel {
+    /// The transactions received via gossip
+    Transactions,
+    /// The block heights reported by the peers
+    BlockHeights,
+    /// The requests of the other services to the p2p task
+    Requests,
+}
+
+impl ConsumerChannel {
+    /// The name of the channel used in the metrics
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConsumerChannel::Transactions => "transactions",
+            ConsumerChannel::BlockHeights => "block_heights",
+            ConsumerChannel::Requests => "requests",
+        }
+    }
+}
+
+/// Applies the overflow policy of the channel once the slowest consumer is `capacity` behind.
+/// The dropped data is counted, and the overflow is logged once until the consumers catch up.
+/// The `Block` policy doesn't drop anything, it is up to the sender to wait for a free slot.
+#[derive(Debug, Clone)]
+struct ChannelOverflow {
+    channel: ConsumerChannel,
+    capacity: usize,
+    policy: OverflowPolicy,
+    /// Whether the slowest consumer is `capacity` behind
+    overflowing: Arc<AtomicBool>,
+    metrics: bool,
+}
+
+impl ChannelOverflow {
+    fn new(
+        channel: ConsumerChannel,
+        capacity: usize,
+        policy: OverflowPolicy,
+        metrics: bool,
+    ) -> Self {
+        Self {
+            channel,
+            // The channel requires at least one slot
+            capacity: capacity.max(1),
+            policy,
+            overflowing: Default::default(),
+            metrics,
+        }
+    }
+
+    /// Returns whether the new data is sent to the channel holding `len` items.
+    fn admit(&self, len: usize) -> bool {
+        if len < self.capacity {
+            if self.overflowing.swap(false, Ordering::Relaxed) {
+                tracing::info!(target: "fuel-p2p", "The consumers of the {:?} caught up", self.channel);
+            }
+            return true
+        }
+
+        if !self.overflowing.swap(true, Ordering::Relaxed) {
+            warn!(target: "fuel-p2p", "The consumer of the {:?} lags behind, the {:?} policy is applied", self.channel, self.policy);
+        }
+        if self.metrics && self.policy != OverflowPolicy::Block {
+            p2p_metrics().count_consumer_channel_drop(self.channel.name());
+        }
+        match self.policy {
+            // The send overwrites the oldest data the slowest consumer hasn't received yet
+            OverflowPolicy::DropOldest => true,
+            OverflowPolicy::DropNewest => false,
+            OverflowPolicy::Block => true,
+        }
+    }
+}
+
+/// Counts the connected peers to tell whether the node has enough of them
+/// to rely on its view of the network.
+#[derive(Debug)]
+struct PeerReadiness {
+    min_peers: usize,
+    connected: HashSet<PeerId>,
+}
+
+impl PeerReadiness {
+    fn new(min_peers: usize) -> Self {
+        Self {
+            min_peers,
+            connected: HashSet::new(),
+        }
+    }
+
+    fn is_ready(&self) -> bool {
+        self.connected.len() >= self.min_peers
+    }
+
+    /// Returns the new readiness if the connection of the peer changed it
+    fn peer_connected(&mut self, peer_id: PeerId) -> Option<bool> {
+        let was_ready = self.is_ready();
+        self.connected.insert(peer_id);
+        self.changed(was_ready)
+    }
+
+    /// Returns the new readiness if the disconnection of the peer changed it
+    fn peer_disconnected(&mut self, peer_id: &PeerId) -> Option<bool> {
+        let was_ready = self.is_ready();
+        self.connected.remove(peer_id);
+        self.changed(was_ready)
+    }
+
+    fn changed(&self, was_ready: bool) -> Option<bool> {
+        let is_ready = self.is_ready();
+        (is_ready != was_ready).then_some(is_ready)
+    }
+}
+
+/// Tracks the consecutive failures to pass the data to the consumer of the channel.
+/// The consumer is considered gone after `threshold` failures in a row.
+#[derive(Debug)]
+struct ConsumerHealth {
+    channel: ConsumerChannel,
+    consecutive_failures: u32,
+    threshold: u32,
+}
+
+impl ConsumerHealth {
+    fn new(channel: ConsumerChannel, threshold: u32) -> Self {
+        Self {
+            channel,
+            consecutive_failures: 0,
+            threshold,
+        }
+    }
+
+    /// Records the result of the send. Returns `true` once the consumer is detected as gone.
+    /// The failures after that aren't logged until the consumer is back.
+    fn record(&mut self, result: &anyhow::Result<()>) -> bool {
+        let Err(err) = result else {
+            self.consecutive_failures = 0;
+            return false
+        };
+
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        if self.consecutive_failures <= self.threshold {
+            warn!(target: "fuel-p2p", "Failed to pass the data to the {:?} consumer: {err}", self.channel);
+        }
+        self.consecutive_failures == self.threshold
+    }
+}
+
+/// The subscription to the gossiped transactions.
+/// The transactions skipped because the subscriber lags behind are counted,
+/// instead of being reported as the error.
+#[derive(Debug)]
+pub struct TransactionGossipSubscription {
+    receiver: broadcast::Receiver<TransactionGossipData>,
+    /// The number of the transactions skipped since the subscription
+    lagged: u64,
+    metrics: bool,
+}
+
+impl TransactionGossipSubscription {
+    /// Receives the next transaction. Returns `None` once the p2p service is gone.
+    pub async fn recv(&mut self) -> Option<TransactionGossipData> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(transaction) => return Some(transaction),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    self.record_lag(skipped)
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Receives the next transaction if there is one, without waiting.
+    pub fn try_recv(
+        &mut self,
+    ) -> Result<TransactionGossipData, broadcast::error::TryRecvError> {
+        loop {
+            match self.receiver.try_recv() {
+                Err(broadcast::error::TryRecvError::Lagged(skipped)) => {
+                    self.record_lag(skipped)
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Re-attaches the subscription to the newest transactions, skipping all pending ones.
+    /// It allows the subscriber that fell behind to catch up at once.
+    /// Returns the number of the skipped transactions, including the already lost ones.
+    pub fn resubscribe(&mut self) -> u64 {
+        let skipped = u64::try_from(self.receiver.len()).unwrap_or(u64::MAX);
+        self.receiver = self.receiver.resubscribe();
+        self.record_lag(skipped);
+        skipped
+    }
+
+    /// The number of the transactions skipped by this subscriber
+    pub fn lagged(&self) -> u64 {
+        self.lagged
+    }
+
+    /// Converts the subscription into the stream of the transactions
+    pub fn into_stream(self) -> BoxStream<TransactionGossipData> {
+        futures::stream::unfold(self, |mut subscription| async move {
+            let transaction = subscription.recv().await?;
+            Some((transaction, subscription))
+        })
+        .into_boxed()
+    }
+
+    fn record_lag(&mut self, skipped: u64) {
+        if skipped == 0 {
+            return
+        }
+        warn!(target: "fuel-p2p", "The subscriber of the gossiped transactions skipped {skipped} transactions");
+        self.lagged = self.lagged.saturating_add(skipped);
+        if self.metrics {
+            p2p_metrics().gossip_tx_subscriber_lagged.inc_by(skipped);
+        }
+    }
+}
+
+/// Checks that the `headers` are exactly the headers of the `range`, in the height order
+fn is_headers_chunk(headers: &[SealedBlockHeader], range: &Range<u32>) -> bool {
+    headers.len() == range.len()
+        && headers
+            .iter()
+            .zip(range.clone())
+            .all(|(header, height)| *header.entity.height() == height.into())
+}
+
+/// The number of the gossip messages of each type processed since the start of the node.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GossipStats {
+    /// Transactions received from the peers
+    pub new_tx_received: u64,
+    /// Transactions accepted by the node and forwarded to its mesh peers
+    pub new_tx_forwarded: u64,
+}
+
+/// The counters behind [`GossipStats`], shared between the `Task` and the `SharedState`.
+#[derive(Debug, Default)]
+struct GossipCounters {
+    new_tx_received: AtomicU64,
+    new_tx_forwarded: AtomicU64,
+}
+
+impl GossipCounters {
+    fn snapshot(&self) -> GossipStats {
+        GossipStats {
+            new_tx_received: self.new_tx_received.load(Ordering::Relaxed),
+            new_tx_forwarded: self.new_tx_forwarded.load(Ordering::Relaxed),
+        }
+    }
 }
 
 pub trait Broadcast: Send {
@@ -271,6 +894,8 @@ pub trait Broadcast: Send {
     ) -> anyhow::Result<()>;
 
     fn tx_broadcast(&self, transaction: TransactionGossipData) -> anyhow::Result<()>;
+
+    fn network_event_broadcast(&self, event: NetworkEvent) -> anyhow::Result<()>;
 }
 
 impl Broadcast for SharedState {
@@ -287,20 +912,42 @@ impl Broadcast for SharedState {
         &self,
         block_height_data: BlockHeightHeartbeatData,
     ) -> anyhow::Result<()> {
-        self.block_height_broadcast.send(block_height_data)?;
+        if self
+            .block_height_overflow
+            .admit(self.block_height_broadcast.len())
+        {
+            self.block_height_broadcast.send(block_height_data)?;
+        }
         Ok(())
     }
 
     fn tx_broadcast(&self, transaction: TransactionGossipData) -> anyhow::Result<()> {
-        self.tx_broadcast.send(transaction)?;
+        if self.tx_overflow.admit(self.tx_broadcast.len()) {
+            self.tx_broadcast.send(transaction)?;
+        }
+        Ok(())
+    }
+
+    fn network_event_broadcast(&self, event: NetworkEvent) -> anyhow::Result<()> {
+        match &event {
+            NetworkEvent::ListenAddrChanged(addresses) => {
+                *self.listen_addresses.lock().expect("poisoned") = addresses.clone();
+            }
+            NetworkEvent::PeersReady(ready) => {
+                self.peers_ready.store(*ready, Ordering::Relaxed);
+            }
+            _ => {}
+        }
+        self.network_event_broadcast.send(event)?;
         Ok(())
     }
 }
 
 /// Uninitialized task for the p2p that can be upgraded later into [`Task`].
-pub struct UninitializedTask<V, B> {
+pub struct UninitializedTask<V, B, S> {
     chain_id: ChainId,
     view_provider: V,
+    peer_store: S,
     next_block_height: BoxStream<BlockHeight>,
     /// Receive internal Task Requests
     request_receiver: mpsc::Receiver<TaskRequest>,
@@ -310,72 +957,900 @@ pub struct UninitializedTask<V, B> {
 
 /// Orchestrates various p2p-related events between the inner `P2pService`
 /// and the top level `NetworkService`.
-pub struct Task<P, V, B> {
+pub struct Task<P, V, B, S> {
     chain_id: ChainId,
     p2p_service: P,
     view_provider: V,
+    /// Persists the bans and the known peers.
+    /// It is shared with the writes running on the blocking thread pool.
+    peer_store: Arc<Mutex<S>>,
+    peer_store_writes: Vec<tokio::task::JoinHandle<()>>,
     next_block_height: BoxStream<BlockHeight>,
     /// Receive internal Task Requests
     request_receiver: mpsc::Receiver<TaskRequest>,
     broadcast: B,
     max_headers_per_request: u32,
+    /// Size in bytes of the chunks the blocks too large for one response are served in
+    block_chunk_size: usize,
+    /// The number of peers the same request for headers is sent to
+    redundant_request_factor: usize,
     // milliseconds wait time between peer heartbeat reputation checks
     heartbeat_check_interval: Duration,
     heartbeat_max_avg_interval: Duration,
     heartbeat_max_time_since_last: Duration,
     next_check_time: Instant,
     heartbeat_peer_reputation_config: HeartbeatPeerReputationConfig,
+    peer_reputation: PeerReputation,
+    response_caches: ResponseCaches,
+    /// Runs the database lookups for the inbound requests off the main loop
+    db_lookups: LookupPool<DbLookupResult>,
+    /// Time to answer the already received requests during the shutdown
+    shutdown_grace_period: Duration,
+    gossip_counters: Arc<GossipCounters>,
+    /// The transactions recently handed to the consumers via gossip with the peers
+    /// that propagated them. The gossipsub forwards them once they are validated,
+    /// so they aren't published again.
+    received_txs: ResponseCache<TxId, PeerId>,
+    /// The transactions recently received via gossip, so their copies
+    /// from the other gossip messages are dropped before the consumers
+    gossip_duplicates: ResponseCache<TxId, PeerId>,
+    metrics: bool,
+    tx_consumer: ConsumerHealth,
+    block_height_consumer: ConsumerHealth,
+    tx_batcher: TxBatcher,
+    batch_reports: BatchReports,
+    /// Holds the received gossip until the consumers are ready.
+    /// It is `None` once they are ready, or if the buffering is disabled.
+    startup_gossip: Option<StartupGossipBuffer>,
+    peer_readiness: PeerReadiness,
+    request_queue: RequestQueue,
+    known_peers: KnownPeers,
 }
 
-#[derive(Clone)]
-pub struct HeartbeatPeerReputationConfig {
-    old_heartbeat_penalty: AppScore,
-    low_heartbeat_frequency_penalty: AppScore,
+/// The gossip messages received before the consumers are ready, the oldest first
+#[derive(Debug)]
+struct StartupGossipBuffer {
+    capacity: usize,
+    messages: VecDeque<(GossipsubMessage, PeerId, Vec<u8>, SystemTime)>,
 }
 
-impl<V> UninitializedTask<V, SharedState> {
-    pub fn new<B: BlockHeightImporter>(
-        chain_id: ChainId,
-        config: Config<NotInitialized>,
-        view_provider: V,
-        block_importer: B,
-    ) -> Self {
-        let (request_sender, request_receiver) = mpsc::channel(1024 * 10);
-        let (tx_broadcast, _) = broadcast::channel(1024 * 10);
-        let (block_height_broadcast, _) = broadcast::channel(1024 * 10);
+impl StartupGossipBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            messages: VecDeque::new(),
+        }
+    }
 
-        let (reserved_peers_broadcast, _) = broadcast::channel::<usize>(
-            config
-                .reserved_nodes
-                .len()
-                .saturating_mul(2)
-                .saturating_add(1),
-        );
-        let next_block_height = block_importer.next_block_height();
+    /// Buffers the message. Returns it back if the buffer is full.
+    fn push(
+        &mut self,
+        message: GossipsubMessage,
+        peer_id: PeerId,
+        message_id: Vec<u8>,
+        received_at: SystemTime,
+    ) -> Option<(GossipsubMessage, PeerId, Vec<u8>, SystemTime)> {
+        if self.messages.len() >= self.capacity {
+            return Some((message, peer_id, message_id, received_at))
+        }
+        self.messages
+            .push_back((message, peer_id, message_id, received_at));
+        None
+    }
+}
 
+/// Buffers the broadcasted transactions to publish them as a single gossip message
+#[derive(Debug)]
+struct TxBatcher {
+    /// The batching is disabled if it is `None`
+    interval: Option<Duration>,
+    max_size: usize,
+    batch: TxBatch,
+    size: usize,
+    /// When the batch should be published, set by the first transaction of the batch
+    deadline: Option<Instant>,
+}
+
+impl TxBatcher {
+    fn new(interval: Option<Duration>, max_size: usize) -> Self {
         Self {
-            chain_id,
-            view_provider,
-            next_block_height,
-            request_receiver,
-            broadcast: SharedState {
-                request_sender,
-                tx_broadcast,
-                reserved_peers_broadcast,
-                block_height_broadcast,
-            },
-            config,
+            interval,
+            max_size,
+            batch: TxBatch::default(),
+            size: 0,
+            deadline: None,
         }
     }
-}
 
-impl<P: TaskP2PService, V, B: Broadcast> Task<P, V, B> {
-    fn peer_heartbeat_reputation_checks(&self) -> anyhow::Result<()> {
-        for (peer_id, peer_info) in self.p2p_service.get_all_peer_info() {
-            if peer_info.heartbeat_data.duration_since_last_heartbeat()
-                > self.heartbeat_max_time_since_last
-            {
-                tracing::debug!("Peer {:?} has old heartbeat", peer_id);
+    fn is_enabled(&self) -> bool {
+        self.interval.is_some()
+    }
+
+    /// Adds the transaction to the batch. Returns the batch if it is full.
+    fn push(
+        &mut self,
+        transaction: Arc<Transaction>,
+        channel: Option<BroadcastResultSender>,
+    ) -> Option<TxBatch> {
+        if self.deadline.is_none() {
+            self.deadline = self
+                .interval
+                .and_then(|interval| Instant::now().checked_add(interval));
+        }
+        self.size = self.size.saturating_add(transaction.size());
+        self.batch.transactions.push(transaction);
+        self.batch.channels.extend(channel);
+
+        (self.size >= self.max_size).then(|| self.take())
+    }
+
+    /// Takes all buffered transactions
+    fn take(&mut self) -> TxBatch {
+        self.size = 0;
+        self.deadline = None;
+        core::mem::take(&mut self.batch)
+    }
+}
+
+/// Holds the requests for the headers while no connected peer can serve them,
+/// until such a peer connects or the request times out.
+#[derive(Debug)]
+struct RequestQueue {
+    /// The queueing is disabled if it is `None`
+    timeout: Option<Duration>,
+    capacity: usize,
+    /// The rejected requests are answered on behalf of the node itself
+    local_peer_id: PeerId,
+    /// Ordered by the deadline, the oldest request is the first
+    requests: VecDeque<QueuedRequest>,
+}
+
+#[derive(Debug)]
+struct QueuedRequest {
+    block_height_range: Range<u32>,
+    channel: OnResponse<Option<Vec<SealedBlockHeader>>>,
+    deadline: Instant,
+}
+
+impl QueuedRequest {
+    /// The height the peer should have to serve the request
+    fn height(&self) -> BlockHeight {
+        BlockHeight::from(self.block_height_range.end.saturating_sub(1))
+    }
+}
+
+impl RequestQueue {
+    fn new(timeout: Option<Duration>, capacity: usize, local_peer_id: PeerId) -> Self {
+        Self {
+            timeout,
+            capacity,
+            local_peer_id,
+            requests: VecDeque::new(),
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.timeout.is_some() && self.capacity > 0
+    }
+
+    /// Queues the request until the timeout.
+    /// If the queue is full, the oldest request is rejected to make room.
+    fn push(
+        &mut self,
+        block_height_range: Range<u32>,
+        channel: OnResponse<Option<Vec<SealedBlockHeader>>>,
+    ) {
+        let Some(deadline) = self
+            .timeout
+            .and_then(|timeout| Instant::now().checked_add(timeout))
+        else {
+            let _ = channel.send((self.local_peer_id, Err(ResponseError::NoPeers)));
+            return
+        };
+        if self.requests.len() >= self.capacity {
+            if let Some(oldest) = self.requests.pop_front() {
+                tracing::debug!(target: "fuel-p2p", "The request queue is full, rejecting the request for {:?}", oldest.block_height_range);
+                self.reject(oldest);
+            }
+        }
+        self.requests.push_back(QueuedRequest {
+            block_height_range,
+            channel,
+            deadline,
+        });
+    }
+
+    /// Puts back the request taken with the [`Self::take_all`], keeping its deadline
+    fn requeue(&mut self, request: QueuedRequest) {
+        self.requests.push_back(request);
+    }
+
+    fn take_all(&mut self) -> VecDeque<QueuedRequest> {
+        core::mem::take(&mut self.requests)
+    }
+
+    /// The deadline of the oldest request
+    fn next_deadline(&self) -> Option<Instant> {
+        self.requests.front().map(|request| request.deadline)
+    }
+
+    /// Rejects the requests that didn't get a peer before their deadline
+    fn expire(&mut self, now: Instant) {
+        while let Some(request) = self.requests.pop_front() {
+            if request.deadline > now {
+                self.requests.push_front(request);
+                break
+            }
+            tracing::debug!(target: "fuel-p2p", "No peer connected in time for the request for {:?}", request.block_height_range);
+            self.reject(request);
+        }
+    }
+
+    fn reject(&self, request: QueuedRequest) {
+        let _ = request
+            .channel
+            .send((self.local_peer_id, Err(ResponseError::NoPeers)));
+    }
+}
+
+/// Remembers the connected peers in the database,
+/// so the node can dial them again after the restart
+#[derive(Debug)]
+struct KnownPeers {
+    max_age: Duration,
+    max_to_dial: usize,
+    save_interval: Duration,
+    /// The interval is checked by `Config::validate`,
+    /// the peers are never saved periodically if it overflows
+    next_save: Option<Instant>,
+}
+
+impl KnownPeers {
+    fn new(max_age: Duration, max_to_dial: usize, save_interval: Duration) -> Self {
+        Self {
+            max_age,
+            max_to_dial,
+            save_interval,
+            next_save: Instant::now().checked_add(save_interval),
+        }
+    }
+
+    fn schedule_next_save(&mut self) {
+        self.next_save = Instant::now().checked_add(self.save_interval);
+    }
+
+    /// The peer is stale if it wasn't seen for longer than the max age
+    fn is_stale(&self, peer: &KnownPeer, now: SystemTime) -> bool {
+        is_stale_peer(peer, now, self.max_age)
+    }
+}
+
+fn is_stale_peer(peer: &KnownPeer, now: SystemTime, max_age: Duration) -> bool {
+    now.duration_since(peer.last_seen)
+        .map(|age| age > max_age)
+        .unwrap_or(false)
+}
+
+/// The transactions published as a single gossip message
+#[derive(Debug, Default)]
+struct TxBatch {
+    transactions: Vec<Arc<Transaction>>,
+    /// Await the result of publishing the batch
+    channels: Vec<BroadcastResultSender>,
+}
+
+/// Resolves at the `deadline`, never if it is `None`
+async fn sleep_until_deadline(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// The validation results of the transactions from the batch received via gossip
+#[derive(Debug, Clone, Copy)]
+struct BatchReport {
+    peer_id: PeerId,
+    size: usize,
+    remaining: usize,
+    acceptance: GossipsubMessageAcceptance,
+    received_at: Instant,
+}
+
+/// Combines the validation results of the transactions from the same batch.
+/// The batch is reported to the gossipsub once all its transactions are validated,
+/// it is accepted only if all of them are accepted.
+///
+/// The batch waits for all its results, however late they are. Only when too many
+/// batches are waiting, the oldest one is abandoned, and the late results of its
+/// transactions are dropped instead of being taken for the single transactions.
+#[derive(Debug)]
+struct BatchReports {
+    pending: HashMap<Vec<u8>, BatchReport>,
+    abandoned: ResponseCache<Vec<u8>, ()>,
+}
+
+impl Default for BatchReports {
+    fn default() -> Self {
+        Self {
+            pending: HashMap::new(),
+            abandoned: ResponseCache::new(Duration::MAX, BATCH_REPORTS_CAPACITY),
+        }
+    }
+}
+
+impl BatchReports {
+    /// Starts waiting for the validation results of the batch. Returns the id of
+    /// the oldest batch with its sender if it was abandoned to make room for the new one.
+    fn insert(
+        &mut self,
+        message_id: Vec<u8>,
+        peer_id: PeerId,
+        size: usize,
+    ) -> Option<(Vec<u8>, PeerId)> {
+        let abandoned = if self.pending.len() >= BATCH_REPORTS_CAPACITY {
+            let oldest = self
+                .pending
+                .iter()
+                .min_by_key(|(_, report)| report.received_at)
+                .map(|(message_id, _)| message_id.clone());
+            oldest
+                .and_then(|oldest| self.pending.remove_entry(&oldest))
+                .map(|(message_id, report)| {
+                    self.abandoned.insert(message_id.clone(), ());
+                    (message_id, report.peer_id)
+                })
+        } else {
+            None
+        };
+
+        let report = BatchReport {
+            peer_id,
+            size,
+            remaining: size,
+            acceptance: GossipsubMessageAcceptance::Accept,
+            received_at: Instant::now(),
+        };
+        self.pending.insert(message_id, report);
+        abandoned
+    }
+
+    /// Records the validation result of the transaction. Returns the result for the whole message
+    /// with the number of transactions in it once it is known.
+    fn combine(
+        &mut self,
+        message_id: &[u8],
+        acceptance: GossipsubMessageAcceptance,
+    ) -> Option<(GossipsubMessageAcceptance, usize)> {
+        let Some(report) = self.pending.get_mut(message_id) else {
+            if self.abandoned.get(message_id).is_some() {
+                tracing::debug!(target: "fuel-p2p", "Dropping the late validation result of the abandoned batch");
+                return None
+            }
+            // The message with a single transaction
+            return Some((acceptance, 1))
+        };
+
+        report.remaining = report.remaining.saturating_sub(1);
+        report.acceptance = match (report.acceptance, acceptance) {
+            (GossipsubMessageAcceptance::Reject, _)
+            | (_, GossipsubMessageAcceptance::Reject) => {
+                GossipsubMessageAcceptance::Reject
+            }
+            (GossipsubMessageAcceptance::Ignore, _)
+            | (_, GossipsubMessageAcceptance::Ignore) => {
+                GossipsubMessageAcceptance::Ignore
+            }
+            _ => GossipsubMessageAcceptance::Accept,
+        };
+
+        if report.remaining == 0 {
+            let report = self.pending.remove(message_id)?;
+            Some((report.acceptance, report.size))
+        } else {
+            None
+        }
+    }
+}
+
+/// Recently served data, reused for duplicate requests.
+/// Shared with the database lookups running in the background.
+#[derive(Clone, Default)]
+struct ResponseCaches {
+    sealed_headers: Arc<Mutex<ResponseCache<Range<u32>, Vec<SealedBlockHeader>>>>,
+    transactions: Arc<Mutex<ResponseCache<Range<u32>, Vec<Transactions>>>>,
+    /// The blocks served in chunks, so the block isn't encoded again for every chunk
+    encoded_blocks: Arc<Mutex<ResponseCache<u32, (BlockId, Arc<Vec<u8>>)>>>,
+}
+
+impl ResponseCaches {
+    fn get_sealed_headers<DB: P2pDb>(
+        &self,
+        db: &DB,
+        range: Range<u32>,
+    ) -> StorageResult<Vec<SealedBlockHeader>> {
+        if let Some(headers) = self.sealed_headers.lock().expect("poisoned").get(&range) {
+            return Ok(headers)
+        }
+
+        let headers = db.get_sealed_headers(range.clone())?;
+        // Missing headers may be imported soon, so only found ones are cached
+        if !headers.is_empty() {
+            self.sealed_headers
+                .lock()
+                .expect("poisoned")
+                .insert(range, headers.clone());
+        }
+        Ok(headers)
+    }
+
+    fn get_transactions<DB: P2pDb>(
+        &self,
+        db: &DB,
+        range: Range<u32>,
+    ) -> StorageResult<Option<Vec<Transactions>>> {
+        if let Some(transactions) =
+            self.transactions.lock().expect("poisoned").get(&range)
+        {
+            return Ok(Some(transactions))
+        }
+
+        let transactions = db.get_transactions(range.clone())?;
+        if let Some(transactions) = &transactions {
+            self.transactions
+                .lock()
+                .expect("poisoned")
+                .insert(range, transactions.clone());
+        }
+        Ok(transactions)
+    }
+
+    /// Returns the id and the encoded [`ChunkedBlock`] of the block at the `block_height`
+    fn get_encoded_block<DB: P2pDb>(
+        &self,
+        db: &DB,
+        block_height: u32,
+    ) -> StorageResult<Option<(BlockId, Arc<Vec<u8>>)>> {
+        if let Some(block) = self
+            .encoded_blocks
+            .lock()
+            .expect("poisoned")
+            .get(&block_height)
+        {
+            return Ok(Some(block))
+        }
+
+        let range = block_height..block_height.saturating_add(1);
+        let Some(header) = db.get_sealed_headers(range.clone())?.into_iter().next()
+        else {
+            return Ok(None)
+        };
+        let Some(transactions) = db
+            .get_transactions(range)?
+            .and_then(|transactions| transactions.into_iter().next())
+        else {
+            return Ok(None)
+        };
+        let block_id = header.entity.id();
+        let data = postcard::to_stdvec(&ChunkedBlock {
+            header,
+            transactions,
+        })
+        .map_err(|e| StorageError::Other(anyhow!(e)))?;
+
+        let block = (block_id, Arc::new(data));
+        self.encoded_blocks
+            .lock()
+            .expect("poisoned")
+            .insert(block_height, block.clone());
+        Ok(Some(block))
+    }
+}
+
+/// Returns the `chunk_index`-th chunk of `chunk_size` bytes of the encoded block,
+/// or `None` if the block has fewer chunks
+fn block_chunk(
+    block_id: BlockId,
+    data: &[u8],
+    chunk_index: u32,
+    chunk_size: usize,
+) -> Option<BlockChunk> {
+    let chunk_size = chunk_size.max(1);
+    let chunk_count = data.len().div_ceil(chunk_size).try_into().ok()?;
+    let chunk = data.chunks(chunk_size).nth(chunk_index.try_into().ok()?)?;
+    Some(BlockChunk {
+        block_id,
+        chunk_count,
+        data: chunk.to_vec(),
+    })
+}
+
+/// Joins the chunks of the block at the `block_height`,
+/// checking that they make up the block with the id they claim
+fn reassemble_block(
+    block_height: u32,
+    chunks: Vec<BlockChunk>,
+    max_block_size: usize,
+) -> Result<ChunkedBlock, P2pError> {
+    let Some(first) = chunks.first() else {
+        return Err(P2pError::InvalidBlockChunks("no chunks"))
+    };
+    let (block_id, chunk_count) = (first.block_id, first.chunk_count);
+    if chunks
+        .iter()
+        .any(|chunk| chunk.block_id != block_id || chunk.chunk_count != chunk_count)
+    {
+        return Err(P2pError::InvalidBlockChunks(
+            "the chunks of different blocks",
+        ))
+    }
+    if usize::try_from(chunk_count).ok() != Some(chunks.len()) {
+        return Err(P2pError::InvalidBlockChunks("missing chunks"))
+    }
+    // All chunks but the last one are of the same size, and none of them is empty
+    let chunk_size = first.data.len();
+    if let Some((last, rest)) = chunks.split_last() {
+        if chunk_size == 0
+            || rest.iter().any(|chunk| chunk.data.len() != chunk_size)
+            || last.data.is_empty()
+            || last.data.len() > chunk_size
+        {
+            return Err(P2pError::InvalidBlockChunks("the chunks of uneven sizes"))
+        }
+    }
+    let size = chunks
+        .iter()
+        .map(|chunk| chunk.data.len())
+        .fold(0usize, usize::saturating_add);
+    if size > max_block_size {
+        return Err(P2pError::InvalidBlockChunks(
+            "the block exceeds the max size",
+        ))
+    }
+
+    let mut data = Vec::with_capacity(size);
+    for chunk in chunks {
+        data.extend(chunk.data);
+    }
+    let block: ChunkedBlock = postcard::from_bytes(&data).map_err(|_| {
+        P2pError::InvalidBlockChunks("the chunks don't decode into a block")
+    })?;
+    if block.header.entity.id() != block_id {
+        return Err(P2pError::InvalidBlockChunks(
+            "the block doesn't match its id",
+        ))
+    }
+    if *block.header.entity.height() != BlockHeight::from(block_height) {
+        return Err(P2pError::InvalidBlockChunks("the block of another height"))
+    }
+    Ok(block)
+}
+
+/// Splits the range of at least two heights in halves
+fn split_range(range: &Range<u32>) -> (Range<u32>, Range<u32>) {
+    let middle = range.start.saturating_add(
+        range
+            .end
+            .saturating_sub(range.start)
+            .checked_div(2)
+            .unwrap_or_default(),
+    );
+    (range.start..middle, middle..range.end)
+}
+
+/// Limits the chunked transfers of the blocks too large for one response
+struct BlockChunkLimits {
+    max_in_flight: usize,
+    timeout: Duration,
+    max_block_size: usize,
+    /// The permits for the chunks requested from each peer
+    in_flight: Mutex<HashMap<PeerId, Arc<Semaphore>>>,
+}
+
+impl BlockChunkLimits {
+    fn new(max_in_flight: usize, timeout: Duration, max_block_size: usize) -> Self {
+        Self {
+            max_in_flight: max_in_flight.max(1),
+            timeout,
+            max_block_size,
+            in_flight: Default::default(),
+        }
+    }
+
+    /// Returns the permits of the peer, shared by all transfers from it
+    fn permits(&self, peer_id: PeerId) -> Arc<Semaphore> {
+        let mut in_flight = self.in_flight.lock().expect("poisoned");
+        // Forgets the peers without the chunks in flight
+        in_flight.retain(|_, permits| Arc::strong_count(permits) > 1);
+        in_flight
+            .entry(peer_id)
+            .or_insert_with(|| Arc::new(Semaphore::new(self.max_in_flight)))
+            .clone()
+    }
+}
+
+/// The result of the database lookup for the inbound request
+enum DbLookupResult {
+    SealedHeaders {
+        request_id: InboundRequestId,
+        range: Range<u32>,
+        result: StorageResult<Vec<SealedBlockHeader>>,
+    },
+    Transactions {
+        request_id: InboundRequestId,
+        range: Range<u32>,
+        result: StorageResult<Option<Vec<Transactions>>>,
+    },
+    Tip {
+        request_id: InboundRequestId,
+        result: StorageResult<Option<(BlockHeight, BlockId)>>,
+    },
+    BlockChunk {
+        request_id: InboundRequestId,
+        block_height: u32,
+        result: StorageResult<Option<BlockChunk>>,
+    },
+    /// The lookup panicked, so the requester only learns that the data isn't served
+    Failed { request_id: InboundRequestId },
+}
+
+#[derive(Clone)]
+pub struct HeartbeatPeerReputationConfig {
+    old_heartbeat_penalty: AppScore,
+    low_heartbeat_frequency_penalty: AppScore,
+}
+
+impl<V, S> UninitializedTask<V, SharedState, S> {
+    pub fn new<B: BlockHeightImporter>(
+        chain_id: ChainId,
+        config: Config<NotInitialized>,
+        view_provider: V,
+        peer_store: S,
+        block_importer: B,
+    ) -> Self {
+        let request_overflow = ChannelOverflow::new(
+            ConsumerChannel::Requests,
+            config.request_channel_capacity,
+            config.request_overflow_policy,
+            config.metrics,
+        );
+        let (request_sender, request_receiver) = mpsc::channel(request_overflow.capacity);
+        let tx_overflow = ChannelOverflow::new(
+            ConsumerChannel::Transactions,
+            config.tx_broadcast_capacity,
+            config.tx_overflow_policy,
+            config.metrics,
+        );
+        let (tx_broadcast, _) = broadcast::channel(tx_overflow.capacity);
+        let block_height_overflow = ChannelOverflow::new(
+            ConsumerChannel::BlockHeights,
+            config.block_height_broadcast_capacity,
+            config.block_height_overflow_policy,
+            config.metrics,
+        );
+        let (block_height_broadcast, _) =
+            broadcast::channel(block_height_overflow.capacity);
+        let (network_event_broadcast, _) =
+            broadcast::channel(config.network_event_broadcast_capacity.max(1));
+
+        let (reserved_peers_broadcast, _) = broadcast::channel::<usize>(
+            config
+                .reserved_nodes
+                .len()
+                .saturating_mul(2)
+                .saturating_add(1),
+        );
+        let next_block_height = block_importer.next_block_height();
+        let local_peer_id = config.keypair.public().to_peer_id();
+
+        Self {
+            chain_id,
+            view_provider,
+            peer_store,
+            next_block_height,
+            request_receiver,
+            broadcast: SharedState {
+                request_sender,
+                request_overflow,
+                tx_broadcast,
+                tx_overflow,
+                reserved_peers_broadcast,
+                block_height_broadcast,
+                block_height_overflow,
+                network_event_broadcast,
+                local_peer_id,
+                gossip_counters: Default::default(),
+                listen_addresses: Default::default(),
+                peers_ready: Arc::new(AtomicBool::new(config.min_peers_for_ready == 0)),
+                block_chunks: Arc::new(BlockChunkLimits::new(
+                    config.max_block_chunks_in_flight,
+                    config.block_chunks_timeout,
+                    config.max_chunked_block_size,
+                )),
+                metrics: config.metrics,
+            },
+            config,
+        }
+    }
+}
+
+impl<P: TaskP2PService, V, B: Broadcast, S> Task<P, V, B, S> {
+    fn publish_tx_batch(&mut self, batch: TxBatch) {
+        let TxBatch {
+            transactions,
+            channels,
+        } = batch;
+        if transactions.is_empty() {
+            return
+        }
+        let count = transactions.len();
+        let broadcast = GossipsubBroadcastRequest::NewTxBatch(transactions);
+        let result = self.p2p_service.publish_message(broadcast);
+        if let Err(e) = &result {
+            tracing::error!(
+                "Got an error during broadcasting of {} transactions {}",
+                count,
+                e
+            );
+        }
+        for channel in channels {
+            let _ = channel.send(result.as_ref().cloned().map_err(Into::into));
+        }
+    }
+
+    /// Passes the transaction received via gossip to its consumer
+    fn broadcast_gossiped_transaction(
+        &mut self,
+        transaction: Transaction,
+        peer_id: PeerId,
+        message_id: Vec<u8>,
+        received_at: SystemTime,
+    ) {
+        self.gossip_counters
+            .new_tx_received
+            .fetch_add(1, Ordering::Relaxed);
+        let tx_id = transaction.id(&self.chain_id);
+        let next_transaction =
+            GossipData::new_with_timestamp(transaction, peer_id, message_id, received_at);
+        let result = self.broadcast.tx_broadcast(next_transaction);
+        if result.is_ok() {
+            self.received_txs.insert(tx_id, peer_id);
+        }
+        if self.tx_consumer.record(&result) {
+            tracing::error!(target: "fuel-p2p", "The consumer of the gossiped transactions is gone");
+            let _ = self
+                .broadcast
+                .network_event_broadcast(NetworkEvent::ConsumerGone(
+                    ConsumerChannel::Transactions,
+                ));
+        }
+    }
+
+    /// Passes the gossip message to its consumers,
+    /// or holds it until they are ready if the node is still starting
+    fn handle_gossip_message(
+        &mut self,
+        message: GossipsubMessage,
+        peer_id: PeerId,
+        message_id: Vec<u8>,
+    ) {
+        let received_at = SystemTime::now();
+        let Some(buffer) = &mut self.startup_gossip else {
+            self.deliver_gossip_message(message, peer_id, message_id, received_at);
+            return
+        };
+        if let Some((_, peer_id, message_id, _)) =
+            buffer.push(message, peer_id, message_id, received_at)
+        {
+            tracing::debug!(target: "fuel-p2p", "The startup gossip buffer is full, ignoring the message from {peer_id}");
+            self.ignore_gossip_message(peer_id, message_id);
+        }
+    }
+
+    /// The message isn't passed to the consumers, so it shouldn't wait in the gossipsub for the report.
+    /// It is ignored rather than rejected, so the peer isn't penalized.
+    fn ignore_gossip_message(&mut self, peer_id: PeerId, message_id: Vec<u8>) {
+        let message_info = GossipsubMessageInfo {
+            message_id,
+            peer_id: FuelPeerId::from(peer_id.to_bytes()),
+        };
+        if let Err(e) = self
+            .p2p_service
+            .report_message(message_info, GossipsubMessageAcceptance::Ignore)
+        {
+            tracing::error!(target: "fuel-p2p", "Failed to report the ignored gossip message: {e}");
+        }
+    }
+
+    /// Remembers the transaction received via gossip. Returns `false` if it was already
+    /// received recently, e.g. within a batch and then alone, so the consumers don't validate it again.
+    fn remember_gossiped_transaction(
+        &mut self,
+        transaction: &Transaction,
+        peer_id: PeerId,
+    ) -> bool {
+        let tx_id = transaction.id(&self.chain_id);
+        if let Some(source) = self.gossip_duplicates.get(&tx_id) {
+            tracing::debug!(target: "fuel-p2p", "Transaction {tx_id} was already received from {source}, dropping the duplicate from {peer_id}");
+            if self.metrics {
+                p2p_metrics().gossip_duplicate_txs.inc();
+            }
+            return false
+        }
+        self.gossip_duplicates.insert(tx_id, peer_id);
+        true
+    }
+
+    fn deliver_gossip_message(
+        &mut self,
+        message: GossipsubMessage,
+        peer_id: PeerId,
+        message_id: Vec<u8>,
+        received_at: SystemTime,
+    ) {
+        match message {
+            GossipsubMessage::NewTx(transaction) => {
+                if !self.remember_gossiped_transaction(&transaction, peer_id) {
+                    self.ignore_gossip_message(peer_id, message_id);
+                    return
+                }
+                self.broadcast_gossiped_transaction(
+                    transaction,
+                    peer_id,
+                    message_id,
+                    received_at,
+                );
+            }
+            GossipsubMessage::NewTxBatch(transactions) => {
+                let mut new_transactions = Vec::with_capacity(transactions.len());
+                for transaction in transactions {
+                    if self.remember_gossiped_transaction(&transaction, peer_id) {
+                        new_transactions.push(transaction);
+                    }
+                }
+                let transactions = new_transactions;
+                if transactions.is_empty() {
+                    self.ignore_gossip_message(peer_id, message_id);
+                    return
+                }
+                // Each transaction is reported separately, but refers to the batch message
+                if let Some((abandoned_id, abandoned_peer)) = self.batch_reports.insert(
+                    message_id.clone(),
+                    peer_id,
+                    transactions.len(),
+                ) {
+                    tracing::warn!(target: "fuel-p2p", "Too many batches await the validation results, ignoring the oldest one");
+                    self.ignore_gossip_message(abandoned_peer, abandoned_id);
+                }
+                for transaction in transactions {
+                    self.broadcast_gossiped_transaction(
+                        transaction,
+                        peer_id,
+                        message_id.clone(),
+                        received_at,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Delivers the gossip received during the startup, the oldest first
+    fn flush_startup_gossip(&mut self) {
+        let Some(buffer) = self.startup_gossip.take() else {
+            return
+        };
+        tracing::debug!(target: "fuel-p2p", "Delivering {} gossip messages received during the startup", buffer.messages.len());
+        for (message, peer_id, message_id, received_at) in buffer.messages {
+            self.deliver_gossip_message(message, peer_id, message_id, received_at);
+        }
+    }
+
+    fn peer_heartbeat_reputation_checks(&self) -> anyhow::Result<()> {
+        for (peer_id, peer_info) in self.p2p_service.get_all_peer_info() {
+            if peer_info.heartbeat_data.duration_since_last_heartbeat()
+                > self.heartbeat_max_time_since_last
+            {
+                tracing::debug!("Peer {:?} has old heartbeat", peer_id);
                 let report = HeartBeatPeerReportReason::OldHeartBeat;
                 let peer_id = convert_peer_id(peer_id)?;
                 self.report_peer(peer_id, report)?;
@@ -412,21 +1887,563 @@ impl<P: TaskP2PService, V, B: Broadcast> Task<P, V, B> {
     }
 }
 
-fn convert_peer_id(peer_id: &PeerId) -> anyhow::Result<FuelPeerId> {
-    let inner = Vec::from(*peer_id);
-    Ok(FuelPeerId::from(inner))
-}
-
-#[async_trait::async_trait]
-impl<V> RunnableService for UninitializedTask<V, SharedState>
-where
+impl<P: TaskP2PService, V, B, S> Task<P, V, B, S> {
+    /// Requests the headers from the peers that have them. If there are none,
+    /// the request waits for such a peer in the queue, if the queueing is enabled.
+    fn request_sealed_headers(
+        &mut self,
+        block_height_range: Range<u32>,
+        channel: OnResponse<Option<Vec<SealedBlockHeader>>>,
+    ) {
+        // Note: this range has already been checked for
+        // validity in `SharedState::get_sealed_block_headers`.
+        let height = BlockHeight::from(block_height_range.end.saturating_sub(1));
+        let peers = self
+            .p2p_service
+            .get_peers_ids_with_height(&height, self.redundant_request_factor);
+        if peers.is_empty() && self.request_queue.is_enabled() {
+            tracing::debug!(target: "fuel-p2p", "No peer has the block at height {height}, queueing the request");
+            self.request_queue.push(block_height_range, channel);
+            return
+        }
+
+        let request_msg = RequestMessage::SealedHeaders(block_height_range);
+        if peers.len() > 1 {
+            self.send_redundant_request(peers, request_msg, channel);
+        } else {
+            let channel = ResponseSender::SealedHeaders(channel);
+            let peer = peers.first().copied();
+            if let Err(err) =
+                self.p2p_service
+                    .send_request_msg(peer, request_msg, channel)
+            {
+                tracing::warn!(
+                    "Failed to request the block at height {:?}: {err}",
+                    height
+                );
+            }
+        }
+    }
+
+    /// Sends the queued requests that the connected peers can serve now
+    fn dispatch_queued_requests(&mut self) {
+        for request in self.request_queue.take_all() {
+            let peers = self
+                .p2p_service
+                .get_peers_ids_with_height(&request.height(), 1);
+            if peers.is_empty() {
+                self.request_queue.requeue(request);
+            } else {
+                self.request_sealed_headers(request.block_height_range, request.channel);
+            }
+        }
+    }
+
+    /// Sends the same request to all `peers` at once.
+    /// The first successful response is forwarded to the `channel`,
+    /// while the responses of the slower peers are ignored.
+    /// The response time of every peer is still recorded and used for the peer selection.
+    fn send_redundant_request(
+        &mut self,
+        peers: Vec<PeerId>,
+        request_msg: RequestMessage,
+        channel: OnResponse<Option<Vec<SealedBlockHeader>>>,
+    ) {
+        let responses = peers
+            .into_iter()
+            .map(|peer_id| {
+                // Every peer gets its own channel, so the late responses
+                // never hit the channel consumed by the winner.
+                let (sender, receiver) = oneshot::channel();
+                let sender = ResponseSender::SealedHeaders(sender);
+                // The rejected request is answered with the error,
+                // so the receiver is kept to forward it if all requests fail
+                let _ = self.p2p_service.send_request_msg(
+                    Some(peer_id),
+                    request_msg.clone(),
+                    sender,
+                );
+                receiver
+            })
+            .collect::<FuturesUnordered<_>>();
+
+        tokio::spawn(forward_fastest_response(responses, channel));
+    }
+
+    /// Answers the inbound requests received before the shutdown.
+    /// The network is polled until all responses are written to the peers.
+    async fn drain_inbound_requests(&mut self) {
+        while self.db_lookups.has_pending()
+            || self.p2p_service.has_pending_inbound_requests()
+        {
+            tokio::select! {
+                Some(result) = self.db_lookups.next_result() => {
+                    if let Err(e) = self.send_lookup_response(result) {
+                        tracing::error!("Failed to answer the request during the shutdown: {e}");
+                    }
+                }
+                p2p_event = self.p2p_service.next_event() => {
+                    if let Some(FuelP2PEvent::InboundRequestMessage { request_id, .. }) = p2p_event {
+                        // The node is shutting down, so it doesn't serve new requests
+                        let _ = self.p2p_service.send_response_msg(
+                            request_id,
+                            ResponseMessage::Error(ResponseMessageError::NotFound),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Responds to the inbound request with the result of its database lookup
+    fn send_lookup_response(&mut self, result: DbLookupResult) -> anyhow::Result<()> {
+        match result {
+            DbLookupResult::Transactions {
+                request_id,
+                range,
+                result,
+            } => match result {
+                Ok(Some(transactions)) => {
+                    let _ = self.p2p_service.send_response_msg(
+                        request_id,
+                        ResponseMessage::Transactions(Some(transactions)),
+                    );
+                }
+                Ok(None) => {
+                    tracing::debug!("Transactions for range {:?} are not found", range);
+                    let _ = self.p2p_service.send_response_msg(
+                        request_id,
+                        ResponseMessage::Error(ResponseMessageError::NotFound),
+                    );
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to get transactions for range {:?}: {:?}",
+                        range,
+                        e
+                    );
+                    let response = None;
+                    let _ = self.p2p_service.send_response_msg(
+                        request_id,
+                        ResponseMessage::Transactions(response),
+                    );
+                    return Err(e.into())
+                }
+            },
+            DbLookupResult::SealedHeaders {
+                request_id,
+                range,
+                result,
+            } => match result {
+                Ok(headers) if headers.is_empty() => {
+                    tracing::debug!("Sealed headers for range {:?} are not found", range);
+                    let _ = self.p2p_service.send_response_msg(
+                        request_id,
+                        ResponseMessage::Error(ResponseMessageError::NotFound),
+                    );
+                }
+                Ok(headers) => {
+                    let response = Some(headers);
+                    let _ = self.p2p_service.send_response_msg(
+                        request_id,
+                        ResponseMessage::SealedHeaders(response),
+                    );
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to get sealed headers for range {:?}: {:?}",
+                        range,
+                        &e
+                    );
+                    let response = None;
+                    let _ = self.p2p_service.send_response_msg(
+                        request_id,
+                        ResponseMessage::SealedHeaders(response),
+                    );
+                    return Err(e.into())
+                }
+            },
+            DbLookupResult::Tip { request_id, result } => match result {
+                Ok(Some(tip)) => {
+                    let _ = self
+                        .p2p_service
+                        .send_response_msg(request_id, ResponseMessage::Tip(Some(tip)));
+                }
+                Ok(None) => {
+                    tracing::debug!("The tip is not found");
+                    let _ = self.p2p_service.send_response_msg(
+                        request_id,
+                        ResponseMessage::Error(ResponseMessageError::NotFound),
+                    );
+                }
+                Err(e) => {
+                    tracing::error!("Failed to get the tip: {:?}", &e);
+                    let _ = self
+                        .p2p_service
+                        .send_response_msg(request_id, ResponseMessage::Tip(None));
+                    return Err(e.into())
+                }
+            },
+            DbLookupResult::BlockChunk {
+                request_id,
+                block_height,
+                result,
+            } => match result {
+                Ok(Some(chunk)) => {
+                    let _ = self.p2p_service.send_response_msg(
+                        request_id,
+                        ResponseMessage::BlockChunk(chunk),
+                    );
+                }
+                Ok(None) => {
+                    tracing::debug!(
+                        "The chunk of the block {} is not found",
+                        block_height
+                    );
+                    let _ = self.p2p_service.send_response_msg(
+                        request_id,
+                        ResponseMessage::Error(ResponseMessageError::NotFound),
+                    );
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to get the chunk of the block {}: {:?}",
+                        block_height,
+                        &e
+                    );
+                    let _ = self.p2p_service.send_response_msg(
+                        request_id,
+                        ResponseMessage::Error(ResponseMessageError::NotFound),
+                    );
+                    return Err(e.into())
+                }
+            },
+            DbLookupResult::Failed { request_id } => {
+                let _ = self.p2p_service.send_response_msg(
+                    request_id,
+                    ResponseMessage::Error(ResponseMessageError::NotFound),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Forwards the first successful response to the `channel`.
+/// If none of the peers has the data, the last received response is forwarded.
+async fn forward_fastest_response<T>(
+    mut responses: FuturesUnordered<
+        oneshot::Receiver<(PeerId, Result<Option<T>, ResponseError>)>,
+    >,
+    channel: OnResponse<Option<T>>,
+) {
+    let mut last_response = None;
+    while let Some(response) = responses.next().await {
+        let Ok(response) = response else { continue };
+
+        if let (winner, Ok(Some(_))) = &response {
+            tracing::debug!(
+                "Peer {winner} was the fastest to respond, {} slower responses are ignored",
+                responses.len()
+            );
+            let _ = channel.send(response);
+            // Drains the responses of the slower peers
+            while responses.next().await.is_some() {}
+            return
+        }
+        last_response = Some(response);
+    }
+
+    if let Some(response) = last_response {
+        let _ = channel.send(response);
+    }
+}
+
+impl<P, V, B, S> Task<P, V, B, S>
+where
+    P: TaskP2PService,
+    S: P2pPeerStore,
+    V: AtomicView,
+    V::View: P2pDb + 'static,
+{
+    fn update_peer_reputation(
+        &mut self,
+        fuel_peer_id: &FuelPeerId,
+        acceptance: GossipsubMessageAcceptance,
+    ) {
+        let Ok(peer_id) = PeerId::from_bytes(fuel_peer_id.as_ref()) else {
+            warn!(target: "fuel-p2p", "Failed to read PeerId from {fuel_peer_id:?}");
+            return
+        };
+
+        if self.peer_reputation.report(peer_id, acceptance) {
+            tracing::info!(target: "fuel-p2p", "Banning {peer_id} due to the low gossip reputation");
+            self.p2p_service.ban_peer(peer_id);
+
+            // The duration is checked by `Config::validate`
+            let Some(expiry) =
+                SystemTime::now().checked_add(self.peer_reputation.ban_duration())
+            else {
+                warn!(target: "fuel-p2p", "The ban of {peer_id} is too long to be persisted");
+                return
+            };
+            let ban = PeerBan {
+                reason: "Low gossip reputation".to_string(),
+                expiry,
+            };
+            let fuel_peer_id = fuel_peer_id.clone();
+            self.spawn_peer_store_write(move |store| {
+                store.insert_peer_ban(&fuel_peer_id, &ban)?;
+                Ok(())
+            });
+        }
+    }
+
+    /// Writes to the peer store on the blocking thread pool,
+    /// so the main loop isn't blocked by the database
+    fn spawn_peer_store_write<F>(&mut self, write: F)
+    where
+        F: FnOnce(&mut S) -> anyhow::Result<()> + Send + 'static,
+    {
+        let peer_store = self.peer_store.clone();
+        self.peer_store_writes.retain(|write| !write.is_finished());
+        self.peer_store_writes
+            .push(tokio::task::spawn_blocking(move || {
+                let mut peer_store = peer_store.lock().expect("poisoned");
+                if let Err(err) = write(&mut peer_store) {
+                    warn!(target: "fuel-p2p", "Failed to write to the peer store: {err}");
+                }
+            }));
+    }
+
+    /// Waits until the writes to the peer store are done
+    async fn await_peer_store_writes(&mut self) {
+        for write in self.peer_store_writes.drain(..) {
+            let _ = write.await;
+        }
+    }
+
+    /// Looks up the requested data in the background, so slow database reads
+    /// don't block the main loop. The result is sent by [`Self::send_lookup_response`].
+    fn process_request(
+        &mut self,
+        request_message: RequestMessage,
+        request_id: InboundRequestId,
+    ) {
+        let view = self.view_provider.latest_view();
+        let caches = self.response_caches.clone();
+        let failed = DbLookupResult::Failed { request_id };
+        let spawned = match request_message {
+            RequestMessage::Transactions(range) => self.db_lookups.try_spawn(
+                move || {
+                    let result = caches.get_transactions(&view, range.clone());
+                    DbLookupResult::Transactions {
+                        request_id,
+                        range,
+                        result,
+                    }
+                },
+                failed,
+            ),
+            RequestMessage::SealedHeaders(range) => {
+                let max_len = self
+                    .max_headers_per_request
+                    .try_into()
+                    .expect("u32 should always fit into usize");
+                if range.len() > max_len {
+                    tracing::error!("Requested range of sealed headers is too big. Requested length: {:?}, Max length: {:?}", range.len(), max_len);
+                    // TODO: Return helpful error message to requester. https://github.com/FuelLabs/fuel-core/issues/1311
+                    let response = None;
+                    let _ = self.p2p_service.send_response_msg(
+                        request_id,
+                        ResponseMessage::SealedHeaders(response),
+                    );
+                    return
+                }
+                self.db_lookups.try_spawn(
+                    move || {
+                        let result = caches.get_sealed_headers(&view, range.clone());
+                        DbLookupResult::SealedHeaders {
+                            request_id,
+                            range,
+                            result,
+                        }
+                    },
+                    failed,
+                )
+            }
+            // The tip changes with every block, so it isn't cached
+            RequestMessage::Tip => self.db_lookups.try_spawn(
+                move || {
+                    let result = view.get_current_tip();
+                    DbLookupResult::Tip { request_id, result }
+                },
+                failed,
+            ),
+            RequestMessage::BlockChunk {
+                block_height,
+                chunk_index,
+            } => {
+                let chunk_size = self.block_chunk_size;
+                self.db_lookups.try_spawn(
+                    move || {
+                        let result =
+                            caches.get_encoded_block(&view, block_height).map(|block| {
+                                block.and_then(|(block_id, data)| {
+                                    block_chunk(block_id, &data, chunk_index, chunk_size)
+                                })
+                            });
+                        DbLookupResult::BlockChunk {
+                            request_id,
+                            block_height,
+                            result,
+                        }
+                    },
+                    failed,
+                )
+            }
+        };
+
+        if !spawned {
+            tracing::debug!(target: "fuel-p2p", "Too many pending database lookups, rejecting the request");
+            let _ = self.p2p_service.send_response_msg(
+                request_id,
+                ResponseMessage::Error(ResponseMessageError::TooManyRequests),
+            );
+        }
+    }
+
+    /// Saves the connected peers with their addresses and removes
+    /// the peers that weren't seen for too long from the database.
+    /// The database is written off the main loop.
+    fn persist_known_peers(&mut self) {
+        let now = SystemTime::now();
+        let max_age = self.known_peers.max_age;
+
+        let connected_peers: Vec<_> = self
+            .p2p_service
+            .get_all_peer_info()
+            .into_iter()
+            .filter(|(_, peer_info)| !peer_info.peer_addresses.is_empty())
+            .map(|(peer_id, peer_info)| {
+                let peer = KnownPeer {
+                    addresses: peer_info
+                        .peer_addresses
+                        .iter()
+                        .map(|address| address.to_vec())
+                        .collect(),
+                    last_seen: now,
+                    score: peer_info.score,
+                };
+                (FuelPeerId::from(peer_id.to_bytes()), peer)
+            })
+            .collect();
+
+        self.spawn_peer_store_write(move |store| {
+            for (fuel_peer_id, peer) in connected_peers {
+                store.insert_known_peer(&fuel_peer_id, &peer)?;
+            }
+            for (fuel_peer_id, peer) in store.load_known_peers()? {
+                if is_stale_peer(&peer, now, max_age) {
+                    store.remove_known_peer(&fuel_peer_id)?;
+                }
+            }
+            Ok(())
+        });
+    }
+
+    /// Dials the most recently seen peers remembered before the restart of the node.
+    /// The stale peers are removed from the database instead.
+    fn restore_known_peers(&mut self) -> anyhow::Result<()> {
+        let now = SystemTime::now();
+
+        let mut peer_store = self.peer_store.lock().expect("poisoned");
+        let mut fresh_peers = vec![];
+        for (fuel_peer_id, peer) in peer_store.load_known_peers()? {
+            if self.known_peers.is_stale(&peer, now) {
+                peer_store.remove_known_peer(&fuel_peer_id)?;
+            } else {
+                fresh_peers.push((fuel_peer_id, peer));
+            }
+        }
+        drop(peer_store);
+        fresh_peers.sort_by(|(_, a), (_, b)| b.last_seen.cmp(&a.last_seen));
+
+        let mut dialed = 0;
+        for (fuel_peer_id, peer) in fresh_peers {
+            if dialed >= self.known_peers.max_to_dial {
+                break
+            }
+            let Ok(peer_id) = PeerId::from_bytes(fuel_peer_id.as_ref()) else {
+                warn!(target: "fuel-p2p", "Failed to read PeerId from {fuel_peer_id:?}");
+                continue
+            };
+            // The bootstrap nodes are already dialed
+            if self.peer_reputation.is_banned(&peer_id)
+                || self.p2p_service.get_peer_info(&peer_id).is_some()
+            {
+                continue
+            }
+            let Some(address) = peer
+                .addresses
+                .into_iter()
+                .find_map(|address| Multiaddr::try_from(address).ok())
+            else {
+                continue
+            };
+
+            tracing::debug!(target: "fuel-p2p", "Dialing the known peer {peer_id} at {address}");
+            // The result of the dial isn't awaited, the connection is reported as an event
+            let (sender, _) = oneshot::channel();
+            self.p2p_service.dial(address, sender);
+            dialed = dialed.saturating_add(1);
+        }
+
+        Ok(())
+    }
+
+    /// Restores the bans persisted before the restart of the node.
+    /// Already expired bans are removed from the database.
+    fn restore_peer_bans(&mut self) -> anyhow::Result<()> {
+        let now = SystemTime::now();
+        let mut peer_store = self.peer_store.lock().expect("poisoned");
+        let bans = peer_store.load_peer_bans()?;
+
+        for (fuel_peer_id, ban) in bans {
+            let Ok(remaining) = ban.expiry.duration_since(now) else {
+                peer_store.remove_peer_ban(&fuel_peer_id)?;
+                continue
+            };
+            let Ok(peer_id) = PeerId::from_bytes(fuel_peer_id.as_ref()) else {
+                warn!(target: "fuel-p2p", "Failed to read PeerId from {fuel_peer_id:?}");
+                continue
+            };
+
+            tracing::info!(target: "fuel-p2p", "Restoring the ban of {peer_id} for {remaining:?}");
+            self.peer_reputation.ban(peer_id, remaining);
+            self.p2p_service.ban_peer(peer_id);
+        }
+
+        Ok(())
+    }
+}
+
+fn convert_peer_id(peer_id: &PeerId) -> anyhow::Result<FuelPeerId> {
+    let inner = Vec::from(*peer_id);
+    Ok(FuelPeerId::from(inner))
+}
+
+#[async_trait::async_trait]
+impl<V, S> RunnableService for UninitializedTask<V, SharedState, S>
+where
     V: AtomicView + 'static,
-    V::View: P2pDb,
+    V::View: P2pDb + 'static,
+    S: P2pPeerStore,
 {
     const NAME: &'static str = "P2P";
 
     type SharedData = SharedState;
-    type Task = Task<FuelP2PService, V, SharedState>;
+    type Task = Task<FuelP2PService, V, SharedState, S>;
     type TaskParams = ();
 
     fn shared_data(&self) -> Self::SharedData {
@@ -441,6 +2458,7 @@ where
         let Self {
             chain_id,
             view_provider,
+            peer_store,
             next_block_height,
             request_receiver,
             broadcast,
@@ -450,14 +2468,42 @@ where
         let view = view_provider.latest_view();
         let genesis = view.get_genesis()?;
         let config = config.init(genesis)?;
+        let peer_reputation = PeerReputation::new(config.peer_reputation_config.clone());
         let Config {
             max_block_size,
+            compression_threshold,
             max_headers_per_request,
+            block_chunk_size,
+            redundant_request_factor,
             heartbeat_check_interval,
             heartbeat_max_avg_interval,
             heartbeat_max_time_since_last,
+            max_concurrent_db_lookups,
+            max_queued_db_lookups,
+            db_lookup_responses_capacity,
+            shutdown_grace_period,
+            max_start_retries,
+            start_retry_backoff,
+            consumer_gone_threshold,
+            tx_batch_interval,
+            tx_batch_max_size,
+            max_tx_gossip_size,
+            max_tx_batch_gossip_size,
+            startup_gossip_buffer_size,
+            gossip_duplicates_cache_size,
+            gossip_duplicates_ttl,
+            min_peers_for_ready,
+            request_queue_timeout,
+            request_queue_capacity,
+            known_peers_max_age,
+            max_known_peers_to_dial,
+            known_peers_save_interval,
+            metrics,
             ..
         } = config;
+        let redundant_request_factor = redundant_request_factor
+            .try_into()
+            .expect("u32 should always fit into usize");
 
         // Hardcoded for now, but left here to be configurable in the future.
         // TODO: https://github.com/FuelLabs/fuel-core/issues/1340
@@ -466,48 +2512,150 @@ where
             low_heartbeat_frequency_penalty: -5.,
         };
 
-        let mut p2p_service = FuelP2PService::new(
-            broadcast.reserved_peers_broadcast.clone(),
+        let codec = PostcardCodec::new(max_block_size)
+            .with_compression_threshold(compression_threshold)
+            .with_max_gossip_size(GossipTopicTag::NewTx, max_tx_gossip_size)
+            .with_max_gossip_size(GossipTopicTag::NewTxBatch, max_tx_batch_gossip_size);
+        let p2p_service = start_p2p_service_with_retries(
+            &broadcast.reserved_peers_broadcast,
             config,
-            PostcardCodec::new(max_block_size),
-        );
-        p2p_service.start().await?;
-
+            codec,
+            max_start_retries,
+            start_retry_backoff,
+        )
+        .await?;
+
+        let gossip_counters = broadcast.gossip_counters.clone();
+        let local_peer_id = broadcast.local_peer_id;
         let next_check_time =
             Instant::now().checked_add(heartbeat_check_interval).expect(
                 "The heartbeat check interval should be small enough to do frequently",
             );
 
-        let task = Task {
+        let mut task = Task {
             chain_id,
             p2p_service,
             view_provider,
+            peer_store: Arc::new(Mutex::new(peer_store)),
+            peer_store_writes: vec![],
             request_receiver,
             next_block_height,
             broadcast,
             max_headers_per_request,
+            block_chunk_size,
+            redundant_request_factor,
             heartbeat_check_interval,
             heartbeat_max_avg_interval,
             heartbeat_max_time_since_last,
             next_check_time,
             heartbeat_peer_reputation_config,
+            peer_reputation,
+            response_caches: ResponseCaches::default(),
+            db_lookups: LookupPool::new(
+                max_concurrent_db_lookups,
+                max_queued_db_lookups,
+                db_lookup_responses_capacity,
+                metrics,
+            ),
+            shutdown_grace_period,
+            gossip_counters,
+            received_txs: ResponseCache::new(RECEIVED_TXS_TTL, RECEIVED_TXS_CAPACITY),
+            gossip_duplicates: ResponseCache::new(
+                gossip_duplicates_ttl,
+                gossip_duplicates_cache_size,
+            ),
+            metrics,
+            tx_consumer: ConsumerHealth::new(
+                ConsumerChannel::Transactions,
+                consumer_gone_threshold,
+            ),
+            block_height_consumer: ConsumerHealth::new(
+                ConsumerChannel::BlockHeights,
+                consumer_gone_threshold,
+            ),
+            tx_batcher: TxBatcher::new(tx_batch_interval, tx_batch_max_size),
+            batch_reports: BatchReports::default(),
+            startup_gossip: startup_gossip_buffer_size.map(StartupGossipBuffer::new),
+            peer_readiness: PeerReadiness::new(min_peers_for_ready),
+            request_queue: RequestQueue::new(
+                request_queue_timeout,
+                request_queue_capacity,
+                local_peer_id,
+            ),
+            known_peers: KnownPeers::new(
+                known_peers_max_age,
+                max_known_peers_to_dial,
+                known_peers_save_interval,
+            ),
         };
+        task.restore_peer_bans()?;
+        task.restore_known_peers()?;
+        // The listeners got their addresses during the start
+        let addresses = task.p2p_service.listen_addresses();
+        let _ = task
+            .broadcast
+            .network_event_broadcast(NetworkEvent::ListenAddrChanged(addresses));
         Ok(task)
     }
 }
 
+/// Builds and starts the network, retrying with the exponential backoff
+/// if the start fails, e.g. because the port is still held by the previous instance.
+async fn start_p2p_service_with_retries(
+    reserved_peers_broadcast: &broadcast::Sender<usize>,
+    config: Config,
+    codec: PostcardCodec,
+    max_retries: u32,
+    mut backoff: Duration,
+) -> anyhow::Result<FuelP2PService> {
+    let mut retries = 0u32;
+    loop {
+        let result = async {
+            let mut p2p_service = FuelP2PService::new(
+                reserved_peers_broadcast.clone(),
+                config.clone(),
+                codec.clone(),
+            )?;
+            p2p_service.start().await?;
+            Ok::<_, anyhow::Error>(p2p_service)
+        }
+        .await;
+
+        match result {
+            Ok(p2p_service) => return Ok(p2p_service),
+            Err(err) if retries < max_retries => {
+                retries = retries.saturating_add(1);
+                warn!(
+                    "Failed to start the p2p service: {err:?}. \
+                    Retrying in {backoff:?} ({retries}/{max_retries})"
+                );
+                if config.metrics {
+                    p2p_metrics().p2p_service_restarts.inc();
+                }
+                tokio::time::sleep(backoff).await;
+                backoff = backoff.saturating_mul(2);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
 // TODO: Add tests https://github.com/FuelLabs/fuel-core/issues/1275
 #[async_trait::async_trait]
-impl<P, V, B> RunnableTask for Task<P, V, B>
+impl<P, V, B, S> RunnableTask for Task<P, V, B, S>
 where
     P: TaskP2PService + 'static,
     V: AtomicView + 'static,
-    V::View: P2pDb,
+    V::View: P2pDb + 'static,
     B: Broadcast + 'static,
+    S: P2pPeerStore,
 {
     async fn run(&mut self, watcher: &mut StateWatcher) -> anyhow::Result<bool> {
         tracing::debug!("P2P task is running");
         let should_continue;
+        let tx_batch_deadline = self.tx_batcher.deadline;
+        let request_queue_deadline = self.request_queue.next_deadline();
+        let known_peers_save_time = self.known_peers.next_save;
 
         tokio::select! {
             biased;
@@ -516,41 +2664,94 @@ where
                 should_continue = false;
             }
 
+            _ = sleep_until_deadline(tx_batch_deadline) => {
+                should_continue = true;
+                let batch = self.tx_batcher.take();
+                self.publish_tx_batch(batch);
+            }
+
+            _ = sleep_until_deadline(request_queue_deadline) => {
+                should_continue = true;
+                self.request_queue.expire(Instant::now());
+            }
+
+            _ = sleep_until_deadline(known_peers_save_time) => {
+                should_continue = true;
+                self.known_peers.schedule_next_save();
+                self.persist_known_peers();
+            }
+
             next_service_request = self.request_receiver.recv() => {
                 should_continue = true;
                 match next_service_request {
-                    Some(TaskRequest::BroadcastTransaction(transaction)) => {
+                    Some(TaskRequest::BroadcastTransaction { transaction, channel }) => {
                         let tx_id = transaction.id(&self.chain_id);
-                        let broadcast = GossipsubBroadcastRequest::NewTx(transaction);
-                        let result = self.p2p_service.publish_message(broadcast);
-                        if let Err(e) = result {
-                            tracing::error!("Got an error during transaction {} broadcasting {}", tx_id, e);
+                        // The gossiped transaction is forwarded by the gossipsub after the validation,
+                        // publishing it again would only send it back towards its source
+                        if let Some(source) = self.received_txs.get(&tx_id) {
+                            tracing::debug!(target: "fuel-p2p", "Transaction {tx_id} was received from {source}, skipping the broadcast");
+                            if let Some(channel) = channel {
+                                let _ = channel.send(Err(BroadcastError::Duplicate));
+                            }
+                        } else if self.tx_batcher.is_enabled() {
+                            if let Some(batch) = self.tx_batcher.push(transaction, channel) {
+                                self.publish_tx_batch(batch);
+                            }
+                        } else {
+                            let broadcast = GossipsubBroadcastRequest::NewTx(transaction);
+                            let result = self.p2p_service.publish_message(broadcast);
+                            if let Err(e) = &result {
+                                tracing::error!("Got an error during transaction {} broadcasting {}", tx_id, e);
+                            }
+                            if let Some(channel) = channel {
+                                let _ = channel.send(result.map_err(|e| BroadcastError::from(&e)));
+                            }
                         }
                     }
                     Some(TaskRequest::GetPeerIds(channel)) => {
                         let peer_ids = self.p2p_service.get_peer_ids();
                         let _ = channel.send(peer_ids);
                     }
-                    Some(TaskRequest::GetSealedHeaders { block_height_range, channel}) => {
+                    Some(TaskRequest::GetSealedHeaders { block_height_range, from_peer: Some(from_peer), channel }) => {
                         let channel = ResponseSender::SealedHeaders(channel);
-                        let request_msg = RequestMessage::SealedHeaders(block_height_range.clone());
-
-                        // Note: this range has already been checked for
-                        // validity in `SharedState::get_sealed_block_headers`.
-                        let height = BlockHeight::from(block_height_range.end.saturating_sub(1));
-                        let peer = self.p2p_service.get_peer_id_with_height(&height);
-                        if self.p2p_service.send_request_msg(peer, request_msg, channel).is_err() {
-                            tracing::warn!("No peers found for block at height {:?}", height);
+                        let request_msg = RequestMessage::SealedHeaders(block_height_range);
+                        if let Err(err) = self.p2p_service.send_request_msg(Some(from_peer), request_msg, channel) {
+                            tracing::debug!(target: "fuel-p2p", "Failed to request the headers from {from_peer}: {err}");
                         }
                     }
+                    Some(TaskRequest::GetSealedHeaders { block_height_range, from_peer: None, channel }) => {
+                        self.request_sealed_headers(block_height_range, channel);
+                    }
                     Some(TaskRequest::GetTransactions { block_height_range, from_peer, channel }) => {
                         let channel = ResponseSender::Transactions(channel);
                         let request_msg = RequestMessage::Transactions(block_height_range);
-                        self.p2p_service.send_request_msg(Some(from_peer), request_msg, channel).expect("We always a peer here, so send has a target");
+                        if let Err(err) = self.p2p_service.send_request_msg(Some(from_peer), request_msg, channel) {
+                            tracing::debug!(target: "fuel-p2p", "Failed to request the transactions from {from_peer}: {err}");
+                        }
+                    }
+                    Some(TaskRequest::GetTip { from_peer, channel }) => {
+                        let channel = ResponseSender::Tip(channel);
+                        if let Err(err) = self.p2p_service.send_request_msg(Some(from_peer), RequestMessage::Tip, channel) {
+                            tracing::debug!(target: "fuel-p2p", "Failed to request the tip from {from_peer}: {err}");
+                        }
+                    }
+                    Some(TaskRequest::GetBlockChunk { block_height, chunk_index, from_peer, channel }) => {
+                        let channel = ResponseSender::BlockChunk(channel);
+                        let request_msg = RequestMessage::BlockChunk { block_height, chunk_index };
+                        if let Err(err) = self.p2p_service.send_request_msg(Some(from_peer), request_msg, channel) {
+                            tracing::debug!(target: "fuel-p2p", "Failed to request the chunk of the block {block_height} from {from_peer}: {err}");
+                        }
                     }
-                    Some(TaskRequest::RespondWithGossipsubMessageReport((message, acceptance))) => {
-                        // report_message(&mut self.p2p_service, message, acceptance);
-                        self.p2p_service.report_message(message, acceptance)?;
+                    Some(TaskRequest::RespondWithGossipsubMessageReport(GossipsubMessageReport { message_info: message, acceptance })) => {
+                        self.update_peer_reputation(&message.peer_id, acceptance);
+                        if let Some((acceptance, tx_count)) = self.batch_reports.combine(&message.message_id, acceptance) {
+                            self.p2p_service.report_message(message, acceptance)?;
+                            // Only transactions are gossiped, so the accepted messages are forwarded transactions
+                            if acceptance == GossipsubMessageAcceptance::Accept {
+                                let tx_count = u64::try_from(tx_count).unwrap_or(u64::MAX);
+                                self.gossip_counters.new_tx_forwarded.fetch_add(tx_count, Ordering::Relaxed);
+                            }
+                        }
                     }
                     Some(TaskRequest::RespondWithPeerReport { peer_id, score, reporting_service }) => {
                         let _ = self.p2p_service.report_peer(peer_id, score, reporting_service);
@@ -562,6 +2763,59 @@ where
                             .collect::<Vec<_>>();
                         let _ = channel.send(peers);
                     }
+                    Some(TaskRequest::GetPeerInfo { peer_id, channel }) => {
+                        let peer_info = self.p2p_service.get_peer_info(&peer_id).cloned();
+                        let _ = channel.send(peer_info);
+                    }
+                    Some(TaskRequest::GetPeerCounts { channel }) => {
+                        let _ = channel.send(self.p2p_service.get_peer_counts());
+                    }
+                    Some(TaskRequest::GetListenAddresses { channel }) => {
+                        let _ = channel.send(self.p2p_service.listen_addresses());
+                    }
+                    Some(TaskRequest::GetPeerReputationScores { channel }) => {
+                        let _ = channel.send(self.peer_reputation.scores());
+                    }
+                    Some(TaskRequest::GetPeerLatencies { channel }) => {
+                        let latencies = self.p2p_service.get_all_peer_info()
+                            .into_iter()
+                            .filter_map(|(id, info)| Some((*id, info.average_response_time?)))
+                            .collect();
+                        let _ = channel.send(latencies);
+                    }
+                    Some(TaskRequest::AddReservedPeer { peer_id, address }) => {
+                        self.p2p_service.add_reserved_peer(peer_id, address);
+                    }
+                    Some(TaskRequest::RemoveReservedPeer { peer_id }) => {
+                        self.p2p_service.remove_reserved_peer(peer_id);
+                    }
+                    Some(TaskRequest::SetTopicSubscription { topic, subscribe }) => {
+                        if let Err(err) = self.p2p_service.set_topic_subscription(topic, subscribe) {
+                            warn!(target: "fuel-p2p", "Failed to change the subscription to {topic:?}: {err}");
+                        }
+                    }
+                    Some(TaskRequest::ForwardGossip { message_id, channel }) => {
+                        let result = self.p2p_service.forward_gossip(&MessageId::new(&message_id));
+                        let _ = channel.send(result);
+                    }
+                    Some(TaskRequest::ConsumersReady) => {
+                        self.flush_startup_gossip();
+                    }
+                    Some(TaskRequest::SetDiscovery { mdns, kad }) => {
+                        self.p2p_service.set_discovery(mdns, kad);
+                    }
+                    Some(TaskRequest::GetDiscoveryStatus { channel }) => {
+                        let _ = channel.send(self.p2p_service.discovery_status());
+                    }
+                    Some(TaskRequest::Dial { address, channel }) => {
+                        self.p2p_service.dial(address, channel);
+                    }
+                    Some(TaskRequest::FindPeers { count, channel }) => {
+                        self.p2p_service.find_peers(count, channel);
+                    }
+                    Some(TaskRequest::HasLocalBlock { height, channel }) => {
+                        let _ = channel.send(self.view_provider.latest_view().has_block(&height));
+                    }
                     None => {
                         unreachable!("The `Task` is holder of the `Sender`, so it should not be possible");
                     }
@@ -571,683 +2825,4102 @@ where
                 should_continue = true;
                 match p2p_event {
                     Some(FuelP2PEvent::PeerInfoUpdated { peer_id, block_height }) => {
-                        let peer_id: Vec<u8> = peer_id.into();
+                        let peer_id_bytes: Vec<u8> = peer_id.into();
                         let block_height_data = BlockHeightHeartbeatData {
-                            peer_id: peer_id.into(),
+                            peer_id: peer_id_bytes.into(),
                             block_height,
                         };
 
-                        let _ = self.broadcast.block_height_broadcast(block_height_data);
+                        let result = self.broadcast.block_height_broadcast(block_height_data);
+                        if self.block_height_consumer.record(&result) {
+                            tracing::error!(target: "fuel-p2p", "The consumer of the block heights is gone");
+                            let _ = self.broadcast.network_event_broadcast(NetworkEvent::ConsumerGone(ConsumerChannel::BlockHeights));
+                        }
+                        let _ = self.broadcast.network_event_broadcast(NetworkEvent::PeerInfoUpdated { peer_id, block_height });
+                        self.dispatch_queued_requests();
+                    }
+                    Some(FuelP2PEvent::PeerConnected(peer_id)) => {
+                        let _ = self.broadcast.network_event_broadcast(NetworkEvent::PeerConnected(peer_id));
+                        self.dispatch_queued_requests();
+                        if let Some(ready) = self.peer_readiness.peer_connected(peer_id) {
+                            let _ = self.broadcast.network_event_broadcast(NetworkEvent::PeersReady(ready));
+                        }
+                    }
+                    Some(FuelP2PEvent::PeerDisconnected(peer_id)) => {
+                        let _ = self.broadcast.network_event_broadcast(NetworkEvent::PeerDisconnected(peer_id));
+                        if let Some(ready) = self.peer_readiness.peer_disconnected(&peer_id) {
+                            let _ = self.broadcast.network_event_broadcast(NetworkEvent::PeersReady(ready));
+                        }
+                    }
+                    Some(FuelP2PEvent::ListenAddrChanged) => {
+                        let addresses = self.p2p_service.listen_addresses();
+                        let _ = self.broadcast.network_event_broadcast(NetworkEvent::ListenAddrChanged(addresses));
                     }
                     Some(FuelP2PEvent::GossipsubMessage { message, message_id, peer_id,.. }) => {
-                        let message_id = message_id.0;
+                        self.handle_gossip_message(message, peer_id, message_id.0);
+                    },
+                    Some(FuelP2PEvent::GossipDecodeError { peer_id, topic_hash }) => {
+                        tracing::debug!(target: "fuel-p2p", "Peer {peer_id} sent an undecodable message to {topic_hash}");
+                        // Undecodable messages are treated as invalid ones
+                        let peer_id = FuelPeerId::from(peer_id.to_bytes());
+                        self.update_peer_reputation(&peer_id, GossipsubMessageAcceptance::Reject);
+                    },
+                    Some(FuelP2PEvent::InboundRequestMessage { request_message, request_id }) => {
+                        self.process_request(request_message, request_id);
+                    },
+                    Some(FuelP2PEvent::ChainMismatch(peer_id)) => {
+                        // The peer is remembered for a while, so it isn't redialed in a loop
+                        self.peer_reputation.ban(peer_id, self.peer_reputation.ban_duration());
+                        self.p2p_service.ban_peer(peer_id);
+                    },
+                    _ => (),
+                }
+            },
+            Some(result) = self.db_lookups.next_result() => {
+                should_continue = true;
+                self.send_lookup_response(result)?;
+            },
+            _  = tokio::time::sleep_until(self.next_check_time) => {
+                should_continue = true;
+                let res = self.peer_heartbeat_reputation_checks();
+                match res {
+                    Ok(_) => tracing::debug!("Peer heartbeat reputation checks completed"),
+                    Err(e) => {
+                        tracing::error!("Failed to perform peer heartbeat reputation checks: {:?}", e);
+                    }
+                }
+                self.next_check_time += self.heartbeat_check_interval;
+            },
+            _ = tokio::time::sleep_until(self.peer_reputation.next_decay_time()) => {
+                should_continue = true;
+                for peer_id in self.peer_reputation.decay() {
+                    tracing::debug!(target: "fuel-p2p", "The ban of {peer_id} has expired");
+                    self.p2p_service.unban_peer(peer_id);
+                }
+            },
+            latest_block_height = self.next_block_height.next() => {
+                if let Some(latest_block_height) = latest_block_height {
+                    let _ = self.p2p_service.update_block_height(latest_block_height);
+                    should_continue = true;
+                } else {
+                    should_continue = false;
+                }
+            }
+        }
+
+        tracing::debug!("P2P task is finished");
+        Ok(should_continue)
+    }
+
+    async fn shutdown(mut self) -> anyhow::Result<()> {
+        // Other modules can't send new requests anymore
+        self.request_receiver.close();
+        // The peers are still connected, so they are saved with the current time
+        self.persist_known_peers();
+        self.await_peer_store_writes().await;
+        // The buffered transactions are published before leaving the topics
+        let batch = self.tx_batcher.take();
+        self.publish_tx_batch(batch);
+        // The unsubscriptions are sent to the peers while the requests are drained
+        self.p2p_service.unsubscribe_from_all_topics();
+
+        let grace_period = self.shutdown_grace_period;
+        let drained =
+            tokio::time::timeout(grace_period, self.drain_inbound_requests()).await;
+
+        // `FuelP2PService` doesn't support graceful shutdown(with informing of connected peers).
+        // https://github.com/libp2p/specs/blob/master/ROADMAP.md#%EF%B8%8F-polite-peering
+        // Dropping of the `FuelP2PService` will close all connections.
+
+        if drained.is_err() {
+            return Err(anyhow!(
+                "The inbound requests weren't answered within {grace_period:?}"
+            ))
+        }
+        tracing::info!("All inbound requests are answered before the shutdown");
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct SharedState {
+    /// Sender of p2p transaction used for subscribing.
+    tx_broadcast: broadcast::Sender<TransactionGossipData>,
+    tx_overflow: ChannelOverflow,
+    /// Sender of reserved peers connection updates.
+    reserved_peers_broadcast: broadcast::Sender<usize>,
+    /// Used for communicating with the `Task`.
+    request_sender: mpsc::Sender<TaskRequest>,
+    request_overflow: ChannelOverflow,
+    /// Sender of p2p blopck height data
+    block_height_broadcast: broadcast::Sender<BlockHeightHeartbeatData>,
+    block_height_overflow: ChannelOverflow,
+    /// Sender of the changes of the network state
+    network_event_broadcast: broadcast::Sender<NetworkEvent>,
+    /// The id of the node in the p2p network
+    local_peer_id: PeerId,
+    gossip_counters: Arc<GossipCounters>,
+    /// The addresses from the latest `NetworkEvent::ListenAddrChanged`
+    listen_addresses: Arc<Mutex<Vec<Multiaddr>>>,
+    /// The readiness from the latest `NetworkEvent::PeersReady`
+    peers_ready: Arc<AtomicBool>,
+    block_chunks: Arc<BlockChunkLimits>,
+    metrics: bool,
+}
+
+impl SharedState {
+    /// Sends the request to the task, applying the `request_overflow_policy`
+    /// once `request_channel_capacity` requests are waiting.
+    /// The dropped request fails with [`P2pError::QueueFull`].
+    async fn send_request(&self, request: TaskRequest) -> Result<(), P2pError> {
+        let queued = self
+            .request_sender
+            .max_capacity()
+            .saturating_sub(self.request_sender.capacity());
+        if !self.request_overflow.admit(queued) {
+            return Err(P2pError::QueueFull)
+        }
+
+        match self.request_overflow.policy {
+            OverflowPolicy::Block => self.request_sender.send(request).await?,
+            OverflowPolicy::DropNewest | OverflowPolicy::DropOldest => {
+                self.request_sender.try_send(request)?
+            }
+        }
+        Ok(())
+    }
+
+    pub fn notify_gossip_transaction_validity(
+        &self,
+        message_info: GossipsubMessageInfo,
+        acceptance: GossipsubMessageAcceptance,
+    ) -> Result<(), P2pError> {
+        self.request_sender
+            .try_send(TaskRequest::RespondWithGossipsubMessageReport(
+                GossipsubMessageReport {
+                    message_info,
+                    acceptance,
+                },
+            ))?;
+        Ok(())
+    }
+
+    pub async fn get_sealed_block_headers(
+        &self,
+        block_height_range: Range<u32>,
+    ) -> Result<(Vec<u8>, Option<Vec<SealedBlockHeader>>), P2pError> {
+        let (sender, receiver) = oneshot::channel();
+
+        if block_height_range.is_empty() {
+            return Err(P2pError::EmptyRange)
+        }
+
+        self.send_request(TaskRequest::GetSealedHeaders {
+            block_height_range,
+            from_peer: None,
+            channel: sender,
+        })
+        .await?;
+
+        let (peer_id, response) = receiver.await?;
+
+        let data = match response {
+            Ok(data) => data,
+            // The peer explicitly told us that it doesn't have the headers
+            Err(ResponseError::NotFound) => return Err(P2pError::NotFound(peer_id)),
+            Err(e) => return Err(e.into()),
+        };
+        Ok((peer_id.to_bytes(), data))
+    }
+
+    /// Requests the headers from the peer picked by the caller, instead of the service.
+    /// Fails with [`P2pError::PeerNotConnected`] if the peer isn't connected.
+    pub async fn get_sealed_block_headers_from_peer(
+        &self,
+        peer_id: Vec<u8>,
+        block_height_range: Range<u32>,
+    ) -> Result<Option<Vec<SealedBlockHeader>>, P2pError> {
+        let (sender, receiver) = oneshot::channel();
+        let from_peer =
+            PeerId::from_bytes(&peer_id).map_err(|_| P2pError::InvalidPeerId(peer_id))?;
+
+        if block_height_range.is_empty() {
+            return Err(P2pError::EmptyRange)
+        }
+
+        self.send_request(TaskRequest::GetSealedHeaders {
+            block_height_range,
+            from_peer: Some(from_peer),
+            channel: sender,
+        })
+        .await?;
+
+        let (_, response) = receiver.await?;
+        match response {
+            Ok(data) => Ok(data),
+            // The peer explicitly told us that it doesn't have the headers
+            Err(ResponseError::NotFound) => Err(P2pError::NotFound(from_peer)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Streams the headers of the `block_height_range` in the height order.
+    ///
+    /// The range is split into chunks of `chunk_size` headers, which are requested
+    /// from the connected peers in turns. At most `window` chunks are in flight,
+    /// and the next chunks are requested only when the consumer polls the stream.
+    /// A chunk too large for one response is requested in halves from the same peer,
+    /// down to the single blocks, which are transferred in chunks of bytes.
+    /// A failed chunk is retried with the other peers; the stream ends with
+    /// [`P2pError::HeadersUnavailable`] if none of them served it.
+    pub fn stream_sealed_block_headers(
+        &self,
+        block_height_range: Range<u32>,
+        chunk_size: u32,
+        window: usize,
+    ) -> BoxStream<Result<SealedBlockHeader, P2pError>> {
+        if block_height_range.is_empty() {
+            return futures::stream::once(async { Err(P2pError::EmptyRange) }).into_boxed()
+        }
+
+        let shared_state = self.clone();
+        let end = block_height_range.end;
+        let chunk_size = chunk_size.max(1);
+        let chunks =
+            std::iter::successors(Some(block_height_range.start), move |start| {
+                start.checked_add(chunk_size).filter(|next| *next < end)
+            })
+            .map(move |start| start..start.saturating_add(chunk_size).min(end))
+            .enumerate();
+
+        futures::stream::iter(chunks)
+            .map(move |(index, range)| {
+                shared_state.clone().fetch_sealed_block_headers(index, range)
+            })
+            .buffered(window.max(1))
+            .flat_map(|chunk| {
+                let headers: Vec<_> = match chunk {
+                    Ok(headers) => headers.into_iter().map(Ok).collect(),
+                    Err(e) => vec![Err(e)],
+                };
+                futures::stream::iter(headers)
+            })
+            // The headers after the failed chunk are not contiguous anymore
+            .scan(false, |failed, header| {
+                let item = (!*failed).then_some(header);
+                *failed = matches!(item, Some(Err(_)));
+                futures::future::ready(item)
+            })
+            .into_boxed()
+    }
+
+    /// Requests the chunk of the headers for the [`Self::stream_sealed_block_headers`],
+    /// starting from the `index`-th connected peer and moving to the next one on failure.
+    async fn fetch_sealed_block_headers(
+        self,
+        index: usize,
+        range: Range<u32>,
+    ) -> Result<Vec<SealedBlockHeader>, P2pError> {
+        let peers = self.get_peer_ids().await?;
+        let first_peer = index.checked_rem(peers.len()).unwrap_or_default();
+
+        for peer_id in peers.iter().cycle().skip(first_peer).take(peers.len()) {
+            match self
+                .get_sealed_block_headers_splitting(peer_id, range.clone())
+                .await
+            {
+                Ok(Some(headers)) if is_headers_chunk(&headers, &range) => {
+                    return Ok(headers)
+                }
+                Ok(_) => {
+                    warn!(target: "fuel-p2p", "Peer {peer_id} didn't serve the headers {range:?}");
+                }
+                Err(e) => {
+                    warn!(target: "fuel-p2p", "Failed to get the headers {range:?} from {peer_id}: {e}");
+                }
+            }
+        }
+        Err(P2pError::HeadersUnavailable(range))
+    }
+
+    /// Requests the headers from the peer, splitting the range in halves
+    /// while the peer responds that the headers don't fit into one response.
+    async fn get_sealed_block_headers_splitting(
+        &self,
+        peer_id: &PeerId,
+        range: Range<u32>,
+    ) -> Result<Option<Vec<SealedBlockHeader>>, P2pError> {
+        let mut pending = vec![range];
+        let mut headers = vec![];
+        while let Some(range) = pending.pop() {
+            match self
+                .get_sealed_block_headers_from_peer(peer_id.to_bytes(), range.clone())
+                .await
+            {
+                Ok(Some(chunk)) => headers.extend(chunk),
+                Err(P2pError::ResponseTooLarge { size })
+                    if range.end.saturating_sub(range.start) > 1 =>
+                {
+                    tracing::debug!(target: "fuel-p2p", "The headers {range:?} of {size} bytes are split for {peer_id}");
+                    let (first_half, second_half) = split_range(&range);
+                    // The first half is popped first, keeping the height order
+                    pending.push(second_half);
+                    pending.push(first_half);
+                }
+                Err(P2pError::ResponseTooLarge { size }) => {
+                    tracing::debug!(target: "fuel-p2p", "The block {} of {size} bytes is requested in chunks from {peer_id}", range.start);
+                    let (header, _) = self
+                        .get_block_from_peer(peer_id.to_bytes(), range.start)
+                        .await?;
+                    headers.push(header);
+                }
+                result => return result,
+            }
+        }
+        Ok(Some(headers))
+    }
+
+    /// Requests the transactions of the range from the peer.
+    /// The range too large for one response is requested in halves,
+    /// and the single block too large for one response in chunks.
+    pub async fn get_transactions_from_peer(
+        &self,
+        peer_id: Vec<u8>,
+        range: Range<u32>,
+    ) -> Result<Option<Vec<Transactions>>, P2pError> {
+        let from_peer =
+            PeerId::from_bytes(&peer_id).map_err(|_| P2pError::InvalidPeerId(peer_id))?;
+
+        let mut pending = vec![range];
+        let mut transactions = vec![];
+        while let Some(range) = pending.pop() {
+            match self
+                .request_transactions_from_peer(from_peer, range.clone())
+                .await
+            {
+                Ok(Some(chunk)) => transactions.extend(chunk),
+                Err(P2pError::ResponseTooLarge { size })
+                    if range.end.saturating_sub(range.start) > 1 =>
+                {
+                    tracing::debug!(target: "fuel-p2p", "The transactions {range:?} of {size} bytes are split for {from_peer}");
+                    let (first_half, second_half) = split_range(&range);
+                    // The first half is popped first, keeping the height order
+                    pending.push(second_half);
+                    pending.push(first_half);
+                }
+                Err(P2pError::ResponseTooLarge { size }) => {
+                    tracing::debug!(target: "fuel-p2p", "The block {} of {size} bytes is requested in chunks from {from_peer}", range.start);
+                    let (_, block_transactions) = self
+                        .get_block_from_peer(from_peer.to_bytes(), range.start)
+                        .await?;
+                    transactions.push(block_transactions);
+                }
+                result => return result,
+            }
+        }
+        Ok(Some(transactions))
+    }
+
+    async fn request_transactions_from_peer(
+        &self,
+        from_peer: PeerId,
+        range: Range<u32>,
+    ) -> Result<Option<Vec<Transactions>>, P2pError> {
+        let (sender, receiver) = oneshot::channel();
+        let request = TaskRequest::GetTransactions {
+            block_height_range: range,
+            from_peer,
+            channel: sender,
+        };
+        self.send_request(request).await?;
+
+        let (response_from_peer, response) = receiver.await?;
+        if response_from_peer != from_peer {
+            return Err(P2pError::UnexpectedResponder {
+                requested: from_peer,
+                responded: response_from_peer,
+            })
+        }
+
+        match response {
+            Ok(data) => Ok(data),
+            // The peer explicitly told us that it doesn't have the transactions
+            Err(ResponseError::NotFound) => Err(P2pError::NotFound(from_peer)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Requests the block too large for one response from the peer in chunks.
+    /// At most `max_block_chunks_in_flight` chunks are requested from the peer at once.
+    /// Fails with [`P2pError::BlockChunksTimeout`] if the block isn't received
+    /// within `block_chunks_timeout`.
+    pub async fn get_block_from_peer(
+        &self,
+        peer_id: Vec<u8>,
+        block_height: u32,
+    ) -> Result<(SealedBlockHeader, Transactions), P2pError> {
+        let from_peer =
+            PeerId::from_bytes(&peer_id).map_err(|_| P2pError::InvalidPeerId(peer_id))?;
+        let limits = &self.block_chunks;
+
+        let transfer = async {
+            let first = self.get_block_chunk(from_peer, block_height, 0).await?;
+            if first.data.is_empty() {
+                return Err(P2pError::InvalidBlockChunks("the empty chunk"))
+            }
+            // All chunks but the last one are of the same size,
+            // so the peer can't make us request more chunks than the max block size allows
+            let max_chunk_count = limits.max_block_size.div_ceil(first.data.len());
+            if first.chunk_count == 0
+                || usize::try_from(first.chunk_count).unwrap_or(usize::MAX)
+                    > max_chunk_count
+            {
+                return Err(P2pError::InvalidBlockChunks("too many chunks"))
+            }
+
+            let rest: Vec<_> = futures::stream::iter(1..first.chunk_count)
+                .map(|chunk_index| {
+                    self.get_block_chunk(from_peer, block_height, chunk_index)
+                })
+                .buffered(limits.max_in_flight)
+                .try_collect()
+                .await?;
+            let chunks = std::iter::once(first).chain(rest).collect();
+            reassemble_block(block_height, chunks, limits.max_block_size)
+        };
+
+        let block = tokio::time::timeout(limits.timeout, transfer)
+            .await
+            .map_err(|_| P2pError::BlockChunksTimeout)??;
+        Ok((block.header, block.transactions))
+    }
+
+    /// Requests the chunk of the block, waiting for a free permit of the peer
+    async fn get_block_chunk(
+        &self,
+        from_peer: PeerId,
+        block_height: u32,
+        chunk_index: u32,
+    ) -> Result<BlockChunk, P2pError> {
+        let permits = self.block_chunks.permits(from_peer);
+        let _permit = permits
+            .acquire()
+            .await
+            .expect("The permits of the peer are never closed");
+
+        let (sender, receiver) = oneshot::channel();
+        self.send_request(TaskRequest::GetBlockChunk {
+            block_height,
+            chunk_index,
+            from_peer,
+            channel: sender,
+        })
+        .await?;
+
+        let (response_from_peer, response) = receiver.await?;
+        if response_from_peer != from_peer {
+            return Err(P2pError::UnexpectedResponder {
+                requested: from_peer,
+                responded: response_from_peer,
+            })
+        }
+        match response {
+            Ok(chunk) => Ok(chunk),
+            Err(ResponseError::NotFound) => Err(P2pError::NotFound(from_peer)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Asks the peer for the height and the id of its latest block,
+    /// so the node knows how far ahead the peer is before syncing
+    pub async fn get_tip_from_peer(
+        &self,
+        peer_id: Vec<u8>,
+    ) -> Result<Option<(BlockHeight, BlockId)>, P2pError> {
+        let (sender, receiver) = oneshot::channel();
+        let from_peer =
+            PeerId::from_bytes(&peer_id).map_err(|_| P2pError::InvalidPeerId(peer_id))?;
+
+        self.send_request(TaskRequest::GetTip {
+            from_peer,
+            channel: sender,
+        })
+        .await?;
+
+        let (_, response) = receiver.await?;
+        match response {
+            Ok(tip) => Ok(tip),
+            // The peer doesn't have any blocks yet
+            Err(ResponseError::NotFound) => Err(P2pError::NotFound(from_peer)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn broadcast_transaction(
+        &self,
+        transaction: Arc<Transaction>,
+    ) -> Result<(), P2pError> {
+        self.request_sender
+            .try_send(TaskRequest::BroadcastTransaction {
+                transaction,
+                channel: None,
+            })?;
+        Ok(())
+    }
+
+    /// Broadcasts the transaction and waits until it is published.
+    /// The batched transaction is published with its batch.
+    /// The publishing failure is returned as [`P2pError::Broadcast`].
+    pub async fn publish_transaction(
+        &self,
+        transaction: Arc<Transaction>,
+    ) -> Result<MessageId, P2pError> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.send_request(TaskRequest::BroadcastTransaction {
+            transaction,
+            channel: Some(sender),
+        })
+        .await?;
+
+        let message_id = receiver.await??;
+        Ok(message_id)
+    }
+
+    pub async fn get_peer_ids(&self) -> Result<Vec<PeerId>, P2pError> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.send_request(TaskRequest::GetPeerIds(sender)).await?;
+
+        Ok(receiver.await?)
+    }
+
+    pub async fn get_all_peers(&self) -> Result<Vec<(PeerId, PeerInfo)>, P2pError> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.send_request(TaskRequest::GetAllPeerInfo { channel: sender })
+            .await?;
+
+        Ok(receiver.await?)
+    }
+
+    pub async fn get_peer_info(
+        &self,
+        peer_id: PeerId,
+    ) -> Result<Option<PeerInfo>, P2pError> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.send_request(TaskRequest::GetPeerInfo {
+            peer_id,
+            channel: sender,
+        })
+        .await?;
+
+        Ok(receiver.await?)
+    }
+
+    pub async fn get_peer_counts(&self) -> Result<PeerCounts, P2pError> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.send_request(TaskRequest::GetPeerCounts { channel: sender })
+            .await?;
+
+        Ok(receiver.await?)
+    }
+
+    /// The id derived from the configured keypair when the service is created,
+    /// so it is known before the start and doesn't change after the restarts.
+    pub fn local_peer_id(&self) -> PeerId {
+        self.local_peer_id
+    }
+
+    /// Returns the addresses reported by the task with the latest
+    /// `NetworkEvent::ListenAddrChanged`, without the round trip to the task.
+    /// They are known once the service is started.
+    pub fn listen_addresses(&self) -> Vec<Multiaddr> {
+        self.listen_addresses.lock().expect("poisoned").clone()
+    }
+
+    /// Returns the addresses the node listens on and the address observed by the peers,
+    /// including its peer id. Other nodes can use them as the bootstrap nodes.
+    pub async fn get_listen_addresses(&self) -> Result<Vec<Multiaddr>, P2pError> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.send_request(TaskRequest::GetListenAddresses { channel: sender })
+            .await?;
+
+        Ok(receiver.await?)
+    }
+
+    pub async fn get_peer_reputation_scores(
+        &self,
+    ) -> Result<Vec<(PeerId, AppScore)>, P2pError> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.send_request(TaskRequest::GetPeerReputationScores { channel: sender })
+            .await?;
+
+        Ok(receiver.await?)
+    }
+
+    /// Returns the average time it took each peer to answer our requests.
+    /// The peers that haven't answered any request yet are skipped.
+    pub async fn get_peer_latencies(&self) -> Result<Vec<(PeerId, Duration)>, P2pError> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.send_request(TaskRequest::GetPeerLatencies { channel: sender })
+            .await?;
+
+        Ok(receiver.await?)
+    }
+
+    /// Adds the peer to the reserved ones, so the connection with it is kept at all times
+    pub fn add_reserved_peer(
+        &self,
+        peer_id: PeerId,
+        address: Multiaddr,
+    ) -> Result<(), P2pError> {
+        self.request_sender
+            .try_send(TaskRequest::AddReservedPeer { peer_id, address })?;
+        Ok(())
+    }
+
+    /// Notifies that the consumers of the gossip are ready,
+    /// so the gossip received during the startup is delivered to them
+    pub fn notify_consumers_ready(&self) -> Result<(), P2pError> {
+        self.request_sender.try_send(TaskRequest::ConsumersReady)?;
+        Ok(())
+    }
+
+    /// Starts or stops the discovery on the local network(mDNS) and via the DHT(Kademlia).
+    /// The mechanisms passed as `None` stay as they are.
+    pub fn set_discovery(
+        &self,
+        mdns: Option<bool>,
+        kad: Option<bool>,
+    ) -> Result<(), P2pError> {
+        self.request_sender
+            .try_send(TaskRequest::SetDiscovery { mdns, kad })?;
+        Ok(())
+    }
+
+    /// Connects to the node listening on the address.
+    /// Returns its peer id once the connection is established.
+    pub async fn dial(&self, address: Multiaddr) -> Result<PeerId, P2pError> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.send_request(TaskRequest::Dial {
+            address: address.clone(),
+            channel: sender,
+        })
+        .await?;
+
+        receiver.await?.map_err(|source| P2pError::DialFailed {
+            address,
+            source: Box::new(source),
+        })
+    }
+
+    /// Queries the Kademlia DHT for up to `count` peers, including the ones
+    /// the node isn't connected to. Fails if Kademlia is disabled.
+    pub async fn find_peers(&self, count: usize) -> Result<Vec<PeerId>, P2pError> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.send_request(TaskRequest::FindPeers {
+            count,
+            channel: sender,
+        })
+        .await?;
+
+        receiver.await?.ok_or(P2pError::KademliaDisabled)
+    }
+
+    /// Checks whether the block at the `height` is already stored locally,
+    /// so it doesn't need to be requested from the peers
+    pub async fn has_local_block(&self, height: BlockHeight) -> Result<bool, P2pError> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.send_request(TaskRequest::HasLocalBlock {
+            height,
+            channel: sender,
+        })
+        .await?;
+
+        let has_block = receiver.await??;
+        Ok(has_block)
+    }
+
+    /// Returns the active discovery mechanisms and the number of peers found by them
+    pub async fn get_discovery_status(&self) -> Result<DiscoveryStatus, P2pError> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.send_request(TaskRequest::GetDiscoveryStatus { channel: sender })
+            .await?;
+
+        Ok(receiver.await?)
+    }
+
+    /// Turns the reserved peer into a regular one
+    pub fn remove_reserved_peer(&self, peer_id: PeerId) -> Result<(), P2pError> {
+        self.request_sender
+            .try_send(TaskRequest::RemoveReservedPeer { peer_id })?;
+        Ok(())
+    }
+
+    /// Joins or leaves the gossip topic, e.g. when the role of the node changes
+    pub fn set_topic_subscription(
+        &self,
+        topic: GossipTopicTag,
+        subscribe: bool,
+    ) -> Result<(), P2pError> {
+        self.request_sender
+            .try_send(TaskRequest::SetTopicSubscription { topic, subscribe })?;
+        Ok(())
+    }
+
+    /// Propagates the received gossip message to the mesh peers again,
+    /// without re-encoding it. Fails if the message isn't cached anymore.
+    pub async fn forward_gossip(&self, message_id: Vec<u8>) -> Result<(), P2pError> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.send_request(TaskRequest::ForwardGossip {
+            message_id,
+            channel: sender,
+        })
+        .await?;
+
+        receiver.await?
+    }
+
+    pub fn subscribe_tx(&self) -> TransactionGossipSubscription {
+        TransactionGossipSubscription {
+            receiver: self.tx_broadcast.subscribe(),
+            lagged: 0,
+            metrics: self.metrics,
+        }
+    }
+
+    pub fn subscribe_block_height(
+        &self,
+    ) -> broadcast::Receiver<BlockHeightHeartbeatData> {
+        self.block_height_broadcast.subscribe()
+    }
+
+    pub fn subscribe_reserved_peers_count(&self) -> broadcast::Receiver<usize> {
+        self.reserved_peers_broadcast.subscribe()
+    }
+
+    pub fn subscribe_events(&self) -> broadcast::Receiver<NetworkEvent> {
+        self.network_event_broadcast.subscribe()
+    }
+
+    /// Returns `true` if at least `min_peers_for_ready` peers are connected,
+    /// so the node's view of the network isn't based on too few peers.
+    /// The changes are reported with `NetworkEvent::PeersReady`.
+    pub fn has_enough_peers(&self) -> bool {
+        self.peers_ready.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of the gossip messages processed since the start
+    pub fn gossip_stats(&self) -> GossipStats {
+        self.gossip_counters.snapshot()
+    }
+
+    pub fn report_peer<T: PeerReport>(
+        &self,
+        peer_id: FuelPeerId,
+        peer_report: T,
+        reporting_service: &'static str,
+    ) -> Result<(), P2pError> {
+        match Vec::from(peer_id).try_into() {
+            Ok(peer_id) => {
+                let score = peer_report.get_score_from_report();
+
+                self.request_sender
+                    .try_send(TaskRequest::RespondWithPeerReport {
+                        peer_id,
+                        score,
+                        reporting_service,
+                    })?;
+
+                Ok(())
+            }
+            Err(e) => {
+                warn!(target: "fuel-p2p", "Failed to read PeerId from {e:?}");
+                Err(P2pError::InvalidPeerId(e))
+            }
+        }
+    }
+}
+
+pub fn new_service<V, S, B>(
+    chain_id: ChainId,
+    p2p_config: Config<NotInitialized>,
+    view_provider: V,
+    peer_store: S,
+    block_importer: B,
+) -> Service<V, S>
+where
+    V: AtomicView + 'static,
+    V::View: P2pDb + 'static,
+    S: P2pPeerStore,
+    B: BlockHeightImporter,
+{
+    let task = UninitializedTask::new(
+        chain_id,
+        p2p_config,
+        view_provider,
+        peer_store,
+        block_importer,
+    );
+    Service::new(task)
+}
+
+pub fn to_message_acceptance(
+    acceptance: &GossipsubMessageAcceptance,
+) -> MessageAcceptance {
+    match acceptance {
+        GossipsubMessageAcceptance::Accept => MessageAcceptance::Accept,
+        GossipsubMessageAcceptance::Reject => MessageAcceptance::Reject,
+        GossipsubMessageAcceptance::Ignore => MessageAcceptance::Ignore,
+    }
+}
+
+fn report_message(
+    p2p_service: &mut FuelP2PService,
+    message: GossipsubMessageInfo,
+    acceptance: GossipsubMessageAcceptance,
+) {
+    let GossipsubMessageInfo {
+        peer_id,
+        message_id,
+    } = message;
+
+    if message_id.is_empty() {
+        warn!(target: "fuel-p2p", "Skipping the report of the gossip message with empty MessageId from {:?}", peer_id.as_ref());
+        p2p_service.count_gossip_report_error();
+        return
+    }
+    let msg_id = message_id.into();
+
+    match PeerId::from_bytes(peer_id.as_ref()) {
+        Ok(peer_id) => {
+            let acceptance = to_message_acceptance(&acceptance);
+            p2p_service.report_message_validation_result(&msg_id, peer_id, acceptance);
+        }
+        Err(_) => {
+            warn!(target: "fuel-p2p", "Failed to read PeerId {:?} of the reported GossipsubMessageId: {}", peer_id.as_ref(), msg_id);
+            p2p_service.count_gossip_report_error();
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(non_snake_case)]
+    use crate::ports::{
+        P2pDb,
+        P2pPeerStore,
+    };
+
+    use super::*;
+
+    use crate::peer_manager::heartbeat_data::HeartbeatData;
+    use fuel_core_services::{
+        Service,
+        State,
+    };
+    use fuel_core_storage::Result as StorageResult;
+    use fuel_core_types::{
+        blockchain::{
+            consensus::Genesis,
+            header::BlockHeader,
+        },
+        fuel_tx::TransactionBuilder,
+        fuel_types::BlockHeight,
+        services::p2p::NetworkData,
+    };
+    use futures::FutureExt;
+    use libp2p::{
+        gossipsub::{
+            MessageId,
+            TopicHash,
+        },
+        request_response::OutboundFailure,
+    };
+    use std::{
+        collections::VecDeque,
+        time::SystemTime,
+    };
+
+    #[derive(Clone, Debug, Default)]
+    struct FakeDb {
+        tip: Option<(BlockHeight, BlockId)>,
+        /// The time it takes to read the headers
+        sealed_headers_delay: Duration,
+        /// Notified when the headers are requested
+        lookup_started: Arc<tokio::sync::Notify>,
+    }
+
+    impl AtomicView for FakeDb {
+        type View = Self;
+
+        type Height = BlockHeight;
+
+        fn latest_height(&self) -> Self::Height {
+            BlockHeight::default()
+        }
+
+        fn view_at(&self, _: &BlockHeight) -> StorageResult<Self::View> {
+            unimplemented!()
+        }
+
+        fn latest_view(&self) -> Self::View {
+            self.clone()
+        }
+    }
+
+    impl P2pDb for FakeDb {
+        fn get_sealed_headers(
+            &self,
+            block_height_range: Range<u32>,
+        ) -> StorageResult<Vec<SealedBlockHeader>> {
+            self.lookup_started.notify_one();
+            std::thread::sleep(self.sealed_headers_delay);
+            Ok(block_height_range
+                .map(|_| SealedBlockHeader::default())
+                .collect())
+        }
+
+        // The node doesn't have the transactions of any block
+        fn get_transactions(
+            &self,
+            _block_height_range: Range<u32>,
+        ) -> StorageResult<Option<Vec<Transactions>>> {
+            Ok(None)
+        }
+
+        fn get_genesis(&self) -> StorageResult<Genesis> {
+            Ok(Default::default())
+        }
+
+        fn get_current_tip(&self) -> StorageResult<Option<(BlockHeight, BlockId)>> {
+            Ok(self.tip)
+        }
+
+        fn has_block(&self, _height: &BlockHeight) -> StorageResult<bool> {
+            unimplemented!()
+        }
+    }
+
+    impl P2pPeerStore for FakeDb {
+        fn insert_peer_ban(
+            &mut self,
+            _peer_id: &FuelPeerId,
+            _ban: &PeerBan,
+        ) -> StorageResult<()> {
+            Ok(())
+        }
+
+        fn remove_peer_ban(&mut self, _peer_id: &FuelPeerId) -> StorageResult<()> {
+            Ok(())
+        }
+
+        fn load_peer_bans(&self) -> StorageResult<Vec<(FuelPeerId, PeerBan)>> {
+            Ok(vec![])
+        }
+
+        fn insert_known_peer(
+            &mut self,
+            _peer_id: &FuelPeerId,
+            _peer: &KnownPeer,
+        ) -> StorageResult<()> {
+            Ok(())
+        }
+
+        fn remove_known_peer(&mut self, _peer_id: &FuelPeerId) -> StorageResult<()> {
+            Ok(())
+        }
+
+        fn load_known_peers(&self) -> StorageResult<Vec<(FuelPeerId, KnownPeer)>> {
+            Ok(vec![])
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct FakeBlockImporter;
+
+    impl BlockHeightImporter for FakeBlockImporter {
+        fn next_block_height(&self) -> BoxStream<BlockHeight> {
+            Box::pin(fuel_core_services::stream::pending())
+        }
+    }
+
+    #[tokio::test]
+    async fn start_and_stop_awaits_works() {
+        let p2p_config = Config::<NotInitialized>::default("start_stop_works");
+        let local_peer_id = p2p_config.keypair.public().to_peer_id();
+        let service = new_service(
+            ChainId::default(),
+            p2p_config,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        assert_eq!(service.state(), State::NotStarted);
+        assert_eq!(service.shared.local_peer_id(), local_peer_id);
+
+        // Node with p2p service started
+        assert!(service.start_and_await().await.unwrap().started());
+        // The service is started only after the node listens for connections
+        let listen_addresses = service.shared.get_listen_addresses().await.unwrap();
+        assert!(!listen_addresses.is_empty());
+        assert!(listen_addresses
+            .iter()
+            .all(|address| address.to_string().ends_with(&local_peer_id.to_string())));
+        assert_eq!(service.shared.listen_addresses(), listen_addresses);
+
+        // Node with p2p service stopped
+        assert!(service.stop_and_await().await.unwrap().stopped());
+        assert_eq!(service.state(), State::Stopped);
+        assert!(matches!(
+            service.shared.get_listen_addresses().await,
+            Err(P2pError::ChannelClosed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn local_peer_id__is_stable_across_restarts() {
+        // given
+        let p2p_config =
+            Config::<NotInitialized>::default("local_peer_id__is_stable_across_restarts");
+        let first_run = new_service(
+            ChainId::default(),
+            p2p_config.clone(),
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        let local_peer_id = first_run.shared.local_peer_id();
+
+        // when
+        assert!(first_run.start_and_await().await.unwrap().started());
+        assert_eq!(first_run.shared.local_peer_id(), local_peer_id);
+        assert!(first_run.stop_and_await().await.unwrap().stopped());
+        let second_run = new_service(
+            ChainId::default(),
+            p2p_config,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        assert!(second_run.start_and_await().await.unwrap().started());
+
+        // then
+        assert_eq!(first_run.shared.local_peer_id(), local_peer_id);
+        assert_eq!(second_run.shared.local_peer_id(), local_peer_id);
+        assert!(second_run.stop_and_await().await.unwrap().stopped());
+        assert_eq!(second_run.shared.local_peer_id(), local_peer_id);
+    }
+
+    #[tokio::test]
+    async fn publish_transaction__isolated_node_gets_insufficient_peers() {
+        // given
+        let service = new_service(
+            ChainId::default(),
+            Config::<NotInitialized>::default(
+                "publish_transaction__isolated_node_gets_insufficient_peers",
+            ),
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        service.start_and_await().await.unwrap();
+
+        // when
+        let result = service
+            .shared
+            .publish_transaction(Arc::new(Transaction::default_test_tx()))
+            .await;
+
+        // then
+        assert!(matches!(
+            result,
+            Err(P2pError::Broadcast(BroadcastError::InsufficientPeers))
+        ));
+        service.stop_and_await().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn get_transactions_from_peer__response_from_other_peer_is_an_error() {
+        // given
+        let (request_sender, mut request_receiver) = mpsc::channel(1);
+        let shared_state = shared_state_with_sender(request_sender);
+        let requested = PeerId::random();
+        let other = PeerId::random();
+        tokio::spawn(async move {
+            if let Some(TaskRequest::GetTransactions { channel, .. }) =
+                request_receiver.recv().await
+            {
+                let _ = channel.send((other, Ok(None)));
+            }
+        });
+
+        // when
+        let result = shared_state
+            .get_transactions_from_peer(requested.to_bytes(), 0..1)
+            .await;
+
+        // then
+        assert!(matches!(
+            result,
+            Err(P2pError::UnexpectedResponder { requested: r, responded: o })
+                if r == requested && o == other
+        ));
+    }
+
+    #[tokio::test]
+    async fn shared_state__missing_data_is_reported_as_not_found() {
+        // given
+        let (request_sender, mut request_receiver) = mpsc::channel(1);
+        let shared_state = shared_state_with_sender(request_sender);
+        let peer = PeerId::random();
+        tokio::spawn(async move {
+            while let Some(request) = request_receiver.recv().await {
+                match request {
+                    TaskRequest::GetSealedHeaders { channel, .. } => {
+                        let _ = channel.send((peer, Err(ResponseError::NotFound)));
+                    }
+                    TaskRequest::GetTransactions { channel, .. } => {
+                        let _ = channel.send((peer, Err(ResponseError::NotFound)));
+                    }
+                    TaskRequest::GetTip { channel, .. } => {
+                        let _ = channel.send((peer, Err(ResponseError::NotFound)));
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        // when
+        let headers = shared_state.get_sealed_block_headers(0..1).await;
+        let headers_from_peer = shared_state
+            .get_sealed_block_headers_from_peer(peer.to_bytes(), 0..1)
+            .await;
+        let transactions = shared_state
+            .get_transactions_from_peer(peer.to_bytes(), 0..1)
+            .await;
+        let tip = shared_state.get_tip_from_peer(peer.to_bytes()).await;
+
+        // then
+        assert!(matches!(headers, Err(P2pError::NotFound(p)) if p == peer));
+        assert!(matches!(headers_from_peer, Err(P2pError::NotFound(p)) if p == peer));
+        assert!(matches!(transactions, Err(P2pError::NotFound(p)) if p == peer));
+        assert!(matches!(tip, Err(P2pError::NotFound(p)) if p == peer));
+    }
+
+    #[test]
+    fn reassemble_block__chunks_make_up_the_block() {
+        // given
+        let (block, chunks) = block_chunks_at(7, 3, 64);
+        assert!(chunks.len() > 2);
+
+        // when
+        let reassembled = reassemble_block(7, chunks, 1024 * 1024).unwrap();
+
+        // then
+        assert_eq!(reassembled.header, block.header);
+        assert_eq!(reassembled.transactions.0, block.transactions.0);
+    }
+
+    #[test]
+    fn reassemble_block__chunks_not_making_up_the_block_are_rejected() {
+        let (_, chunks) = block_chunks_at(7, 3, 64);
+
+        let mut other_id = chunks.clone();
+        for chunk in &mut other_id {
+            chunk.block_id = BlockId::from([1; 32]);
+        }
+        let mut missing = chunks.clone();
+        missing.pop();
+        let mut reordered = chunks.clone();
+        reordered.swap(0, 1);
+        // The same data split into the chunks of different sizes
+        let mut uneven = chunks.clone();
+        let moved = uneven[0].data.pop().unwrap();
+        uneven[1].data.insert(0, moved);
+        let mut empty = chunks.clone();
+        let mut moved = std::mem::take(&mut empty[0].data);
+        moved.append(&mut empty[1].data);
+        empty[1].data = moved;
+
+        for (chunks, height, max_size) in [
+            (other_id, 7, 1024 * 1024),
+            (missing, 7, 1024 * 1024),
+            (reordered, 7, 1024 * 1024),
+            (uneven, 7, 1024 * 1024),
+            (empty, 7, 1024 * 1024),
+            (chunks.clone(), 8, 1024 * 1024),
+            (chunks, 7, 64),
+        ] {
+            assert!(matches!(
+                reassemble_block(height, chunks, max_size),
+                Err(P2pError::InvalidBlockChunks(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn get_transactions_from_peer__too_large_blocks_are_transferred_in_chunks() {
+        // given
+        let (request_sender, mut request_receiver) = mpsc::channel(1);
+        let shared_state = shared_state_with_sender(request_sender);
+        let peer = PeerId::random();
+        tokio::spawn(async move {
+            while let Some(request) = request_receiver.recv().await {
+                match request {
+                    TaskRequest::GetTransactions { channel, .. } => {
+                        let too_large = ResponseError::TooLarge { size: u64::MAX };
+                        let _ = channel.send((peer, Err(too_large)));
+                    }
+                    TaskRequest::GetBlockChunk {
+                        block_height,
+                        chunk_index,
+                        channel,
+                        ..
+                    } => {
+                        let (_, chunks) = block_chunks_at(block_height, 2, 64);
+                        let chunk = chunks[chunk_index as usize].clone();
+                        let _ = channel.send((peer, Ok(chunk)));
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        // when
+        let transactions = shared_state
+            .get_transactions_from_peer(peer.to_bytes(), 0..2)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // then
+        let expected: Vec<_> = (0..2)
+            .map(|height| block_chunks_at(height, 2, 64).0.transactions.0)
+            .collect();
+        let transactions: Vec<_> = transactions.into_iter().map(|t| t.0).collect();
+        assert_eq!(transactions, expected);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn get_block_from_peer__missing_chunks_time_out() {
+        // given
+        let (request_sender, mut request_receiver) = mpsc::channel(1);
+        let shared_state = shared_state_with_sender(request_sender);
+        let peer = PeerId::random();
+        tokio::spawn(async move {
+            let mut unanswered = vec![];
+            while let Some(request) = request_receiver.recv().await {
+                if let TaskRequest::GetBlockChunk {
+                    chunk_index,
+                    channel,
+                    ..
+                } = request
+                {
+                    let (_, chunks) = block_chunks_at(0, 2, 64);
+                    if chunk_index == 0 {
+                        let _ = channel.send((peer, Ok(chunks[0].clone())));
+                    } else {
+                        unanswered.push(channel);
+                    }
+                }
+            }
+        });
+
+        // when
+        let result = shared_state.get_block_from_peer(peer.to_bytes(), 0).await;
+
+        // then
+        assert!(matches!(result, Err(P2pError::BlockChunksTimeout)));
+    }
+
+    #[tokio::test]
+    async fn get_block_from_peer__empty_first_chunk_is_rejected() {
+        // given
+        let (request_sender, mut request_receiver) = mpsc::channel(1);
+        let shared_state = shared_state_with_sender(request_sender);
+        let peer = PeerId::random();
+        let requested_chunks = Arc::new(AtomicU64::new(0));
+        let counter = requested_chunks.clone();
+        tokio::spawn(async move {
+            while let Some(request) = request_receiver.recv().await {
+                if let TaskRequest::GetBlockChunk { channel, .. } = request {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    let chunk = BlockChunk {
+                        block_id: Default::default(),
+                        chunk_count: u32::MAX,
+                        data: vec![],
+                    };
+                    let _ = channel.send((peer, Ok(chunk)));
+                }
+            }
+        });
+
+        // when
+        let result = shared_state.get_block_from_peer(peer.to_bytes(), 0).await;
+
+        // then
+        assert!(matches!(result, Err(P2pError::InvalidBlockChunks(_))));
+        assert_eq!(requested_chunks.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn get_block_from_peer__chunks_from_other_peers_are_rejected() {
+        // given
+        let (request_sender, mut request_receiver) = mpsc::channel(1);
+        let shared_state = shared_state_with_sender(request_sender);
+        let peer = PeerId::random();
+        let other_peer = PeerId::random();
+        tokio::spawn(async move {
+            while let Some(request) = request_receiver.recv().await {
+                if let TaskRequest::GetBlockChunk {
+                    block_height,
+                    chunk_index,
+                    channel,
+                    ..
+                } = request
+                {
+                    let (_, chunks) = block_chunks_at(block_height, 2, 64);
+                    let chunk = chunks[chunk_index as usize].clone();
+                    let _ = channel.send((other_peer, Ok(chunk)));
+                }
+            }
+        });
+
+        // when
+        let result = shared_state.get_block_from_peer(peer.to_bytes(), 0).await;
+
+        // then
+        assert!(matches!(
+            result,
+            Err(P2pError::UnexpectedResponder { requested, responded })
+                if requested == peer && responded == other_peer
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn get_block_from_peer__chunks_in_flight_are_limited_per_peer() {
+        // given
+        let (request_sender, mut request_receiver) = mpsc::channel(10);
+        let shared_state = shared_state_with_sender(request_sender);
+        let peer = PeerId::random();
+        let in_flight = Arc::new(AtomicU64::new(0));
+        let max_in_flight = Arc::new(AtomicU64::new(0));
+        let (counter, max_counter) = (in_flight.clone(), max_in_flight.clone());
+        tokio::spawn(async move {
+            while let Some(request) = request_receiver.recv().await {
+                if let TaskRequest::GetBlockChunk {
+                    block_height,
+                    chunk_index,
+                    channel,
+                    ..
+                } = request
+                {
+                    let now = counter.fetch_add(1, Ordering::SeqCst).saturating_add(1);
+                    max_counter.fetch_max(now, Ordering::SeqCst);
+                    let counter = counter.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                        let (_, chunks) = block_chunks_at(block_height, 3, 64);
+                        counter.fetch_sub(1, Ordering::SeqCst);
+                        let chunk = chunks[chunk_index as usize].clone();
+                        let _ = channel.send((peer, Ok(chunk)));
+                    });
+                }
+            }
+        });
+
+        // when
+        let (first, second) = tokio::join!(
+            shared_state.get_block_from_peer(peer.to_bytes(), 0),
+            shared_state.get_block_from_peer(peer.to_bytes(), 1),
+        );
+
+        // then
+        assert!(first.is_ok());
+        assert!(second.is_ok());
+        // The limit of the test `SharedState` is shared by both transfers
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn shared_state__invalid_requests_are_reported_as_p2p_errors() {
+        // given
+        let mut p2p_config = Config::<NotInitialized>::default(
+            "shared_state__invalid_requests_are_reported_as_p2p_errors",
+        );
+        p2p_config.enable_kademlia = false;
+        let service = new_service(
+            ChainId::default(),
+            p2p_config,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        service.start_and_await().await.unwrap();
+
+        // when
+        let empty_range = service.shared.get_sealed_block_headers(1..1).await;
+        let invalid_peer = service
+            .shared
+            .get_tip_from_peer(b"not a peer id".to_vec())
+            .await;
+        let invalid_transactions_peer = service
+            .shared
+            .get_transactions_from_peer(b"not a peer id".to_vec(), 0..1)
+            .await;
+        let find_peers = service.shared.find_peers(1).await;
+
+        // then
+        assert!(matches!(empty_range, Err(P2pError::EmptyRange)));
+        assert!(matches!(invalid_peer, Err(P2pError::InvalidPeerId(_))));
+        assert!(matches!(
+            invalid_transactions_peer,
+            Err(P2pError::InvalidPeerId(_))
+        ));
+        assert!(matches!(find_peers, Err(P2pError::KademliaDisabled)));
+        service.stop_and_await().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn get_listen_addresses__reports_the_port_resolved_for_port_zero() {
+        // given
+        let mut p2p_config = Config::<NotInitialized>::default(
+            "get_listen_addresses__reports_the_port_resolved_for_port_zero",
+        );
+        p2p_config.tcp_port = 0;
+        let service = new_service(
+            ChainId::default(),
+            p2p_config,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        service.start_and_await().await.unwrap();
+
+        // when
+        let listen_addresses = service.shared.get_listen_addresses().await.unwrap();
+
+        // then
+        let ports: Vec<_> = listen_addresses
+            .iter()
+            .flat_map(|address| address.iter())
+            .filter_map(|protocol| match protocol {
+                libp2p::multiaddr::Protocol::Tcp(port) => Some(port),
+                _ => None,
+            })
+            .collect();
+        assert!(!ports.is_empty());
+        assert!(ports.iter().all(|port| *port != 0));
+        service.stop_and_await().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn start__retries_until_the_busy_port_is_released() {
+        // given
+        let (mut p2p_config, _) =
+            config_with_free_port("start__retries_until_the_busy_port_is_released");
+        p2p_config.max_start_retries = 10;
+        p2p_config.start_retry_backoff = Duration::from_millis(50);
+        let busy_port =
+            std::net::TcpListener::bind(("0.0.0.0", p2p_config.tcp_port)).unwrap();
+        let service = new_service(
+            ChainId::default(),
+            p2p_config,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+
+        // when
+        service.start().unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(service.state(), State::Starting);
+        drop(busy_port);
+
+        // then
+        let state =
+            tokio::time::timeout(Duration::from_secs(10), service.await_start_or_stop())
+                .await
+                .unwrap()
+                .unwrap();
+        assert!(state.started());
+        service.stop_and_await().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn stop_and_await__releases_the_port_for_the_next_service() {
+        // given
+        let mut p2p_config = Config::<NotInitialized>::default(
+            "stop_and_await__releases_the_port_for_the_next_service",
+        );
+        p2p_config.tcp_port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let service = new_service(
+            ChainId::default(),
+            p2p_config.clone(),
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        assert!(service.start_and_await().await.unwrap().started());
+
+        // when
+        assert!(service.stop_and_await().await.unwrap().stopped());
+
+        // then
+        // The task is dropped before the service is reported as stopped,
+        // so the port is free without waiting
+        let next_service = new_service(
+            ChainId::default(),
+            p2p_config,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        assert!(next_service.start_and_await().await.unwrap().started());
+        assert!(next_service.stop_and_await().await.unwrap().stopped());
+    }
+
+    /// Returns the config of the node listening on a free port and the address to dial it
+    fn config_with_free_port(network_name: &str) -> (Config<NotInitialized>, Multiaddr) {
+        let mut p2p_config = Config::<NotInitialized>::default(network_name);
+        p2p_config.tcp_port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let peer_id = p2p_config.keypair.public().to_peer_id();
+        let address = format!("/ip4/127.0.0.1/tcp/{}/p2p/{peer_id}", p2p_config.tcp_port)
+            .parse()
+            .unwrap();
+        (p2p_config, address)
+    }
+
+    /// Waits until the info about the peer satisfies the `condition`
+    async fn wait_for_peer_info<F>(
+        service: &Service<FakeDb>,
+        peer_id: PeerId,
+        condition: F,
+    ) -> PeerInfo
+    where
+        F: Fn(&PeerInfo) -> bool,
+    {
+        let peer_info = async {
+            loop {
+                let peer_info = service.shared.get_peer_info(peer_id).await.unwrap();
+                if let Some(peer_info) = peer_info {
+                    if condition(&peer_info) {
+                        return peer_info
+                    }
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(10), peer_info)
+            .await
+            .expect("The peer info should satisfy the condition")
+    }
+
+    /// Returns whether the peers identify the node with the `is_archival` config as archival
+    async fn archival_flag_identified_by_peers(
+        network_name: &str,
+        is_archival: bool,
+    ) -> bool {
+        let (mut archival_config, archival_address) = config_with_free_port(network_name);
+        archival_config.is_archival = is_archival;
+        let archival_peer_id = archival_config.keypair.public().to_peer_id();
+
+        let mut p2p_config = Config::<NotInitialized>::default(network_name);
+        p2p_config.bootstrap_nodes = vec![archival_address];
+
+        let archival_service = new_service(
+            ChainId::default(),
+            archival_config,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        let service = new_service(
+            ChainId::default(),
+            p2p_config,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+
+        archival_service.start_and_await().await.unwrap();
+        service.start_and_await().await.unwrap();
+
+        let peer_info = wait_for_peer_info(&service, archival_peer_id, |peer_info| {
+            peer_info.client_version.is_some()
+        })
+        .await;
+
+        service.stop_and_await().await.unwrap();
+        archival_service.stop_and_await().await.unwrap();
+        peer_info.is_archival
+    }
+
+    #[tokio::test]
+    async fn peer_info__archival_node_is_identified_by_peers() {
+        let is_archival = archival_flag_identified_by_peers(
+            "peer_info__archival_node_is_identified_by_peers",
+            true,
+        )
+        .await;
+        assert!(is_archival);
+    }
+
+    #[tokio::test]
+    async fn peer_info__non_archival_node_is_identified_by_peers() {
+        let is_archival = archival_flag_identified_by_peers(
+            "peer_info__non_archival_node_is_identified_by_peers",
+            false,
+        )
+        .await;
+        assert!(!is_archival);
+    }
+
+    #[tokio::test]
+    async fn get_tip_from_peer__returns_the_tip_of_the_peer() {
+        // given
+        let network_name = "get_tip_from_peer__returns_the_tip_of_the_peer";
+        let (serving_config, serving_address) = config_with_free_port(network_name);
+        let serving_peer_id = serving_config.keypair.public().to_peer_id();
+        let tip = (BlockHeight::from(42), BlockId::from([1; 32]));
+
+        let mut p2p_config = Config::<NotInitialized>::default(network_name);
+        p2p_config.bootstrap_nodes = vec![serving_address];
+
+        let serving_service = new_service(
+            ChainId::default(),
+            serving_config,
+            FakeDb {
+                tip: Some(tip),
+                ..Default::default()
+            },
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        let service = new_service(
+            ChainId::default(),
+            p2p_config,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        serving_service.start_and_await().await.unwrap();
+        service.start_and_await().await.unwrap();
+        wait_for_peer_info(&service, serving_peer_id, |_| true).await;
+
+        // when
+        let received_tip = service
+            .shared
+            .get_tip_from_peer(serving_peer_id.to_bytes())
+            .await
+            .unwrap();
+
+        // then
+        assert_eq!(received_tip, Some(tip));
+
+        service.stop_and_await().await.unwrap();
+        serving_service.stop_and_await().await.unwrap();
+    }
+
+    /// Counts the `PeerConnected` events for the peer.
+    /// Waits for the first one and then for a while for the possible duplicates.
+    async fn count_peer_connected_events(
+        events: &mut broadcast::Receiver<NetworkEvent>,
+        peer_id: PeerId,
+    ) -> usize {
+        let mut count = 0;
+        let mut timeout = Duration::from_secs(10);
+        while let Ok(event) = tokio::time::timeout(timeout, events.recv()).await {
+            if event.unwrap() == NetworkEvent::PeerConnected(peer_id) {
+                count = count.saturating_add(1);
+                timeout = Duration::from_secs(1);
+            }
+        }
+        count
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_events__each_node_observes_one_peer_connected_for_the_other() {
+        // given
+        let network_name =
+            "subscribe_events__each_node_observes_one_peer_connected_for_the_other";
+        let (config_a, address_a) = config_with_free_port(network_name);
+        let peer_id_a = config_a.keypair.public().to_peer_id();
+        let (mut config_b, _) = config_with_free_port(network_name);
+        let peer_id_b = config_b.keypair.public().to_peer_id();
+        config_b.bootstrap_nodes = vec![address_a];
+
+        let service_a = new_service(
+            ChainId::default(),
+            config_a,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        let service_b = new_service(
+            ChainId::default(),
+            config_b,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        let mut events_a = service_a.shared.subscribe_events();
+        let mut events_b = service_b.shared.subscribe_events();
+
+        // when
+        service_a.start_and_await().await.unwrap();
+        service_b.start_and_await().await.unwrap();
+
+        // then
+        let (connected_to_b, connected_to_a) = tokio::join!(
+            count_peer_connected_events(&mut events_a, peer_id_b),
+            count_peer_connected_events(&mut events_b, peer_id_a),
+        );
+        assert_eq!(connected_to_b, 1);
+        assert_eq!(connected_to_a, 1);
+
+        service_b.stop_and_await().await.unwrap();
+        service_a.stop_and_await().await.unwrap();
+    }
+
+    /// Waits until the node is connected to `count` peers
+    async fn wait_for_peers(service: &Service<FakeDb>, count: usize) {
+        let peers = async {
+            while service.shared.get_all_peers().await.unwrap().len() < count {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(10), peers)
+            .await
+            .expect("The node should connect to the peers");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn broadcast_transaction__gossiped_transaction_is_not_sent_back_to_its_origin()
+    {
+        // given
+        let network_name =
+            "broadcast_transaction__gossiped_transaction_is_not_sent_back_to_its_origin";
+        let (config_origin, address_origin) = config_with_free_port(network_name);
+        let (mut config_relay, address_relay) = config_with_free_port(network_name);
+        config_relay.bootstrap_nodes = vec![address_origin];
+        let (mut config_receiver, _) = config_with_free_port(network_name);
+        config_receiver.bootstrap_nodes = vec![address_relay];
+
+        let [origin, relay, receiver] = [config_origin, config_relay, config_receiver]
+            .map(|config| {
+                new_service(
+                    ChainId::default(),
+                    config,
+                    FakeDb::default(),
+                    FakeDb::default(),
+                    FakeBlockImporter,
+                )
+            });
+        let mut origin_txs = origin.shared.subscribe_tx();
+        let mut relay_txs = relay.shared.subscribe_tx();
+        let mut receiver_txs = receiver.shared.subscribe_tx();
+        for service in [&origin, &relay, &receiver] {
+            service.start_and_await().await.unwrap();
+        }
+        wait_for_peers(&relay, 2).await;
+        wait_for_peers(&receiver, 1).await;
+        // The gossipsub meshes are formed on the heartbeats
+        tokio::time::sleep(Duration::from_secs(2)).await;
+
+        // when
+        let transaction = Arc::new(Transaction::default_test_tx());
+        let gossip = async {
+            loop {
+                origin
+                    .shared
+                    .broadcast_transaction(transaction.clone())
+                    .unwrap();
+                if let Ok(gossip) =
+                    tokio::time::timeout(Duration::from_millis(500), relay_txs.recv())
+                        .await
+                {
+                    return gossip.unwrap()
+                }
+            }
+        };
+        let gossip = tokio::time::timeout(Duration::from_secs(20), gossip)
+            .await
+            .expect("The relay should receive the transaction");
+        // The relay's txpool accepts the transaction and broadcasts it
+        let report = gossip.into_report(GossipsubMessageAcceptance::Accept);
+        relay
+            .shared
+            .notify_gossip_transaction_validity(report.message_info, report.acceptance)
+            .unwrap();
+        relay.shared.broadcast_transaction(transaction).unwrap();
+
+        // then
+        tokio::time::timeout(Duration::from_secs(10), receiver_txs.recv())
+            .await
+            .expect("The receiver should get the transaction")
+            .unwrap();
+        let echo = tokio::time::timeout(Duration::from_secs(2), origin_txs.recv()).await;
+        assert!(
+            echo.is_err(),
+            "The origin received its own transaction back"
+        );
+
+        for service in [receiver, relay, origin] {
+            service.stop_and_await().await.unwrap();
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn shutdown__request_received_before_stop_is_answered() {
+        // given
+        let network_name = "shutdown__request_received_before_stop_is_answered";
+        let (responder_config, responder_address) = config_with_free_port(network_name);
+        let responder_peer_id = responder_config.keypair.public().to_peer_id();
+        let lookup_started = Arc::new(tokio::sync::Notify::new());
+        let responder_db = FakeDb {
+            sealed_headers_delay: Duration::from_millis(500),
+            lookup_started: lookup_started.clone(),
+            ..Default::default()
+        };
+
+        let mut requester_config = Config::<NotInitialized>::default(network_name);
+        requester_config.bootstrap_nodes = vec![responder_address];
+
+        let responder = new_service(
+            ChainId::default(),
+            responder_config,
+            responder_db,
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        let requester = new_service(
+            ChainId::default(),
+            requester_config,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        responder.start_and_await().await.unwrap();
+        requester.start_and_await().await.unwrap();
+        // The requester needs the height of the responder from its heartbeat
+        wait_for_peer_info(&requester, responder_peer_id, |peer_info| {
+            peer_info.heartbeat_data.block_height.is_some()
+        })
+        .await;
+
+        // when
+        let shared = requester.shared.clone();
+        let request =
+            tokio::spawn(async move { shared.get_sealed_block_headers(0..1).await });
+        lookup_started.notified().await;
+        responder.stop_and_await().await.unwrap();
+
+        // then
+        let (peer_id, headers) = request.await.unwrap().unwrap();
+        assert_eq!(peer_id, responder_peer_id.to_bytes());
+        assert_eq!(headers, Some(vec![SealedBlockHeader::default()]));
+
+        requester.stop_and_await().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn get_transactions_from_peer__not_found_response_is_detected() {
+        // given
+        let network_name = "get_transactions_from_peer__not_found_response_is_detected";
+        let (responder_config, responder_address) = config_with_free_port(network_name);
+        let responder_peer_id = responder_config.keypair.public().to_peer_id();
+
+        let mut requester_config = Config::<NotInitialized>::default(network_name);
+        requester_config.bootstrap_nodes = vec![responder_address];
+
+        let responder = new_service(
+            ChainId::default(),
+            responder_config,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        let requester = new_service(
+            ChainId::default(),
+            requester_config,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        responder.start_and_await().await.unwrap();
+        requester.start_and_await().await.unwrap();
+        wait_for_peer_info(&requester, responder_peer_id, |peer_info| {
+            peer_info.heartbeat_data.block_height.is_some()
+        })
+        .await;
+
+        // when
+        // The database of the responder doesn't have the transactions
+        let result = requester
+            .shared
+            .get_transactions_from_peer(responder_peer_id.to_bytes(), 0..2)
+            .await;
+
+        // then
+        assert!(matches!(result, Err(P2pError::NotFound(p)) if p == responder_peer_id));
+
+        requester.stop_and_await().await.unwrap();
+        responder.stop_and_await().await.unwrap();
+    }
+
+    #[derive(Default)]
+    struct FakeP2PService {
+        peer_info: Vec<(PeerId, PeerInfo)>,
+        banned_peers: Vec<PeerId>,
+        reported_messages: Vec<(GossipsubMessageInfo, GossipsubMessageAcceptance)>,
+        /// The time it takes the peer to respond to a request
+        response_delays: Vec<(PeerId, Duration)>,
+        /// The events returned by the `next_event` before it becomes pending
+        events: VecDeque<FuelP2PEvent>,
+        published: Vec<GossipsubBroadcastRequest>,
+        /// The peers that close the connection after serving the given number of requests
+        failing_peers: Vec<(PeerId, usize)>,
+        /// The max number of headers that fit into one response of the peers
+        max_headers_per_response: Option<u32>,
+        /// The requests sent by the task, in the order they were sent
+        sent_requests: Vec<(PeerId, RequestMessage)>,
+        dialed: Vec<Multiaddr>,
+    }
+
+    impl TaskP2PService for FakeP2PService {
+        fn get_peer_ids(&self) -> Vec<PeerId> {
+            self.response_delays
+                .iter()
+                .map(|(peer_id, _)| *peer_id)
+                .collect()
+        }
+
+        fn get_all_peer_info(&self) -> Vec<(&PeerId, &PeerInfo)> {
+            self.peer_info.iter().map(|tup| (&tup.0, &tup.1)).collect()
+        }
+
+        fn get_peer_info(&self, peer_id: &PeerId) -> Option<&PeerInfo> {
+            self.peer_info
+                .iter()
+                .find(|(id, _)| id == peer_id)
+                .map(|(_, info)| info)
+        }
+
+        fn get_peer_counts(&self) -> PeerCounts {
+            PeerCounts {
+                non_reserved_peers: self.peer_info.len(),
+                ..Default::default()
+            }
+        }
+
+        fn listen_addresses(&self) -> Vec<Multiaddr> {
+            todo!()
+        }
+
+        fn get_peer_id_with_height(&mut self, _height: &BlockHeight) -> Option<PeerId> {
+            todo!()
+        }
+
+        fn get_peers_ids_with_height(
+            &mut self,
+            _height: &BlockHeight,
+            count: usize,
+        ) -> Vec<PeerId> {
+            self.response_delays
+                .iter()
+                .map(|(peer_id, _)| *peer_id)
+                .take(count)
+                .collect()
+        }
+
+        fn next_event(&mut self) -> BoxFuture<'_, Option<FuelP2PEvent>> {
+            match self.events.pop_front() {
+                Some(event) => std::future::ready(Some(event)).boxed(),
+                None => std::future::pending().boxed(),
+            }
+        }
+
+        fn publish_message(
+            &mut self,
+            message: GossipsubBroadcastRequest,
+        ) -> Result<MessageId, PublishError> {
+            self.published.push(message);
+            Ok(MessageId::new(&[]))
+        }
+
+        fn send_request_msg(
+            &mut self,
+            peer_id: Option<PeerId>,
+            request_msg: RequestMessage,
+            on_response: ResponseSender,
+        ) -> anyhow::Result<()> {
+            let peer_id = peer_id.expect("The peer is always selected in tests");
+            self.sent_requests.push((peer_id, request_msg.clone()));
+            let delay = self
+                .response_delays
+                .iter()
+                .find(|(id, _)| id == &peer_id)
+                .map(|(_, delay)| *delay)
+                .expect("The peer should be known");
+            let (
+                RequestMessage::SealedHeaders(range),
+                ResponseSender::SealedHeaders(channel),
+            ) = (request_msg, on_response)
+            else {
+                todo!()
+            };
+            let fails = self
+                .failing_peers
+                .iter_mut()
+                .find(|(id, _)| id == &peer_id)
+                .map(|(_, served)| match served.checked_sub(1) {
+                    Some(left) => {
+                        *served = left;
+                        false
+                    }
+                    None => true,
+                })
+                .unwrap_or(false);
+            let too_large = self
+                .max_headers_per_response
+                .filter(|max| range.end.saturating_sub(range.start) > *max)
+                .is_some();
+
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                let response = if fails {
+                    Err(ResponseError::P2P(OutboundFailure::ConnectionClosed))
+                } else if too_large {
+                    Err(ResponseError::TooLarge { size: u64::MAX })
+                } else {
+                    Ok(Some(range.map(sealed_header_at).collect()))
+                };
+                let _ = channel.send((peer_id, response));
+            });
+            Ok(())
+        }
+
+        fn send_response_msg(
+            &mut self,
+            _request_id: InboundRequestId,
+            _message: ResponseMessage,
+        ) -> anyhow::Result<()> {
+            todo!()
+        }
+
+        fn report_message(
+            &mut self,
+            message: GossipsubMessageInfo,
+            acceptance: GossipsubMessageAcceptance,
+        ) -> anyhow::Result<()> {
+            self.reported_messages.push((message, acceptance));
+            Ok(())
+        }
+
+        fn forward_gossip(&mut self, _message_id: &MessageId) -> Result<(), P2pError> {
+            todo!()
+        }
+
+        fn report_peer(
+            &mut self,
+            _peer_id: PeerId,
+            _score: AppScore,
+            _reporting_service: &str,
+        ) -> anyhow::Result<()> {
+            todo!()
+        }
+
+        fn update_block_height(&mut self, _height: BlockHeight) -> anyhow::Result<()> {
+            todo!()
+        }
+
+        fn ban_peer(&mut self, peer_id: PeerId) {
+            self.banned_peers.push(peer_id);
+        }
+
+        fn unban_peer(&mut self, peer_id: PeerId) {
+            self.banned_peers.retain(|banned| banned != &peer_id);
+        }
+
+        fn add_reserved_peer(&mut self, _peer_id: PeerId, _address: Multiaddr) {
+            todo!()
+        }
+
+        fn remove_reserved_peer(&mut self, _peer_id: PeerId) {
+            todo!()
+        }
+
+        fn set_discovery(&mut self, _mdns: Option<bool>, _kad: Option<bool>) {
+            todo!()
+        }
+
+        fn discovery_status(&mut self) -> DiscoveryStatus {
+            todo!()
+        }
+
+        fn dial(
+            &mut self,
+            address: Multiaddr,
+            _channel: oneshot::Sender<Result<PeerId, DialError>>,
+        ) {
+            self.dialed.push(address);
+        }
+
+        fn find_peers(
+            &mut self,
+            _count: usize,
+            _channel: oneshot::Sender<Option<Vec<PeerId>>>,
+        ) {
+            todo!()
+        }
+
+        fn has_pending_inbound_requests(&self) -> bool {
+            false
+        }
+
+        fn set_topic_subscription(
+            &mut self,
+            _topic: GossipTopicTag,
+            _subscribe: bool,
+        ) -> anyhow::Result<()> {
+            todo!()
+        }
+
+        fn unsubscribe_from_all_topics(&mut self) {}
+    }
+
+    #[derive(Clone, Default)]
+    struct FakeDB {
+        peer_bans: Arc<std::sync::Mutex<Vec<(FuelPeerId, PeerBan)>>>,
+        known_peers: Arc<std::sync::Mutex<Vec<(FuelPeerId, KnownPeer)>>>,
+        sealed_headers_requests: Arc<std::sync::atomic::AtomicUsize>,
+        stored_heights: Vec<BlockHeight>,
+    }
+
+    impl AtomicView for FakeDB {
+        type View = Self;
+
+        type Height = BlockHeight;
+
+        fn latest_height(&self) -> Self::Height {
+            BlockHeight::default()
+        }
+
+        fn view_at(&self, _: &BlockHeight) -> StorageResult<Self::View> {
+            unimplemented!()
+        }
+
+        fn latest_view(&self) -> Self::View {
+            self.clone()
+        }
+    }
+
+    impl P2pDb for FakeDB {
+        fn get_sealed_headers(
+            &self,
+            block_height_range: Range<u32>,
+        ) -> StorageResult<Vec<SealedBlockHeader>> {
+            self.sealed_headers_requests
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(block_height_range
+                .map(|_| SealedBlockHeader::default())
+                .collect())
+        }
+
+        fn get_transactions(
+            &self,
+            _block_height_range: Range<u32>,
+        ) -> StorageResult<Option<Vec<Transactions>>> {
+            todo!()
+        }
+
+        fn get_genesis(&self) -> StorageResult<Genesis> {
+            todo!()
+        }
+
+        fn get_current_tip(&self) -> StorageResult<Option<(BlockHeight, BlockId)>> {
+            todo!()
+        }
+
+        fn has_block(&self, height: &BlockHeight) -> StorageResult<bool> {
+            Ok(self.stored_heights.contains(height))
+        }
+    }
+
+    impl P2pPeerStore for FakeDB {
+        fn insert_peer_ban(
+            &mut self,
+            peer_id: &FuelPeerId,
+            ban: &PeerBan,
+        ) -> StorageResult<()> {
+            let mut peer_bans = self.peer_bans.lock().unwrap();
+            peer_bans.retain(|(banned, _)| banned != peer_id);
+            peer_bans.push((peer_id.clone(), ban.clone()));
+            Ok(())
+        }
+
+        fn remove_peer_ban(&mut self, peer_id: &FuelPeerId) -> StorageResult<()> {
+            self.peer_bans
+                .lock()
+                .unwrap()
+                .retain(|(banned, _)| banned != peer_id);
+            Ok(())
+        }
+
+        fn load_peer_bans(&self) -> StorageResult<Vec<(FuelPeerId, PeerBan)>> {
+            Ok(self.peer_bans.lock().unwrap().clone())
+        }
+
+        fn insert_known_peer(
+            &mut self,
+            peer_id: &FuelPeerId,
+            peer: &KnownPeer,
+        ) -> StorageResult<()> {
+            let mut known_peers = self.known_peers.lock().unwrap();
+            known_peers.retain(|(known, _)| known != peer_id);
+            known_peers.push((peer_id.clone(), peer.clone()));
+            Ok(())
+        }
+
+        fn remove_known_peer(&mut self, peer_id: &FuelPeerId) -> StorageResult<()> {
+            self.known_peers
+                .lock()
+                .unwrap()
+                .retain(|(known, _)| known != peer_id);
+            Ok(())
+        }
+
+        fn load_known_peers(&self) -> StorageResult<Vec<(FuelPeerId, KnownPeer)>> {
+            Ok(self.known_peers.lock().unwrap().clone())
+        }
+    }
+
+    struct FakeBroadcast {
+        pub peer_reports: mpsc::Sender<(FuelPeerId, AppScore, String)>,
+    }
+
+    impl Broadcast for FakeBroadcast {
+        fn report_peer(
+            &self,
+            peer_id: FuelPeerId,
+            report: AppScore,
+            reporting_service: &'static str,
+        ) -> anyhow::Result<()> {
+            self.peer_reports.try_send((
+                peer_id,
+                report,
+                reporting_service.to_string(),
+            ))?;
+            Ok(())
+        }
+
+        fn block_height_broadcast(
+            &self,
+            _block_height_data: BlockHeightHeartbeatData,
+        ) -> anyhow::Result<()> {
+            todo!()
+        }
+
+        fn tx_broadcast(
+            &self,
+            _transaction: TransactionGossipData,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn network_event_broadcast(&self, _event: NetworkEvent) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn peer_heartbeat_reputation_checks__slow_heartbeat_sends_reports() {
+        // given
+        let peer_id = PeerId::random();
+        // more than limit
+        let last_duration = Duration::from_secs(30);
+        let mut durations = VecDeque::new();
+        durations.push_front(last_duration);
+
+        let heartbeat_data = HeartbeatData {
+            block_height: None,
+            last_heartbeat: Instant::now(),
+            last_heartbeat_sys: SystemTime::now(),
+            window: 0,
+            durations,
+        };
+        let peer_info = PeerInfo {
+            peer_addresses: Default::default(),
+            client_version: None,
+            protocols: vec![],
+            heartbeat_data,
+            score: 100.0,
+            gossipsub_score: None,
+            direction: None,
+            last_response_time: None,
+            average_response_time: None,
+            transport_security: Default::default(),
+            is_archival: false,
+            last_activity: Instant::now(),
+            bandwidth: Default::default(),
+        };
+        let (report_sender, mut report_receiver) = mpsc::channel(100);
+        let broadcast = FakeBroadcast {
+            peer_reports: report_sender,
+        };
+        let (mut task, _request_sender) = task_with_fake(FakeDB::default(), broadcast);
+        task.p2p_service.peer_info = vec![(peer_id, peer_info)];
+
+        // Less than actual
+        task.heartbeat_max_avg_interval = Duration::from_secs(20);
+        // Greater than actual
+        task.heartbeat_max_time_since_last = Duration::from_secs(40);
+        // The heartbeats are checked on the first run
+        task.heartbeat_check_interval = Duration::from_secs(0);
+        task.next_check_time = Instant::now();
+
+        // Arbitrary values
+        let heartbeat_peer_reputation_config = HeartbeatPeerReputationConfig {
+            old_heartbeat_penalty: 5.6,
+            low_heartbeat_frequency_penalty: 20.45,
+        };
+        task.heartbeat_peer_reputation_config = heartbeat_peer_reputation_config.clone();
+        let (watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+
+        // when
+        task.run(&mut watcher).await.unwrap();
+
+        // then
+        let (report_peer_id, report, reporting_service) =
+            report_receiver.recv().await.unwrap();
+
+        watch_sender.send(State::Stopped).unwrap();
+
+        assert_eq!(
+            FuelPeerId::from(peer_id.to_bytes().to_vec()),
+            report_peer_id
+        );
+        assert_eq!(
+            report,
+            heartbeat_peer_reputation_config.low_heartbeat_frequency_penalty
+        );
+        assert_eq!(reporting_service, "p2p");
+    }
+
+    #[tokio::test]
+    async fn peer_heartbeat_reputation_checks__old_heartbeat_sends_reports() {
+        // given
+        let peer_id = PeerId::random();
+        // under the limit
+        let last_duration = Duration::from_secs(5);
+        let last_heartbeat = Instant::now() - Duration::from_secs(50);
+        let last_heartbeat_sys = SystemTime::now() - Duration::from_secs(50);
+        let mut durations = VecDeque::new();
+        durations.push_front(last_duration);
+
+        let heartbeat_data = HeartbeatData {
+            block_height: None,
+            last_heartbeat,
+            last_heartbeat_sys,
+            window: 0,
+            durations,
+        };
+        let peer_info = PeerInfo {
+            peer_addresses: Default::default(),
+            client_version: None,
+            protocols: vec![],
+            heartbeat_data,
+            score: 100.0,
+            gossipsub_score: None,
+            direction: None,
+            last_response_time: None,
+            average_response_time: None,
+            transport_security: Default::default(),
+            is_archival: false,
+            last_activity: Instant::now(),
+            bandwidth: Default::default(),
+        };
+        let (report_sender, mut report_receiver) = mpsc::channel(100);
+        let broadcast = FakeBroadcast {
+            peer_reports: report_sender,
+        };
+        let (mut task, _request_sender) = task_with_fake(FakeDB::default(), broadcast);
+        task.p2p_service.peer_info = vec![(peer_id, peer_info)];
+
+        // Greater than actual
+        task.heartbeat_max_avg_interval = Duration::from_secs(20);
+        // Less than actual
+        task.heartbeat_max_time_since_last = Duration::from_secs(40);
+        // The heartbeats are checked on the first run
+        task.heartbeat_check_interval = Duration::from_secs(0);
+        task.next_check_time = Instant::now();
+
+        // Arbitrary values
+        let heartbeat_peer_reputation_config = HeartbeatPeerReputationConfig {
+            old_heartbeat_penalty: 5.6,
+            low_heartbeat_frequency_penalty: 20.45,
+        };
+        task.heartbeat_peer_reputation_config = heartbeat_peer_reputation_config.clone();
+        let (watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+
+        // when
+        task.run(&mut watcher).await.unwrap();
+
+        // then
+        let (report_peer_id, report, reporting_service) =
+            report_receiver.recv().await.unwrap();
+
+        watch_sender.send(State::Stopped).unwrap();
+
+        assert_eq!(
+            FuelPeerId::from(peer_id.to_bytes().to_vec()),
+            report_peer_id
+        );
+        assert_eq!(
+            report,
+            heartbeat_peer_reputation_config.old_heartbeat_penalty
+        );
+        assert_eq!(reporting_service, "p2p");
+    }
+
+    #[tokio::test]
+    async fn gossip_reports__rejected_messages_ban_the_peer_once() {
+        // given
+        let peer_id = PeerId::random();
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+
+        // Much more reports than required to cross the ban threshold
+        let reports = 50;
+        for _ in 0..reports {
+            let message_info = GossipsubMessageInfo {
+                message_id: vec![],
+                peer_id: FuelPeerId::from(peer_id.to_bytes()),
+            };
+            request_sender
+                .try_send(TaskRequest::RespondWithGossipsubMessageReport(
+                    GossipsubMessageReport {
+                        message_info,
+                        acceptance: GossipsubMessageAcceptance::Reject,
+                    },
+                ))
+                .unwrap();
+        }
+
+        // when
+        for _ in 0..reports {
+            task.run(&mut watcher).await.unwrap();
+        }
+
+        // then
+        assert_eq!(task.p2p_service.banned_peers, vec![peer_id]);
+    }
+
+    fn task_with_view_provider(
+        view_provider: FakeDB,
+    ) -> (
+        Task<FakeP2PService, FakeDB, FakeBroadcast, FakeDB>,
+        mpsc::Sender<TaskRequest>,
+    ) {
+        let (report_sender, _) = mpsc::channel(100);
+        let broadcast = FakeBroadcast {
+            peer_reports: report_sender,
+        };
+        task_with_fake(view_provider, broadcast)
+    }
+
+    /// The task running on the fakes with the defaults suitable for most tests.
+    /// The tests override its fields, including the ones of the `p2p_service`.
+    fn task_with_fake<B>(
+        view_provider: FakeDB,
+        broadcast: B,
+    ) -> (
+        Task<FakeP2PService, FakeDB, B, FakeDB>,
+        mpsc::Sender<TaskRequest>,
+    ) {
+        let (request_sender, request_receiver) = mpsc::channel(100);
 
-                        match message {
-                            GossipsubMessage::NewTx(transaction) => {
-                                let next_transaction = GossipData::new(transaction, peer_id, message_id);
-                                let _ = self.broadcast.tx_broadcast(next_transaction);
-                            },
-                        }
-                    },
-                    Some(FuelP2PEvent::InboundRequestMessage { request_message, request_id }) => {
-                        match request_message {
-                            RequestMessage::Transactions(range) => {
-                                let view = self.view_provider.latest_view();
-                                match view.get_transactions(range.clone()) {
-                                    Ok(response) => {
-                                        let _ = self.p2p_service.send_response_msg(request_id, ResponseMessage::Transactions(response));
-                                    },
-                                    Err(e) => {
-                                        tracing::error!("Failed to get transactions for range {:?}: {:?}", range, e);
-                                        let response = None;
-                                        let _ = self.p2p_service.send_response_msg(request_id, ResponseMessage::Transactions(response));
-                                        return Err(e.into())
-                                    }
-                                }
-                            }
-                            RequestMessage::SealedHeaders(range) => {
-                                let max_len = self.max_headers_per_request.try_into().expect("u32 should always fit into usize");
-                                if range.len() > max_len {
-                                    tracing::error!("Requested range of sealed headers is too big. Requested length: {:?}, Max length: {:?}", range.len(), max_len);
-                                    // TODO: Return helpful error message to requester. https://github.com/FuelLabs/fuel-core/issues/1311
-                                    let response = None;
-                                    let _ = self.p2p_service.send_response_msg(request_id, ResponseMessage::SealedHeaders(response));
-                                } else {
-                                    let view = self.view_provider.latest_view();
-                                    match view.get_sealed_headers(range.clone()) {
-                                        Ok(headers) => {
-                                            let response = Some(headers);
-                                            let _ = self.p2p_service.send_response_msg(request_id, ResponseMessage::SealedHeaders(response));
-                                        },
-                                        Err(e) => {
-                                            tracing::error!("Failed to get sealed headers for range {:?}: {:?}", range, &e);
-                                            let response = None;
-                                            let _ = self.p2p_service.send_response_msg(request_id, ResponseMessage::SealedHeaders(response));
-                                            return Err(e.into())
-                                        }
-                                    }
-                                };
-                            }
-                        }
-                    },
-                    _ => (),
-                }
-            },
-            _  = tokio::time::sleep_until(self.next_check_time) => {
-                should_continue = true;
-                let res = self.peer_heartbeat_reputation_checks();
-                match res {
-                    Ok(_) => tracing::debug!("Peer heartbeat reputation checks completed"),
-                    Err(e) => {
-                        tracing::error!("Failed to perform peer heartbeat reputation checks: {:?}", e);
-                    }
-                }
-                self.next_check_time += self.heartbeat_check_interval;
+        let task = Task {
+            chain_id: Default::default(),
+            p2p_service: FakeP2PService::default(),
+            peer_store: Arc::new(Mutex::new(view_provider.clone())),
+            peer_store_writes: vec![],
+            view_provider,
+            next_block_height: FakeBlockImporter.next_block_height(),
+            request_receiver,
+            broadcast,
+            max_headers_per_request: 0,
+            block_chunk_size: 1024,
+            redundant_request_factor: 1,
+            heartbeat_check_interval: Duration::from_secs(10),
+            heartbeat_max_avg_interval: Duration::from_secs(20),
+            heartbeat_max_time_since_last: Duration::from_secs(40),
+            next_check_time: Instant::now().checked_add(Duration::from_secs(10)).unwrap(),
+            heartbeat_peer_reputation_config: HeartbeatPeerReputationConfig {
+                old_heartbeat_penalty: 0.,
+                low_heartbeat_frequency_penalty: 0.,
             },
-            latest_block_height = self.next_block_height.next() => {
-                if let Some(latest_block_height) = latest_block_height {
-                    let _ = self.p2p_service.update_block_height(latest_block_height);
-                    should_continue = true;
-                } else {
-                    should_continue = false;
-                }
-            }
-        }
+            peer_reputation: PeerReputation::new(Default::default()),
+            response_caches: Default::default(),
+            db_lookups: LookupPool::new(1, 1, 1, false),
+            shutdown_grace_period: Duration::default(),
+            gossip_counters: Default::default(),
+            received_txs: Default::default(),
+            gossip_duplicates: Default::default(),
+            metrics: false,
+            tx_consumer: ConsumerHealth::new(ConsumerChannel::Transactions, 10),
+            block_height_consumer: ConsumerHealth::new(ConsumerChannel::BlockHeights, 10),
+            tx_batcher: TxBatcher::new(None, 0),
+            batch_reports: Default::default(),
+            startup_gossip: None,
+            peer_readiness: PeerReadiness::new(0),
+            request_queue: RequestQueue::new(None, 0, PeerId::random()),
+            known_peers: KnownPeers::new(Duration::MAX, 10, Duration::from_secs(60 * 60)),
+        };
+        (task, request_sender)
+    }
 
-        tracing::debug!("P2P task is finished");
-        Ok(should_continue)
+    /// The block at the `height` with `tx_count` transactions and its chunks of `chunk_size` bytes
+    fn block_chunks_at(
+        height: u32,
+        tx_count: usize,
+        chunk_size: usize,
+    ) -> (ChunkedBlock, Vec<BlockChunk>) {
+        let block = ChunkedBlock {
+            header: sealed_header_at(height),
+            transactions: Transactions(
+                (0..tx_count)
+                    .map(|_| Transaction::default_test_tx())
+                    .collect(),
+            ),
+        };
+        let data = postcard::to_stdvec(&block).unwrap();
+        let block_id = block.header.entity.id();
+        let chunks = (0..)
+            .map_while(|index| block_chunk(block_id, &data, index, chunk_size))
+            .collect();
+        (block, chunks)
     }
 
-    async fn shutdown(self) -> anyhow::Result<()> {
-        // Nothing to shut down because we don't have any temporary state that should be dumped,
-        // and we don't spawn any sub-tasks that we need to finish or await.
+    fn sealed_header_at(height: u32) -> SealedBlockHeader {
+        let mut header = BlockHeader::default();
+        header.set_block_height(height.into());
+        SealedBlockHeader {
+            entity: header,
+            consensus: Default::default(),
+        }
+    }
 
-        // `FuelP2PService` doesn't support graceful shutdown(with informing of connected peers).
-        // https://github.com/libp2p/specs/blob/master/ROADMAP.md#%EF%B8%8F-polite-peering
-        // Dropping of the `FuelP2PService` will close all connections.
+    /// The `SharedState` that sends its requests to the task behind the `request_sender`
+    fn shared_state_with_sender(
+        request_sender: mpsc::Sender<TaskRequest>,
+    ) -> SharedState {
+        let request_overflow = ChannelOverflow::new(
+            ConsumerChannel::Requests,
+            request_sender.max_capacity(),
+            OverflowPolicy::Block,
+            false,
+        );
+        let tx_overflow = ChannelOverflow::new(
+            ConsumerChannel::Transactions,
+            10,
+            Default::default(),
+            false,
+        );
+        let block_height_overflow = ChannelOverflow::new(
+            ConsumerChannel::BlockHeights,
+            10,
+            Default::default(),
+            false,
+        );
+        SharedState {
+            request_sender,
+            request_overflow,
+            tx_broadcast: broadcast::channel(tx_overflow.capacity).0,
+            tx_overflow,
+            reserved_peers_broadcast: broadcast::channel(1).0,
+            block_height_broadcast: broadcast::channel(block_height_overflow.capacity).0,
+            block_height_overflow,
+            network_event_broadcast: broadcast::channel(1).0,
+            local_peer_id: PeerId::random(),
+            gossip_counters: Default::default(),
+            listen_addresses: Default::default(),
+            peers_ready: Default::default(),
+            block_chunks: Arc::new(BlockChunkLimits::new(
+                2,
+                Duration::from_secs(60),
+                1024 * 1024,
+            )),
+            metrics: false,
+        }
+    }
 
-        Ok(())
+    /// The gossiped transactions must differ, otherwise they are dropped as duplicates
+    fn unique_tx(seed: u8) -> Transaction {
+        TransactionBuilder::script(vec![seed], vec![]).finalize_as_transaction()
     }
-}
 
-#[derive(Clone)]
-pub struct SharedState {
-    /// Sender of p2p transaction used for subscribing.
-    tx_broadcast: broadcast::Sender<TransactionGossipData>,
-    /// Sender of reserved peers connection updates.
-    reserved_peers_broadcast: broadcast::Sender<usize>,
-    /// Used for communicating with the `Task`.
-    request_sender: mpsc::Sender<TaskRequest>,
-    /// Sender of p2p blopck height data
-    block_height_broadcast: broadcast::Sender<BlockHeightHeartbeatData>,
-}
+    #[tokio::test(start_paused = true)]
+    async fn get_sealed_headers__redundant_request_completes_at_fastest_peer_latency() {
+        // given
+        let fast_peer = PeerId::random();
+        let slow_peer = PeerId::random();
+        let fast_peer_delay = Duration::from_millis(100);
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        task.redundant_request_factor = 2;
+        task.p2p_service.response_delays = vec![
+            (slow_peer, Duration::from_secs(10)),
+            (fast_peer, fast_peer_delay),
+        ];
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
 
-impl SharedState {
-    pub fn notify_gossip_transaction_validity(
-        &self,
-        message_info: GossipsubMessageInfo,
-        acceptance: GossipsubMessageAcceptance,
-    ) -> anyhow::Result<()> {
-        self.request_sender
-            .try_send(TaskRequest::RespondWithGossipsubMessageReport((
-                message_info,
-                acceptance,
-            )))?;
-        Ok(())
+        // when
+        let started_at = Instant::now();
+        let (sender, receiver) = oneshot::channel();
+        request_sender
+            .try_send(TaskRequest::GetSealedHeaders {
+                block_height_range: 0..2,
+                from_peer: None,
+                channel: sender,
+            })
+            .unwrap();
+        task.run(&mut watcher).await.unwrap();
+        let (peer_id, response) = receiver.await.unwrap();
+
+        // then
+        assert_eq!(peer_id, fast_peer);
+        assert_eq!(response.unwrap().unwrap().len(), 2);
+        assert!(started_at.elapsed() >= fast_peer_delay);
+        assert!(started_at.elapsed() < Duration::from_secs(10));
     }
 
-    pub async fn get_sealed_block_headers(
-        &self,
-        block_height_range: Range<u32>,
-    ) -> anyhow::Result<(Vec<u8>, Option<Vec<SealedBlockHeader>>)> {
+    #[tokio::test(start_paused = true)]
+    async fn get_sealed_headers__request_from_peer_is_sent_only_to_that_peer() {
+        // given
+        let other_peer = PeerId::random();
+        let requested_peer = PeerId::random();
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        task.redundant_request_factor = 2;
+        task.p2p_service.response_delays = vec![
+            (other_peer, Duration::from_millis(100)),
+            (requested_peer, Duration::from_millis(100)),
+        ];
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+
+        // when
         let (sender, receiver) = oneshot::channel();
+        request_sender
+            .try_send(TaskRequest::GetSealedHeaders {
+                block_height_range: 0..2,
+                from_peer: Some(requested_peer),
+                channel: sender,
+            })
+            .unwrap();
+        task.run(&mut watcher).await.unwrap();
+        let (peer_id, _) = receiver.await.unwrap();
 
-        if block_height_range.is_empty() {
-            return Err(anyhow!(
-                "Cannot retrieve headers for an empty range of block heights"
-            ))
-        }
+        // then
+        assert_eq!(peer_id, requested_peer);
+        assert_eq!(
+            task.p2p_service.sent_requests,
+            vec![(requested_peer, RequestMessage::SealedHeaders(0..2))]
+        );
+    }
 
-        self.request_sender
-            .send(TaskRequest::GetSealedHeaders {
-                block_height_range,
+    #[tokio::test(start_paused = true)]
+    async fn get_sealed_headers__redundant_request_is_sent_to_each_selected_peer() {
+        // given
+        let peers: Vec<_> = (0..3).map(|_| PeerId::random()).collect();
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        task.redundant_request_factor = 2;
+        task.p2p_service.response_delays = peers
+            .iter()
+            .map(|peer_id| (*peer_id, Duration::from_millis(100)))
+            .collect();
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+
+        // when
+        let (sender, _receiver) = oneshot::channel();
+        request_sender
+            .try_send(TaskRequest::GetSealedHeaders {
+                block_height_range: 0..2,
+                from_peer: None,
                 channel: sender,
             })
-            .await?;
+            .unwrap();
+        task.run(&mut watcher).await.unwrap();
 
-        let (peer_id, response) = receiver.await.map_err(|e| anyhow!("{e}"))?;
+        // then
+        let expected: Vec<_> = peers
+            .into_iter()
+            .take(2)
+            .map(|peer_id| (peer_id, RequestMessage::SealedHeaders(0..2)))
+            .collect();
+        assert_eq!(task.p2p_service.sent_requests, expected);
+    }
 
-        let data = response.map_err(|e| anyhow!("Invalid response from peer {e:?}"))?;
-        Ok((peer_id.to_bytes(), data))
+    #[tokio::test(start_paused = true)]
+    async fn request_queue__request_is_sent_once_a_peer_connects() {
+        // given
+        let peer = PeerId::random();
+        let timeout = Duration::from_secs(10);
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        task.request_queue = RequestQueue::new(Some(timeout), 10, PeerId::random());
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+        let (sender, mut receiver) = oneshot::channel();
+        request_sender
+            .try_send(TaskRequest::GetSealedHeaders {
+                block_height_range: 0..2,
+                from_peer: None,
+                channel: sender,
+            })
+            .unwrap();
+        task.run(&mut watcher).await.unwrap();
+        assert!(task.p2p_service.sent_requests.is_empty());
+        assert!(receiver.try_recv().is_err());
+
+        // when
+        tokio::time::advance(timeout / 2).await;
+        task.p2p_service.response_delays = vec![(peer, Duration::from_millis(100))];
+        task.p2p_service
+            .events
+            .push_back(FuelP2PEvent::PeerConnected(peer));
+        task.run(&mut watcher).await.unwrap();
+
+        // then
+        let (peer_id, response) = receiver.await.unwrap();
+        assert_eq!(peer_id, peer);
+        assert_eq!(response.unwrap().unwrap().len(), 2);
     }
 
-    pub async fn get_transactions_from_peer(
-        &self,
-        peer_id: Vec<u8>,
-        range: Range<u32>,
-    ) -> anyhow::Result<Option<Vec<Transactions>>> {
+    #[tokio::test(start_paused = true)]
+    async fn request_queue__request_without_peers_fails_after_the_timeout() {
+        // given
+        let timeout = Duration::from_secs(5);
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        task.request_queue = RequestQueue::new(Some(timeout), 10, PeerId::random());
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
         let (sender, receiver) = oneshot::channel();
-        let from_peer = PeerId::from_bytes(&peer_id).expect("Valid PeerId");
+        request_sender
+            .try_send(TaskRequest::GetSealedHeaders {
+                block_height_range: 0..2,
+                from_peer: None,
+                channel: sender,
+            })
+            .unwrap();
+        task.run(&mut watcher).await.unwrap();
 
-        let request = TaskRequest::GetTransactions {
-            block_height_range: range,
-            from_peer,
-            channel: sender,
-        };
-        self.request_sender.send(request).await?;
+        // when
+        let started_at = Instant::now();
+        task.run(&mut watcher).await.unwrap();
 
-        let (response_from_peer, response) =
-            receiver.await.map_err(|e| anyhow!("{e}"))?;
-        assert_eq!(
-            peer_id,
-            response_from_peer.to_bytes(),
-            "Bug: response from non-requested peer"
-        );
+        // then
+        let (_, response) = receiver.await.unwrap();
+        assert!(matches!(response, Err(ResponseError::NoPeers)));
+        assert_eq!(started_at.elapsed(), timeout);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn request_queue__oldest_request_is_rejected_when_the_queue_is_full() {
+        // given
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        task.request_queue =
+            RequestQueue::new(Some(Duration::from_secs(10)), 1, PeerId::random());
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+        let (oldest_sender, oldest_receiver) = oneshot::channel();
+        let (newest_sender, mut newest_receiver) = oneshot::channel();
+
+        // when
+        for (range, sender) in [(0..2, oldest_sender), (2..4, newest_sender)] {
+            request_sender
+                .try_send(TaskRequest::GetSealedHeaders {
+                    block_height_range: range,
+                    from_peer: None,
+                    channel: sender,
+                })
+                .unwrap();
+            task.run(&mut watcher).await.unwrap();
+        }
 
-        response.map_err(|e| anyhow!("Invalid response from peer {e:?}"))
+        // then
+        let (_, response) = oldest_receiver.await.unwrap();
+        assert!(matches!(response, Err(ResponseError::NoPeers)));
+        assert!(newest_receiver.try_recv().is_err());
     }
 
-    pub fn broadcast_transaction(
-        &self,
-        transaction: Arc<Transaction>,
-    ) -> anyhow::Result<()> {
-        self.request_sender
-            .try_send(TaskRequest::BroadcastTransaction(transaction))?;
-        Ok(())
+    #[tokio::test(start_paused = true)]
+    async fn stream_sealed_block_headers__chunks_of_failed_peer_are_served_by_other_peers(
+    ) {
+        // given
+        let first_peer = PeerId::random();
+        let failing_peer = PeerId::random();
+        let third_peer = PeerId::random();
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        task.p2p_service.response_delays = vec![
+            (first_peer, Duration::from_millis(100)),
+            (failing_peer, Duration::from_millis(50)),
+            (third_peer, Duration::from_millis(200)),
+        ];
+        task.p2p_service.failing_peers = vec![(failing_peer, 2)];
+        let shared_state = shared_state_with_sender(request_sender);
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+        tokio::spawn(async move { while task.run(&mut watcher).await.unwrap() {} });
+
+        // when
+        let headers: Vec<_> = shared_state
+            .stream_sealed_block_headers(0..30, 2, 3)
+            .collect()
+            .await;
+
+        // then
+        let heights: Vec<u32> = headers
+            .into_iter()
+            .map(|header| (*header.unwrap().entity.height()).into())
+            .collect();
+        assert_eq!(heights, (0..30).collect::<Vec<_>>());
     }
 
-    pub async fn get_peer_ids(&self) -> anyhow::Result<Vec<PeerId>> {
-        let (sender, receiver) = oneshot::channel();
+    #[tokio::test(start_paused = true)]
+    async fn stream_sealed_block_headers__too_large_chunks_are_split() {
+        // given
+        let peer = PeerId::random();
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        task.p2p_service.response_delays = vec![(peer, Duration::from_millis(100))];
+        task.p2p_service.max_headers_per_response = Some(3);
+        let shared_state = shared_state_with_sender(request_sender);
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+        tokio::spawn(async move { while task.run(&mut watcher).await.unwrap() {} });
 
-        self.request_sender
-            .send(TaskRequest::GetPeerIds(sender))
-            .await?;
+        // when
+        let headers: Vec<_> = shared_state
+            .stream_sealed_block_headers(0..10, 10, 1)
+            .collect()
+            .await;
 
-        receiver.await.map_err(|e| anyhow!("{}", e))
+        // then
+        let heights: Vec<u32> = headers
+            .into_iter()
+            .map(|header| (*header.unwrap().entity.height()).into())
+            .collect();
+        assert_eq!(heights, (0..10).collect::<Vec<_>>());
     }
 
-    pub async fn get_all_peers(&self) -> anyhow::Result<Vec<(PeerId, PeerInfo)>> {
-        let (sender, receiver) = oneshot::channel();
+    #[tokio::test]
+    async fn get_sealed_headers__duplicate_requests_fetch_from_db_once() {
+        // given
+        let db = FakeDB::default();
+        let caches = ResponseCaches::default();
 
-        self.request_sender
-            .send(TaskRequest::GetAllPeerInfo { channel: sender })
-            .await?;
+        // when
+        let first_response = caches.get_sealed_headers(&db, 0..2).unwrap();
+        let second_response = caches.get_sealed_headers(&db, 0..2).unwrap();
 
-        receiver.await.map_err(|e| anyhow!("{}", e))
+        // then
+        assert_eq!(first_response, second_response);
+        assert_eq!(
+            db.sealed_headers_requests
+                .load(std::sync::atomic::Ordering::SeqCst),
+            1
+        );
     }
 
-    pub fn subscribe_tx(&self) -> broadcast::Receiver<TransactionGossipData> {
-        self.tx_broadcast.subscribe()
+    #[tokio::test]
+    async fn peer_bans__are_restored_after_restart() {
+        // given
+        let mut db = FakeDB::default();
+        let banned_peer = PeerId::random();
+        let expired_peer = PeerId::random();
+        db.insert_peer_ban(
+            &FuelPeerId::from(expired_peer.to_bytes()),
+            &PeerBan {
+                reason: "Expired".to_string(),
+                expiry: SystemTime::now()
+                    .checked_sub(Duration::from_secs(1))
+                    .unwrap(),
+            },
+        )
+        .unwrap();
+
+        let (mut task, request_sender) = task_with_view_provider(db.clone());
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+        let reports = 50;
+        for _ in 0..reports {
+            let message_info = GossipsubMessageInfo {
+                message_id: vec![],
+                peer_id: FuelPeerId::from(banned_peer.to_bytes()),
+            };
+            request_sender
+                .try_send(TaskRequest::RespondWithGossipsubMessageReport(
+                    GossipsubMessageReport {
+                        message_info,
+                        acceptance: GossipsubMessageAcceptance::Reject,
+                    },
+                ))
+                .unwrap();
+        }
+        for _ in 0..reports {
+            task.run(&mut watcher).await.unwrap();
+        }
+        assert_eq!(task.p2p_service.banned_peers, vec![banned_peer]);
+        task.await_peer_store_writes().await;
+        drop(task);
+
+        // when
+        let (mut restarted_task, _) = task_with_view_provider(db.clone());
+        restarted_task.restore_peer_bans().unwrap();
+
+        // then
+        assert_eq!(restarted_task.p2p_service.banned_peers, vec![banned_peer]);
+        assert!(restarted_task.peer_reputation.is_banned(&banned_peer));
+        assert!(!restarted_task.peer_reputation.is_banned(&expired_peer));
+        let persisted: Vec<_> = db
+            .load_peer_bans()
+            .unwrap()
+            .into_iter()
+            .map(|(peer_id, _)| peer_id)
+            .collect();
+        assert_eq!(persisted, vec![FuelPeerId::from(banned_peer.to_bytes())]);
     }
 
-    pub fn subscribe_block_height(
-        &self,
-    ) -> broadcast::Receiver<BlockHeightHeartbeatData> {
-        self.block_height_broadcast.subscribe()
+    #[tokio::test]
+    async fn known_peers__are_dialed_after_restart() {
+        // given
+        let mut db = FakeDB::default();
+        let peer_id = PeerId::random();
+        let address: Multiaddr = "/ip4/127.0.0.1/tcp/4001".parse().unwrap();
+        let stale_peer = FuelPeerId::from(PeerId::random().to_bytes());
+        db.insert_known_peer(
+            &stale_peer,
+            &KnownPeer {
+                addresses: vec!["/ip4/127.0.0.2/tcp/4001"
+                    .parse::<Multiaddr>()
+                    .unwrap()
+                    .to_vec()],
+                last_seen: SystemTime::now()
+                    .checked_sub(Duration::from_secs(60 * 60))
+                    .unwrap(),
+                score: 0.,
+            },
+        )
+        .unwrap();
+
+        let (mut task, _) = task_with_view_provider(db.clone());
+        task.known_peers.max_age = Duration::from_secs(60);
+        let peer_info = PeerInfo {
+            peer_addresses: HashSet::from([address.clone()]),
+            client_version: None,
+            protocols: vec![],
+            heartbeat_data: HeartbeatData::new(1),
+            score: 100.0,
+            gossipsub_score: None,
+            direction: None,
+            last_response_time: None,
+            average_response_time: None,
+            transport_security: Default::default(),
+            is_archival: false,
+            last_activity: Instant::now(),
+            bandwidth: Default::default(),
+        };
+        task.p2p_service.peer_info = vec![(peer_id, peer_info)];
+        task.shutdown().await.unwrap();
+
+        // when
+        let (mut restarted_task, _) = task_with_view_provider(db.clone());
+        restarted_task.known_peers.max_age = Duration::from_secs(60);
+        restarted_task.restore_known_peers().unwrap();
+
+        // then
+        assert_eq!(restarted_task.p2p_service.dialed, vec![address]);
+        let known_peers = db.load_known_peers().unwrap();
+        assert_eq!(known_peers.len(), 1);
+        assert_eq!(known_peers[0].0, FuelPeerId::from(peer_id.to_bytes()));
     }
 
-    pub fn subscribe_reserved_peers_count(&self) -> broadcast::Receiver<usize> {
-        self.reserved_peers_broadcast.subscribe()
+    #[tokio::test]
+    async fn gossip_reports__malformed_peer_id_does_not_stop_the_task() {
+        // given
+        let peer_id = PeerId::random();
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+        let malformed_info = GossipsubMessageInfo {
+            message_id: vec![1, 2, 3],
+            peer_id: FuelPeerId::from(vec![0xde, 0xad, 0xbe, 0xef]),
+        };
+        let valid_info = GossipsubMessageInfo {
+            message_id: vec![4, 5, 6],
+            peer_id: FuelPeerId::from(peer_id.to_bytes()),
+        };
+
+        // when
+        request_sender
+            .try_send(TaskRequest::RespondWithGossipsubMessageReport(
+                GossipsubMessageReport {
+                    message_info: malformed_info,
+                    acceptance: GossipsubMessageAcceptance::Reject,
+                },
+            ))
+            .unwrap();
+        let should_continue = task.run(&mut watcher).await.unwrap();
+        assert!(should_continue);
+        request_sender
+            .try_send(TaskRequest::RespondWithGossipsubMessageReport(
+                GossipsubMessageReport {
+                    message_info: valid_info.clone(),
+                    acceptance: GossipsubMessageAcceptance::Accept,
+                },
+            ))
+            .unwrap();
+        task.run(&mut watcher).await.unwrap();
+
+        // then
+        assert!(task.p2p_service.banned_peers.is_empty());
+        assert_eq!(
+            task.p2p_service.reported_messages.last(),
+            Some(&(valid_info, GossipsubMessageAcceptance::Accept))
+        );
     }
 
-    pub fn report_peer<T: PeerReport>(
-        &self,
-        peer_id: FuelPeerId,
-        peer_report: T,
-        reporting_service: &'static str,
-    ) -> anyhow::Result<()> {
-        match Vec::from(peer_id).try_into() {
-            Ok(peer_id) => {
-                let score = peer_report.get_score_from_report();
+    #[tokio::test]
+    async fn report_message__malformed_peer_id_is_counted_and_skipped() {
+        // given
+        let mut p2p_config = Config::default_initialized(
+            "report_message__malformed_peer_id_is_counted_and_skipped",
+        );
+        p2p_config.metrics = true;
+        let (reserved_peers_broadcast, _) = broadcast::channel(1);
+        let mut p2p_service = FuelP2PService::new(
+            reserved_peers_broadcast,
+            p2p_config,
+            PostcardCodec::new(1024),
+        )
+        .unwrap();
+        let errors_before = p2p_metrics().gossip_report_errors.get();
 
-                self.request_sender
-                    .try_send(TaskRequest::RespondWithPeerReport {
-                        peer_id,
-                        score,
-                        reporting_service,
-                    })?;
+        // when
+        let malformed_info = GossipsubMessageInfo {
+            message_id: vec![1, 2, 3],
+            peer_id: FuelPeerId::from(vec![0xde, 0xad, 0xbe, 0xef]),
+        };
+        report_message(
+            &mut p2p_service,
+            malformed_info,
+            GossipsubMessageAcceptance::Reject,
+        );
+
+        // then
+        assert!(p2p_metrics().gossip_report_errors.get() > errors_before);
+    }
+
+    #[tokio::test]
+    async fn tx_broadcast__slow_subscriber_is_counted_as_lagging() {
+        // given
+        let mut p2p_config = Config::<NotInitialized>::default(
+            "tx_broadcast__slow_subscriber_is_counted_as_lagging",
+        );
+        p2p_config.metrics = true;
+        p2p_config.tx_broadcast_capacity = 4;
+        let task = UninitializedTask::new(
+            ChainId::default(),
+            p2p_config,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        let shared = task.broadcast;
+        let mut slow_subscriber = shared.subscribe_tx();
+        let transaction = || {
+            GossipData::new(
+                Transaction::default_test_tx(),
+                PeerId::random().to_bytes(),
+                vec![],
+            )
+        };
+        for _ in 0..4 {
+            shared.tx_broadcast(transaction()).unwrap();
+        }
+        let drops_before =
+            p2p_metrics().consumer_channel_drops(ConsumerChannel::Transactions.name());
+        let skipped_before = p2p_metrics().gossip_tx_subscriber_lagged.get();
+
+        // when
+        shared.tx_broadcast(transaction()).unwrap();
+
+        // then
+        assert!(
+            p2p_metrics().consumer_channel_drops(ConsumerChannel::Transactions.name())
+                > drops_before
+        );
+        // The subscriber skips the oldest transaction and receives the rest
+        for _ in 0..4 {
+            assert!(slow_subscriber.try_recv().is_ok());
+        }
+        assert!(slow_subscriber.try_recv().is_err());
+        assert_eq!(slow_subscriber.lagged(), 1);
+        assert!(p2p_metrics().gossip_tx_subscriber_lagged.get() > skipped_before);
+    }
 
-                Ok(())
-            }
-            Err(e) => {
-                warn!(target: "fuel-p2p", "Failed to read PeerId from {e:?}");
-                Err(anyhow::anyhow!("Failed to read PeerId from {e:?}"))
-            }
+    fn block_height_broadcast_with_policy(
+        policy: OverflowPolicy,
+    ) -> (SharedState, broadcast::Receiver<BlockHeightHeartbeatData>) {
+        let mut p2p_config = Config::<NotInitialized>::default("block_height_broadcast");
+        p2p_config.metrics = true;
+        p2p_config.block_height_broadcast_capacity = 4;
+        p2p_config.block_height_overflow_policy = policy;
+        let task = UninitializedTask::new(
+            ChainId::default(),
+            p2p_config,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        let shared = task.broadcast;
+        // The consumer never reads the channel
+        let receiver = shared.subscribe_block_height();
+        (shared, receiver)
+    }
+
+    fn fill_block_heights(shared: &SharedState) {
+        for height in 0..6u32 {
+            shared
+                .block_height_broadcast(BlockHeightHeartbeatData {
+                    peer_id: PeerId::random().to_bytes().into(),
+                    block_height: height.into(),
+                })
+                .unwrap();
         }
     }
-}
 
-pub fn new_service<V, B>(
-    chain_id: ChainId,
-    p2p_config: Config<NotInitialized>,
-    view_provider: V,
-    block_importer: B,
-) -> Service<V>
-where
-    V: AtomicView + 'static,
-    V::View: P2pDb,
-    B: BlockHeightImporter,
-{
-    let task =
-        UninitializedTask::new(chain_id, p2p_config, view_provider, block_importer);
-    Service::new(task)
-}
+    #[tokio::test]
+    async fn block_height_broadcast__drop_newest_keeps_the_oldest_heights() {
+        // given
+        let (shared, mut receiver) =
+            block_height_broadcast_with_policy(OverflowPolicy::DropNewest);
+        let drops_before =
+            p2p_metrics().consumer_channel_drops(ConsumerChannel::BlockHeights.name());
 
-pub fn to_message_acceptance(
-    acceptance: &GossipsubMessageAcceptance,
-) -> MessageAcceptance {
-    match acceptance {
-        GossipsubMessageAcceptance::Accept => MessageAcceptance::Accept,
-        GossipsubMessageAcceptance::Reject => MessageAcceptance::Reject,
-        GossipsubMessageAcceptance::Ignore => MessageAcceptance::Ignore,
+        // when
+        fill_block_heights(&shared);
+
+        // then
+        for height in 0..4u32 {
+            assert_eq!(
+                receiver.try_recv().unwrap().block_height,
+                BlockHeight::from(height)
+            );
+        }
+        assert!(receiver.try_recv().is_err());
+        assert!(
+            p2p_metrics().consumer_channel_drops(ConsumerChannel::BlockHeights.name())
+                >= drops_before.saturating_add(2)
+        );
     }
-}
 
-fn report_message(
-    p2p_service: &mut FuelP2PService,
-    message: GossipsubMessageInfo,
-    acceptance: GossipsubMessageAcceptance,
-) {
-    let GossipsubMessageInfo {
-        peer_id,
-        message_id,
-    } = message;
+    #[tokio::test]
+    async fn block_height_broadcast__drop_oldest_keeps_the_latest_heights() {
+        // given
+        let (shared, mut receiver) =
+            block_height_broadcast_with_policy(OverflowPolicy::DropOldest);
+        let drops_before =
+            p2p_metrics().consumer_channel_drops(ConsumerChannel::BlockHeights.name());
 
-    let msg_id = message_id.into();
-    let peer_id: Vec<u8> = peer_id.into();
+        // when
+        fill_block_heights(&shared);
 
-    if let Ok(peer_id) = peer_id.try_into() {
-        let acceptance = to_message_acceptance(&acceptance);
-        p2p_service.report_message_validation_result(&msg_id, peer_id, acceptance);
-    } else {
-        warn!(target: "fuel-p2p", "Failed to read PeerId from received GossipsubMessageId: {}", msg_id);
+        // then
+        assert!(matches!(
+            receiver.try_recv(),
+            Err(broadcast::error::TryRecvError::Lagged(2))
+        ));
+        for height in 2..6u32 {
+            assert_eq!(
+                receiver.try_recv().unwrap().block_height,
+                BlockHeight::from(height)
+            );
+        }
+        assert!(
+            p2p_metrics().consumer_channel_drops(ConsumerChannel::BlockHeights.name())
+                >= drops_before.saturating_add(2)
+        );
     }
-}
 
-#[cfg(test)]
-pub mod tests {
-    #![allow(non_snake_case)]
-    use crate::ports::P2pDb;
+    fn full_request_channel_with_policy(
+        policy: OverflowPolicy,
+    ) -> (UninitializedTask<FakeDb, SharedState, FakeDb>, SharedState) {
+        let mut p2p_config = Config::<NotInitialized>::default("full_request_channel");
+        p2p_config.metrics = true;
+        p2p_config.request_channel_capacity = 1;
+        p2p_config.request_overflow_policy = policy;
+        let task = UninitializedTask::new(
+            ChainId::default(),
+            p2p_config,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        let shared = task.broadcast.clone();
+        // The task never reads the channel
+        shared.notify_consumers_ready().unwrap();
+        (task, shared)
+    }
 
-    use super::*;
+    #[tokio::test]
+    async fn request_channel__drop_newest_rejects_the_request_when_full() {
+        // given
+        let (_task, shared) =
+            full_request_channel_with_policy(OverflowPolicy::DropNewest);
+        let drops_before =
+            p2p_metrics().consumer_channel_drops(ConsumerChannel::Requests.name());
 
-    use crate::peer_manager::heartbeat_data::HeartbeatData;
-    use fuel_core_services::{
-        Service,
-        State,
-    };
-    use fuel_core_storage::Result as StorageResult;
-    use fuel_core_types::{
-        blockchain::consensus::Genesis,
-        fuel_types::BlockHeight,
-    };
-    use futures::FutureExt;
-    use std::{
-        collections::VecDeque,
-        time::SystemTime,
-    };
+        // when
+        let result = shared.get_peer_ids().await;
 
-    #[derive(Clone, Debug)]
-    struct FakeDb;
+        // then
+        assert!(matches!(result, Err(P2pError::QueueFull)));
+        assert!(
+            p2p_metrics().consumer_channel_drops(ConsumerChannel::Requests.name())
+                > drops_before
+        );
+    }
 
-    impl AtomicView for FakeDb {
-        type View = Self;
+    #[tokio::test]
+    async fn request_channel__block_waits_for_a_free_slot() {
+        // given
+        let (mut task, shared) = full_request_channel_with_policy(OverflowPolicy::Block);
+        let request = tokio::spawn(async move { shared.get_peer_ids().await });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!request.is_finished());
 
-        type Height = BlockHeight;
+        // when
+        assert!(matches!(
+            task.request_receiver.recv().await,
+            Some(TaskRequest::ConsumersReady)
+        ));
 
-        fn latest_height(&self) -> Self::Height {
-            BlockHeight::default()
+        // then
+        match task.request_receiver.recv().await {
+            Some(TaskRequest::GetPeerIds(channel)) => {
+                channel.send(vec![]).unwrap();
+            }
+            _ => panic!("The blocked request should be delivered"),
         }
+        assert!(request.await.unwrap().unwrap().is_empty());
+    }
 
-        fn view_at(&self, _: &BlockHeight) -> StorageResult<Self::View> {
-            unimplemented!()
+    #[tokio::test]
+    async fn tx_subscription__resubscribe_skips_the_pending_transactions() {
+        // given
+        let mut p2p_config = Config::<NotInitialized>::default(
+            "tx_subscription__resubscribe_skips_the_pending_transactions",
+        );
+        p2p_config.tx_broadcast_capacity = 4;
+        let task = UninitializedTask::new(
+            ChainId::default(),
+            p2p_config,
+            FakeDb::default(),
+            FakeDb::default(),
+            FakeBlockImporter,
+        );
+        let shared = task.broadcast;
+        let mut subscription = shared.subscribe_tx();
+        let transaction = |message_id: u8| {
+            GossipData::new(
+                Transaction::default_test_tx(),
+                PeerId::random().to_bytes(),
+                vec![message_id],
+            )
+        };
+        for message_id in 0..6 {
+            shared.tx_broadcast(transaction(message_id)).unwrap();
         }
 
-        fn latest_view(&self) -> Self::View {
-            self.clone()
-        }
+        // when
+        let skipped = subscription.resubscribe();
+
+        // then
+        assert_eq!(skipped, 6);
+        assert_eq!(subscription.lagged(), 6);
+        assert!(subscription.try_recv().is_err());
+        shared.tx_broadcast(transaction(6)).unwrap();
+        assert_eq!(subscription.try_recv().unwrap().message_id, vec![6]);
     }
 
-    impl P2pDb for FakeDb {
-        fn get_sealed_headers(
-            &self,
-            _block_height_range: Range<u32>,
-        ) -> StorageResult<Vec<SealedBlockHeader>> {
-            unimplemented!()
-        }
+    #[tokio::test]
+    async fn gossip_reports__report_refers_to_the_received_message() {
+        // given
+        let peer_id = PeerId::random();
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
 
-        fn get_transactions(
-            &self,
-            _block_height_range: Range<u32>,
-        ) -> StorageResult<Option<Vec<Transactions>>> {
-            unimplemented!()
-        }
+        // The gossip is received the same way as in the `run`
+        let mut gossip = GossipData::new(
+            Transaction::default_test_tx(),
+            peer_id.to_bytes(),
+            vec![1, 2, 3],
+        );
+        assert!(gossip.take_data().is_some());
 
-        fn get_genesis(&self) -> StorageResult<Genesis> {
-            Ok(Default::default())
-        }
+        // when
+        let report = gossip.into_report(GossipsubMessageAcceptance::Accept);
+        request_sender
+            .try_send(TaskRequest::RespondWithGossipsubMessageReport(report))
+            .unwrap();
+        task.run(&mut watcher).await.unwrap();
+
+        // then
+        let expected_info = GossipsubMessageInfo {
+            message_id: vec![1, 2, 3],
+            peer_id: FuelPeerId::from(peer_id.to_bytes()),
+        };
+        assert_eq!(
+            task.p2p_service.reported_messages,
+            vec![(expected_info, GossipsubMessageAcceptance::Accept)]
+        );
     }
 
-    #[derive(Clone, Debug)]
-    struct FakeBlockImporter;
+    #[tokio::test]
+    async fn gossip_stats__counts_received_and_forwarded_transactions() {
+        // given
+        let peer_id = PeerId::random();
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        let counters = task.gossip_counters.clone();
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+        for seed in [1, 2] {
+            task.p2p_service
+                .events
+                .push_back(FuelP2PEvent::GossipsubMessage {
+                    peer_id,
+                    message_id: MessageId::new(&[seed]),
+                    topic_hash: TopicHash::from_raw("new_tx"),
+                    message: GossipsubMessage::NewTx(unique_tx(seed)),
+                });
+        }
 
-    impl BlockHeightImporter for FakeBlockImporter {
-        fn next_block_height(&self) -> BoxStream<BlockHeight> {
-            Box::pin(fuel_core_services::stream::pending())
+        // when
+        task.run(&mut watcher).await.unwrap();
+        task.run(&mut watcher).await.unwrap();
+        for acceptance in [
+            GossipsubMessageAcceptance::Accept,
+            GossipsubMessageAcceptance::Reject,
+        ] {
+            let message_info = GossipsubMessageInfo {
+                message_id: vec![],
+                peer_id: FuelPeerId::from(peer_id.to_bytes()),
+            };
+            request_sender
+                .try_send(TaskRequest::RespondWithGossipsubMessageReport(
+                    GossipsubMessageReport {
+                        message_info,
+                        acceptance,
+                    },
+                ))
+                .unwrap();
+            task.run(&mut watcher).await.unwrap();
         }
+
+        // then
+        assert_eq!(
+            counters.snapshot(),
+            GossipStats {
+                new_tx_received: 2,
+                new_tx_forwarded: 1,
+            }
+        );
     }
 
     #[tokio::test]
-    async fn start_and_stop_awaits_works() {
-        let p2p_config = Config::<NotInitialized>::default("start_stop_works");
-        let service =
-            new_service(ChainId::default(), p2p_config, FakeDb, FakeBlockImporter);
+    async fn broadcast_transaction__skips_transaction_received_via_gossip() {
+        // given
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+        let transaction = Transaction::default_test_tx();
+        task.p2p_service
+            .events
+            .push_back(FuelP2PEvent::GossipsubMessage {
+                peer_id: PeerId::random(),
+                message_id: MessageId::new(&[1]),
+                topic_hash: TopicHash::from_raw("new_tx"),
+                message: GossipsubMessage::NewTx(transaction.clone()),
+            });
+        task.run(&mut watcher).await.unwrap();
 
-        // Node with p2p service started
-        assert!(service.start_and_await().await.unwrap().started());
-        // Node with p2p service stopped
-        assert!(service.stop_and_await().await.unwrap().stopped());
+        // when
+        request_sender
+            .try_send(TaskRequest::BroadcastTransaction {
+                transaction: Arc::new(transaction),
+                channel: None,
+            })
+            .unwrap();
+        task.run(&mut watcher).await.unwrap();
+
+        // then
+        assert!(task.p2p_service.published.is_empty());
     }
 
-    struct FakeP2PService {
-        peer_info: Vec<(PeerId, PeerInfo)>,
+    #[tokio::test]
+    async fn broadcast_transaction__origin_check_doesnt_depend_on_the_duplicates_cache() {
+        // given
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        task.gossip_duplicates = ResponseCache::new(Duration::ZERO, 0);
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+        let transaction = Transaction::default_test_tx();
+        task.p2p_service
+            .events
+            .push_back(FuelP2PEvent::GossipsubMessage {
+                peer_id: PeerId::random(),
+                message_id: MessageId::new(&[1]),
+                topic_hash: TopicHash::from_raw("new_tx"),
+                message: GossipsubMessage::NewTx(transaction.clone()),
+            });
+        task.run(&mut watcher).await.unwrap();
+
+        // when
+        let (sender, receiver) = oneshot::channel();
+        request_sender
+            .try_send(TaskRequest::BroadcastTransaction {
+                transaction: Arc::new(transaction),
+                channel: Some(sender),
+            })
+            .unwrap();
+        task.run(&mut watcher).await.unwrap();
+
+        // then
+        assert!(task.p2p_service.published.is_empty());
+        assert_eq!(receiver.await.unwrap(), Err(BroadcastError::Duplicate));
     }
 
-    impl TaskP2PService for FakeP2PService {
-        fn get_peer_ids(&self) -> Vec<PeerId> {
-            todo!()
-        }
+    #[tokio::test]
+    async fn broadcast_transaction__publishes_gossiped_transaction_not_handed_to_consumers(
+    ) {
+        // given
+        let uninitialized = UninitializedTask::new(
+            ChainId::default(),
+            Config::<NotInitialized>::default(
+                "broadcast_transaction__publishes_gossiped_transaction_not_handed_to_consumers",
+            ),
+            FakeDB::default(),
+            FakeDB::default(),
+            FakeBlockImporter,
+        );
+        // Nobody is subscribed, so the gossiped transaction isn't handed to the consumers
+        let shared = uninitialized.broadcast.clone();
+        let (mut task, request_sender) = task_with_fake(FakeDB::default(), shared);
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+        let transaction = Transaction::default_test_tx();
+        task.p2p_service
+            .events
+            .push_back(FuelP2PEvent::GossipsubMessage {
+                peer_id: PeerId::random(),
+                message_id: MessageId::new(&[1]),
+                topic_hash: TopicHash::from_raw("new_tx"),
+                message: GossipsubMessage::NewTx(transaction.clone()),
+            });
+        task.run(&mut watcher).await.unwrap();
 
-        fn get_all_peer_info(&self) -> Vec<(&PeerId, &PeerInfo)> {
-            self.peer_info.iter().map(|tup| (&tup.0, &tup.1)).collect()
-        }
+        // when
+        request_sender
+            .try_send(TaskRequest::BroadcastTransaction {
+                transaction: Arc::new(transaction),
+                channel: None,
+            })
+            .unwrap();
+        task.run(&mut watcher).await.unwrap();
 
-        fn get_peer_id_with_height(&self, _height: &BlockHeight) -> Option<PeerId> {
-            todo!()
-        }
+        // then
+        assert_eq!(task.p2p_service.published.len(), 1);
+    }
 
-        fn next_event(&mut self) -> BoxFuture<'_, Option<FuelP2PEvent>> {
-            std::future::pending().boxed()
-        }
+    #[tokio::test]
+    async fn has_local_block__reports_only_the_stored_heights() {
+        // given
+        let db = FakeDB {
+            stored_heights: vec![BlockHeight::from(1)],
+            ..Default::default()
+        };
+        let (mut task, request_sender) = task_with_view_provider(db);
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+        let has_local_block = |height: u32| {
+            let (sender, receiver) = oneshot::channel();
+            request_sender
+                .try_send(TaskRequest::HasLocalBlock {
+                    height: height.into(),
+                    channel: sender,
+                })
+                .unwrap();
+            receiver
+        };
 
-        fn publish_message(
-            &mut self,
-            _message: GossipsubBroadcastRequest,
-        ) -> anyhow::Result<()> {
-            todo!()
-        }
+        // when
+        let known = has_local_block(1);
+        task.run(&mut watcher).await.unwrap();
+        let unknown = has_local_block(2);
+        task.run(&mut watcher).await.unwrap();
 
-        fn send_request_msg(
-            &mut self,
-            _peer_id: Option<PeerId>,
-            _request_msg: RequestMessage,
-            _on_response: ResponseSender,
-        ) -> anyhow::Result<()> {
-            todo!()
-        }
+        // then
+        assert!(known.await.unwrap().unwrap());
+        assert!(!unknown.await.unwrap().unwrap());
+    }
 
-        fn send_response_msg(
-            &mut self,
-            _request_id: InboundRequestId,
-            _message: ResponseMessage,
-        ) -> anyhow::Result<()> {
-            todo!()
-        }
+    #[tokio::test]
+    async fn get_peer_latencies__reports_only_the_peers_that_answered() {
+        // given
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        let answered_peer = PeerId::random();
+        let silent_peer = PeerId::random();
+        let mut answered_peer_info = PeerInfo::new(1);
+        answered_peer_info.average_response_time = Some(Duration::from_millis(150));
+        task.p2p_service.peer_info = vec![
+            (answered_peer, answered_peer_info),
+            (silent_peer, PeerInfo::new(1)),
+        ];
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
 
-        fn report_message(
-            &mut self,
-            _message: GossipsubMessageInfo,
-            _acceptance: GossipsubMessageAcceptance,
-        ) -> anyhow::Result<()> {
-            todo!()
-        }
+        // when
+        let (sender, receiver) = oneshot::channel();
+        request_sender
+            .try_send(TaskRequest::GetPeerLatencies { channel: sender })
+            .unwrap();
+        task.run(&mut watcher).await.unwrap();
 
-        fn report_peer(
-            &mut self,
-            _peer_id: PeerId,
-            _score: AppScore,
-            _reporting_service: &str,
-        ) -> anyhow::Result<()> {
-            todo!()
+        // then
+        assert_eq!(
+            receiver.await.unwrap(),
+            vec![(answered_peer, Duration::from_millis(150))]
+        );
+    }
+
+    #[tokio::test]
+    async fn block_height_consumer__dropped_receiver_is_reported_as_gone() {
+        // given
+        let uninitialized = UninitializedTask::new(
+            ChainId::default(),
+            Config::<NotInitialized>::default(
+                "block_height_consumer__dropped_receiver_is_reported_as_gone",
+            ),
+            FakeDB::default(),
+            FakeDB::default(),
+            FakeBlockImporter,
+        );
+        let shared = uninitialized.broadcast.clone();
+        let mut events = shared.subscribe_events();
+        // Nobody subscribes to the block heights
+        let (mut task, _) = task_with_fake(FakeDB::default(), shared);
+        let threshold = 3;
+        task.block_height_consumer =
+            ConsumerHealth::new(ConsumerChannel::BlockHeights, threshold);
+        let peer_id = PeerId::random();
+        for _ in 0..threshold {
+            task.p2p_service
+                .events
+                .push_back(FuelP2PEvent::PeerInfoUpdated {
+                    peer_id,
+                    block_height: 1.into(),
+                });
         }
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
 
-        fn update_block_height(&mut self, _height: BlockHeight) -> anyhow::Result<()> {
-            todo!()
+        // when
+        for _ in 0..threshold {
+            task.run(&mut watcher).await.unwrap();
+        }
+
+        // then
+        let mut consumers_gone = vec![];
+        while let Ok(event) = events.try_recv() {
+            if let NetworkEvent::ConsumerGone(channel) = event {
+                consumers_gone.push(channel);
+            }
         }
+        assert_eq!(consumers_gone, vec![ConsumerChannel::BlockHeights]);
     }
 
-    #[derive(Clone)]
-    struct FakeDB;
+    #[test]
+    fn consumer_health__is_reported_once_and_reset_by_successful_send() {
+        let mut health = ConsumerHealth::new(ConsumerChannel::Transactions, 2);
+        let failure = || Err(anyhow!("The receiver is dropped"));
 
-    impl AtomicView for FakeDB {
-        type View = Self;
+        assert!(!health.record(&failure()));
+        assert!(!health.record(&Ok(())));
+        assert!(!health.record(&failure()));
+        assert!(health.record(&failure()));
+        assert!(!health.record(&failure()));
+    }
 
-        type Height = BlockHeight;
+    #[tokio::test(start_paused = true)]
+    async fn tx_batcher__queued_transactions_are_published_as_one_message() {
+        // given
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        task.tx_batcher = TxBatcher::new(Some(Duration::from_millis(10)), 1024 * 1024);
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+        let transactions = 3;
 
-        fn latest_height(&self) -> Self::Height {
-            BlockHeight::default()
+        // when
+        for _ in 0..transactions {
+            request_sender
+                .try_send(TaskRequest::BroadcastTransaction {
+                    transaction: Arc::new(Transaction::default_test_tx()),
+                    channel: None,
+                })
+                .unwrap();
+            task.run(&mut watcher).await.unwrap();
         }
+        assert!(task.p2p_service.published.is_empty());
+        // The batch is published when the interval elapses
+        task.run(&mut watcher).await.unwrap();
 
-        fn view_at(&self, _: &BlockHeight) -> StorageResult<Self::View> {
-            unimplemented!()
+        // then
+        let [GossipsubBroadcastRequest::NewTxBatch(batch)] =
+            task.p2p_service.published.as_slice()
+        else {
+            panic!("Expected a single batch")
+        };
+        assert_eq!(batch.len(), transactions);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn tx_batcher__publish_result_is_sent_for_each_transaction_of_the_batch() {
+        // given
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        task.tx_batcher = TxBatcher::new(Some(Duration::from_millis(10)), 1024 * 1024);
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+        let mut receivers = vec![];
+        for _ in 0..2 {
+            let (sender, receiver) = oneshot::channel();
+            request_sender
+                .try_send(TaskRequest::BroadcastTransaction {
+                    transaction: Arc::new(Transaction::default_test_tx()),
+                    channel: Some(sender),
+                })
+                .unwrap();
+            task.run(&mut watcher).await.unwrap();
+            receivers.push(receiver);
         }
 
-        fn latest_view(&self) -> Self::View {
-            self.clone()
+        // when
+        task.run(&mut watcher).await.unwrap();
+
+        // then
+        assert_eq!(task.p2p_service.published.len(), 1);
+        for receiver in receivers {
+            assert_eq!(receiver.await.unwrap(), Ok(MessageId::new(&[])));
         }
     }
 
-    impl P2pDb for FakeDB {
-        fn get_sealed_headers(
-            &self,
-            _block_height_range: Range<u32>,
-        ) -> StorageResult<Vec<SealedBlockHeader>> {
-            todo!()
-        }
+    #[tokio::test]
+    async fn tx_batcher__full_batch_is_published_immediately() {
+        // given
+        let (mut task, request_sender) = task_with_view_provider(FakeDB::default());
+        // Any transaction fills the batch
+        task.tx_batcher = TxBatcher::new(Some(Duration::from_secs(100)), 1);
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
 
-        fn get_transactions(
-            &self,
-            _block_height_range: Range<u32>,
-        ) -> StorageResult<Option<Vec<Transactions>>> {
-            todo!()
-        }
+        // when
+        request_sender
+            .try_send(TaskRequest::BroadcastTransaction {
+                transaction: Arc::new(Transaction::default_test_tx()),
+                channel: None,
+            })
+            .unwrap();
+        task.run(&mut watcher).await.unwrap();
 
-        fn get_genesis(&self) -> StorageResult<Genesis> {
-            todo!()
-        }
+        // then
+        assert_eq!(task.p2p_service.published.len(), 1);
+        assert!(task.tx_batcher.deadline.is_none());
     }
 
-    struct FakeBroadcast {
-        pub peer_reports: mpsc::Sender<(FuelPeerId, AppScore, String)>,
+    #[tokio::test(start_paused = true)]
+    async fn batch_reports__late_results_are_combined_with_their_batch() {
+        // given
+        let mut reports = BatchReports::default();
+        let message_id = vec![1, 2, 3];
+        reports.insert(message_id.clone(), PeerId::random(), 2);
+        let first = reports.combine(&message_id, GossipsubMessageAcceptance::Accept);
+
+        // when
+        tokio::time::advance(Duration::from_secs(60 * 60)).await;
+        let second = reports.combine(&message_id, GossipsubMessageAcceptance::Reject);
+
+        // then
+        assert_eq!(first, None);
+        assert_eq!(second, Some((GossipsubMessageAcceptance::Reject, 2)));
     }
 
-    impl Broadcast for FakeBroadcast {
-        fn report_peer(
-            &self,
-            peer_id: FuelPeerId,
-            report: AppScore,
-            reporting_service: &'static str,
-        ) -> anyhow::Result<()> {
-            self.peer_reports.try_send((
-                peer_id,
-                report,
-                reporting_service.to_string(),
-            ))?;
-            Ok(())
+    #[tokio::test(start_paused = true)]
+    async fn batch_reports__late_results_of_abandoned_batches_are_dropped() {
+        // given
+        let mut reports = BatchReports::default();
+        let oldest_peer = PeerId::random();
+        reports.insert(vec![0], oldest_peer, 2);
+        for id in 1..BATCH_REPORTS_CAPACITY {
+            tokio::time::advance(Duration::from_millis(1)).await;
+            reports.insert(id.to_be_bytes().to_vec(), PeerId::random(), 2);
         }
 
-        fn block_height_broadcast(
-            &self,
-            _block_height_data: BlockHeightHeartbeatData,
-        ) -> anyhow::Result<()> {
-            todo!()
-        }
+        // when
+        let abandoned = reports.insert(vec![u8::MAX; 9], PeerId::random(), 2);
+        let late_result = reports.combine(&[0], GossipsubMessageAcceptance::Accept);
 
-        fn tx_broadcast(
-            &self,
-            _transaction: TransactionGossipData,
-        ) -> anyhow::Result<()> {
-            todo!()
-        }
+        // then
+        assert_eq!(abandoned, Some((vec![0], oldest_peer)));
+        assert_eq!(late_result, None);
+        assert_eq!(
+            reports.combine(&[4, 5, 6], GossipsubMessageAcceptance::Accept),
+            Some((GossipsubMessageAcceptance::Accept, 1))
+        );
     }
 
     #[tokio::test]
-    async fn peer_heartbeat_reputation_checks__slow_heartbeat_sends_reports() {
+    async fn tx_batch__is_unpacked_and_reported_as_one_message() {
         // given
+        let uninitialized = UninitializedTask::new(
+            ChainId::default(),
+            Config::<NotInitialized>::default(
+                "tx_batch__is_unpacked_and_reported_as_one_message",
+            ),
+            FakeDB::default(),
+            FakeDB::default(),
+            FakeBlockImporter,
+        );
+        let shared = uninitialized.broadcast.clone();
+        let mut gossiped_txs = shared.subscribe_tx();
+        let (mut task, request_sender) = task_with_fake(FakeDB::default(), shared);
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
         let peer_id = PeerId::random();
-        // more than limit
-        let last_duration = Duration::from_secs(30);
-        let mut durations = VecDeque::new();
-        durations.push_front(last_duration);
+        let message_id = vec![1, 2, 3];
+        let transactions: Vec<_> = (0..3).map(unique_tx).collect();
+        task.p2p_service
+            .events
+            .push_back(FuelP2PEvent::GossipsubMessage {
+                peer_id,
+                message_id: MessageId::new(&message_id),
+                topic_hash: TopicHash::from_raw("new_tx_batch"),
+                message: GossipsubMessage::NewTxBatch(transactions.clone()),
+            });
 
-        let heartbeat_data = HeartbeatData {
-            block_height: None,
-            last_heartbeat: Instant::now(),
-            last_heartbeat_sys: SystemTime::now(),
-            window: 0,
-            durations,
-        };
-        let peer_info = PeerInfo {
-            peer_addresses: Default::default(),
-            client_version: None,
-            heartbeat_data,
-            score: 100.0,
-        };
-        let peer_info = vec![(peer_id, peer_info)];
-        let p2p_service = FakeP2PService { peer_info };
-        let (_request_sender, request_receiver) = mpsc::channel(100);
+        // when
+        task.run(&mut watcher).await.unwrap();
 
-        let (report_sender, mut report_receiver) = mpsc::channel(100);
-        let broadcast = FakeBroadcast {
-            peer_reports: report_sender,
-        };
+        // then
+        let mut reports = vec![];
+        for transaction in &transactions {
+            let mut gossip = gossiped_txs.try_recv().unwrap();
+            assert_eq!(gossip.message_id, message_id);
+            assert_eq!(gossip.take_data().as_ref(), Some(transaction));
+            reports.push(gossip);
+        }
+        assert!(gossiped_txs.try_recv().is_err());
 
-        // Less than actual
-        let heartbeat_max_avg_interval = Duration::from_secs(20);
-        // Greater than actual
-        let heartbeat_max_time_since_last = Duration::from_secs(40);
+        // when
+        let acceptances = [
+            GossipsubMessageAcceptance::Accept,
+            GossipsubMessageAcceptance::Reject,
+            GossipsubMessageAcceptance::Accept,
+        ];
+        for (gossip, acceptance) in reports.into_iter().zip(acceptances) {
+            request_sender
+                .try_send(TaskRequest::RespondWithGossipsubMessageReport(
+                    gossip.into_report(acceptance),
+                ))
+                .unwrap();
+            task.run(&mut watcher).await.unwrap();
+        }
 
-        // Arbitrary values
-        let heartbeat_peer_reputation_config = HeartbeatPeerReputationConfig {
-            old_heartbeat_penalty: 5.6,
-            low_heartbeat_frequency_penalty: 20.45,
+        // then
+        let expected_info = GossipsubMessageInfo {
+            message_id,
+            peer_id: FuelPeerId::from(peer_id.to_bytes()),
         };
+        assert_eq!(
+            task.p2p_service.reported_messages,
+            vec![(expected_info, GossipsubMessageAcceptance::Reject)]
+        );
+    }
 
-        let mut task = Task {
-            chain_id: Default::default(),
-            p2p_service,
-            view_provider: FakeDB,
-            next_block_height: FakeBlockImporter.next_block_height(),
-            request_receiver,
-            broadcast,
-            max_headers_per_request: 0,
-            heartbeat_check_interval: Duration::from_secs(0),
-            heartbeat_max_avg_interval,
-            heartbeat_max_time_since_last,
-            next_check_time: Instant::now(),
-            heartbeat_peer_reputation_config: heartbeat_peer_reputation_config.clone(),
-        };
-        let (watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+    #[tokio::test]
+    async fn gossip_duplicates__transaction_is_delivered_once_for_different_messages() {
+        // given
+        let uninitialized = UninitializedTask::new(
+            ChainId::default(),
+            Config::<NotInitialized>::default(
+                "gossip_duplicates__transaction_is_delivered_once_for_different_messages",
+            ),
+            FakeDB::default(),
+            FakeDB::default(),
+            FakeBlockImporter,
+        );
+        let shared = uninitialized.broadcast.clone();
+        let mut gossiped_txs = shared.subscribe_tx();
+        let (mut task, _) = task_with_fake(FakeDB::default(), shared);
+        task.metrics = true;
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
         let mut watcher = StateWatcher::from(watch_receiver);
+        let first_peer = PeerId::random();
+        let second_peer = PeerId::random();
+        // The same transaction is published alone and then within a batch
+        task.p2p_service
+            .events
+            .push_back(FuelP2PEvent::GossipsubMessage {
+                peer_id: first_peer,
+                message_id: MessageId::new(&[1]),
+                topic_hash: TopicHash::from_raw("new_tx"),
+                message: GossipsubMessage::NewTx(unique_tx(1)),
+            });
+        task.p2p_service
+            .events
+            .push_back(FuelP2PEvent::GossipsubMessage {
+                peer_id: second_peer,
+                message_id: MessageId::new(&[2]),
+                topic_hash: TopicHash::from_raw("new_tx_batch"),
+                message: GossipsubMessage::NewTxBatch(vec![unique_tx(1)]),
+            });
+        let duplicates_before = p2p_metrics().gossip_duplicate_txs.get();
 
         // when
         task.run(&mut watcher).await.unwrap();
+        task.run(&mut watcher).await.unwrap();
 
         // then
-        let (report_peer_id, report, reporting_service) =
-            report_receiver.recv().await.unwrap();
-
-        watch_sender.send(State::Stopped).unwrap();
-
-        assert_eq!(
-            FuelPeerId::from(peer_id.to_bytes().to_vec()),
-            report_peer_id
-        );
+        let gossip = gossiped_txs.try_recv().unwrap();
+        assert_eq!(gossip.message_id, vec![1]);
+        assert!(gossiped_txs.try_recv().is_err());
+        // The duplicate doesn't wait for the validation and its peer isn't penalized
+        let expected_info = GossipsubMessageInfo {
+            message_id: vec![2],
+            peer_id: FuelPeerId::from(second_peer.to_bytes()),
+        };
         assert_eq!(
-            report,
-            heartbeat_peer_reputation_config.low_heartbeat_frequency_penalty
+            task.p2p_service.reported_messages,
+            vec![(expected_info, GossipsubMessageAcceptance::Ignore)]
         );
-        assert_eq!(reporting_service, "p2p");
+        assert!(p2p_metrics().gossip_duplicate_txs.get() > duplicates_before);
     }
 
     #[tokio::test]
-    async fn peer_heartbeat_reputation_checks__old_heartbeat_sends_reports() {
+    async fn startup_gossip__is_delivered_oldest_first_once_consumers_are_ready() {
         // given
+        let uninitialized = UninitializedTask::new(
+            ChainId::default(),
+            Config::<NotInitialized>::default(
+                "startup_gossip__is_delivered_oldest_first_once_consumers_are_ready",
+            ),
+            FakeDB::default(),
+            FakeDB::default(),
+            FakeBlockImporter,
+        );
+        let shared = uninitialized.broadcast.clone();
+        let mut gossiped_txs = shared.subscribe_tx();
+        let (mut task, request_sender) = task_with_fake(FakeDB::default(), shared);
+        task.startup_gossip = Some(StartupGossipBuffer::new(2));
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
         let peer_id = PeerId::random();
-        // under the limit
-        let last_duration = Duration::from_secs(5);
-        let last_heartbeat = Instant::now() - Duration::from_secs(50);
-        let last_heartbeat_sys = SystemTime::now() - Duration::from_secs(50);
-        let mut durations = VecDeque::new();
-        durations.push_front(last_duration);
-
-        let heartbeat_data = HeartbeatData {
-            block_height: None,
-            last_heartbeat,
-            last_heartbeat_sys,
-            window: 0,
-            durations,
-        };
-        let peer_info = PeerInfo {
-            peer_addresses: Default::default(),
-            client_version: None,
-            heartbeat_data,
-            score: 100.0,
+        for seed in [1, 2, 3] {
+            task.p2p_service
+                .events
+                .push_back(FuelP2PEvent::GossipsubMessage {
+                    peer_id,
+                    message_id: MessageId::new(&[seed]),
+                    topic_hash: TopicHash::from_raw("new_tx"),
+                    message: GossipsubMessage::NewTx(unique_tx(seed)),
+                });
+        }
+        for _ in 0..3 {
+            task.run(&mut watcher).await.unwrap();
+        }
+        assert!(gossiped_txs.try_recv().is_err());
+        // The message above the buffer capacity is ignored
+        let expected_info = GossipsubMessageInfo {
+            message_id: vec![3],
+            peer_id: FuelPeerId::from(peer_id.to_bytes()),
         };
-        let peer_info = vec![(peer_id, peer_info)];
-        let p2p_service = FakeP2PService { peer_info };
-        let (_request_sender, request_receiver) = mpsc::channel(100);
+        assert_eq!(
+            task.p2p_service.reported_messages,
+            vec![(expected_info, GossipsubMessageAcceptance::Ignore)]
+        );
 
-        let (report_sender, mut report_receiver) = mpsc::channel(100);
-        let broadcast = FakeBroadcast {
-            peer_reports: report_sender,
-        };
+        // when
+        request_sender
+            .try_send(TaskRequest::ConsumersReady)
+            .unwrap();
+        task.run(&mut watcher).await.unwrap();
 
-        // Greater than actual
-        let heartbeat_max_avg_interval = Duration::from_secs(20);
-        // Less than actual
-        let heartbeat_max_time_since_last = Duration::from_secs(40);
+        // then
+        assert_eq!(gossiped_txs.try_recv().unwrap().message_id, vec![1]);
+        assert_eq!(gossiped_txs.try_recv().unwrap().message_id, vec![2]);
+        assert!(gossiped_txs.try_recv().is_err());
+
+        // The gossip received after that is delivered immediately
+        task.p2p_service
+            .events
+            .push_back(FuelP2PEvent::GossipsubMessage {
+                peer_id,
+                message_id: MessageId::new(&[4]),
+                topic_hash: TopicHash::from_raw("new_tx"),
+                message: GossipsubMessage::NewTx(unique_tx(4)),
+            });
+        task.run(&mut watcher).await.unwrap();
+        assert_eq!(gossiped_txs.try_recv().unwrap().message_id, vec![4]);
+    }
 
-        // Arbitrary values
-        let heartbeat_peer_reputation_config = HeartbeatPeerReputationConfig {
-            old_heartbeat_penalty: 5.6,
-            low_heartbeat_frequency_penalty: 20.45,
+    #[tokio::test]
+    async fn has_enough_peers__flips_when_the_peers_cross_the_threshold() {
+        // given
+        let mut config = Config::<NotInitialized>::default(
+            "has_enough_peers__flips_when_the_peers_cross_the_threshold",
+        );
+        config.min_peers_for_ready = 2;
+        let uninitialized = UninitializedTask::new(
+            ChainId::default(),
+            config,
+            FakeDB::default(),
+            FakeDB::default(),
+            FakeBlockImporter,
+        );
+        let shared = uninitialized.broadcast.clone();
+        let mut events = shared.subscribe_events();
+        let (mut task, _request_sender) =
+            task_with_fake(FakeDB::default(), shared.clone());
+        task.peer_readiness = PeerReadiness::new(2);
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+        let first_peer = PeerId::random();
+        let second_peer = PeerId::random();
+        let mut readiness_changes = || {
+            std::iter::from_fn(|| events.try_recv().ok())
+                .filter_map(|event| match event {
+                    NetworkEvent::PeersReady(ready) => Some(ready),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
         };
 
-        let mut task = Task {
-            chain_id: Default::default(),
-            p2p_service,
-            view_provider: FakeDB,
-            next_block_height: FakeBlockImporter.next_block_height(),
-            request_receiver,
-            broadcast,
-            max_headers_per_request: 0,
-            heartbeat_check_interval: Duration::from_secs(0),
-            heartbeat_max_avg_interval,
-            heartbeat_max_time_since_last,
-            next_check_time: Instant::now(),
-            heartbeat_peer_reputation_config: heartbeat_peer_reputation_config.clone(),
-        };
-        let (watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
-        let mut watcher = StateWatcher::from(watch_receiver);
+        // when
+        task.p2p_service
+            .events
+            .push_back(FuelP2PEvent::PeerConnected(first_peer));
+        task.run(&mut watcher).await.unwrap();
+
+        // then
+        assert!(!shared.has_enough_peers());
+        assert_eq!(readiness_changes(), vec![]);
 
         // when
+        task.p2p_service
+            .events
+            .push_back(FuelP2PEvent::PeerConnected(second_peer));
         task.run(&mut watcher).await.unwrap();
 
         // then
-        let (report_peer_id, report, reporting_service) =
-            report_receiver.recv().await.unwrap();
+        assert!(shared.has_enough_peers());
+        assert_eq!(readiness_changes(), vec![true]);
 
-        watch_sender.send(State::Stopped).unwrap();
+        // when
+        task.p2p_service
+            .events
+            .push_back(FuelP2PEvent::PeerDisconnected(first_peer));
+        task.run(&mut watcher).await.unwrap();
 
-        assert_eq!(
-            FuelPeerId::from(peer_id.to_bytes().to_vec()),
-            report_peer_id
-        );
-        assert_eq!(
-            report,
-            heartbeat_peer_reputation_config.old_heartbeat_penalty
+        // then
+        assert!(!shared.has_enough_peers());
+        assert_eq!(readiness_changes(), vec![false]);
+
+        // when
+        task.p2p_service
+            .events
+            .push_back(FuelP2PEvent::PeerConnected(first_peer));
+        task.run(&mut watcher).await.unwrap();
+
+        // then
+        assert!(shared.has_enough_peers());
+        assert_eq!(readiness_changes(), vec![true]);
+    }
+
+    #[tokio::test]
+    async fn startup_gossip__keeps_the_time_when_it_was_received() {
+        // given
+        let uninitialized = UninitializedTask::new(
+            ChainId::default(),
+            Config::<NotInitialized>::default(
+                "startup_gossip__keeps_the_time_when_it_was_received",
+            ),
+            FakeDB::default(),
+            FakeDB::default(),
+            FakeBlockImporter,
         );
-        assert_eq!(reporting_service, "p2p");
+        let shared = uninitialized.broadcast.clone();
+        let mut gossiped_txs = shared.subscribe_tx();
+        let (mut task, request_sender) = task_with_fake(FakeDB::default(), shared);
+        task.startup_gossip = Some(StartupGossipBuffer::new(1));
+        let (_watch_sender, watch_receiver) = tokio::sync::watch::channel(State::Started);
+        let mut watcher = StateWatcher::from(watch_receiver);
+        task.p2p_service
+            .events
+            .push_back(FuelP2PEvent::GossipsubMessage {
+                peer_id: PeerId::random(),
+                message_id: MessageId::new(&[1]),
+                topic_hash: TopicHash::from_raw("new_tx"),
+                message: GossipsubMessage::NewTx(Transaction::default_test_tx()),
+            });
+        let before_receive = SystemTime::now();
+        task.run(&mut watcher).await.unwrap();
+        let after_receive = SystemTime::now();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // when
+        request_sender
+            .try_send(TaskRequest::ConsumersReady)
+            .unwrap();
+        task.run(&mut watcher).await.unwrap();
+
+        // then
+        let gossip = gossiped_txs.try_recv().unwrap();
+        let received_at = gossip.received_at().unwrap();
+        assert!(before_receive <= received_at);
+        assert!(received_at <= after_receive);
     }
 }
@@ -45,6 +45,7 @@ pub use service::{
     RunnableTask,
     Service,
     ServiceRunner,
+    ServiceStopping,
     Shared,
     SharedMutex,
 };
@@ -31,12 +31,29 @@ impl<T> Clone for SharedMutex<T> {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct EmptyShared;
 
+/// The error returned when the service is started while it is still shutting down.
+/// The caller can wait for the shutdown with [`Service::await_stop`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServiceStopping {
+    /// The name of the service
+    pub service: &'static str,
+}
+
+impl std::fmt::Display for ServiceStopping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The service `{}` is stopping.", self.service)
+    }
+}
+
+impl std::error::Error for ServiceStopping {}
+
 /// Trait for service runners, providing a minimal interface for managing
 /// the lifecycle of services such as start/stop and health status.
 #[async_trait::async_trait]
 pub trait Service {
     /// Send a start signal to the service without waiting for it to start.
-    /// Returns an error if the service was already started.
+    /// Returns an error if the service was already started,
+    /// or [`ServiceStopping`] if it is still shutting down.
     fn start(&self) -> anyhow::Result<()>;
 
     /// Send a start signal to the service and wait for it to start up.
@@ -188,17 +205,21 @@ where
     S: RunnableService + 'static,
 {
     fn start(&self) -> anyhow::Result<()> {
+        let mut stopping = false;
         let started = self.state.send_if_modified(|state| {
             if state.not_started() {
                 *state = State::Starting;
                 true
             } else {
+                stopping = state.stopping();
                 false
             }
         });
 
         if started {
             Ok(())
+        } else if stopping {
+            Err(ServiceStopping { service: S::NAME }.into())
         } else {
             Err(anyhow!(
                 "The service `{}` already has been started.",
@@ -515,6 +536,55 @@ mod tests {
         assert!(service.start().is_err());
     }
 
+    #[tokio::test]
+    async fn concurrent_start_runs_the_task_once() {
+        const STARTS: usize = 16;
+        let mut mock = MockService::default();
+        mock.expect_shared_data().returning(|| EmptyShared);
+        mock.expect_into_task().times(1).returning(|_, _| {
+            let mut mock = MockTask::default();
+            mock.expect_run().returning(|watcher| {
+                let mut watcher = watcher.clone();
+                Box::pin(async move {
+                    watcher.while_started().await.unwrap();
+                    let should_continue = false;
+                    Ok(should_continue)
+                })
+            });
+            mock.expect_shutdown().times(1).returning(|| Ok(()));
+            Ok(mock)
+        });
+        let service = ServiceRunner::new(mock);
+        let barrier = std::sync::Barrier::new(STARTS);
+
+        let results: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..STARTS)
+                .map(|_| {
+                    scope.spawn(|| {
+                        barrier.wait();
+                        service.start()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        let (started, rejected): (Vec<_>, Vec<_>) =
+            results.into_iter().partition(Result::is_ok);
+        assert_eq!(started.len(), 1);
+        assert!(rejected.into_iter().all(|result| result
+            .unwrap_err()
+            .to_string()
+            .contains("already has been started")));
+        let state = service.await_start_or_stop().await.unwrap();
+        assert!(state.started());
+        let state = service.stop_and_await().await.unwrap();
+        assert!(matches!(state, State::Stopped));
+    }
+
     #[tokio::test]
     async fn double_start_and_await_fails() {
         let service = ServiceRunner::new(MockService::new_empty());
@@ -522,6 +592,24 @@ mod tests {
         assert!(service.start_and_await().await.is_err());
     }
 
+    #[tokio::test]
+    async fn start_during_stop_fails_with_service_stopping() {
+        let service = ServiceRunner::new(MockService::new_empty());
+        service.start_and_await().await.unwrap();
+
+        service.stop();
+        let err = service.start().expect_err("The service is stopping");
+
+        assert_eq!(
+            err.downcast_ref::<ServiceStopping>(),
+            Some(&ServiceStopping {
+                service: MockService::NAME
+            })
+        );
+        let state = service.await_stop().await.unwrap();
+        assert!(matches!(state, State::Stopped));
+    }
+
     #[tokio::test]
     async fn stop_without_start() {
         let service = ServiceRunner::new(MockService::new_empty());
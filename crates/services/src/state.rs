@@ -35,6 +35,11 @@ impl State {
         self == &State::Started
     }
 
+    /// is stopping
+    pub fn stopping(&self) -> bool {
+        self == &State::Stopping
+    }
+
     /// is stopped
     pub fn stopped(&self) -> bool {
         matches!(self, State::Stopped | State::StoppedWithError(_))
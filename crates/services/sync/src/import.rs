@@ -40,6 +40,7 @@ use crate::{
     ports::{
         BlockImporterPort,
         ConsensusPort,
+        DataNotFound,
         PeerReportReason,
         PeerToPeerPort,
     },
@@ -67,6 +68,10 @@ pub struct Config {
     pub header_batch_size: usize,
 }
 
+/// The number of the requests of the headers batch, while the peers
+/// respond that they don't have the headers.
+const HEADERS_REQUEST_ATTEMPTS: usize = 3;
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -439,11 +444,21 @@ where
         range.start,
         range.end
     );
-    p2p.get_sealed_block_headers(range)
-        .await
-        .trace_err("Failed to get headers")
-        .unwrap_or_default()
-        .map(|inner| inner.unwrap_or_default())
+    for _ in 0..HEADERS_REQUEST_ATTEMPTS {
+        match p2p.get_sealed_block_headers(range.clone()).await {
+            // The network picks the next peer for the new request
+            Err(e) if e.downcast_ref::<DataNotFound>().is_some() => {
+                tracing::debug!("{e}, requesting the headers from the next peer");
+            }
+            result => {
+                return result
+                    .trace_err("Failed to get headers")
+                    .unwrap_or_default()
+                    .map(|inner| inner.unwrap_or_default())
+            }
+        }
+    }
+    Default::default()
 }
 
 async fn get_transactions<P>(
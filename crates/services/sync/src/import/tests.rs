@@ -7,6 +7,7 @@ use crate::{
         random_peer,
     },
     ports::{
+        DataNotFound,
         MockBlockImporterPort,
         MockConsensusPort,
         MockPeerToPeerPort,
@@ -67,6 +68,65 @@ async fn test_import_0_to_5() {
     assert_eq!(v, expected);
 }
 
+#[tokio::test]
+async fn import__headers_not_found_are_requested_from_the_next_peer() {
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .times(6)
+        .returning(|_| Ok(true));
+    consensus_port
+        .expect_await_da_height()
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let mut p2p = MockPeerToPeerPort::default();
+    let mut seq = mockall::Sequence::new();
+    p2p.expect_get_sealed_block_headers()
+        .times(1)
+        .in_sequence(&mut seq)
+        .returning(|_| {
+            Err(DataNotFound {
+                peer_id: random_peer(),
+            }
+            .into())
+        });
+    p2p.expect_get_sealed_block_headers()
+        .times(1)
+        .in_sequence(&mut seq)
+        .returning(|range| {
+            let peer = random_peer();
+            let headers = Some(range.map(empty_header).collect());
+            let headers = peer.bind(headers);
+            Ok(headers)
+        });
+    p2p.expect_get_transactions()
+        .times(1)
+        .returning(|block_ids| {
+            let data = block_ids.data;
+            let v = data.into_iter().map(|_| Transactions::default()).collect();
+            Ok(Some(v))
+        });
+    // The peer without the headers isn't reported
+    p2p.expect_report_peer().never();
+
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+    };
+    let mocks = Mocks {
+        consensus_port,
+        p2p,
+        executor: DefaultMocks::times([6]),
+    };
+
+    let state = State::new(None, 5);
+    let state = SharedMutex::new(state);
+    let v = test_import_inner(state, mocks, None, params).await;
+    let expected = (State::new(5, None), true);
+    assert_eq!(v, expected);
+}
+
 #[tokio::test]
 async fn test_import_3_to_5() {
     let mut consensus_port = MockConsensusPort::default();
@@ -34,6 +34,26 @@ pub enum PeerReportReason {
     InvalidTransactions,
 }
 
+/// The error of the [`PeerToPeerPort`] requests when the peer responded
+/// that it doesn't have the requested data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DataNotFound {
+    /// The peer that doesn't have the data
+    pub peer_id: PeerId,
+}
+
+impl std::fmt::Display for DataNotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The peer {:?} doesn't have the requested data",
+            self.peer_id
+        )
+    }
+}
+
+impl std::error::Error for DataNotFound {}
+
 #[cfg_attr(any(test, feature = "benchmarking"), mockall::automock)]
 #[async_trait::async_trait]
 /// Port for communication with the network.
@@ -42,6 +62,7 @@ pub trait PeerToPeerPort {
     fn height_stream(&self) -> BoxStream<BlockHeight>;
 
     /// Request a range of sealed block headers from the network.
+    /// Fails with [`DataNotFound`] if the peer doesn't have the headers.
     async fn get_sealed_block_headers(
         &self,
         block_height_range: Range<u32>,
@@ -49,6 +70,7 @@ pub trait PeerToPeerPort {
 
     /// Request transactions from the network for the given block
     /// and source peer.
+    /// Fails with [`DataNotFound`] if the peer doesn't have the transactions.
     async fn get_transactions(
         &self,
         block_ids: SourcePeer<Range<u32>>,
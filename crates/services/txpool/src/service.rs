@@ -227,7 +227,7 @@ where
             }
 
             new_transaction = self.gossiped_tx_stream.next() => {
-                if let Some(GossipData { data: Some(tx), message_id, peer_id }) = new_transaction {
+                if let Some(GossipData { data: Some(tx), message_id, peer_id, .. }) = new_transaction {
                     let id = tx.id(&self.shared.consensus_params.chain_id);
                     let current_height = *self.shared.current_height.lock();
 
@@ -64,6 +64,10 @@ pub enum Column {
     Metadata = 17,
     /// See [`SealedBlockConsensus`](crate::tables::SealedBlockConsensus)
     FuelBlockConsensus = 18,
+    /// The column of the active bans of misbehaving p2p peers
+    PeerBans = 19,
+    /// The column of the p2p peers the node was connected to
+    KnownPeers = 20,
 }
 
 impl Column {
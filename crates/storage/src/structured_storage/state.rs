@@ -10,7 +10,11 @@ use crate::{
         raw::Raw,
     },
     column::Column,
-    structured_storage::TableWithBlueprint,
+    kv_store::KeyValueStore,
+    structured_storage::{
+        StructuredStorage,
+        TableWithBlueprint,
+    },
     tables::{
         merkle::{
             ContractsStateMerkleData,
@@ -19,7 +23,9 @@ use crate::{
         ContractsState,
     },
     Mappable,
+    StorageRead,
 };
+use core::ops::Deref;
 use fuel_core_types::fuel_vm::ContractsStateKey;
 
 /// The key convertor used to convert the key from the `ContractsState` table
@@ -50,6 +56,30 @@ impl TableWithBlueprint for ContractsState {
     }
 }
 
+// The values are stored with the `Raw` codec, so the stored bytes
+// are the bytes of the `Bytes32` and can be read without decoding.
+impl<S> StorageRead<ContractsState> for StructuredStorage<S>
+where
+    S: KeyValueStore<Column = Column>,
+{
+    fn read(
+        &self,
+        key: &ContractsStateKey,
+        buf: &mut [u8],
+    ) -> Result<Option<usize>, Self::Error> {
+        self.storage.read(key.as_ref(), Column::ContractsState, buf)
+    }
+
+    fn read_alloc(
+        &self,
+        key: &ContractsStateKey,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        self.storage
+            .get(key.as_ref(), Column::ContractsState)
+            .map(|value| value.map(|value| value.deref().clone()))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -83,6 +113,34 @@ mod test {
         bytes.into()
     }
 
+    #[test]
+    fn get_corrupted_value_returns_codec_error() {
+        use crate::{
+            kv_store::KeyValueStore,
+            structured_storage::{
+                test::InMemoryStorage,
+                StructuredStorage,
+            },
+            Error,
+            StorageAsRef,
+        };
+
+        // given
+        let mut storage = InMemoryStorage::<Column>::default();
+        let key = <ContractsState as Mappable>::Key::default();
+        // The value of the `ContractsState` is always 32 bytes long
+        storage
+            .write(key.as_ref(), Column::ContractsState, &[1, 2, 3])
+            .unwrap();
+        let structured_storage = StructuredStorage::new(&mut storage);
+
+        // when
+        let result = structured_storage.storage::<ContractsState>().get(&key);
+
+        // then
+        assert!(matches!(result, Err(Error::Codec(_))));
+    }
+
     crate::root_storage_tests!(
         ContractsState,
         ContractsStateMerkleMetadata,
@@ -52,32 +52,75 @@ pub struct VmStorage<D> {
     current_block_height: BlockHeight,
     current_timestamp: Tai64,
     coinbase: ContractId,
+    timestamp_interpolation: Option<TimestampInterpolation>,
     database: D,
 }
 
+/// Interpolates the time of the blocks that aren't stored anymore,
+/// e.g. because the node pruned the old blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimestampInterpolation {
+    /// The time of the genesis block.
+    pub genesis_time: Tai64,
+    /// The number of seconds between the blocks.
+    pub block_interval: u64,
+}
+
+impl TimestampInterpolation {
+    /// Returns `genesis_time + height * block_interval`.
+    /// Fails with [`TimestampOverflow`] if the time doesn't fit into `Tai64`.
+    pub fn block_time(&self, height: BlockHeight) -> Result<Tai64, TimestampOverflow> {
+        u64::from(*height)
+            .checked_mul(self.block_interval)
+            .and_then(|elapsed| self.genesis_time.0.checked_add(elapsed))
+            .map(Tai64)
+            .ok_or(TimestampOverflow { height })
+    }
+}
+
+/// The interpolated time of the block doesn't fit into `Tai64`.
+/// It is returned inside of the [`StorageError::Other`], so the caller can downcast it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, derive_more::Display)]
+#[display(fmt = "the interpolated time of the block at height {height} overflows")]
+pub struct TimestampOverflow {
+    /// The height of the block
+    pub height: BlockHeight,
+}
+
+impl std::error::Error for TimestampOverflow {}
+
 /// The trait around the `U256` type allows increasing the key by one.
-pub trait IncreaseStorageKey {
+pub trait IncreaseStorageKey: Sized {
+    /// Returns the key increased by one without modifying the original key.
+    ///
+    /// Returns a `Result::Err` in the case of overflow.
+    fn next_key(&self) -> anyhow::Result<Self>;
+
     /// Increases the key by one.
     ///
     /// Returns a `Result::Err` in the case of overflow.
-    fn increase(&mut self) -> anyhow::Result<()>;
+    fn increase(&mut self) -> anyhow::Result<()> {
+        *self = self.next_key()?;
+        Ok(())
+    }
 }
 
 impl IncreaseStorageKey for U256 {
-    fn increase(&mut self) -> anyhow::Result<()> {
-        *self = self
-            .checked_add(1.into())
-            .ok_or_else(|| anyhow!("range op exceeded available keyspace"))?;
-        Ok(())
+    fn next_key(&self) -> anyhow::Result<Self> {
+        self.checked_add(1.into())
+            .ok_or_else(|| anyhow!("range op exceeded available keyspace"))
     }
 }
 
+/// The default storage is at the zero block height with the current timestamp.
+/// Its coinbase is the zero contract id, use [`VmStorage::with_coinbase`] to set another one.
 impl<D: Default> Default for VmStorage<D> {
     fn default() -> Self {
         Self {
             current_block_height: Default::default(),
             current_timestamp: Tai64::now(),
             coinbase: Default::default(),
+            timestamp_interpolation: None,
             database: D::default(),
         }
     }
@@ -94,10 +137,27 @@ impl<D> VmStorage<D> {
             current_block_height: header.height,
             current_timestamp: header.time,
             coinbase,
+            timestamp_interpolation: None,
             database,
         }
     }
 
+    /// Replaces the coinbase contract id that receives the fees of the block.
+    pub fn with_coinbase(mut self, coinbase: ContractId) -> Self {
+        self.coinbase = coinbase;
+        self
+    }
+
+    /// Interpolates the time of the blocks missing in the storage instead of failing
+    /// the `TIME` instruction.
+    pub fn with_timestamp_interpolation(
+        mut self,
+        interpolation: TimestampInterpolation,
+    ) -> Self {
+        self.timestamp_interpolation = Some(interpolation);
+        self
+    }
+
     /// The helper function allows modification of the underlying storage.
     #[cfg(feature = "test-helpers")]
     pub fn database_mut(&mut self) -> &mut D {
@@ -105,6 +165,104 @@ impl<D> VmStorage<D> {
     }
 }
 
+impl<D> VmStorage<D>
+where
+    D: StorageInspect<ContractsState, Error = StorageError>,
+{
+    /// Reads `range` sequential slots of the contract state starting from the `start_key`
+    /// into the `out` buffer. The buffer is cleared before the read, so it can be reused
+    /// between the reads without allocations. If the read fails, the buffer contains
+    /// the slots read before the failure.
+    pub fn merkle_contract_state_range_into<'a>(
+        &'a self,
+        contract_id: &ContractId,
+        start_key: &Bytes32,
+        range: usize,
+        out: &mut Vec<Option<Cow<'a, Bytes32>>>,
+    ) -> Result<(), StorageError> {
+        use crate::StorageAsRef;
+
+        out.clear();
+        out.reserve(range);
+
+        for_each_state_key(contract_id, start_key, range, |multikey| {
+            out.push(self.database.storage::<ContractsState>().get(multikey)?);
+            Ok(())
+        })
+    }
+
+    /// Reads `range` sequential slots of the contract state starting from the `start_key`.
+    /// Unlike the [`InterpreterStorage::merkle_contract_state_range`], all slots
+    /// in the range are expected to be set, and the gap is reported as an error.
+    pub fn merkle_contract_state_range_dense(
+        &self,
+        contract_id: &ContractId,
+        start_key: &Bytes32,
+        range: usize,
+    ) -> Result<Vec<Cow<Bytes32>>, StorageError> {
+        use crate::StorageAsRef;
+
+        let mut results = Vec::with_capacity(range);
+        for_each_state_key(contract_id, start_key, range, |multikey| {
+            let value = self
+                .database
+                .storage::<ContractsState>()
+                .get(multikey)?
+                .ok_or(not_found!(ContractsState))?;
+            results.push(value);
+            Ok(())
+        })?;
+        Ok(results)
+    }
+}
+
+impl<D> VmStorage<D>
+where
+    D: StorageRead<ContractsState, Error = StorageError>,
+{
+    /// Reads `range` sequential slots of the contract state starting from the `start_key`
+    /// as they are stored, without decoding the values into `Bytes32`.
+    /// It allows passing the state through to another node as is.
+    pub fn merkle_contract_state_range_raw(
+        &self,
+        contract_id: &ContractId,
+        start_key: &Bytes32,
+        range: usize,
+    ) -> Result<Vec<Option<Vec<u8>>>, StorageError> {
+        let mut results = Vec::with_capacity(range);
+        for_each_state_key(contract_id, start_key, range, |multikey| {
+            results.push(StorageRead::<ContractsState>::read_alloc(
+                &self.database,
+                multikey,
+            )?);
+            Ok(())
+        })?;
+        Ok(results)
+    }
+}
+
+/// Calls `f` with the keys of `range` sequential slots of the contract state,
+/// starting from the `start_key`. Fails if the range exceeds the keyspace.
+fn for_each_state_key<F>(
+    contract_id: &ContractId,
+    start_key: &Bytes32,
+    range: usize,
+    mut f: F,
+) -> Result<(), StorageError>
+where
+    F: FnMut(&ContractsStateKey) -> Result<(), StorageError>,
+{
+    let mut key = U256::from_big_endian(start_key.as_ref());
+    let mut state_key = Bytes32::zeroed();
+
+    for _ in 0..range {
+        key.to_big_endian(state_key.as_mut());
+        f(&ContractsStateKey::new(contract_id, &state_key))?;
+        key.increase()?;
+    }
+    Ok(())
+}
+
 impl<D, M: Mappable> StorageInspect<M> for VmStorage<D>
 where
     D: StorageInspect<M, Error = StorageError>,
@@ -178,12 +336,7 @@ impl<D> ContractsAssetsStorage for VmStorage<D> where
 
 impl<D> InterpreterStorage for VmStorage<D>
 where
-    D: StorageMutate<ContractsInfo, Error = StorageError>
-        + MerkleRootStorage<ContractId, ContractsState, Error = StorageError>
-        + StorageMutate<ContractsRawCode, Error = StorageError>
-        + StorageRead<ContractsRawCode, Error = StorageError>
-        + MerkleRootStorage<ContractId, ContractsAssets, Error = StorageError>
-        + VmStorageRequirements<Error = StorageError>,
+    D: VmStorageBackend,
 {
     type DataError = StorageError;
 
@@ -198,7 +351,20 @@ where
                 return Err(anyhow!("block height too high for timestamp").into())
             }
             height if height == self.current_block_height => self.current_timestamp,
-            height => self.database.block_time(&height)?,
+            height => {
+                match (
+                    self.database.block_time(&height),
+                    self.timestamp_interpolation,
+                ) {
+                    // The block is pruned, so its time is interpolated if it is allowed
+                    (Err(StorageError::NotFound(_, _)), Some(interpolation)) => {
+                        interpolation
+                            .block_time(height)
+                            .map_err(anyhow::Error::new)?
+                    }
+                    (result, _) => result?,
+                }
+            }
         };
         Ok(timestamp.0)
     }
@@ -245,18 +411,13 @@ where
         start_key: &Bytes32,
         range: usize,
     ) -> Result<Vec<Option<Cow<Bytes32>>>, Self::DataError> {
-        use crate::StorageAsRef;
-
-        let mut key = U256::from_big_endian(start_key.as_ref());
-        let mut state_key = Bytes32::zeroed();
-
         let mut results = Vec::new();
-        for _ in 0..range {
-            key.to_big_endian(state_key.as_mut());
-            let multikey = ContractsStateKey::new(contract_id, &state_key);
-            results.push(self.database.storage::<ContractsState>().get(&multikey)?);
-            key.increase()?;
-        }
+        self.merkle_contract_state_range_into(
+            contract_id,
+            start_key,
+            range,
+            &mut results,
+        )?;
         Ok(results)
     }
 
@@ -274,23 +435,28 @@ where
 
         let mut key_bytes = Bytes32::zeroed();
         let mut found_unset = 0u32;
+        let mut slots = Vec::with_capacity(values.len());
         for value in values {
             current_key.to_big_endian(key_bytes.as_mut());
 
-            let option = self
-                .database
-                .storage::<ContractsState>()
-                .insert(&(contract_id, &key_bytes).into(), value)?;
+            let is_set = StorageInspect::<ContractsState>::contains_key(
+                &self.database,
+                &(contract_id, &key_bytes).into(),
+            )?;
 
-            if option.is_none() {
+            if !is_set {
                 found_unset = found_unset
                     .checked_add(1)
                     .expect("We've checked it above via `values.len()`");
             }
+            slots.push((key_bytes, *value));
 
             current_key.increase()?;
         }
 
+        self.database
+            .insert_contract_state(contract_id, slots.into_iter())?;
+
         Ok(found_unset as usize)
     }
 
@@ -326,6 +492,30 @@ where
     }
 }
 
+/// The storage operations used by the [`VmStorage`] to run the VM.
+///
+/// It is implemented for any storage that provides them, so the VM can run on top of
+/// a custom backend, like an in-memory map, and not only on top of the node's database.
+pub trait VmStorageBackend:
+    StorageMutate<ContractsInfo, Error = StorageError>
+    + MerkleRootStorage<ContractId, ContractsState, Error = StorageError>
+    + StorageMutate<ContractsRawCode, Error = StorageError>
+    + StorageRead<ContractsRawCode, Error = StorageError>
+    + MerkleRootStorage<ContractId, ContractsAssets, Error = StorageError>
+    + VmStorageRequirements<Error = StorageError>
+{
+}
+
+impl<T> VmStorageBackend for T where
+    T: StorageMutate<ContractsInfo, Error = StorageError>
+        + MerkleRootStorage<ContractId, ContractsState, Error = StorageError>
+        + StorageMutate<ContractsRawCode, Error = StorageError>
+        + StorageRead<ContractsRawCode, Error = StorageError>
+        + MerkleRootStorage<ContractId, ContractsAssets, Error = StorageError>
+        + VmStorageRequirements<Error = StorageError>
+{
+}
+
 /// The requirements for the storage for optimal work of the [`VmStorage`].
 pub trait VmStorageRequirements {
     /// The error used by the storage.
@@ -343,6 +533,14 @@ pub trait VmStorageRequirements {
         contract_id: &ContractId,
         slots: S,
     ) -> Result<(), Self::Error>;
+
+    /// Inserts a batch of the key/value pairs into the contract state,
+    /// overriding the existing values.
+    fn insert_contract_state<S: Iterator<Item = (Bytes32, Bytes32)>>(
+        &mut self,
+        contract_id: &ContractId,
+        slots: S,
+    ) -> Result<(), Self::Error>;
 }
 
 impl<T> VmStorageRequirements for T
@@ -380,4 +578,44 @@ where
             .collect_vec();
         self.init_storage(slots.iter().map(|kv| (&kv.0, &kv.1)))
     }
+
+    fn insert_contract_state<S: Iterator<Item = (Bytes32, Bytes32)>>(
+        &mut self,
+        contract_id: &ContractId,
+        slots: S,
+    ) -> Result<(), Self::Error> {
+        let slots = slots
+            .map(|(key, value)| (ContractsStateKey::new(contract_id, &key), value))
+            .collect_vec();
+        self.insert_batch(slots.iter().map(|kv| (&kv.0, &kv.1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_key_returns_the_increased_key() {
+        let key = U256::from(41);
+
+        assert_eq!(key.next_key().unwrap(), U256::from(42));
+        assert_eq!(key, U256::from(41));
+    }
+
+    #[test]
+    fn next_key_at_max_fails_without_changing_the_key() {
+        let key = U256::MAX;
+
+        assert!(key.next_key().is_err());
+        assert_eq!(key, U256::MAX);
+    }
+
+    #[test]
+    fn increase_at_max_fails_without_changing_the_key() {
+        let mut key = U256::MAX;
+
+        assert!(key.increase().is_err());
+        assert_eq!(key, U256::MAX);
+    }
 }
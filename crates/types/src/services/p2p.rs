@@ -33,6 +33,15 @@ pub struct GossipsubMessageInfo {
     pub peer_id: PeerId,
 }
 
+/// The validation result of the gossiped message, reported back to the network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GossipsubMessageReport {
+    /// The identity of the validated message
+    pub message_info: GossipsubMessageInfo,
+    /// The validation result of the message
+    pub acceptance: GossipsubMessageAcceptance,
+}
+
 // TODO: Maybe we can remove most of types from here directly into P2P
 
 /// Reporting levels on the status of a message received via Gossip
@@ -58,6 +67,9 @@ pub struct GossipData<T> {
     pub peer_id: PeerId,
     /// The message id that corresponds to a message payload (typically a unique hash)
     pub message_id: Vec<u8>,
+    /// The time when the message arrived from the network,
+    /// read with [`NetworkData::received_at`]
+    received_at: SystemTime,
 }
 
 /// Transactions gossiped by peers for inclusion into a block
@@ -86,16 +98,48 @@ impl<T> SourcePeer<T> {
 }
 
 impl<T> GossipData<T> {
-    /// Construct a new gossip message
+    /// Construct a new gossip message received right now
     pub fn new(
         data: T,
         peer_id: impl Into<Vec<u8>>,
         message_id: impl Into<Vec<u8>>,
+    ) -> Self {
+        Self::new_with_timestamp(data, peer_id, message_id, SystemTime::now())
+    }
+
+    /// Construct a new gossip message received at the given time
+    pub fn new_with_timestamp(
+        data: T,
+        peer_id: impl Into<Vec<u8>>,
+        message_id: impl Into<Vec<u8>>,
+        received_at: SystemTime,
     ) -> Self {
         Self {
             data: Some(data),
             peer_id: PeerId::from(peer_id.into()),
             message_id: message_id.into(),
+            received_at,
+        }
+    }
+
+    /// Returns the identity of the gossiped message,
+    /// required to report the validation result back to the network
+    pub fn message_info(&self) -> GossipsubMessageInfo {
+        GossipsubMessageInfo {
+            message_id: self.message_id.clone(),
+            peer_id: self.peer_id.clone(),
+        }
+    }
+
+    /// Consumes the message and pairs its identity with the validation result.
+    /// It guarantees that the report refers to the same message and peer.
+    pub fn into_report(
+        self,
+        acceptance: GossipsubMessageAcceptance,
+    ) -> GossipsubMessageReport {
+        GossipsubMessageReport {
+            message_info: self.message_info(),
+            acceptance,
         }
     }
 }
@@ -104,12 +148,21 @@ impl<T> GossipData<T> {
 pub trait NetworkData<T>: Debug + Send {
     /// Consume ownership of data from a gossipped message
     fn take_data(&mut self) -> Option<T>;
+
+    /// The time when the data arrived from the network, if it is known
+    fn received_at(&self) -> Option<SystemTime> {
+        None
+    }
 }
 
 impl<T: Debug + Send + 'static> NetworkData<T> for GossipData<T> {
     fn take_data(&mut self) -> Option<T> {
         self.data.take()
     }
+
+    fn received_at(&self) -> Option<SystemTime> {
+        Some(self.received_at)
+    }
 }
 /// Used for relying latest `BlockHeight` info from connected peers
 #[derive(Debug, Clone)]
@@ -120,6 +173,28 @@ pub struct BlockHeightHeartbeatData {
     pub block_height: BlockHeight,
 }
 
+/// The ban of a misbehaving peer
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PeerBan {
+    /// The reason why the peer was banned
+    pub reason: String,
+    /// The time when the ban expires
+    pub expiry: SystemTime,
+}
+
+/// The peer the node was connected to, remembered to dial it after the restart
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KnownPeer {
+    /// The binary encoded multiaddresses of the peer
+    pub addresses: Vec<Vec<u8>>,
+    /// The last time the node was connected to the peer
+    pub last_seen: SystemTime,
+    /// The reputation of the peer when it was last seen
+    pub score: f64,
+}
+
 /// Opaque peer identifier.
 #[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -3,15 +3,44 @@ mod tests {
     use fuel_core::database::Database;
 
     use fuel_core_storage::{
+        column::Column,
+        kv_store::{
+            BatchOperations,
+            KeyValueStore,
+            Value,
+            WriteOperation,
+        },
+        structured_storage::{
+            test::InMemoryStorage,
+            StructuredStorage,
+        },
         tables::ContractsState,
-        vm_storage::VmStorage,
+        vm_storage::{
+            TimestampInterpolation,
+            TimestampOverflow,
+            VmStorage,
+            VmStorageBackend,
+        },
+        Error as StorageError,
         InterpreterStorage,
+        Result as StorageResult,
         StorageMutate,
+        StorageRead,
     };
     use fuel_core_txpool::types::ContractId;
-    use fuel_core_types::fuel_types::Bytes32;
+    use fuel_core_types::{
+        blockchain::header::{
+            ConsensusHeader,
+            GeneratedConsensusFields,
+        },
+        fuel_types::Bytes32,
+        tai64::Tai64,
+    };
     use primitive_types::U256;
-    use std::borrow::Cow;
+    use std::{
+        borrow::Cow,
+        cell::Cell,
+    };
     use test_case::test_case;
 
     fn u256_to_bytes32(u: U256) -> Bytes32 {
@@ -20,6 +49,15 @@ mod tests {
         Bytes32::from(bytes)
     }
 
+    /// The VM storage on top of the plain in-memory map instead of the node's database
+    fn in_memory_storage() -> VmStorage<StructuredStorage<InMemoryStorage<Column>>> {
+        VmStorage::new::<GeneratedConsensusFields>(
+            StructuredStorage::new(InMemoryStorage::default()),
+            &Default::default(),
+            ContractId::zeroed(),
+        )
+    }
+
     const fn key(k: u8) -> [u8; 32] {
         [
             0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -87,8 +125,28 @@ mod tests {
         start_key: [u8; 32],
         range: usize,
     ) -> Result<Vec<Option<[u8; 32]>>, ()> {
-        let mut db = VmStorage::<Database>::default();
+        let result = read_sequential_range_on(
+            VmStorage::<Database>::default(),
+            prefilled_slots,
+            start_key,
+            range,
+        );
+        let in_memory_result = read_sequential_range_on(
+            in_memory_storage(),
+            prefilled_slots,
+            start_key,
+            range,
+        );
+        assert_eq!(result, in_memory_result);
+        result
+    }
 
+    fn read_sequential_range_on<D: VmStorageBackend>(
+        mut db: VmStorage<D>,
+        prefilled_slots: &[([u8; 32], [u8; 32])],
+        start_key: [u8; 32],
+        range: usize,
+    ) -> Result<Vec<Option<[u8; 32]>>, ()> {
         let contract_id = ContractId::new([0u8; 32]);
 
         // prefill db
@@ -113,6 +171,108 @@ mod tests {
             .collect())
     }
 
+    #[test]
+    fn read_range_into_reused_buffer_does_not_keep_stale_entries() {
+        let mut db = VmStorage::<Database>::default();
+        let contract_id = ContractId::new([0u8; 32]);
+
+        for (key, value) in [(key(0), [0; 32]), (key(1), [1; 32]), (key(5), [5; 32])] {
+            StorageMutate::<ContractsState>::insert(
+                db.database_mut(),
+                &(&contract_id, &Bytes32::from(key)).into(),
+                &Bytes32::new(value),
+            )
+            .unwrap();
+        }
+
+        fn to_owned(buffer: &[Option<Cow<Bytes32>>]) -> Vec<Option<Bytes32>> {
+            buffer.iter().map(|v| v.as_deref().copied()).collect()
+        }
+        let mut buffer = Vec::new();
+
+        db.merkle_contract_state_range_into(
+            &contract_id,
+            &Bytes32::new(key(0)),
+            3,
+            &mut buffer,
+        )
+        .unwrap();
+        assert_eq!(
+            to_owned(&buffer),
+            vec![Some([0; 32].into()), Some([1; 32].into()), None]
+        );
+
+        db.merkle_contract_state_range_into(
+            &contract_id,
+            &Bytes32::new(key(5)),
+            1,
+            &mut buffer,
+        )
+        .unwrap();
+        assert_eq!(to_owned(&buffer), vec![Some([5; 32].into())]);
+    }
+
+    #[test_case(
+    &[(key(0), [0; 32]), (key(1), [1; 32]), (key(2), [2; 32])], key(0), 3
+    => Ok(vec![[0; 32], [1; 32], [2; 32]])
+    ; "dense read of fully initialized range"
+    )]
+    #[test_case(
+    &[(key(0), [0; 32]), (key(2), [2; 32])], key(0), 3
+    => Err(())
+    ; "dense read fails on range with a hole"
+    )]
+    fn read_dense_range(
+        prefilled_slots: &[([u8; 32], [u8; 32])],
+        start_key: [u8; 32],
+        range: usize,
+    ) -> Result<Vec<[u8; 32]>, ()> {
+        let result = read_dense_range_on(
+            VmStorage::<Database>::default(),
+            prefilled_slots,
+            start_key,
+            range,
+        );
+        let in_memory_result =
+            read_dense_range_on(in_memory_storage(), prefilled_slots, start_key, range);
+        assert_eq!(result, in_memory_result);
+        result
+    }
+
+    fn read_dense_range_on<D: VmStorageBackend>(
+        mut db: VmStorage<D>,
+        prefilled_slots: &[([u8; 32], [u8; 32])],
+        start_key: [u8; 32],
+        range: usize,
+    ) -> Result<Vec<[u8; 32]>, ()> {
+        let contract_id = ContractId::new([0u8; 32]);
+
+        // prefill db
+        for (key, value) in prefilled_slots {
+            let key = Bytes32::from(*key);
+            let value = Bytes32::new(*value);
+
+            StorageMutate::<ContractsState>::insert(
+                db.database_mut(),
+                &(&contract_id, &key).into(),
+                &value,
+            )
+            .unwrap();
+        }
+
+        // perform dense read
+        Ok(db
+            .merkle_contract_state_range_dense(
+                &contract_id,
+                &Bytes32::new(start_key),
+                range,
+            )
+            .map_err(|_| ())?
+            .into_iter()
+            .map(|v| *v.into_owned())
+            .collect())
+    }
+
     #[test_case(
     &[], key(0), &[[1; 32]]
     => Ok(false)
@@ -163,8 +323,28 @@ mod tests {
         start_key: [u8; 32],
         insertion_range: &[[u8; 32]],
     ) -> Result<bool, ()> {
-        let mut db = VmStorage::<Database>::default();
+        let result = insert_range_on(
+            VmStorage::<Database>::default(),
+            prefilled_slots,
+            start_key,
+            insertion_range,
+        );
+        let in_memory_result = insert_range_on(
+            in_memory_storage(),
+            prefilled_slots,
+            start_key,
+            insertion_range,
+        );
+        assert_eq!(result, in_memory_result);
+        result
+    }
 
+    fn insert_range_on<D: VmStorageBackend>(
+        mut db: VmStorage<D>,
+        prefilled_slots: &[([u8; 32], [u8; 32])],
+        start_key: [u8; 32],
+        insertion_range: &[[u8; 32]],
+    ) -> Result<bool, ()> {
         let contract_id = ContractId::new([0u8; 32]);
 
         // prefill db
@@ -219,6 +399,81 @@ mod tests {
         insert_status
     }
 
+    /// Counts how the contract state slots are written into the in-memory storage
+    #[derive(Default)]
+    struct WriteCountingStorage {
+        storage: InMemoryStorage<Column>,
+        single_writes: Cell<usize>,
+        batch_writes: Cell<usize>,
+    }
+
+    impl KeyValueStore for WriteCountingStorage {
+        type Column = Column;
+
+        fn write(
+            &self,
+            key: &[u8],
+            column: Self::Column,
+            buf: &[u8],
+        ) -> StorageResult<usize> {
+            if column == Column::ContractsState {
+                self.single_writes.set(self.single_writes.get() + 1);
+            }
+            self.storage.write(key, column, buf)
+        }
+
+        fn delete(&self, key: &[u8], column: Self::Column) -> StorageResult<()> {
+            self.storage.delete(key, column)
+        }
+
+        fn get(&self, key: &[u8], column: Self::Column) -> StorageResult<Option<Value>> {
+            self.storage.get(key, column)
+        }
+    }
+
+    impl BatchOperations for WriteCountingStorage {
+        fn batch_write(
+            &self,
+            entries: &mut dyn Iterator<Item = (Vec<u8>, Self::Column, WriteOperation)>,
+        ) -> StorageResult<()> {
+            let mut entries = entries.peekable();
+            if let Some((_, Column::ContractsState, _)) = entries.peek() {
+                self.batch_writes.set(self.batch_writes.get() + 1);
+            }
+            self.storage.batch_write(&mut entries)
+        }
+    }
+
+    #[test]
+    fn insert_range_writes_the_slots_in_a_single_batch() {
+        // given
+        let mut db = VmStorage::new::<GeneratedConsensusFields>(
+            StructuredStorage::new(WriteCountingStorage::default()),
+            &Default::default(),
+            ContractId::zeroed(),
+        );
+        let contract_id = ContractId::zeroed();
+        let values = vec![Bytes32::new([1; 32]); 1000];
+
+        // when
+        let unset = db
+            .merkle_contract_state_insert_range(&contract_id, &Bytes32::zeroed(), &values)
+            .unwrap();
+
+        // then
+        assert_eq!(unset, values.len());
+        let storage = db.database_mut().as_ref();
+        assert_eq!(storage.single_writes.get(), 0);
+        assert_eq!(storage.batch_writes.get(), 1);
+        let last_key = u256_to_bytes32(U256::from(values.len() - 1));
+        assert_eq!(
+            db.merkle_contract_state(&contract_id, &last_key)
+                .unwrap()
+                .map(Cow::into_owned),
+            Some(Bytes32::new([1; 32]))
+        );
+    }
+
     #[test_case(
     &[], [0; 32], 1
     => (vec![], false)
@@ -259,8 +514,28 @@ mod tests {
         start_key: [u8; 32],
         remove_count: usize,
     ) -> (Vec<[u8; 32]>, bool) {
-        let mut db = VmStorage::<Database>::default();
+        let result = remove_range_on(
+            VmStorage::<Database>::default(),
+            prefilled_slots,
+            start_key,
+            remove_count,
+        );
+        let in_memory_result = remove_range_on(
+            in_memory_storage(),
+            prefilled_slots,
+            start_key,
+            remove_count,
+        );
+        assert_eq!(result, in_memory_result);
+        result
+    }
 
+    fn remove_range_on<D: VmStorageBackend>(
+        mut db: VmStorage<D>,
+        prefilled_slots: &[([u8; 32], [u8; 32])],
+        start_key: [u8; 32],
+        remove_count: usize,
+    ) -> (Vec<[u8; 32]>, bool) {
         let contract_id = ContractId::new([0u8; 32]);
 
         // prefill db
@@ -308,4 +583,158 @@ mod tests {
 
         (results, remove_status)
     }
+
+    #[test]
+    fn timestamp_interpolation_overflow_is_reported_as_error() {
+        // given
+        let interpolation = TimestampInterpolation {
+            genesis_time: Tai64(u64::MAX - 10),
+            block_interval: u64::MAX,
+        };
+        let header = ConsensusHeader::<GeneratedConsensusFields> {
+            height: u32::MAX.into(),
+            ..Default::default()
+        };
+        let storage = VmStorage::new(
+            StructuredStorage::new(InMemoryStorage::<Column>::default()),
+            &header,
+            ContractId::zeroed(),
+        )
+        .with_timestamp_interpolation(interpolation);
+        let extreme_height = (u32::MAX - 1).into();
+
+        // when
+        let result = storage.timestamp(extreme_height);
+
+        // then
+        let Err(StorageError::Other(err)) = result.as_ref() else {
+            panic!("Expected the overflow error, got {result:?}")
+        };
+        assert_eq!(
+            err.downcast_ref::<TimestampOverflow>(),
+            Some(&TimestampOverflow {
+                height: extreme_height
+            })
+        );
+    }
+
+    #[test]
+    fn read_raw_range_returns_the_stored_bytes() {
+        let expected = vec![Some(vec![1; 32]), None, Some(vec![2; 32])];
+
+        assert_eq!(
+            read_raw_range_on(VmStorage::<Database>::default()),
+            expected
+        );
+        assert_eq!(read_raw_range_on(in_memory_storage()), expected);
+    }
+
+    fn read_raw_range_on<D>(mut db: VmStorage<D>) -> Vec<Option<Vec<u8>>>
+    where
+        D: VmStorageBackend + StorageRead<ContractsState, Error = StorageError>,
+    {
+        let contract_id = ContractId::new([0u8; 32]);
+        for (key, value) in [(key(0), [1; 32]), (key(2), [2; 32])] {
+            StorageMutate::<ContractsState>::insert(
+                db.database_mut(),
+                &(&contract_id, &Bytes32::new(key)).into(),
+                &Bytes32::new(value),
+            )
+            .unwrap();
+        }
+
+        let raw = db
+            .merkle_contract_state_range_raw(&contract_id, &Bytes32::new(key(0)), 3)
+            .unwrap();
+
+        // The raw bytes are the encoding of the values read by the typed range
+        let typed: Vec<_> = db
+            .merkle_contract_state_range(&contract_id, &Bytes32::new(key(0)), 3)
+            .unwrap()
+            .into_iter()
+            .map(|value| value.map(|value| value.to_vec()))
+            .collect();
+        assert_eq!(raw, typed);
+        raw
+    }
+
+    #[test]
+    fn state_root_after_range_updates_matches_the_full_recompute() {
+        let contract_id = ContractId::new([0u8; 32]);
+        let mut db = VmStorage::<Database>::default();
+        let values: Vec<_> = (0..8u8).map(|i| Bytes32::new([i; 32])).collect();
+
+        db.merkle_contract_state_insert_range(&contract_id, &key(0).into(), &values)
+            .unwrap();
+        db.merkle_contract_state_remove_range(&contract_id, &key(2).into(), 3)
+            .unwrap();
+        db.merkle_contract_state_insert_range(
+            &contract_id,
+            &key(4).into(),
+            &[Bytes32::new([9; 32]), Bytes32::new([10; 32])],
+        )
+        .unwrap();
+        db.merkle_contract_state_remove_range(&contract_id, &key(7).into(), 1)
+            .unwrap();
+
+        assert!(db
+            .database_mut()
+            .verify_contract_state_root(&contract_id)
+            .unwrap());
+    }
+
+    #[test]
+    fn default_coinbase_is_zero() {
+        let db = VmStorage::<Database>::default();
+
+        assert_eq!(db.coinbase().unwrap(), ContractId::zeroed());
+    }
+
+    #[test]
+    fn coinbase_is_the_configured_one() {
+        let coinbase = ContractId::new([1u8; 32]);
+
+        let db = VmStorage::new::<GeneratedConsensusFields>(
+            Database::default(),
+            &Default::default(),
+            coinbase,
+        );
+        assert_eq!(db.coinbase().unwrap(), coinbase);
+
+        let db = VmStorage::<Database>::default().with_coinbase(coinbase);
+        assert_eq!(db.coinbase().unwrap(), coinbase);
+    }
+
+    #[test]
+    fn timestamp_of_pruned_block_is_interpolated_if_enabled() {
+        // given
+        let interpolation = TimestampInterpolation {
+            genesis_time: Tai64(1_000),
+            block_interval: 2,
+        };
+        let header = ConsensusHeader::<GeneratedConsensusFields> {
+            height: 10.into(),
+            time: Tai64(1_020),
+            ..Default::default()
+        };
+        let storage = || {
+            VmStorage::new(
+                StructuredStorage::new(InMemoryStorage::<Column>::default()),
+                &header,
+                ContractId::zeroed(),
+            )
+        };
+        let without_interpolation = storage();
+        let with_interpolation = storage().with_timestamp_interpolation(interpolation);
+
+        // when
+        let missing_block_time = without_interpolation.timestamp(5.into());
+        let interpolated_block_time = with_interpolation.timestamp(5.into());
+
+        // then
+        assert!(missing_block_time.is_err());
+        assert_eq!(interpolated_block_time.unwrap(), 1_010);
+        // The time of the current block is still taken from its header
+        assert_eq!(with_interpolation.timestamp(10.into()).unwrap(), 1_020);
+    }
 }